@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+#
+# The weights of the pallets below are, in whole or in part, hand-written estimates rather than
+# benchmark results. Run this script on the reference hardware to regenerate them, then drop the
+# pallets from the list, and the script itself once the list is empty.
+#
+# pallet_escrow, pallet_merkle_airdrop and pallet_streaming_payments are not part of the node
+# runtime, so the benchmark command can't run them yet. Their weights stay estimates until they
+# are added to it, and to this list.
+
+set -e
+
+PROJECT_ROOT=`git rev-parse --show-toplevel`
+cd $PROJECT_ROOT
+
+PALLETS=(
+  "pallet_elections_phragmen elections-phragmen"
+  "pallet_mmr merkle-mountain-range"
+  "pallet_vesting vesting"
+)
+
+cargo build --release --features runtime-benchmarks --manifest-path bin/node/cli/Cargo.toml
+
+for ENTRY in "${PALLETS[@]}"; do
+  read -r PALLET DIR <<< "$ENTRY"
+  ./target/release/substrate benchmark --chain=dev --steps=50 --repeat=20 \
+    --pallet=$PALLET --extrinsic='*' --execution=wasm --wasm-execution=compiled \
+    --heap-pages=4096 --output=./frame/$DIR/src/weights.rs \
+    --template=./.maintain/frame-weight-template.hbs
+done
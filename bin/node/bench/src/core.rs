@@ -67,6 +67,13 @@ pub struct BenchmarkOutput {
 	average: u64,
 }
 
+impl BenchmarkOutput {
+	/// The average duration of the runs in nanoseconds, without the outliers.
+	pub fn average(&self) -> u64 {
+		self.average
+	}
+}
+
 pub struct NsFormatter(pub u64);
 
 impl fmt::Display for NsFormatter {
@@ -22,6 +22,7 @@ mod construct;
 mod core;
 mod generator;
 mod import;
+mod overhead;
 mod simple_trie;
 mod state_sizes;
 mod tempdb;
@@ -74,6 +75,14 @@ struct Opt {
 	/// so that actual interval can be selected in the profiler of choice.
 	#[structopt(short, long, default_value = "regular")]
 	mode: BenchmarkMode,
+
+	/// Measure the block and extrinsic overhead instead of running the benchmarks.
+	///
+	/// The `BlockExecutionWeight` and `ExtrinsicBaseWeight` constants of this machine are written
+	/// to the given path. The extrinsic overhead is measured with a block of `--transactions`
+	/// no-op extrinsics, 500 by default.
+	#[structopt(long, value_name = "PATH")]
+	overhead: Option<std::path::PathBuf>,
 }
 
 fn main() {
@@ -83,6 +92,19 @@ fn main() {
 		sp_tracing::try_init_simple();
 	}
 
+	if let Some(path) = opt.overhead.as_ref() {
+		let overhead = overhead::measure(
+			opt.transactions.unwrap_or(500),
+			BenchDataBaseType::RocksDb,
+			opt.mode,
+		);
+		if let Err(e) = overhead.write(path) {
+			eprintln!("Failed to write the overhead weights to {}: {}", path.display(), e);
+			std::process::exit(1);
+		}
+		return
+	}
+
 	let mut import_benchmarks = Vec::new();
 
 	for profile in [Profile::Wasm, Profile::Native].iter() {
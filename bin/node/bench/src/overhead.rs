@@ -0,0 +1,123 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Block and extrinsic overhead benchmark.
+//!
+//! Measures the time to import an empty block and a block of no-op extrinsics (`System::remark`)
+//! with the wasm runtime on the current machine. The base costs derived from it are written as the
+//! `BlockExecutionWeight` and `ExtrinsicBaseWeight` constants, which complement the per-pallet
+//! weight files, as these only account for the cost of the calls themselves.
+//!
+//! A runtime uses the generated constants when building its block weights:
+//!
+//! ```ignore
+//! BlockWeights::builder()
+//! 	.base_block(overhead::BlockExecutionWeight::get())
+//! 	.for_class(DispatchClass::all(), |weights| {
+//! 		weights.base_extrinsic = overhead::ExtrinsicBaseWeight::get();
+//! 	})
+//! ```
+
+use std::{io, path::Path};
+
+use node_testing::bench::{BlockType, DatabaseType, KeyTypes, Profile};
+
+use crate::{
+	common::SizeType,
+	core::{run_benchmark, Mode, NsFormatter},
+	import::ImportBenchmarkDescription,
+};
+
+/// The base costs of the block and extrinsic execution, in nanoseconds.
+pub struct Overhead {
+	/// The time to import an empty block.
+	pub block_execution: u64,
+	/// The time to import a no-op extrinsic, on top of the block execution.
+	pub extrinsic_base: u64,
+	/// The number of extrinsics of the block the extrinsic cost was derived from.
+	pub extrinsics: usize,
+	/// The database the blocks were imported into.
+	pub database_type: DatabaseType,
+}
+
+/// Measure the overhead by importing an empty block and a block of `extrinsics` no-op
+/// extrinsics.
+pub fn measure(extrinsics: usize, database_type: DatabaseType, mode: Mode) -> Overhead {
+	assert!(extrinsics > 0, "at least one extrinsic is needed to measure its base cost");
+
+	let import = |size| {
+		run_benchmark(
+			Box::new(ImportBenchmarkDescription {
+				profile: Profile::Wasm,
+				key_types: KeyTypes::Sr25519,
+				block_type: BlockType::Noop,
+				size,
+				database_type,
+			}),
+			mode,
+		)
+	};
+
+	let empty = import(SizeType::Empty);
+	log::info!("{}", empty);
+	let full = import(SizeType::Custom(extrinsics));
+	log::info!("{}", full);
+
+	Overhead {
+		block_execution: empty.average(),
+		extrinsic_base: full.average().saturating_sub(empty.average()) / extrinsics as u64,
+		extrinsics,
+		database_type,
+	}
+}
+
+impl Overhead {
+	/// Render the overhead as a Rust file defining the weight constants.
+	pub fn render(&self) -> String {
+		format!(
+			r#"//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE NODE BENCHMARKS.
+//!
+//! The base costs of importing blocks and extrinsics into {database:?}, measured by importing an
+//! empty block and a block of {extrinsics} `System::remark` extrinsics with the wasm runtime.
+
+use frame_support::{{
+	parameter_types,
+	weights::{{constants::WEIGHT_PER_NANOS, Weight}},
+}};
+
+parameter_types! {{
+	/// Importing an empty block takes ~{block_fmt}.
+	pub const BlockExecutionWeight: Weight = {block} * WEIGHT_PER_NANOS;
+	/// Importing a no-op extrinsic takes ~{extrinsic_fmt} on top of the block execution.
+	pub const ExtrinsicBaseWeight: Weight = {extrinsic} * WEIGHT_PER_NANOS;
+}}
+"#,
+			database = self.database_type,
+			extrinsics = self.extrinsics,
+			block_fmt = NsFormatter(self.block_execution),
+			block = self.block_execution,
+			extrinsic_fmt = NsFormatter(self.extrinsic_base),
+			extrinsic = self.extrinsic_base,
+		)
+	}
+
+	/// Write the weight constants to the file at `path`.
+	pub fn write(&self, path: &Path) -> io::Result<()> {
+		std::fs::write(path, self.render())
+	}
+}
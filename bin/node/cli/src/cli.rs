@@ -43,6 +43,13 @@ pub enum Subcommand {
 	)]
 	Inspect(node_inspect::cli::InspectCmd),
 
+	/// The custom inspect-vesting subcommand for dumping vesting schedules.
+	#[structopt(
+		name = "inspect-vesting",
+		about = "Dump the vesting schedules stored at a block as JSON or CSV."
+	)]
+	InspectVesting(node_inspect::cli::InspectVestingCmd),
+
 	/// The custom benchmark subcommmand benchmarking runtime pallets.
 	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
@@ -24,13 +24,15 @@ use futures::prelude::*;
 use node_executor::Executor;
 use node_primitives::Block;
 use node_runtime::RuntimeApi;
-use sc_client_api::{ExecutorProvider, RemoteBackend};
+use pallet_mmr_gadget::MmrGadget;
+use sc_client_api::{Backend, ExecutorProvider, RemoteBackend};
 use sc_consensus_babe::{self, SlotProportion};
 use sc_network::{Event, NetworkService};
 use sc_service::{config::Configuration, error::Error as ServiceError, RpcHandlers, TaskManager};
 use sc_telemetry::{Telemetry, TelemetryWorker};
 use sp_runtime::traits::Block as BlockT;
 use std::sync::Arc;
+use substrate_frame_event_indexer::{storage_value_key, EventIndexer};
 
 type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
 type FullBackend = sc_service::TFullBackend<Block>;
@@ -158,6 +160,8 @@ pub fn new_partial(
 		let select_chain = select_chain.clone();
 		let keystore = keystore_container.sync_keystore();
 		let chain_spec = config.chain_spec.cloned_box();
+		let event_index =
+			if config.offchain_worker.indexing_enabled { backend.offchain_storage() } else { None };
 
 		let rpc_extensions_builder = move |deny_unsafe, subscription_executor| {
 			let deps = node_rpc::FullDeps {
@@ -178,6 +182,7 @@ pub fn new_partial(
 					subscription_executor,
 					finality_provider: finality_proof_provider.clone(),
 				},
+				event_index: event_index.clone(),
 			};
 
 			node_rpc::create_full(deps)
@@ -198,6 +203,18 @@ pub fn new_partial(
 	})
 }
 
+/// The topic under which `event` is indexed by the event indexer, if any.
+fn event_topic(event: &node_runtime::Event) -> Option<&'static str> {
+	use pallet_elections_phragmen::Event as ElectionsEvent;
+
+	match event {
+		node_runtime::Event::Vesting(_) => Some("vesting"),
+		node_runtime::Event::Elections(ElectionsEvent::NewTerm { .. }) |
+		node_runtime::Event::Elections(ElectionsEvent::EmptyTerm) => Some("elections"),
+		_ => None,
+	}
+}
+
 pub struct NewFullBase {
 	pub task_manager: TaskManager,
 	pub client: Arc<FullClient>,
@@ -254,6 +271,25 @@ pub fn new_full_base(
 		);
 	}
 
+	if config.offchain_worker.indexing_enabled {
+		if let Some(storage) = backend.offchain_storage() {
+			let indexer = EventIndexer::<Block, FullClient, FullBackend, _, _>::new(
+				client.clone(),
+				storage,
+				event_topic,
+			)
+			.with_storage_value("mmr", storage_value_key("Mmr", "RootHash"));
+			task_manager.spawn_handle().spawn("event-indexer", indexer.run());
+		}
+		if let Some(storage) = backend.offchain_storage() {
+			let prefix = <node_runtime::Runtime as pallet_mmr::Config>::INDEXING_PREFIX;
+			let gadget =
+				MmrGadget::<Block, FullClient, FullBackend, _>::new(client.clone(), storage)
+					.with_instance("Mmr", prefix);
+			task_manager.spawn_handle().spawn("mmr-gadget", gadget.run());
+		}
+	}
+
 	let role = config.role.clone();
 	let force_authoring = config.force_authoring;
 	let backoff_authoring_blocks =
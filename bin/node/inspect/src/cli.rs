@@ -18,7 +18,7 @@
 
 //! Structs to easily compose inspect sub-command for CLI.
 
-use sc_cli::{ImportParams, SharedParams};
+use sc_cli::{BlockNumberOrHash, ImportParams, PruningParams, SharedParams};
 use std::fmt::Debug;
 use structopt::StructOpt;
 
@@ -62,3 +62,27 @@ pub enum InspectSubCmd {
 		input: String,
 	},
 }
+
+/// The `inspect-vesting` command used to dump the vesting schedules stored at a block.
+#[derive(Debug, StructOpt)]
+pub struct InspectVestingCmd {
+	/// Block hash or number. Defaults to the best block.
+	#[structopt(value_name = "HASH or NUMBER")]
+	pub input: Option<BlockNumberOrHash>,
+
+	/// The name of the vesting pallet in the runtime.
+	#[structopt(long, default_value = "Vesting")]
+	pub pallet: String,
+
+	/// Print the schedules as CSV instead of JSON.
+	#[structopt(long)]
+	pub csv: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+}
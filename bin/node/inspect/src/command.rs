@@ -19,13 +19,21 @@
 //! Command ran by the CLI
 
 use crate::{
-	cli::{InspectCmd, InspectSubCmd},
+	cli::{InspectCmd, InspectSubCmd, InspectVestingCmd},
+	vesting::{vesting_schedules, VestingEntry},
 	Inspector,
 };
-use sc_cli::{CliConfiguration, ImportParams, Result, SharedParams};
+use codec::Decode;
+use sc_cli::{CliConfiguration, ImportParams, PruningParams, Result, SharedParams};
+use sc_client_api::{backend::Backend, StorageProvider};
 use sc_service::{new_full_client, Configuration, NativeExecutionDispatch};
-use sp_runtime::traits::Block;
-use std::str::FromStr;
+use serde::Serialize;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{AtLeast32BitUnsigned, Block, NumberFor},
+};
+use std::{fmt::Display, str::FromStr, sync::Arc};
 
 impl InspectCmd {
 	/// Run the inspect command, passing the inspector.
@@ -65,3 +73,49 @@ impl CliConfiguration for InspectCmd {
 		Some(&self.import_params)
 	}
 }
+
+impl InspectVestingCmd {
+	/// Run the inspect-vesting command, printing the schedules to stdout.
+	pub fn run<B, BA, C, AccountId, Balance>(&self, client: Arc<C>) -> Result<()>
+	where
+		B: Block,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: std::fmt::Debug,
+		NumberFor<B>: FromStr + Display,
+		<NumberFor<B> as FromStr>::Err: std::fmt::Debug,
+		BA: Backend<B>,
+		C: StorageProvider<B, BA> + HeaderBackend<B>,
+		AccountId: Decode + Serialize + Display,
+		Balance: Decode + Serialize + Display + AtLeast32BitUnsigned + Copy,
+	{
+		let block_id = match &self.input {
+			Some(input) => input.parse()?,
+			None => BlockId::Hash(client.info().best_hash),
+		};
+		let schedules =
+			vesting_schedules::<B, BA, C, AccountId, Balance>(&*client, &block_id, &self.pallet)
+				.map_err(|e| format!("{}", e))?;
+
+		if self.csv {
+			println!("{}", VestingEntry::<AccountId, Balance, NumberFor<B>>::CSV_HEADER);
+			for entry in schedules {
+				println!("{}", entry.to_csv());
+			}
+		} else {
+			let json = serde_json::to_string_pretty(&schedules)
+				.map_err(|e| format!("Failed to serialize vesting schedules: {}", e))?;
+			println!("{}", json);
+		}
+		Ok(())
+	}
+}
+
+impl CliConfiguration for InspectVestingCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+}
@@ -21,11 +21,14 @@
 //!
 //! The blocks and extrinsics can either be retrieved from the database (on-chain),
 //! or a raw SCALE-encoding can be provided.
+//!
+//! The vesting schedules of `pallet-vesting` can be dumped from the state of a block as well.
 
 #![warn(missing_docs)]
 
 pub mod cli;
 pub mod command;
+pub mod vesting;
 
 use codec::{Decode, Encode};
 use sc_client_api::BlockBackend;
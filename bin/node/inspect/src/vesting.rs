@@ -0,0 +1,160 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Reading the vesting schedules of `pallet-vesting` from the state of a block.
+
+use crate::Error;
+use codec::Decode;
+use pallet_vesting::VestingInfo;
+use sc_client_api::{backend::Backend, StorageProvider};
+use serde::Serialize;
+use sp_core::{hashing::twox_128, storage::StorageKey};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{AtLeast32BitUnsigned, Block, NumberFor},
+};
+use std::fmt;
+
+/// The length of the `Blake2_128Concat` hash prefixing the account in the storage keys.
+const BLAKE2_128_LEN: usize = 16;
+
+/// The vesting schedule of an account.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VestingEntry<AccountId, Balance, BlockNumber> {
+	/// The vesting account.
+	pub account: AccountId,
+	/// The amount locked by the schedule at its start.
+	pub locked: Balance,
+	/// The amount unlocked every block after the start.
+	pub per_block: Balance,
+	/// The block at which the schedule starts unlocking funds.
+	pub starting_block: BlockNumber,
+	/// The block before which the schedule does not unlock any funds, if any.
+	pub cliff_block: Option<BlockNumber>,
+	/// The amount unlocked along with the last `per_block`.
+	pub remainder: Balance,
+	/// The block at which the schedule is fully vested, `None` if it never is or if it does not
+	/// fit in a block number.
+	pub ending_block: Option<BlockNumber>,
+}
+
+impl<AccountId, Balance, BlockNumber> VestingEntry<AccountId, Balance, BlockNumber>
+where
+	Balance: AtLeast32BitUnsigned + Copy,
+	BlockNumber: AtLeast32BitUnsigned + Copy,
+{
+	fn new(account: AccountId, schedule: VestingInfo<Balance, BlockNumber>) -> Self {
+		let ending_block = schedule.ending_block();
+		let VestingInfo { locked, per_block, starting_block, cliff_block, remainder } = schedule;
+		Self { account, locked, per_block, starting_block, cliff_block, remainder, ending_block }
+	}
+}
+
+impl<AccountId, Balance, BlockNumber> VestingEntry<AccountId, Balance, BlockNumber>
+where
+	AccountId: fmt::Display,
+	Balance: fmt::Display,
+	BlockNumber: fmt::Display,
+{
+	/// The header of the CSV output.
+	pub const CSV_HEADER: &'static str =
+		"account,locked,per_block,starting_block,cliff_block,remainder,ending_block";
+
+	/// Format the entry as a CSV record.
+	pub fn to_csv(&self) -> String {
+		format!(
+			"{},{},{},{},{},{},{}",
+			self.account,
+			self.locked,
+			self.per_block,
+			self.starting_block,
+			self.cliff_block.as_ref().map(ToString::to_string).unwrap_or_default(),
+			self.remainder,
+			self.ending_block.as_ref().map(ToString::to_string).unwrap_or_default(),
+		)
+	}
+}
+
+/// Read the vesting schedules of all accounts from the state of `block_id`.
+///
+/// `pallet_name` is the name of the vesting pallet in the runtime.
+pub fn vesting_schedules<B, BA, C, AccountId, Balance>(
+	client: &C,
+	block_id: &BlockId<B>,
+	pallet_name: &str,
+) -> Result<Vec<VestingEntry<AccountId, Balance, NumberFor<B>>>, Error>
+where
+	B: Block,
+	BA: Backend<B>,
+	C: StorageProvider<B, BA>,
+	AccountId: Decode,
+	Balance: Decode + AtLeast32BitUnsigned + Copy,
+{
+	let prefix = [twox_128(pallet_name.as_bytes()), twox_128(b"Vesting")].concat();
+	client
+		.storage_pairs(block_id, &StorageKey(prefix.clone()))?
+		.into_iter()
+		.map(|(key, value)| {
+			let mut raw_account = key.0.get(prefix.len() + BLAKE2_128_LEN..).unwrap_or_default();
+			let account = AccountId::decode(&mut raw_account)?;
+			let schedule = VestingInfo::decode(&mut &value.0[..])?;
+			Ok(VestingEntry::new(account, schedule))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn entry(locked: u128, per_block: u128, starting_block: u32) -> VestingEntry<u64, u128, u32> {
+		entry_with_cliff(locked, per_block, starting_block, None)
+	}
+
+	fn entry_with_cliff(
+		locked: u128,
+		per_block: u128,
+		starting_block: u32,
+		cliff_block: Option<u32>,
+	) -> VestingEntry<u64, u128, u32> {
+		VestingEntry::new(
+			1,
+			VestingInfo { locked, per_block, starting_block, cliff_block, remainder: 0 },
+		)
+	}
+
+	#[test]
+	fn should_compute_ending_block() {
+		assert_eq!(entry(100, 10, 5).ending_block, Some(15));
+		assert_eq!(entry(101, 10, 5).ending_block, Some(16));
+		assert_eq!(entry(0, 10, 5).ending_block, Some(5));
+		assert_eq!(entry(100, 0, 5).ending_block, None);
+		assert_eq!(entry(u128::MAX, 1, 5).ending_block, None);
+		assert_eq!(entry_with_cliff(100, 10, 5, Some(10)).ending_block, Some(15));
+		assert_eq!(entry_with_cliff(100, 10, 5, Some(20)).ending_block, Some(20));
+		assert_eq!(entry_with_cliff(100, 0, 5, Some(20)).ending_block, None);
+	}
+
+	#[test]
+	fn should_format_csv() {
+		assert_eq!(entry(101, 10, 5).to_csv(), "1,101,10,5,,0,16");
+		assert_eq!(entry(100, 0, 5).to_csv(), "1,100,0,5,,0,");
+		assert_eq!(entry_with_cliff(101, 10, 5, Some(20)).to_csv(), "1,101,10,5,20,0,20");
+	}
+}
@@ -26,8 +26,9 @@ use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		AllowAll, Currency, DenyAll, Imbalance, InstanceFilter, KeyOwnerProofSystem,
-		LockIdentifier, OnUnbalanced, U128CurrencyToVote,
+		AllowAll, Currency, DenyAll, Imbalance, InspectBalanceRestrictions, InstanceFilter,
+		KeyOwnerProofSystem, LockIdentifier, OnUnbalanced, U128CurrencyToVote, VestingSchedule,
+		WithdrawReasons,
 	},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -49,7 +50,7 @@ use pallet_grandpa::{
 use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
 use pallet_session::historical as pallet_session_historical;
 pub use pallet_transaction_payment::{CurrencyAdapter, Multiplier, TargetedFeeAdjustment};
-use pallet_transaction_payment::{FeeDetails, RuntimeDispatchInfo};
+use pallet_transaction_payment::{DryRunInfo, FeeDetails, RuntimeDispatchInfo};
 use sp_api::impl_runtime_apis;
 use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use sp_core::{
@@ -117,7 +118,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 267,
+	spec_version: 268,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 2,
@@ -278,6 +279,7 @@ impl InstanceFilter<Call> for ProxyType {
 				Call::Balances(..) |
 					Call::Assets(..) | Call::Uniques(..) |
 					Call::Vesting(pallet_vesting::Call::vested_transfer(..)) |
+					Call::Vesting(pallet_vesting::Call::vested_transfer_keep_alive(..)) |
 					Call::Indices(pallet_indices::Call::transfer(..))
 			),
 			ProxyType::Governance => matches!(
@@ -692,6 +694,17 @@ parameter_types! {
 	pub const TermDuration: BlockNumber = 7 * DAYS;
 	pub const DesiredMembers: u32 = 13;
 	pub const DesiredRunnersUp: u32 = 7;
+	pub const MaxCandidates: u32 = 1000;
+	pub const MaxCandidateMetadata: u32 = 256;
+	pub const MaxVoters: u32 = 10 * 1000;
+	pub const MaxVotesPerVoter: u32 = 16;
+	// the voters are snapshotted over at most 10 blocks.
+	pub const VoterSnapshotPerBlock: u32 = 1000;
+	pub const ElectionsSignedPhase: BlockNumber = 10 * MINUTES;
+	pub const SolutionDeposit: Balance = 10 * DOLLARS;
+	pub const DefunctVoterReward: Perbill = Perbill::from_percent(10);
+	pub const ElectionsFallback: pallet_elections_phragmen::FallbackStrategy =
+		pallet_elections_phragmen::FallbackStrategy::Emergency;
 	pub const ElectionsPhragmenPalletId: LockIdentifier = *b"phrelect";
 }
 
@@ -703,6 +716,7 @@ impl pallet_elections_phragmen::Config for Runtime {
 	type PalletId = ElectionsPhragmenPalletId;
 	type Currency = Balances;
 	type ChangeMembers = Council;
+	type RankedMembers = ();
 	// NOTE: this implies that council's genesis members cannot be set directly and must come from
 	// this module.
 	type InitializeMembers = Council;
@@ -712,9 +726,24 @@ impl pallet_elections_phragmen::Config for Runtime {
 	type VotingBondFactor = VotingBondFactor;
 	type LoserCandidate = ();
 	type KickedMember = ();
+	type BadSolution = Treasury;
+	type DefunctVoterReward = DefunctVoterReward;
+	type Fallback = ElectionsFallback;
+	type MaxElectionWeight = ();
+	type ElectWinners = ();
+	type Succession = ();
 	type DesiredMembers = DesiredMembers;
 	type DesiredRunnersUp = DesiredRunnersUp;
+	type MaxCandidates = MaxCandidates;
+	type MaxCandidateMetadata = MaxCandidateMetadata;
+	type MaxVoters = MaxVoters;
+	type MaxVotesPerVoter = MaxVotesPerVoter;
+	type VoterSnapshotPerBlock = VoterSnapshotPerBlock;
+	type SignedPhase = ElectionsSignedPhase;
+	type SolutionDeposit = SolutionDeposit;
 	type TermDuration = TermDuration;
+	type TermOrigin = EnsureRoot<AccountId>;
+	type BlockNumberProvider = System;
 	type WeightInfo = pallet_elections_phragmen::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1055,6 +1084,10 @@ impl pallet_society::Config for Runtime {
 
 parameter_types! {
 	pub const MinVestedTransfer: Balance = 100 * DOLLARS;
+	pub const MaxAutoVestsPerBlock: u32 = 16;
+	pub UnvestedFundsAllowedWithdrawReasons: WithdrawReasons =
+		WithdrawReasons::except(WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE);
+	pub const VestOtherReward: Perbill = Perbill::from_perthousand(1);
 }
 
 impl pallet_vesting::Config for Runtime {
@@ -1062,16 +1095,29 @@ impl pallet_vesting::Config for Runtime {
 	type Currency = Balances;
 	type BlockNumberToBalance = ConvertInto;
 	type MinVestedTransfer = MinVestedTransfer;
+	type BlockNumberProvider = System;
+	type ForceOrigin = EnsureRootOrHalfCouncil;
+	type PauseOrigin = EnsureRootOrHalfCouncil;
+	type VestingHooks = ();
+	type MaxAutoVestsPerBlock = MaxAutoVestsPerBlock;
+	type UnvestedFundsAllowedWithdrawReasons = UnvestedFundsAllowedWithdrawReasons;
+	type VestOtherReward = VestOtherReward;
 	type WeightInfo = pallet_vesting::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const MmrPruningDepth: u64 = DAYS as u64;
+}
+
 impl pallet_mmr::Config for Runtime {
 	const INDEXING_PREFIX: &'static [u8] = b"mmr";
 	type Hashing = <Runtime as frame_system::Config>::Hashing;
 	type Hash = <Runtime as frame_system::Config>::Hash;
 	type LeafData = frame_system::Pallet<Self>;
 	type OnNewRoot = ();
-	type WeightInfo = ();
+	type PruningDepth = MmrPruningDepth;
+	type LeafSource = frame_system::Pallet<Self>;
+	type WeightInfo = pallet_mmr::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -1264,9 +1310,22 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPallets,
-	(),
+	Migrations,
 >;
 
+/// The storage migrations run on runtime upgrade, in order.
+///
+/// Each migration only runs from the storage version it migrates, so the chained migrations of a
+/// pallet upgrade it from whichever version it is at.
+pub type Migrations = (
+	pallet_vesting::migrations::v1::MigrateV0ToV1<Runtime>,
+	pallet_vesting::migrations::v2::MigrateV1ToV2<Runtime>,
+	pallet_vesting::migrations::v3::MigrateV2ToV3<Runtime>,
+	pallet_mmr::migrations::v1::MigrateV0ToV1<Runtime>,
+	pallet_elections_phragmen::migrations::v5::MigrateV4ToV5<Runtime>,
+	pallet_elections_phragmen::migrations::v6::MigrateV5ToV6<Runtime>,
+);
+
 /// MMR helper types.
 mod mmr {
 	use super::Runtime;
@@ -1433,6 +1492,14 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl frame_system_rpc_runtime_api::StorageVersionApi<Block> for Runtime {
+		fn storage_versions() -> Vec<frame_system_rpc_runtime_api::PalletStorageVersion> {
+			use frame_support::traits::GetStorageVersions;
+
+			AllPalletsWithSystem::storage_versions()
+		}
+	}
+
 	impl pallet_contracts_rpc_runtime_api::ContractsApi<
 		Block, AccountId, Balance, BlockNumber, Hash,
 	>
@@ -1486,6 +1553,62 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_transaction_payment_rpc_runtime_api::TransactionDryRunApi<
+		Block,
+		Balance,
+	> for Runtime {
+		fn dry_run(uxt: <Block as BlockT>::Extrinsic, len: u32) -> DryRunInfo<Balance> {
+			TransactionPayment::query_dry_run(uxt, len, Executive::apply_extrinsic)
+		}
+	}
+
+	impl pallet_balances_runtime_api::BalanceRestrictionsApi<
+		Block,
+		AccountId,
+		Balance,
+		[u8; 8],
+	> for Runtime {
+		fn balance_restrictions(
+			who: AccountId,
+		) -> Vec<pallet_balances_runtime_api::BalanceRestriction<Balance, [u8; 8]>> {
+			Balances::balance_restrictions(&who)
+		}
+	}
+
+	impl pallet_vesting_runtime_api::VestingApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn vesting_balance(who: AccountId) -> Option<Balance> {
+			<Vesting as VestingSchedule<AccountId>>::vesting_balance(&who)
+		}
+
+		fn vesting_schedules(
+			who: AccountId,
+		) -> Vec<pallet_vesting_runtime_api::VestingInfo<Balance, BlockNumber>> {
+			Vesting::vesting(&who).into_iter().collect()
+		}
+
+		fn vested_at(who: AccountId, at: BlockNumber) -> Option<Balance> {
+			Vesting::vested_at(&who, at)
+		}
+
+		fn vesting_summary(
+			who: AccountId,
+		) -> Option<pallet_vesting_runtime_api::VestingSummary<Balance, BlockNumber>> {
+			Vesting::account_vesting_summary(&who)
+		}
+	}
+
+	impl pallet_elections_phragmen_runtime_api::ElectionsPhragmenApi<
+		Block,
+		AccountId,
+		Balance,
+	> for Runtime {
+		fn predict_result()
+			-> pallet_elections_phragmen_runtime_api::ElectionPrediction<AccountId, Balance>
+		{
+			Elections::predict_result()
+		}
+	}
+
 	impl pallet_mmr::primitives::MmrApi<
 		Block,
 		mmr::Hash,
@@ -1515,6 +1638,68 @@ impl_runtime_apis! {
 			let node = mmr::DataOrHash::Data(leaf.into_opaque_leaf());
 			pallet_mmr::verify_leaf_proof::<mmr::Hashing, _>(root, node, proof)
 		}
+
+		fn generate_historical_proof(leaf_index: u64, leaves_count: u64)
+			-> Result<(mmr::EncodableOpaqueLeaf, mmr::Proof<mmr::Hash>), mmr::Error>
+		{
+			Mmr::generate_historical_proof(leaf_index, leaves_count)
+				.map(|(leaf, proof)| (mmr::EncodableOpaqueLeaf::from_leaf(&leaf), proof))
+		}
+
+		fn generate_ancestry_proof(prev_leaf_count: u64)
+			-> Result<mmr::AncestryProof<mmr::Hash>, mmr::Error>
+		{
+			Mmr::generate_ancestry_proof(prev_leaf_count)
+		}
+
+		fn verify_ancestry_proof_stateless(
+			root: mmr::Hash,
+			prev_root: mmr::Hash,
+			proof: mmr::AncestryProof<mmr::Hash>
+		) -> Result<(), mmr::Error> {
+			pallet_mmr::verify_ancestry_proof::<mmr::Hashing>(root, prev_root, proof)
+		}
+
+		fn generate_batch_proof(leaf_indices: Vec<u64>)
+			-> Result<(Vec<mmr::EncodableOpaqueLeaf>, mmr::BatchProof<mmr::Hash>), mmr::Error>
+		{
+			Mmr::generate_batch_proof(leaf_indices).map(|(leaves, proof)| {
+				let leaves = leaves.iter().map(mmr::EncodableOpaqueLeaf::from_leaf).collect();
+				(leaves, proof)
+			})
+		}
+
+		fn generate_proof_range(first_leaf: u64, last_leaf: u64)
+			-> Result<(Vec<mmr::EncodableOpaqueLeaf>, mmr::BatchProof<mmr::Hash>), mmr::Error>
+		{
+			Mmr::generate_proof_range(first_leaf, last_leaf).map(|(leaves, proof)| {
+				let leaves = leaves.iter().map(mmr::EncodableOpaqueLeaf::from_leaf).collect();
+				(leaves, proof)
+			})
+		}
+
+		fn verify_batch_proof(
+			leaves: Vec<mmr::EncodableOpaqueLeaf>,
+			proof: mmr::BatchProof<mmr::Hash>
+		) -> Result<(), mmr::Error> {
+			let leaves = leaves
+				.into_iter()
+				.map(|leaf| leaf.into_opaque_leaf().try_decode().ok_or(mmr::Error::Verify))
+				.collect::<Result<Vec<mmr::Leaf>, _>>()?;
+			Mmr::verify_leaves(leaves, proof)
+		}
+
+		fn verify_batch_proof_stateless(
+			root: mmr::Hash,
+			leaves: Vec<mmr::EncodableOpaqueLeaf>,
+			proof: mmr::BatchProof<mmr::Hash>
+		) -> Result<(), mmr::Error> {
+			let nodes = leaves
+				.into_iter()
+				.map(|leaf| mmr::DataOrHash::Data(leaf.into_opaque_leaf()))
+				.collect();
+			pallet_mmr::verify_leaves_proof::<mmr::Hashing, _>(root, nodes, proof)
+		}
 	}
 
 	impl sp_session::SessionKeys<Block> for Runtime {
@@ -1679,4 +1864,35 @@ mod tests {
 
 		is_submit_signed_transaction::<Runtime>();
 	}
+
+	#[cfg(feature = "try-runtime")]
+	#[test]
+	fn migrations_upgrade_from_the_previous_storage_versions() {
+		use frame_support::{
+			storage::unhashed,
+			traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		};
+
+		sp_io::TestExternalities::default().execute_with(|| {
+			StorageVersion::new(0).put::<Vesting>();
+			StorageVersion::new(0).put::<Mmr>();
+			StorageVersion::new(4).put::<Elections>();
+			// A vesting schedule as stored in version 0, without a cliff.
+			let who = AccountId::from([1u8; 32]);
+			unhashed::put(
+				&pallet_vesting::Vesting::<Runtime>::hashed_key_for(&who),
+				&(1_000 * DOLLARS, DOLLARS, 10 as BlockNumber),
+			);
+
+			assert_eq!(Migrations::pre_upgrade(), Ok(()));
+			Migrations::on_runtime_upgrade();
+			assert_eq!(Migrations::post_upgrade(), Ok(()));
+
+			assert_eq!(Vesting::on_chain_storage_version(), Vesting::current_storage_version());
+			assert_eq!(Mmr::on_chain_storage_version(), Mmr::current_storage_version());
+			assert_eq!(Elections::on_chain_storage_version(), Elections::current_storage_version());
+			assert_eq!(Vesting::vesting(&who).map(|schedule| schedule.cliff_block), Some(None));
+			assert_eq!(Vesting::vesting_accounts_count(), 1);
+		});
+	}
 }
@@ -27,7 +27,7 @@ use crate::{
 };
 use regex::Regex;
 use sc_service::{
-	config::{BasePath, PrometheusConfig, TransactionPoolOptions},
+	config::{BasePath, PrometheusConfig, RpcMethodFilter, TransactionPoolOptions},
 	ChainSpec, Role,
 };
 use sc_telemetry::TelemetryEndpoints;
@@ -85,6 +85,19 @@ pub struct RunCmd {
 	)]
 	pub rpc_methods: RpcMethods,
 
+	/// Only allow the given RPC methods to be called.
+	///
+	/// A comma-separated list of method names, e.g. `mmr_generateProof,system_health`. By default
+	/// all methods exposed by `--rpc-methods` may be called.
+	#[structopt(long = "rpc-allow-methods", value_name = "METHODS", use_delimiter = true)]
+	pub rpc_allow_methods: Option<Vec<String>>,
+
+	/// Deny calls to the given RPC methods.
+	///
+	/// A comma-separated list of method names. Takes precedence over `--rpc-allow-methods`.
+	#[structopt(long = "rpc-deny-methods", value_name = "METHODS", use_delimiter = true)]
+	pub rpc_deny_methods: Vec<String>,
+
 	/// Listen to all Websocket interfaces.
 	///
 	/// Default is local. Note: not all RPC methods are safe to be exposed publicly. Use an RPC
@@ -436,6 +449,10 @@ impl CliConfiguration for RunCmd {
 		Ok(self.rpc_methods.into())
 	}
 
+	fn rpc_method_filter(&self) -> Result<RpcMethodFilter> {
+		Ok(RpcMethodFilter::new(self.rpc_allow_methods.clone(), self.rpc_deny_methods.clone()))
+	}
+
 	fn rpc_max_payload(&self) -> Result<Option<usize>> {
 		Ok(self.rpc_max_payload)
 	}
@@ -29,8 +29,8 @@ use sc_service::{
 	config::{
 		BasePath, Configuration, DatabaseConfig, ExtTransport, KeystoreConfig,
 		NetworkConfiguration, NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode,
-		Role, RpcMethods, TaskExecutor, TelemetryEndpoints, TransactionPoolOptions,
-		WasmExecutionMethod,
+		Role, RpcMethodFilter, RpcMethods, TaskExecutor, TelemetryEndpoints,
+		TransactionPoolOptions, WasmExecutionMethod,
 	},
 	ChainSpec, KeepBlocks, TracingReceiver, TransactionStorageMode,
 };
@@ -334,6 +334,13 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(Default::default())
 	}
 
+	/// Returns the filter of the RPC methods which may be called.
+	///
+	/// By default all methods may be called.
+	fn rpc_method_filter(&self) -> Result<RpcMethodFilter> {
+		Ok(Default::default())
+	}
+
 	/// Get the RPC websockets maximum connections (`None` if unlimited).
 	///
 	/// By default this is `None`.
@@ -517,6 +524,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			rpc_ws: self.rpc_ws(DCV::rpc_ws_listen_port())?,
 			rpc_ipc: self.rpc_ipc()?,
 			rpc_methods: self.rpc_methods()?,
+			rpc_method_filter: self.rpc_method_filter()?,
 			rpc_ws_max_connections: self.rpc_ws_max_connections()?,
 			rpc_http_threads: self.rpc_http_threads()?,
 			rpc_cors: self.rpc_cors(is_dev)?,
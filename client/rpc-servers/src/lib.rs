@@ -42,19 +42,24 @@ const HTTP_THREADS: usize = 4;
 pub type RpcHandler<T> = pubsub::PubSubHandler<T, RpcMiddleware>;
 
 pub use self::inner::*;
-pub use middleware::{RpcMetrics, RpcMiddleware};
+pub use middleware::{RpcMethodFilter, RpcMetrics, RpcMiddleware};
 
 /// Construct rpc `IoHandler`
 pub fn rpc_handler<M: PubSubMetadata>(
 	extension: impl IoHandlerExtension<M>,
 	rpc_middleware: RpcMiddleware,
 ) -> RpcHandler<M> {
+	let method_filter = rpc_middleware.method_filter().clone();
 	let io_handler = MetaIoHandler::with_middleware(rpc_middleware);
 	let mut io = pubsub::PubSubHandler::new(io_handler);
 	extension.augment(&mut io);
 
 	// add an endpoint to list all available methods.
-	let mut methods = io.iter().map(|x| x.0.clone()).collect::<Vec<String>>();
+	let mut methods = io
+		.iter()
+		.map(|x| x.0.clone())
+		.filter(|method| method_filter.is_allowed(method))
+		.collect::<Vec<String>>();
 	io.add_method("rpc_methods", {
 		methods.sort();
 		let methods = serde_json::to_value(&methods)
@@ -17,18 +17,34 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 //! Middleware for RPC requests.
+//!
+//! Records Prometheus metrics about the calls made over a transport and rejects calls to the
+//! methods disallowed by a [`RpcMethodFilter`].
+
+use std::collections::HashSet;
 
 use jsonrpc_core::{
-	FutureOutput, FutureResponse, Metadata, Middleware as RequestMiddleware, Request, Response,
+	Call, Error, ErrorCode, FutureOutput, FutureResponse, Metadata,
+	Middleware as RequestMiddleware, Output, Request, Response,
+};
+use prometheus_endpoint::{
+	exponential_buckets, register, CounterVec, HistogramOpts, HistogramVec, Opts, PrometheusError,
+	Registry, U64,
 };
-use prometheus_endpoint::{register, CounterVec, Opts, PrometheusError, Registry, U64};
+use wasm_timer::Instant;
 
-use futures::{future::Either, Future};
+use futures::{future, future::Either, Future};
+
+/// The method label used for calls to methods which don't exist, to bound the cardinality of the
+/// metrics.
+const UNKNOWN_METHOD_LABEL: &str = "<unknown>";
 
 /// Metrics for RPC middleware
 #[derive(Debug, Clone)]
 pub struct RpcMetrics {
 	rpc_calls: Option<CounterVec<U64>>,
+	calls_time: Option<HistogramVec>,
+	calls_finished: Option<CounterVec<U64>>,
 }
 
 impl RpcMetrics {
@@ -46,13 +62,100 @@ impl RpcMetrics {
 					)
 				})
 				.transpose()?,
+			calls_time: metrics_registry
+				.map(|r| {
+					register(
+						HistogramVec::new(
+							HistogramOpts::new(
+								"rpc_calls_time",
+								"Total time [s] to process an RPC method call",
+							)
+							.buckets(exponential_buckets(0.001, 4.0, 9)?),
+							&["protocol", "method"],
+						)?,
+						r,
+					)
+				})
+				.transpose()?,
+			calls_finished: metrics_registry
+				.map(|r| {
+					register(
+						CounterVec::new(
+							Opts::new("rpc_calls_finished", "Number of processed RPC method calls"),
+							&["protocol", "method", "is_error"],
+						)?,
+						r,
+					)
+				})
+				.transpose()?,
 		})
 	}
+
+	fn is_enabled(&self) -> bool {
+		self.calls_time.is_some() || self.calls_finished.is_some()
+	}
+
+	fn on_call_finished(
+		&self,
+		transport_label: &str,
+		method: &str,
+		output: Option<&Output>,
+		started: Instant,
+	) {
+		let (method, is_error) = match output {
+			Some(Output::Failure(failure)) if failure.error.code == ErrorCode::MethodNotFound =>
+				(UNKNOWN_METHOD_LABEL, true),
+			Some(Output::Failure(_)) => (method, true),
+			_ => (method, false),
+		};
+
+		if let Some(ref calls_time) = self.calls_time {
+			calls_time
+				.with_label_values(&[transport_label, method])
+				.observe(started.elapsed().as_secs_f64());
+		}
+		if let Some(ref calls_finished) = self.calls_finished {
+			calls_finished
+				.with_label_values(&[
+					transport_label,
+					method,
+					if is_error { "true" } else { "false" },
+				])
+				.inc();
+		}
+	}
+}
+
+/// The RPC methods which may be called.
+///
+/// A method may be called if it is in the allow-list, when there is one, and is not in the
+/// deny-list. By default, all methods may be called.
+#[derive(Debug, Clone, Default)]
+pub struct RpcMethodFilter {
+	allowed: Option<HashSet<String>>,
+	denied: HashSet<String>,
+}
+
+impl RpcMethodFilter {
+	/// Create a filter from an optional allow-list and a deny-list of method names.
+	pub fn new(allowed: Option<Vec<String>>, denied: Vec<String>) -> Self {
+		Self {
+			allowed: allowed.map(|allowed| allowed.into_iter().collect()),
+			denied: denied.into_iter().collect(),
+		}
+	}
+
+	/// Returns whether `method` may be called.
+	pub fn is_allowed(&self, method: &str) -> bool {
+		!self.denied.contains(method) &&
+			self.allowed.as_ref().map_or(true, |allowed| allowed.contains(method))
+	}
 }
 
 /// Middleware for RPC calls
 pub struct RpcMiddleware {
 	metrics: RpcMetrics,
+	method_filter: RpcMethodFilter,
 	transport_label: String,
 }
 
@@ -62,7 +165,24 @@ impl RpcMiddleware {
 	/// - `metrics`: Will be used to report statistics.
 	/// - `transport_label`: The label that is used when reporting the statistics.
 	pub fn new(metrics: RpcMetrics, transport_label: &str) -> Self {
-		RpcMiddleware { metrics, transport_label: String::from(transport_label) }
+		RpcMiddleware {
+			metrics,
+			method_filter: Default::default(),
+			transport_label: String::from(transport_label),
+		}
+	}
+
+	/// Only allow calls to the methods allowed by `method_filter`.
+	///
+	/// Calls to other methods are answered with a "method not found" error.
+	pub fn with_method_filter(mut self, method_filter: RpcMethodFilter) -> Self {
+		self.method_filter = method_filter;
+		self
+	}
+
+	/// The filter of the methods which may be called through this middleware.
+	pub fn method_filter(&self) -> &RpcMethodFilter {
+		&self.method_filter
 	}
 }
 
@@ -81,4 +201,96 @@ impl<M: Metadata> RequestMiddleware<M> for RpcMiddleware {
 
 		Either::B(next(request, meta))
 	}
+
+	fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<FutureOutput, X>
+	where
+		F: Fn(Call, M) -> X + Send + Sync,
+		X: Future<Item = Option<Output>, Error = ()> + Send + 'static,
+	{
+		let method = match call {
+			Call::MethodCall(ref call) => &call.method,
+			Call::Notification(ref notification) => &notification.method,
+			Call::Invalid { .. } => return Either::B(next(call, meta)),
+		};
+
+		if !self.method_filter.is_allowed(method) {
+			log::debug!(target: "rpc", "Rejected call to disallowed method {}", method);
+			// Notifications are not answered.
+			let output = match call {
+				Call::MethodCall(call) => Some(Output::from(
+					Err(Error {
+						code: ErrorCode::MethodNotFound,
+						message: "Method not allowed".into(),
+						data: None,
+					}),
+					call.id,
+					call.jsonrpc,
+				)),
+				_ => None,
+			};
+			return Either::A(Box::new(future::ok(output)))
+		}
+
+		if !self.metrics.is_enabled() {
+			return Either::B(next(call, meta))
+		}
+
+		let method = method.clone();
+		let metrics = self.metrics.clone();
+		let transport_label = self.transport_label.clone();
+		let started = Instant::now();
+		Either::A(Box::new(next(call, meta).map(move |output| {
+			metrics.on_call_finished(&transport_label, &method, output.as_ref(), started);
+			output
+		})))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use jsonrpc_core::{MetaIoHandler, Params, Value};
+
+	fn io(method_filter: RpcMethodFilter) -> MetaIoHandler<(), RpcMiddleware> {
+		let metrics = RpcMetrics::new(Some(&Registry::new())).unwrap();
+		let middleware = RpcMiddleware::new(metrics, "test").with_method_filter(method_filter);
+		let mut io = MetaIoHandler::with_middleware(middleware);
+		io.add_method("mmr_generateProof", |_: Params| Ok(Value::Bool(true)));
+		io.add_method("author_rotateKeys", |_: Params| Ok(Value::Bool(true)));
+		io
+	}
+
+	fn call(io: &MetaIoHandler<(), RpcMiddleware>, method: &str) -> String {
+		let request = format!(r#"{{"jsonrpc":"2.0","method":"{}","params":[],"id":1}}"#, method);
+		io.handle_request_sync(&request, ()).unwrap()
+	}
+
+	const ALLOWED: &str = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	const NOT_ALLOWED: &str =
+		r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"Method not allowed"},"id":1}"#;
+
+	#[test]
+	fn all_methods_are_allowed_by_default() {
+		let io = io(Default::default());
+		assert_eq!(call(&io, "mmr_generateProof"), ALLOWED);
+		assert_eq!(call(&io, "author_rotateKeys"), ALLOWED);
+	}
+
+	#[test]
+	fn method_filter_restricts_calls() {
+		let io = io(RpcMethodFilter::new(None, vec!["author_rotateKeys".into()]));
+		assert_eq!(call(&io, "mmr_generateProof"), ALLOWED);
+		assert_eq!(call(&io, "author_rotateKeys"), NOT_ALLOWED);
+
+		let io = io(RpcMethodFilter::new(
+			Some(vec!["mmr_generateProof".into(), "author_rotateKeys".into()]),
+			vec!["author_rotateKeys".into()],
+		));
+		assert_eq!(call(&io, "mmr_generateProof"), ALLOWED);
+		assert_eq!(call(&io, "author_rotateKeys"), NOT_ALLOWED);
+
+		let io = io(RpcMethodFilter::new(Some(vec!["mmr_generateProof".into()]), vec![]));
+		assert_eq!(call(&io, "mmr_generateProof"), ALLOWED);
+		assert_eq!(call(&io, "author_rotateKeys"), NOT_ALLOWED);
+	}
 }
@@ -32,6 +32,7 @@ pub use sc_network::{
 	},
 	Multiaddr,
 };
+pub use sc_rpc_server::RpcMethodFilter;
 
 use prometheus_endpoint::Registry;
 use sc_chain_spec::ChainSpec;
@@ -104,6 +105,8 @@ pub struct Configuration {
 	pub rpc_cors: Option<Vec<String>>,
 	/// RPC methods to expose (by default only a safe subset or all of them).
 	pub rpc_methods: RpcMethods,
+	/// RPC methods which may be called over the HTTP, WS and IPC servers.
+	pub rpc_method_filter: RpcMethodFilter,
 	/// Maximum payload of rpc request/responses.
 	pub rpc_max_payload: Option<usize>,
 	/// Prometheus endpoint configuration. `None` if disabled.
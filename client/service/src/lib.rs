@@ -389,7 +389,8 @@ fn start_rpc_servers<
 				&*path,
 				gen_handler(
 					sc_rpc::DenyUnsafe::No,
-					sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), "ipc"),
+					sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), "ipc")
+						.with_method_filter(config.rpc_method_filter.clone()),
 				),
 			)
 		}),
@@ -400,7 +401,8 @@ fn start_rpc_servers<
 				config.rpc_cors.as_ref(),
 				gen_handler(
 					deny_unsafe(&address, &config.rpc_methods),
-					sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), "http"),
+					sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), "http")
+						.with_method_filter(config.rpc_method_filter.clone()),
 				),
 				config.rpc_max_payload,
 			)
@@ -413,7 +415,8 @@ fn start_rpc_servers<
 				config.rpc_cors.as_ref(),
 				gen_handler(
 					deny_unsafe(&address, &config.rpc_methods),
-					sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), "ws"),
+					sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), "ws")
+						.with_method_filter(config.rpc_method_filter.clone()),
 				),
 				config.rpc_max_payload,
 			)
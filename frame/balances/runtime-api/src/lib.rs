@@ -0,0 +1,41 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API for inspecting the locks and reserves of an account.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use frame_support::traits::BalanceRestriction;
+
+sp_api::decl_runtime_apis! {
+	/// API to enumerate everything restricting the use of the balance of an account.
+	///
+	/// Runtimes usually implement this using
+	/// [`InspectBalanceRestrictions`](frame_support::traits::InspectBalanceRestrictions) of their
+	/// native currency.
+	pub trait BalanceRestrictionsApi<AccountId, Balance, ReserveIdentifier> where
+		AccountId: Codec,
+		Balance: Codec,
+		ReserveIdentifier: Codec,
+	{
+		/// All active locks and reserves on the account of `who`.
+		fn balance_restrictions(who: AccountId) -> Vec<BalanceRestriction<Balance, ReserveIdentifier>>;
+	}
+}
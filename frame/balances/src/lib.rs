@@ -164,10 +164,11 @@ use frame_support::{
 	ensure,
 	traits::{
 		tokens::{fungible, BalanceStatus as Status, DepositConsequence, WithdrawConsequence},
-		Currency, ExistenceRequirement,
+		BalanceRestriction, Currency, ExistenceRequirement,
 		ExistenceRequirement::{AllowDeath, KeepAlive},
-		Get, Imbalance, LockIdentifier, LockableCurrency, NamedReservableCurrency, OnUnbalanced,
-		ReservableCurrency, SignedImbalance, StoredMap, TryDrop, WithdrawReasons,
+		Get, Imbalance, InspectBalanceRestrictions, LockIdentifier, LockableCurrency,
+		NamedReservableCurrency, OnUnbalanced, ReservableCurrency, SignedImbalance, StoredMap,
+		TryDrop, WithdrawReasons,
 	},
 	WeakBoundedVec,
 };
@@ -2115,3 +2116,34 @@ where
 		Self::update_locks(who, &locks[..]);
 	}
 }
+
+impl<T: Config<I>, I: 'static> InspectBalanceRestrictions<T::AccountId> for Pallet<T, I>
+where
+	T::Balance: MaybeSerializeDeserialize + Debug,
+{
+	type ReserveIdentifier = T::ReserveIdentifier;
+
+	// Locks first, then named reserves, then whatever is left of the reserved balance.
+	fn balance_restrictions(
+		who: &T::AccountId,
+	) -> Vec<BalanceRestriction<Self::Balance, Self::ReserveIdentifier>> {
+		let mut restrictions = Self::locks(who)
+			.into_iter()
+			.map(|lock| BalanceRestriction::Lock { id: lock.id, amount: lock.amount })
+			.collect::<Vec<_>>();
+
+		let mut named_reserved = Zero::zero();
+		for reserve in Self::reserves(who) {
+			named_reserved = reserve.amount.saturating_add(named_reserved);
+			restrictions
+				.push(BalanceRestriction::NamedReserve { id: reserve.id, amount: reserve.amount });
+		}
+
+		let anonymous_reserved = Self::reserved_balance(who).saturating_sub(named_reserved);
+		if !anonymous_reserved.is_zero() {
+			restrictions.push(BalanceRestriction::AnonymousReserve { amount: anonymous_reserved });
+		}
+
+		restrictions
+	}
+}
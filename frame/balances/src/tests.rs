@@ -1201,6 +1201,29 @@ macro_rules! decl_tests {
 			});
 		}
 
+		#[test]
+		fn balance_restrictions_should_work() {
+			<$ext_builder>::default().build().execute_with(|| {
+				let _ = Balances::deposit_creating(&1, 100);
+				assert_eq!(Balances::balance_restrictions(&1), vec![]);
+
+				Balances::set_lock(ID_1, &1, 10, WithdrawReasons::all());
+				Balances::set_lock(ID_2, &1, 20, WithdrawReasons::TRANSFER);
+				assert_ok!(Balances::reserve_named(&[1u8; 8], &1, 5));
+				assert_ok!(Balances::reserve(&1, 7));
+
+				assert_eq!(
+					Balances::balance_restrictions(&1),
+					vec![
+						BalanceRestriction::Lock { id: ID_1, amount: 10 },
+						BalanceRestriction::Lock { id: ID_2, amount: 20 },
+						BalanceRestriction::NamedReserve { id: [1u8; 8], amount: 5 },
+						BalanceRestriction::AnonymousReserve { amount: 7 },
+					]
+				);
+			});
+		}
+
 		#[test]
 		fn repatriate_all_reserved_named_should_work() {
 			<$ext_builder>::default().build().execute_with(|| {
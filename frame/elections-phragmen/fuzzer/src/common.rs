@@ -0,0 +1,260 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Common fuzzing utils: a runtime containing the elections-phragmen pallet, random election
+//! scenarios and invariants of the pallet's state.
+
+// Each function will be used based on which fuzzer binary is being used.
+#![allow(dead_code)]
+
+use frame_support::{parameter_types, traits::LockIdentifier};
+use rand::{seq::SliceRandom, Rng};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+pub type AccountId = u64;
+pub type Balance = u64;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Elections: pallet_elections_phragmen::{Pallet, Call, Storage, Config<T>, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Runtime {
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type AccountId = AccountId;
+	type BaseCallFilter = frame_support::traits::AllowAll;
+	type BlockHashCount = BlockHashCount;
+	type BlockLength = ();
+	type BlockNumber = u64;
+	type BlockWeights = ();
+	type Call = Call;
+	type DbWeight = ();
+	type Event = Event;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type Header = Header;
+	type Index = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type OnKilledAccount = ();
+	type OnNewAccount = ();
+	type OnSetCode = ();
+	type Origin = Origin;
+	type PalletInfo = PalletInfo;
+	type SS58Prefix = ();
+	type SystemWeightInfo = ();
+	type Version = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+impl pallet_balances::Config for Runtime {
+	type AccountStore = System;
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const ElectionsPhragmenPalletId: LockIdentifier = *b"phrelect";
+	pub const CandidacyBond: Balance = 3;
+	pub const VotingBondBase: Balance = 2;
+	pub const VotingBondFactor: Balance = 1;
+	pub const TermDuration: u64 = 5;
+	pub static DesiredMembers: u32 = 2;
+	pub static DesiredRunnersUp: u32 = 0;
+	pub const MaxCandidates: u32 = 100;
+	pub const MaxVoters: u32 = 1000;
+	pub const MaxVotesPerVoter: u32 = 16;
+}
+impl pallet_elections_phragmen::Config for Runtime {
+	type BadSolution = ();
+	type BlockNumberProvider = System;
+	type CandidacyBond = CandidacyBond;
+	type ChangeMembers = ();
+	type Currency = Balances;
+	type CurrencyToVote = frame_support::traits::SaturatingCurrencyToVote;
+	type DefunctVoterReward = ();
+	type DesiredMembers = DesiredMembers;
+	type DesiredRunnersUp = DesiredRunnersUp;
+	type Event = Event;
+	type Fallback = ();
+	type MaxElectionWeight = ();
+	type ElectWinners = ();
+	type InitializeMembers = ();
+	type KickedMember = ();
+	type LoserCandidate = ();
+	type MaxCandidateMetadata = ();
+	type MaxCandidates = MaxCandidates;
+	type MaxVoters = MaxVoters;
+	type MaxVotesPerVoter = MaxVotesPerVoter;
+	type PalletId = ElectionsPhragmenPalletId;
+	type RankedMembers = ();
+	type SignedPhase = ();
+	type SolutionDeposit = ();
+	type Succession = ();
+	type TermDuration = TermDuration;
+	type TermOrigin = frame_system::EnsureRoot<AccountId>;
+	type VoterSnapshotPerBlock = ();
+	type VotingBondBase = VotingBondBase;
+	type VotingBondFactor = VotingBondFactor;
+	type WeightInfo = ();
+}
+
+/// converts x into the range [a, b] in a pseudo-fair way.
+pub fn to_range(x: usize, a: usize, b: usize) -> usize {
+	// does not work correctly if b < 2 * a
+	assert!(b >= 2 * a);
+	let collapsed = x % b;
+	if collapsed >= a {
+		collapsed
+	} else {
+		collapsed + a
+	}
+}
+
+/// Set the number of seats of the next election.
+pub fn set_desired_seats(members: u32, runners_up: u32) {
+	DESIRED_MEMBERS.with(|v| *v.borrow_mut() = members);
+	DESIRED_RUNNERS_UP.with(|v| *v.borrow_mut() = runners_up);
+}
+
+/// A random set of candidates and voters of an election.
+#[derive(Debug)]
+pub struct Scenario {
+	/// The endowment of every account.
+	pub balances: Vec<(AccountId, Balance)>,
+	/// The accounts which submit their candidacy, in order.
+	pub candidates: Vec<AccountId>,
+	/// The votes and the voted amount of each voter.
+	pub voters: Vec<(AccountId, Vec<AccountId>, Balance)>,
+}
+
+impl Scenario {
+	/// Generate a scenario with `candidate_count` candidates and `voter_count` voters.
+	///
+	/// Balances are small compared to the bonds, so that some candidacies and votes fail. Some of
+	/// the candidates vote as well.
+	pub fn random(candidate_count: usize, voter_count: usize, mut rng: impl Rng) -> Self {
+		let candidates = (1..=candidate_count as AccountId).collect::<Vec<_>>();
+		let mut voter_ids = (1_000..1_000 + voter_count as AccountId).collect::<Vec<_>>();
+
+		let balances = candidates
+			.iter()
+			.chain(voter_ids.iter())
+			.map(|who| (*who, rng.gen_range(1, 1_000)))
+			.collect::<Vec<_>>();
+
+		voter_ids.extend(candidates.iter().filter(|_| rng.gen_ratio(1, 4)));
+
+		let voters = voter_ids
+			.into_iter()
+			.map(|who| {
+				let vote_count =
+					rng.gen_range(1, (MaxVotesPerVoter::get() as usize).min(candidate_count) + 1);
+				let votes = candidates.choose_multiple(&mut rng, vote_count).cloned().collect();
+				(who, votes, rng.gen_range(0, 2_000))
+			})
+			.collect();
+
+		Self { balances, candidates, voters }
+	}
+
+	/// Build the genesis state of the scenario.
+	pub fn build(&self) -> sp_io::TestExternalities {
+		let mut storage =
+			frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> { balances: self.balances.clone() }
+			.assimilate_storage(&mut storage)
+			.unwrap();
+		let mut ext = sp_io::TestExternalities::new(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	/// Submit all candidacies and votes, ignoring the ones which fail.
+	///
+	/// Returns the accounts whose candidacy was accepted.
+	pub fn submit(&self) -> Vec<AccountId> {
+		let mut submitted = Vec::new();
+		for who in self.candidates.iter() {
+			let candidate_count = Elections::candidates().len() as u32;
+			if Elections::submit_candidacy(Origin::signed(*who), candidate_count).is_ok() {
+				submitted.push(*who);
+			}
+		}
+		for (who, votes, value) in self.voters.iter() {
+			let _ = Elections::vote(Origin::signed(*who), votes.clone(), *value);
+		}
+		submitted
+	}
+}
+
+/// The amount locked by the elections pallet on the account of `who`.
+pub fn election_lock(who: &AccountId) -> Balance {
+	Balances::locks(who)
+		.iter()
+		.find(|lock| lock.id == ElectionsPhragmenPalletId::get())
+		.map_or(0, |lock| lock.amount)
+}
+
+/// Assert that the funds reserved and locked by the elections pallet match its bookkeeping, for
+/// all accounts of `scenario`.
+pub fn assert_bond_accounting(scenario: &Scenario) {
+	let members = Elections::members();
+	let runners_up = Elections::runners_up();
+	let candidates = Elections::candidates();
+
+	for (who, _) in scenario.balances.iter() {
+		let voter = Elections::voting(who);
+		let seat_deposit = members
+			.iter()
+			.chain(runners_up.iter())
+			.find(|seat| seat.who == *who)
+			.map(|seat| seat.deposit)
+			.or_else(|| candidates.iter().find(|(c, _)| c == who).map(|(_, deposit)| *deposit))
+			.unwrap_or(0);
+
+		assert_eq!(
+			Balances::reserved_balance(who),
+			voter.deposit + seat_deposit,
+			"reserved balance of {} does not match its bonds",
+			who,
+		);
+		assert_eq!(election_lock(who), voter.stake, "lock of {} does not match its stake", who);
+	}
+}
@@ -0,0 +1,102 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fuzzing for the election of the elections-phragmen pallet.
+//!
+//! Random candidates and voters are submitted through the pallet's calls, after which an election
+//! is run. The elected seats must be feasible, and the bonds reserved and stakes locked must match
+//! the bookkeeping of the pallet, before and after the election.
+
+mod common;
+
+use common::*;
+use frame_support::traits::OnInitialize;
+use honggfuzz::fuzz;
+use rand::{self, SeedableRng};
+use sp_runtime::traits::Zero;
+
+fn main() {
+	loop {
+		fuzz!(|data: (usize, usize, usize, usize, u64)| {
+			let (mut candidate_count, mut voter_count, mut members, mut runners_up, seed) = data;
+			let rng = rand::rngs::SmallRng::seed_from_u64(seed);
+			candidate_count = to_range(candidate_count, 1, 50);
+			voter_count = to_range(voter_count, 1, 100);
+			members = to_range(members, 1, 20);
+			runners_up = runners_up % 10;
+
+			println!(
+				"++ [candidate_count: {} / voter_count: {} / members: {} / runners_up: {}]",
+				candidate_count, voter_count, members, runners_up,
+			);
+			set_desired_seats(members as u32, runners_up as u32);
+			let scenario = Scenario::random(candidate_count, voter_count, rng);
+
+			scenario.build().execute_with(|| {
+				let submitted = scenario.submit();
+				assert_bond_accounting(&scenario);
+
+				let any_backing = scenario.voters.iter().any(|(who, _, _)| {
+					let voter = Elections::voting(who);
+					!voter.stake.is_zero() && voter.votes.iter().any(|c| submitted.contains(c))
+				});
+				let issuance = Balances::total_issuance();
+
+				System::set_block_number(TermDuration::get());
+				Elections::on_initialize(TermDuration::get());
+
+				// Feasibility of the elected seats.
+				let elected = Elections::members()
+					.into_iter()
+					.chain(Elections::runners_up().into_iter())
+					.collect::<Vec<_>>();
+				assert!(Elections::members().len() <= members);
+				assert!(Elections::runners_up().len() <= runners_up);
+				assert!(Elections::candidates().is_empty());
+				assert_eq!(any_backing, !Elections::members().is_empty());
+				for (i, seat) in elected.iter().enumerate() {
+					assert!(
+						submitted.contains(&seat.who),
+						"{} was elected without running",
+						seat.who
+					);
+					assert!(
+						elected[i + 1..].iter().all(|other| other.who != seat.who),
+						"{} holds more than one seat",
+						seat.who,
+					);
+					let backing: u64 = scenario
+						.voters
+						.iter()
+						.map(|(who, _, _)| Elections::voting(who))
+						.filter(|voter| voter.votes.contains(&seat.who))
+						.map(|voter| voter.stake)
+						.sum();
+					assert!(!seat.stake.is_zero() && seat.stake <= backing);
+					assert_eq!(seat.deposit, CandidacyBond::get());
+				}
+
+				// Bonds of the candidates which were not elected are slashed.
+				let losers =
+					submitted.iter().filter(|c| elected.iter().all(|seat| seat.who != **c)).count()
+						as u64;
+				assert_eq!(Balances::total_issuance(), issuance - losers * CandidacyBond::get());
+				assert_bond_accounting(&scenario);
+			});
+		});
+	}
+}
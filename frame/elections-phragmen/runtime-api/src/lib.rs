@@ -0,0 +1,39 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API for predicting the outcome of the phragmen election.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+pub use pallet_elections_phragmen::ElectionPrediction;
+
+sp_api::decl_runtime_apis! {
+	/// API to pre-compute the phragmen election off-chain.
+	pub trait ElectionsPhragmenApi<AccountId, Balance> where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// The members and runners-up the election would yield as of the block queried, with
+		/// the approval stake of every candidate.
+		///
+		/// During a deferred election, this is the outcome of the ongoing election once its
+		/// snapshot is complete.
+		fn predict_result() -> ElectionPrediction<AccountId, Balance>;
+	}
+}
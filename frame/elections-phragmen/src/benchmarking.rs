@@ -22,7 +22,10 @@
 use super::*;
 
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelist};
-use frame_support::{dispatch::DispatchResultWithPostInfo, traits::OnInitialize};
+use frame_support::{
+	dispatch::DispatchResultWithPostInfo,
+	traits::{EnsureOrigin, OnInitialize, UnfilteredDispatchable},
+};
 use frame_system::RawOrigin;
 
 use crate::Pallet as Elections;
@@ -139,18 +142,77 @@ fn fill_seats_up_to<T: Config>(m: u32) -> Result<Vec<T::AccountId>, &'static str
 		.collect())
 }
 
+/// Start a deferred election, and snapshot all the voters up to its signed phase.
+fn snapshot_election<T: Config>() {
+	let now = frame_system::Pallet::<T>::block_number();
+	<Elections<T>>::start_election();
+	while let ElectionPhase::Snapshot(last_key) = <Phase<T>>::get() {
+		<Elections<T>>::snapshot_voters(last_key, now);
+	}
+}
+
+/// The solution of phragmen for the snapshot of the ongoing deferred election.
+fn snapshot_solution<T: Config>() -> Result<RawSolution<T::AccountId>, &'static str> {
+	let candidates = <SnapshotCandidates<T>>::get().into_iter().map(|(c, _)| c).collect::<Vec<_>>();
+	let num_to_elect = T::DesiredMembers::get().saturating_add(T::DesiredRunnersUp::get());
+	let total_issuance = T::Currency::total_issuance();
+	let voters = <Elections<T>>::snapshotted_voters()
+		.into_iter()
+		.map(|(voter, stake, votes)| {
+			(voter, T::CurrencyToVote::to_vote(stake, total_issuance), votes)
+		})
+		.collect::<Vec<_>>();
+	let stakes = voters
+		.iter()
+		.map(|(voter, stake, _)| (voter.clone(), *stake))
+		.collect::<BTreeMap<_, _>>();
+
+	let ElectionResult { winners, assignments } = sp_npos_elections::seq_phragmen::<
+		T::AccountId,
+		Perbill,
+	>(
+		num_to_elect as usize, candidates, voters, None
+	)
+	.map_err(|_| "failed to run phragmen")?;
+	let winners = winners.into_iter().map(|(w, _)| w).collect::<Vec<_>>();
+	let assignments =
+		sp_npos_elections::assignment_ratio_to_staked_normalized(assignments, |who| {
+			stakes.get(who).cloned().unwrap_or_default()
+		})
+		.map_err(|_| "failed to normalize the assignments")?;
+	let score =
+		(&to_supports(&winners, &assignments).map_err(|_| "invalid assignments")?).evaluate();
+
+	Ok(RawSolution {
+		winners,
+		assignments: assignments.into_iter().map(|a| (a.who, a.distribution)).collect(),
+		score,
+	})
+}
+
 /// removes all the storage items to reverse any genesis state.
 fn clean<T: Config>() {
 	<Members<T>>::kill();
 	<Candidates<T>>::kill();
 	<RunnersUp<T>>::kill();
 	<Voting<T>>::remove_all(None);
+	<CounterForVoters<T>>::kill();
+	<Phase<T>>::kill();
+	<SnapshotCandidates<T>>::kill();
+	<SnapshotVoters<T>>::remove_all(None);
+	<SnapshotVoterPages<T>>::kill();
+	<BestSubmission<T>>::kill();
+	<MinimumUntrustedScore<T>>::kill();
+	<DefunctVoterCursor<T>>::kill();
+	<TermDurationOverride<T>>::kill();
+	<NextElectionAt<T>>::put(T::TermDuration::get());
+	<ForceNewTerm<T>>::kill();
 }
 
 benchmarks! {
 	// -- Signed ones
 	vote_equal {
-		let v in 1 .. (MAXIMUM_VOTE as u32);
+		let v in 1 .. T::MaxVotesPerVoter::get();
 		clean::<T>();
 
 		// create a bunch of candidates.
@@ -170,7 +232,7 @@ benchmarks! {
 	}: vote(RawOrigin::Signed(caller), votes, stake)
 
 	vote_more {
-		let v in 2 .. (MAXIMUM_VOTE  as u32);
+		let v in 2 .. T::MaxVotesPerVoter::get();
 		clean::<T>();
 
 		// create a bunch of candidates.
@@ -191,7 +253,7 @@ benchmarks! {
 	}: vote(RawOrigin::Signed(caller), votes, stake / <BalanceOf<T>>::from(10u32))
 
 	vote_less {
-		let v in 2 .. (MAXIMUM_VOTE  as u32);
+		let v in 2 .. T::MaxVotesPerVoter::get();
 		clean::<T>();
 
 		// create a bunch of candidates.
@@ -213,7 +275,7 @@ benchmarks! {
 
 	remove_voter {
 		// we fix the number of voted candidates to max
-		let v = MAXIMUM_VOTE as u32;
+		let v = T::MaxVotesPerVoter::get();
 		clean::<T>();
 
 		// create a bunch of candidates.
@@ -227,6 +289,31 @@ benchmarks! {
 		whitelist!(caller);
 	}: _(RawOrigin::Signed(caller))
 
+	refund_voter_deposit {
+		// we fix the number of voted candidates to max
+		let v = T::MaxVotesPerVoter::get();
+		clean::<T>();
+
+		// create a bunch of candidates.
+		let all_candidates = submit_candidates::<T>(v, "candidates")?;
+
+		let caller = endowed_account::<T>("caller", 0);
+
+		// the deposit exceeds what the votes require, as if the voting bond was lowered. It is
+		// reserved before voting, as the voting lock covers the whole balance.
+		let excess = T::Currency::minimum_balance();
+		T::Currency::reserve(&caller, excess)?;
+
+		let stake = default_stake::<T>(BALANCE_FACTOR);
+		submit_voter::<T>(caller.clone(), all_candidates, stake)?;
+		<Voting<T>>::mutate(&caller, |v| v.deposit = v.deposit.saturating_add(excess));
+
+		whitelist!(caller);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(<Voting<T>>::get(&caller).deposit, <Elections<T>>::deposit_of(v as usize));
+	}
+
 	submit_candidacy {
 		// number of already existing candidates.
 		let c in 1 .. MAX_CANDIDATES;
@@ -349,7 +436,7 @@ benchmarks! {
 		let replacements = submit_candidates_with_self_vote::<T>(c, "new_candidate")?;
 
 		// create some voters for these replacements.
-		distribute_voters::<T>(replacements, MAX_VOTERS, MAXIMUM_VOTE)?;
+		distribute_voters::<T>(replacements, MAX_VOTERS, T::MaxVotesPerVoter::get() as usize)?;
 
 		let to_remove = as_lookup::<T>(all_members[0].clone());
 	}: remove_member(RawOrigin::Root, to_remove, false)
@@ -419,18 +506,30 @@ benchmarks! {
 		clean::<T>();
 
 		let all_candidates = submit_candidates::<T>(v, "candidates")?;
-		distribute_voters::<T>(all_candidates, v, MAXIMUM_VOTE)?;
+		distribute_voters::<T>(all_candidates, v, T::MaxVotesPerVoter::get() as usize)?;
 
 		// all candidates leave.
 		<Candidates<T>>::kill();
 
-		// now everyone is defunct
-		assert!(<Voting<T>>::iter().all(|(_, v)| <Elections<T>>::is_defunct_voter(&v.votes)));
+		// all but `d` voters vote for a new candidate.
+		let alive = submit_candidates::<T>(1, "alive")?;
+		let stake = default_stake::<T>(BALANCE_FACTOR);
+		for i in d .. v {
+			submit_voter::<T>(account("voter", i, 0), alive.clone(), stake)?;
+		}
+
+		// now `d` voters are defunct
+		assert_eq!(
+			<Voting<T>>::iter().filter(|(_, v)| <Elections<T>>::is_defunct_voter(&v.votes)).count()
+				as u32,
+			d,
+		);
 		assert_eq!(<Voting<T>>::iter().count() as u32, v);
-		let root = RawOrigin::Root;
-	}: _(root, v, d)
+		let caller = endowed_account::<T>("caller", 0);
+		whitelist!(caller);
+	}: _(RawOrigin::Signed(caller), v, d)
 	verify {
-		assert_eq!(<Voting<T>>::iter().count() as u32, 0);
+		assert_eq!(<Voting<T>>::iter().count() as u32, v - d);
 	}
 
 	election_phragmen {
@@ -440,20 +539,21 @@ benchmarks! {
 		// that we give all candidates a self vote to make sure they are all considered.
 		let c in 1 .. MAX_CANDIDATES;
 		let v in 1 .. MAX_VOTERS;
-		let e in MAX_VOTERS .. MAX_VOTERS * MAXIMUM_VOTE as u32;
+		let e in MAX_VOTERS .. MAX_VOTERS * T::MaxVotesPerVoter::get();
 		clean::<T>();
 
 		// so we have a situation with v and e. we want e to basically always be in the range of `e
-		// -> e * MAXIMUM_VOTE`, but we cannot express that now with the benchmarks. So what we do
-		// is: when c is being iterated, v, and e are max and fine. when v is being iterated, e is
-		// being set to max and this is a problem. In these cases, we cap e to a lower value, namely
-		// v * MAXIMUM_VOTE. when e is being iterated, v is at max, and again fine. all in all,
-		// votes_per_voter can never be more than MAXIMUM_VOTE. Note that this might cause `v` to be
-		// an overestimate.
-		let votes_per_voter = (e / v).min(MAXIMUM_VOTE as u32);
+		// -> e * MaxVotesPerVoter`, but we cannot express that now with the benchmarks. So what we
+		// do is: when c is being iterated, v, and e are max and fine. when v is being iterated, e
+		// is being set to max and this is a problem. In these cases, we cap e to a lower value,
+		// namely v * MaxVotesPerVoter. when e is being iterated, v is at max, and again fine. all
+		// in all, votes_per_voter can never be more than MaxVotesPerVoter. Note that this might
+		// cause `v` to be an overestimate.
+		let votes_per_voter = (e / v).min(T::MaxVotesPerVoter::get());
 
 		let all_candidates = submit_candidates_with_self_vote::<T>(c, "candidates")?;
 		let _ = distribute_voters::<T>(all_candidates, v, votes_per_voter as usize)?;
+		frame_system::Pallet::<T>::set_block_number(T::TermDuration::get());
 	}: {
 		<Elections<T>>::on_initialize(T::TermDuration::get());
 	}
@@ -475,7 +575,7 @@ benchmarks! {
 	#[extra]
 	election_phragmen_c_e {
 		let c in 1 .. MAX_CANDIDATES;
-		let e in MAX_VOTERS .. MAX_VOTERS * MAXIMUM_VOTE as u32;
+		let e in MAX_VOTERS .. MAX_VOTERS * T::MaxVotesPerVoter::get();
 		let fixed_v = MAX_VOTERS;
 		clean::<T>();
 
@@ -483,6 +583,7 @@ benchmarks! {
 
 		let all_candidates = submit_candidates_with_self_vote::<T>(c, "candidates")?;
 		let _ = distribute_voters::<T>(all_candidates, fixed_v, votes_per_voter as usize)?;
+		frame_system::Pallet::<T>::set_block_number(T::TermDuration::get());
 	}: {
 		<Elections<T>>::on_initialize(T::TermDuration::get());
 	}
@@ -512,6 +613,7 @@ benchmarks! {
 
 		let all_candidates = submit_candidates_with_self_vote::<T>(fixed_c, "candidates")?;
 		let _ = distribute_voters::<T>(all_candidates, v, votes_per_voter as usize)?;
+		frame_system::Pallet::<T>::set_block_number(T::TermDuration::get());
 	}: {
 		<Elections<T>>::on_initialize(T::TermDuration::get());
 	}
@@ -529,6 +631,121 @@ benchmarks! {
 			MEMBERS.with(|m| *m.borrow_mut() = vec![]);
 		}
 	}
+
+	#[extra]
+	election_phragmen_worst_case {
+		// The full rotation at the bounds of the configuration, i.e. the components of
+		// `election_phragmen` which `MaxElectionWeight` should admit: all the seats are held by
+		// members and runners-up who lose them to `MaxCandidates` new candidates, each of the other
+		// voters voting for `MaxVotesPerVoter` of them.
+		clean::<T>();
+		let seats = T::DesiredMembers::get().saturating_add(T::DesiredRunnersUp::get());
+		let outgoing = fill_seats_up_to::<T>(seats)?;
+		let c = T::MaxCandidates::get();
+		let v = T::MaxVoters::get().saturating_sub(seats);
+
+		let all_candidates = submit_candidates::<T>(c, "candidates")?;
+		distribute_voters::<T>(all_candidates, v, T::MaxVotesPerVoter::get() as usize)?;
+		frame_system::Pallet::<T>::set_block_number(T::TermDuration::get());
+	}: {
+		<Elections<T>>::on_initialize(T::TermDuration::get());
+	}
+	verify {
+		assert_eq!(<Elections<T>>::members().len() as u32, T::DesiredMembers::get().min(c));
+		assert!(<Elections<T>>::members_ids().iter().all(|m| !outgoing.contains(m)));
+		assert!(<Elections<T>>::runners_up().iter().all(|r| !outgoing.contains(&r.who)));
+
+		#[cfg(test)]
+		{
+			// reset members in between benchmark tests.
+			use crate::tests::MEMBERS;
+			MEMBERS.with(|m| *m.borrow_mut() = vec![]);
+		}
+	}
+
+	submit_solution {
+		// The snapshot is read as a whole to check the solution, hence its size is varied as in
+		// `election_phragmen`. A previous, weaker submission is replaced, returning its deposit.
+		let c in 1 .. MAX_CANDIDATES;
+		let v in 1 .. MAX_VOTERS;
+		let e in MAX_VOTERS .. MAX_VOTERS * T::MaxVotesPerVoter::get();
+		clean::<T>();
+
+		let votes_per_voter = (e / v).min(T::MaxVotesPerVoter::get());
+
+		let all_candidates = submit_candidates_with_self_vote::<T>(c, "candidates")?;
+		let _ = distribute_voters::<T>(all_candidates, v, votes_per_voter as usize)?;
+		snapshot_election::<T>();
+		let solution = snapshot_solution::<T>()?;
+
+		let previous = endowed_account::<T>("previous", 0);
+		let deposit = T::SolutionDeposit::get();
+		T::Currency::reserve(&previous, deposit)?;
+		<BestSubmission<T>>::put(SignedSubmission {
+			who: previous,
+			deposit,
+			score: [0, 0, ExtendedBalance::max_value()],
+			winners: Vec::new(),
+		});
+
+		let caller = endowed_account::<T>("caller", 0);
+		let _ = T::Currency::make_free_balance_be(
+			&caller,
+			deposit.saturating_add(default_stake::<T>(BALANCE_FACTOR)),
+		);
+		whitelist!(caller);
+	}: _(RawOrigin::Signed(caller.clone()), solution)
+	verify {
+		assert_eq!(<BestSubmission<T>>::get().map(|best| best.who), Some(caller));
+	}
+
+	force_new_term {
+		clean::<T>();
+		let origin = T::TermOrigin::successful_origin();
+		let call = Call::<T>::force_new_term();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(<ForceNewTerm<T>>::get());
+	}
+
+	set_term_duration {
+		clean::<T>();
+		let origin = T::TermOrigin::successful_origin();
+		let duration = T::TermDuration::get().saturating_add(1u32.into());
+		let call = Call::<T>::set_term_duration(duration);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(<Elections<T>>::term_duration(), duration);
+	}
+
+	set_candidate_metadata {
+		// the worst case is a runner-up, checked after the candidates and the members, attaching
+		// the longest metadata.
+		clean::<T>();
+		let seats = T::DesiredMembers::get().saturating_add(T::DesiredRunnersUp::get());
+		let _ = fill_seats_up_to::<T>(seats)?;
+		let runner_up = <Elections<T>>::runners_up()
+			.into_inner()
+			.pop()
+			.map(|r| r.who)
+			.ok_or("no runners-up were elected")?;
+		let _ = submit_candidates::<T>(T::MaxCandidates::get(), "candidates")?;
+		let metadata = vec![0u8; T::MaxCandidateMetadata::get() as usize];
+		whitelist!(runner_up);
+	}: _(RawOrigin::Signed(runner_up.clone()), metadata.clone())
+	verify {
+		assert_eq!(<CandidateMetadata<T>>::get(&runner_up).map(|m| m.into_inner()), Some(metadata));
+	}
+
+	set_minimum_untrusted_score {
+		clean::<T>();
+		let origin = T::TermOrigin::successful_origin();
+		let score = [1, 1, 1];
+		let call = Call::<T>::set_minimum_untrusted_score(Some(score));
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(<MinimumUntrustedScore<T>>::get(), Some(score));
+	}
 }
 
 impl_benchmark_test_suite!(
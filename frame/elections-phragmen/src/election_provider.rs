@@ -0,0 +1,173 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An opt-in adapter to elect the members and runners-up with an [`ElectionProvider`].
+//!
+//! [`Pallet`] is the [`ElectionDataProvider`] of its candidates, members, runners-up and voters,
+//! and [`ElectionProviderAdapter`] elects the winners of each term with
+//! [`Config::ElectionProvider`] fed by it. A runtime can thus swap between an on-chain election,
+//! such as [`SeqPhragmen`], and a multi-phase one, while the members are still reported through
+//! [`super::Config::ChangeMembers`].
+//!
+//! The adapter is only used for the elections which are not deferred, hence
+//! [`super::Config::VoterSnapshotPerBlock`] should be zero.
+
+use super::{
+	BalanceOf, Config as PhragmenConfig, CounterForVoters, ElectWinners, ForceNewTerm, Pallet,
+	Voter, Voting, WeightInfo,
+};
+use frame_election_provider_support::{
+	data_provider, onchain::Error, ElectionDataProvider, ElectionProvider, ExtendedBalance,
+	Supports, VoteWeight,
+};
+use frame_support::{
+	traits::{BlockNumberProvider, Currency, CurrencyToVote, Get},
+	weights::Weight,
+};
+use sp_npos_elections::{
+	assignment_ratio_to_staked_normalized, seq_phragmen, to_supports, to_without_backing,
+	ElectionResult,
+};
+use sp_runtime::{
+	traits::{Bounded, Saturating, Zero},
+	Perbill,
+};
+use sp_std::{collections::btree_map::BTreeMap, marker::PhantomData, prelude::*};
+
+/// Config necessary for the election provider adapter.
+pub trait Config: PhragmenConfig {
+	/// Maximum number of votes per voter, at least [`PhragmenConfig::MaxVotesPerVoter`].
+	const MAX_VOTES_PER_VOTER: u32;
+
+	/// The election provider electing the members and runners-up, fed by this pallet.
+	type ElectionProvider: ElectionProvider<
+		Self::AccountId,
+		Self::BlockNumber,
+		DataProvider = Pallet<Self>,
+	>;
+}
+
+impl<T: Config> ElectionDataProvider<T::AccountId, T::BlockNumber> for Pallet<T> {
+	const MAXIMUM_VOTES_PER_VOTER: u32 = T::MAX_VOTES_PER_VOTER;
+
+	fn targets(maybe_max_len: Option<usize>) -> data_provider::Result<(Vec<T::AccountId>, Weight)> {
+		let targets =
+			Self::candidates_and_deposit().into_iter().map(|(c, _)| c).collect::<Vec<_>>();
+		if maybe_max_len.map_or(false, |max_len| targets.len() > max_len) {
+			return Err("Target snapshot too big")
+		}
+
+		// candidates, members and runners-up.
+		Ok((targets, T::DbWeight::get().reads(3)))
+	}
+
+	fn voters(
+		maybe_max_len: Option<usize>,
+	) -> data_provider::Result<(Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)>, Weight)> {
+		let voter_count = CounterForVoters::<T>::get() as usize;
+		if maybe_max_len.map_or(false, |max_len| voter_count > max_len) {
+			return Err("Voter snapshot too big")
+		}
+
+		let total_issuance = T::Currency::total_issuance();
+		let to_votes = |b: BalanceOf<T>| T::CurrencyToVote::to_vote(b, total_issuance);
+		let voters = Voting::<T>::iter()
+			.map(|(voter, Voter { stake, votes, .. })| (voter, to_votes(stake), votes.into_inner()))
+			.collect::<Vec<_>>();
+		Ok((voters, T::DbWeight::get().reads(voter_count as Weight + 2)))
+	}
+
+	fn desired_targets() -> data_provider::Result<(u32, Weight)> {
+		Ok((T::DesiredMembers::get().saturating_add(T::DesiredRunnersUp::get()), 0))
+	}
+
+	fn next_election_prediction(now: T::BlockNumber) -> T::BlockNumber {
+		if <ForceNewTerm<T>>::get() {
+			return now.saturating_add(1u32.into())
+		}
+
+		let term_duration = Self::term_duration();
+		if term_duration.is_zero() {
+			return Bounded::max_value()
+		}
+
+		// terms are counted in blocks of `BlockNumberProvider`, which might not be `now`.
+		let provider_now = T::BlockNumberProvider::current_block_number();
+		let next_election = Pallet::<T>::next_election_at(provider_now, term_duration);
+		now.saturating_add(next_election.saturating_sub(provider_now))
+	}
+}
+
+/// Elects the members and runners-up with [`Config::ElectionProvider`].
+///
+/// The winners are ranked by their total support, and only those which are still candidates when
+/// the term starts win a seat.
+pub struct ElectionProviderAdapter<T>(PhantomData<T>);
+
+impl<T: Config> ElectWinners<T::AccountId> for ElectionProviderAdapter<T> {
+	type Error = <T::ElectionProvider as ElectionProvider<T::AccountId, T::BlockNumber>>::Error;
+
+	fn elect_winners() -> Option<Result<(Vec<(T::AccountId, ExtendedBalance)>, Weight), Self::Error>>
+	{
+		let result = T::ElectionProvider::elect().map(|(supports, weight)| {
+			let mut winners = supports
+				.into_iter()
+				.map(|(who, support)| (who, support.total))
+				.collect::<Vec<_>>();
+			winners.sort_by(|a, b| b.1.cmp(&a.1));
+			(winners, weight)
+		});
+		Some(result)
+	}
+}
+
+/// An [`ElectionProvider`] running sequential phragmen on-chain with the data of [`Pallet`], as the
+/// built-in election does.
+pub struct SeqPhragmen<T>(PhantomData<T>);
+
+impl<T: Config> ElectionProvider<T::AccountId, T::BlockNumber> for SeqPhragmen<T> {
+	type Error = Error;
+	type DataProvider = Pallet<T>;
+
+	fn elect() -> Result<(Supports<T::AccountId>, Weight), Self::Error> {
+		let (voters, voters_weight) =
+			Self::DataProvider::voters(None).map_err(Error::DataProvider)?;
+		let (targets, targets_weight) =
+			Self::DataProvider::targets(None).map_err(Error::DataProvider)?;
+		let (desired_targets, _) =
+			Self::DataProvider::desired_targets().map_err(Error::DataProvider)?;
+
+		let edges = voters
+			.iter()
+			.fold(0u32, |edges, (_, _, votes)| edges.saturating_add(votes.len() as u32));
+		let weight =
+			T::WeightInfo::election_phragmen(targets.len() as u32, voters.len() as u32, edges)
+				.saturating_add(voters_weight)
+				.saturating_add(targets_weight);
+
+		let stake_map = voters.iter().map(|(v, s, _)| (v.clone(), *s)).collect::<BTreeMap<_, _>>();
+		let stake_of =
+			|w: &T::AccountId| -> VoteWeight { stake_map.get(w).cloned().unwrap_or_default() };
+
+		let ElectionResult { winners, assignments } =
+			seq_phragmen::<_, Perbill>(desired_targets as usize, targets, voters, None)?;
+		let staked = assignment_ratio_to_staked_normalized(assignments, &stake_of)?;
+		let winners = to_without_backing(winners);
+
+		to_supports(&winners, &staked).map_err(Error::from).map(|s| (s, weight))
+	}
+}
@@ -26,10 +26,55 @@
 //! lasts for some number of blocks defined by [`Config::TermDuration`]. The words _term_ and
 //! _round_ can be used interchangeably in this context.
 //!
-//! [`Config::TermDuration`] might change during a round. This can shorten or extend the length of
-//! the round. The next election round's block number is never stored but rather always checked on
-//! the fly. Based on the current block number and [`Config::TermDuration`], the condition
-//! `BlockNumber % TermDuration == 0` being satisfied will always trigger a new election round.
+//! The start of the next round is scheduled in [`NextElectionAt`], a block number of
+//! [`Config::BlockNumberProvider`]. That block number might skip some numbers, e.g. a relay chain
+//! block number, hence the round starts in the first block at or after it. Each round schedules the
+//! next one a term duration after its own scheduled start, so that a late start does not delay the
+//! following rounds. The first round is scheduled at genesis.
+//!
+//! [`Config::TermDuration`] might change during a round. The round still ends as scheduled, and the
+//! new term duration applies from the next round on.
+//!
+//! [`Config::TermOrigin`] can override the term duration with [`Call::set_term_duration`], or
+//! dissolve the current term with [`Call::force_new_term`], in which case an election round starts
+//! in the next block.
+//!
+//! By default the whole election runs in the block starting the new round. If
+//! [`Config::VoterSnapshotPerBlock`] is not zero, the election is deferred instead: the candidates
+//! are snapshotted in that block, the voters in the following blocks, at most
+//! [`Config::VoterSnapshotPerBlock`] of them per block, and the election runs in the block after the
+//! snapshot is complete. Candidacies and seats are frozen until then, see [`ElectionPhase`]. Votes
+//! are only frozen while the voters are snapshotted, and the stake of each voter is capped by their
+//! balance at that time.
+//!
+//! ### Signed Solutions
+//!
+//! Once the snapshot of a deferred election is complete, anyone can submit a pre-computed solution
+//! of the election during [`Config::SignedPhase`] blocks, by reserving [`Config::SolutionDeposit`].
+//! The solution is checked and its score evaluated on-chain upon submission, which is much cheaper
+//! than solving the election. A solution which is invalid, or whose claimed score is wrong, gets
+//! its deposit slashed. Only the best solution is kept, and it is used instead of running
+//! sequential phragmen when the election ends, after which its submitter gets the deposit back.
+//! Without any solution, sequential phragmen is run as usual.
+//!
+//! A solution must distribute the whole stake of every voter who voted for one of its winners.
+//! Its score is not compared to the one of phragmen, hence [`Config::TermOrigin`] should set a
+//! [`MinimumUntrustedScore`] which the first solution must beat.
+//!
+//! See [`Call::submit_solution`].
+//!
+//! ### Fallback
+//!
+//! If the phragmen election fails, [`Config::Fallback`] decides what happens instead, see
+//! [`FallbackStrategy`]. So it does if the election is refused beforehand, because its weight
+//! projected from the number of candidates, voters and votes is above
+//! [`Config::MaxElectionWeight`].
+//!
+//! ### Election Provider
+//!
+//! [`Config::ElectWinners`] can elect the winners of a term which is not deferred in place of the
+//! built-in sequential phragmen. With the `election-provider` feature, the `election_provider`
+//! module elects them with any `ElectionProvider`, e.g. a multi-phase one, fed by this pallet.
 //!
 //! ### Bonds and Deposits
 //!
@@ -43,14 +88,15 @@
 //! ### Voting
 //!
 //! Voters can vote for a limited number of the candidates by providing a list of account ids,
-//! bounded by [`MAXIMUM_VOTE`]. Invalid votes (voting for non-candidates) and duplicate votes are
-//! ignored during election. Yet, a voter _might_ vote for a future candidate. Voters reserve a bond
-//! as they vote. Each vote defines a `value`. This amount is locked from the account of the voter
-//! and indicates the weight of the vote. Voters can update their votes at any time by calling
-//! `vote()` again. This can update the vote targets (which might update the deposit) or update the
-//! vote's stake ([`Voter::stake`]). After a round, votes are kept and might still be valid for
-//! further rounds. A voter is responsible for calling `remove_voter` once they are done to have
-//! their bond back and remove the lock.
+//! bounded by [`Config::MaxVotesPerVoter`]. At most [`Config::MaxVoters`] voters can exist at once.
+//! Invalid votes (voting for non-candidates) and duplicate votes are ignored during election. Yet,
+//! a voter _might_ vote for a future candidate. Voters reserve a bond as they vote. Each vote
+//! defines a `value`. This amount is locked from the account of the voter and indicates the weight
+//! of the vote. Voters can update their votes at any time by calling `vote()` again. This can
+//! update the vote targets (which might update the deposit) or update the vote's stake
+//! ([`Voter::stake`]). After a round, votes are kept and might still be valid for further rounds. A
+//! voter is responsible for calling `remove_voter` once they are done to have their bond back and
+//! remove the lock.
 //!
 //! See [`Call::vote`], [`Call::remove_voter`].
 //!
@@ -58,9 +104,9 @@
 //!
 //! A voter is defunct once all of the candidates that they have voted for are not a valid candidate
 //! (as seen further below, members and runners-up are also always candidates). Defunct voters can
-//! be removed via a root call ([`Call::clean_defunct_voters`]). Upon being removed, their bond is
-//! returned. This is an administrative operation and can be called only by the root origin in the
-//! case of state bloat.
+//! be removed by anyone, a few at a time ([`Call::clean_defunct_voters`]), which keeps the voter
+//! set from bloating. Upon being removed, their bond is returned, except for
+//! [`Config::DefunctVoterReward`] of it, which rewards the caller.
 //!
 //! ### Candidacy and Members
 //!
@@ -101,21 +147,36 @@
 use codec::{Decode, Encode};
 use frame_support::{
 	dispatch::WithPostDispatchInfo,
+	ensure,
+	migrations::LazyMigrationExecutor,
 	traits::{
-		ChangeMembers, Contains, ContainsLengthBound, Currency, CurrencyToVote, Get,
-		InitializeMembers, LockIdentifier, LockableCurrency, OnUnbalanced, ReservableCurrency,
-		SortedMembers, StorageVersion, WithdrawReasons,
+		BalanceStatus, BlockNumberProvider, ChangeMembers, CheckInvariants, Contains,
+		ContainsLengthBound, Currency, CurrencyToVote, Get, InitializeMembers, LockIdentifier,
+		LockableCurrency, OnUnbalanced, ReservableCurrency, SortedMembers, StorageVersion,
+		WithdrawReasons,
 	},
 	weights::Weight,
+	BoundedVec, CloneNoBound, DefaultNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+};
+use sp_npos_elections::{
+	is_score_better, to_supports, ElectionResult, ElectionScore, EvaluateSupport, ExtendedBalance,
+	StakedAssignment,
 };
-use sp_npos_elections::{ElectionResult, ExtendedBalance};
 use sp_runtime::{
-	traits::{Saturating, StaticLookup, Zero},
+	traits::{One, Saturating, StaticLookup, Zero},
 	DispatchError, Perbill, RuntimeDebug,
 };
-use sp_std::{cmp::Ordering, prelude::*};
+use sp_std::{
+	cmp::Ordering,
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	convert::TryFrom,
+	fmt,
+	prelude::*,
+};
 
 mod benchmarking;
+#[cfg(feature = "election-provider")]
+pub mod election_provider;
 pub mod weights;
 pub use weights::WeightInfo;
 
@@ -123,10 +184,7 @@ pub use weights::WeightInfo;
 pub mod migrations;
 
 /// The current storage version.
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
-
-/// The maximum votes allowed per voter.
-pub const MAXIMUM_VOTE: usize = 16;
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(6);
 
 type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
@@ -146,10 +204,14 @@ pub enum Renouncing {
 }
 
 /// An active voter.
-#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq)]
-pub struct Voter<AccountId, Balance> {
-	/// The members being backed.
-	pub votes: Vec<AccountId>,
+#[derive(Encode, Decode, CloneNoBound, DefaultNoBound, RuntimeDebugNoBound, PartialEqNoBound)]
+pub struct Voter<
+	AccountId: Clone + PartialEq + fmt::Debug,
+	Balance: Clone + Default + PartialEq + fmt::Debug,
+	MaxVotes: Get<u32>,
+> {
+	/// The members being backed, at most `MaxVotes`.
+	pub votes: BoundedVec<AccountId, MaxVotes>,
 	/// The amount of stake placed on this vote.
 	pub stake: Balance,
 	/// The amount of deposit reserved for this vote.
@@ -171,6 +233,183 @@ pub struct SeatHolder<AccountId, Balance> {
 	pub deposit: Balance,
 }
 
+/// The phase of a deferred election, see [`Config::VoterSnapshotPerBlock`].
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub enum ElectionPhase<BlockNumber> {
+	/// No election is ongoing.
+	Off,
+	/// The voters are being snapshotted, resuming after the given raw key of [`Voting`], if any.
+	Snapshot(Option<Vec<u8>>),
+	/// The snapshot is complete. Solutions can be submitted up to the given block of
+	/// [`Config::BlockNumberProvider`], the election runs in the first block after it.
+	Signed(BlockNumber),
+	/// The election failed and [`FallbackStrategy::Emergency`] is used. The snapshot is kept
+	/// until [`Call::set_members`] is called.
+	Emergency,
+}
+
+impl<BlockNumber> Default for ElectionPhase<BlockNumber> {
+	fn default() -> Self {
+		ElectionPhase::Off
+	}
+}
+
+/// Why an election failed, see [`Event::ElectionError`].
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum ElectionErrorReason {
+	/// Sequential phragmen failed.
+	Phragmen,
+	/// [`Config::ElectWinners`] failed.
+	ElectWinners,
+	/// The election was refused beforehand, its projected `weight` being above
+	/// [`Config::MaxElectionWeight`].
+	TooHeavy { weight: Weight },
+}
+
+/// What to do when the phragmen election fails, see [`Config::Fallback`].
+#[derive(Encode, Decode, Clone, Copy, PartialEq, RuntimeDebug)]
+pub enum FallbackStrategy {
+	/// Keep the current members and runners-up until the next election.
+	KeepMembers,
+	/// Elect the candidates with the most approval stake, i.e. the total stake of the voters who
+	/// voted for them.
+	Approval,
+	/// Keep the current members and runners-up, and freeze candidacies and seats until the root
+	/// origin sets the new ones through [`Call::set_members`].
+	Emergency,
+}
+
+impl Default for FallbackStrategy {
+	fn default() -> Self {
+		FallbackStrategy::KeepMembers
+	}
+}
+
+/// How the seat of a member who renounces or is removed mid-term is filled, see
+/// [`Config::Succession`].
+#[derive(Encode, Decode, Clone, Copy, PartialEq, RuntimeDebug)]
+pub enum SuccessionPolicy {
+	/// Promote the best runner-up, if any, to the vacant seat.
+	PromoteRunnerUp,
+	/// Hold the seat vacant until the next election. The runners-up are kept as such.
+	HoldVacant,
+	/// Hold the seat vacant and start an election in the next block, as if the term was dissolved
+	/// by [`Call::force_new_term`].
+	ByElection,
+}
+
+impl Default for SuccessionPolicy {
+	fn default() -> Self {
+		SuccessionPolicy::PromoteRunnerUp
+	}
+}
+
+/// Something which should be notified of the members ranked by their backing stake whenever they
+/// change, see [`Config::RankedMembers`].
+pub trait ChangeRankedMembers<AccountId, Balance> {
+	/// The members changed. `ranked` are the new members with their backing stake, from the most
+	/// to the least backed.
+	fn change_ranked_members(ranked: &[(AccountId, Balance)]);
+}
+
+impl<AccountId, Balance> ChangeRankedMembers<AccountId, Balance> for () {
+	fn change_ranked_members(_: &[(AccountId, Balance)]) {}
+}
+
+/// Something which elects the members and runners-up of a new term in place of the built-in
+/// sequential phragmen, see [`Config::ElectWinners`].
+pub trait ElectWinners<AccountId> {
+	/// The error returned when the election fails.
+	type Error: fmt::Debug;
+
+	/// Elect the winners of a new term among the current candidates, members and runners-up,
+	/// ranked from best to worst with their backing stake in vote units of
+	/// [`Config::CurrencyToVote`], along with the weight consumed.
+	///
+	/// `None` means that the built-in sequential phragmen should be run instead.
+	fn elect_winners() -> Option<Result<(Vec<(AccountId, ExtendedBalance)>, Weight), Self::Error>>;
+}
+
+impl<AccountId> ElectWinners<AccountId> for () {
+	type Error = ();
+
+	fn elect_winners() -> Option<Result<(Vec<(AccountId, ExtendedBalance)>, Weight), Self::Error>> {
+		None
+	}
+}
+
+/// A solution of a deferred election, computed off-chain.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct RawSolution<AccountId> {
+	/// The winners, i.e. the members and runners-up in any order.
+	pub winners: Vec<AccountId>,
+	/// The stake of each voter distributed among the winners they voted for, in vote units of
+	/// [`Config::CurrencyToVote`].
+	pub assignments: Vec<(AccountId, Vec<(AccountId, ExtendedBalance)>)>,
+	/// The claimed score of the solution.
+	pub score: ElectionScore,
+}
+
+impl<AccountId> RawSolution<AccountId> {
+	/// The number of edges of the solution, i.e. of distributions from a voter to a winner.
+	pub fn edge_count(&self) -> u32 {
+		self.assignments
+			.iter()
+			.fold(0u32, |count, (_, distribution)| count.saturating_add(distribution.len() as u32))
+	}
+}
+
+/// The best solution submitted so far, see [`Call::submit_solution`].
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct SignedSubmission<AccountId, Balance> {
+	/// The submitter.
+	pub who: AccountId,
+	/// The deposit reserved from the submitter.
+	pub deposit: Balance,
+	/// The score of the solution.
+	pub score: ElectionScore,
+	/// The winners of the solution with their total support, ranked from best to worst.
+	pub winners: Vec<(AccountId, ExtendedBalance)>,
+}
+
+/// The outcome an election would have, see [`Pallet::predict_result`].
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct ElectionPrediction<AccountId, Balance> {
+	/// The members with their backing stake, sorted by account id.
+	pub members: Vec<(AccountId, Balance)>,
+	/// The runners-up with their backing stake, sorted by rank (worse to best).
+	pub runners_up: Vec<(AccountId, Balance)>,
+	/// The approval stake of every candidate, i.e. the total stake of the voters who voted for
+	/// them, sorted by account id.
+	pub approvals: Vec<(AccountId, Balance)>,
+}
+
+/// The reason a [`RawSolution`] is not feasible.
+#[derive(Clone, Copy, PartialEq, RuntimeDebug)]
+pub enum FeasibilityError {
+	/// The number of winners differs from the number of seats up for election.
+	WrongWinnerCount,
+	/// A winner is not a candidate of the election, or appears twice.
+	InvalidWinner,
+	/// A voter is not a voter of the election, or appears twice.
+	InvalidVoter,
+	/// A voter of the election who voted for a winner is missing.
+	MissingVoter,
+	/// Some stake is distributed to a non-winner, or to a winner the voter did not vote for.
+	InvalidVote,
+	/// A voter who voted for a winner does not distribute exactly their stake.
+	InvalidStake,
+	/// The claimed score differs from the actual one.
+	InvalidScore,
+}
+
+/// A voter of the election: their account, stake and votes.
+type VoterOf<T> = (
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	Vec<<T as frame_system::Config>::AccountId>,
+);
+
 pub use pallet::*;
 
 #[frame_support::pallet]
@@ -194,6 +433,12 @@ pub mod pallet {
 		/// What to do when the members change.
 		type ChangeMembers: ChangeMembers<Self::AccountId>;
 
+		/// What to do when the members change, given them ranked by their backing stake.
+		///
+		/// This complements [`Config::ChangeMembers`], e.g. for a collective weighting the votes of
+		/// its members by rank.
+		type RankedMembers: ChangeRankedMembers<Self::AccountId, BalanceOf<Self>>;
+
 		/// What to do with genesis members
 		type InitializeMembers: InitializeMembers<Self::AccountId>;
 
@@ -222,6 +467,39 @@ pub mod pallet {
 		/// Handler for the unbalanced reduction when a member has been kicked.
 		type KickedMember: OnUnbalanced<NegativeImbalanceOf<Self>>;
 
+		/// Handler for the slashed deposits of invalid solutions.
+		type BadSolution: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// The portion of the deposit of a defunct voter paid to whoever removes them, see
+		/// [`Call::clean_defunct_voters`].
+		#[pallet::constant]
+		type DefunctVoterReward: Get<Perbill>;
+
+		/// What to do when the phragmen election fails.
+		#[pallet::constant]
+		type Fallback: Get<FallbackStrategy>;
+
+		/// The maximum weight of the phragmen election, as a fraction of the maximum block weight.
+		///
+		/// An election whose weight, as projected by [`WeightInfo::election_phragmen`] before it
+		/// runs, is higher is refused and [`Config::Fallback`] is applied instead. `None` means no
+		/// limit.
+		#[pallet::constant]
+		type MaxElectionWeight: Get<Option<Perbill>>;
+
+		/// What elects the winners of a term which is not deferred, `()` being the built-in
+		/// sequential phragmen.
+		///
+		/// With the `election-provider` feature, `election_provider::ElectionProviderAdapter` elects
+		/// them with any `ElectionProvider`, e.g. an on-chain or a multi-phase one.
+		type ElectWinners: ElectWinners<Self::AccountId>;
+
+		/// How the seat of a member who renounces or is removed mid-term is filled.
+		///
+		/// This is read whenever a seat is vacated, so the runtime can implement it to decide
+		/// depending on its own state, e.g. on a governance decision.
+		type Succession: Get<SuccessionPolicy>;
+
 		/// Number of members to elect.
 		#[pallet::constant]
 		type DesiredMembers: Get<u32>;
@@ -230,12 +508,67 @@ pub mod pallet {
 		#[pallet::constant]
 		type DesiredRunnersUp: Get<u32>;
 
-		/// How long each seat is kept. This defines the next block number at which an election
-		/// round will happen. If set to zero, no elections are ever triggered and the module will
-		/// be in passive mode.
+		/// The maximum number of candidates in a phragmen election.
+		///
+		/// Candidacy submissions are rejected once this many candidates exist.
+		#[pallet::constant]
+		type MaxCandidates: Get<u32>;
+
+		/// The maximum length, in bytes, of the metadata attached to a candidacy.
+		#[pallet::constant]
+		type MaxCandidateMetadata: Get<u32>;
+
+		/// The maximum number of voters to allow in a phragmen election.
+		///
+		/// New voters are rejected once this many voters exist.
+		#[pallet::constant]
+		type MaxVoters: Get<u32>;
+
+		/// The maximum number of candidates a voter can vote for.
+		///
+		/// The first vote of a voter weighs this much in the election of the prime member.
+		#[pallet::constant]
+		type MaxVotesPerVoter: Get<u32>;
+
+		/// How long each seat is kept, the number of blocks between the scheduled starts of two
+		/// election rounds. If set to zero, no elections are ever triggered and the module will be
+		/// in passive mode.
+		///
+		/// This is only the default, which can be overridden by [`Call::set_term_duration`].
 		#[pallet::constant]
 		type TermDuration: Get<Self::BlockNumber>;
 
+		/// The origin which may dissolve the current term, change the term duration or set the
+		/// minimum score of submitted solutions.
+		type TermOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The number of voters to snapshot per block in a deferred election.
+		///
+		/// If zero, the election runs entirely in the `on_initialize` of the block starting the
+		/// new round. Otherwise it is deferred, see [`ElectionPhase`].
+		#[pallet::constant]
+		type VoterSnapshotPerBlock: Get<u32>;
+
+		/// The number of blocks during which solutions can be submitted once the snapshot of a
+		/// deferred election is complete.
+		///
+		/// The election runs in the block after the signed phase. If zero, solutions can only be
+		/// submitted in the block completing the snapshot.
+		#[pallet::constant]
+		type SignedPhase: Get<Self::BlockNumber>;
+
+		/// How much should be reserved to submit a solution.
+		///
+		/// Slashed if the solution is invalid, returned otherwise.
+		#[pallet::constant]
+		type SolutionDeposit: Get<BalanceOf<Self>>;
+
+		/// Provider for the block number against which term boundaries are checked.
+		///
+		/// [`NextElectionAt`] and `TermDuration` are in blocks of this provider. If it skips
+		/// blocks, the round starts in the first block at or after its scheduled start.
+		type BlockNumberProvider: BlockNumberProvider<BlockNumber = Self::BlockNumber>;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -249,14 +582,78 @@ pub mod pallet {
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		/// What to do at the end of each block.
 		///
-		/// Checks if an election needs to happen or not.
-		fn on_initialize(n: T::BlockNumber) -> Weight {
-			let term_duration = T::TermDuration::get();
-			if !term_duration.is_zero() && (n % term_duration).is_zero() {
-				Self::do_phragmen()
-			} else {
-				0
+		/// Checks if an election needs to happen or not, based on the block number of
+		/// [`Config::BlockNumberProvider`], and continues any deferred election.
+		fn on_initialize(_n: T::BlockNumber) -> Weight {
+			if Self::is_migrating_voters() {
+				// elections are deferred until the voters are migrated.
+				return T::DbWeight::get().reads(1)
+			}
+			let now = T::BlockNumberProvider::current_block_number();
+			let phase_weight = T::DbWeight::get().reads(1);
+			match <Phase<T>>::get() {
+				ElectionPhase::Off => {
+					let term_duration = Self::term_duration();
+					let mut weight = T::DbWeight::get().reads(3);
+					let forced = <ForceNewTerm<T>>::get();
+					if forced {
+						<ForceNewTerm<T>>::kill();
+						weight = weight.saturating_add(T::DbWeight::get().writes(1));
+					}
+					let scheduled = <NextElectionAt<T>>::get();
+					let term_ended = !term_duration.is_zero() &&
+						match scheduled {
+							Some(next_election) => now >= next_election,
+							None => {
+								// the term duration was zero so far, the first term starts now.
+								Self::schedule_next_election(now, None, term_duration);
+								weight = weight.saturating_add(T::DbWeight::get().writes(1));
+								false
+							},
+						};
+					if forced || term_ended {
+						let scheduled = if forced { None } else { scheduled };
+						Self::schedule_next_election(now, scheduled, term_duration);
+						let election_weight = if T::VoterSnapshotPerBlock::get().is_zero() {
+							Self::do_phragmen()
+						} else {
+							Self::start_election()
+						};
+						weight
+							.saturating_add(T::DbWeight::get().writes(1))
+							.saturating_add(election_weight)
+					} else {
+						weight
+					}
+				},
+				ElectionPhase::Snapshot(last_key) => Self::snapshot_voters(last_key, now),
+				ElectionPhase::Signed(end) =>
+					if now > end {
+						Self::elect_from_snapshot()
+					} else {
+						0
+					},
+				ElectionPhase::Emergency => 0,
 			}
+			.saturating_add(phase_weight)
+		}
+
+		/// Migrate the voters with the remaining weight of the block, see
+		/// [`migrations::v6::BoundVoters`].
+		fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			LazyMigrationExecutor::<migrations::v6::BoundVoters<T>, T::DbWeight>::execute(
+				remaining_weight,
+			)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
 		}
 	}
 
@@ -272,6 +669,10 @@ pub mod pallet {
 		///   - not be empty.
 		///   - be less than the number of possible candidates. Note that all current members and
 		///     runners-up are also automatically candidates for the next round.
+		///   - be at most [`Config::MaxVotesPerVoter`].
+		///
+		/// A new voter is rejected if [`Config::MaxVoters`] voters exist already. Votes cannot
+		/// change while the voters of a deferred election are snapshotted.
 		///
 		/// If `value` is more than `who`'s total balance, then the maximum of the two is used.
 		///
@@ -296,9 +697,11 @@ pub mod pallet {
 			#[pallet::compact] value: BalanceOf<T>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_voting_frozen(), Error::<T>::VotingFrozen);
 
-			// votes should not be empty and more than `MAXIMUM_VOTE` in any case.
-			ensure!(votes.len() <= MAXIMUM_VOTE, Error::<T>::MaximumVotesExceeded);
+			// votes should not be empty and more than `MaxVotesPerVoter` in any case.
+			let votes = BoundedVec::<_, T::MaxVotesPerVoter>::try_from(votes)
+				.map_err(|_| Error::<T>::MaximumVotesExceeded)?;
 			ensure!(!votes.is_empty(), Error::<T>::NoVotes);
 
 			let candidates_count = <Candidates<T>>::decode_len().unwrap_or(0);
@@ -315,6 +718,14 @@ pub mod pallet {
 
 			ensure!(value > T::Currency::minimum_balance(), Error::<T>::LowBalance);
 
+			let is_new_voter = !Self::is_voter(&who);
+			if is_new_voter {
+				ensure!(
+					CounterForVoters::<T>::get() < T::MaxVoters::get(),
+					Error::<T>::TooManyVoters,
+				);
+			}
+
 			// Reserve bond.
 			let new_deposit = Self::deposit_of(votes.len());
 			let Voter { deposit: old_deposit, .. } = <Voting<T>>::get(&who);
@@ -339,17 +750,22 @@ pub mod pallet {
 			T::Currency::set_lock(T::PalletId::get(), &who, locked_stake, WithdrawReasons::all());
 
 			Voting::<T>::insert(&who, Voter { votes, deposit: new_deposit, stake: locked_stake });
+			if is_new_voter {
+				CounterForVoters::<T>::mutate(|x| x.saturating_inc());
+			}
 			Ok(None.into())
 		}
 
 		/// Remove `origin` as a voter.
 		///
-		/// This removes the lock and returns the deposit.
+		/// This removes the lock and returns the deposit. Voters cannot be removed while the voters
+		/// of a deferred election are snapshotted.
 		///
 		/// The dispatch origin of this call must be signed and be a voter.
 		#[pallet::weight(T::WeightInfo::remove_voter())]
 		pub fn remove_voter(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_voting_frozen(), Error::<T>::VotingFrozen);
 			ensure!(Self::is_voter(&who), Error::<T>::MustBeVoter);
 			Self::do_remove_voter(&who);
 			Ok(None.into())
@@ -358,7 +774,8 @@ pub mod pallet {
 		/// Submit oneself for candidacy. A fixed amount of deposit is recorded.
 		///
 		/// All candidates are wiped at the end of the term. They either become a member/runner-up,
-		/// or leave the system while their deposit is slashed.
+		/// or leave the system while their deposit is slashed. No candidacy can be submitted while a
+		/// deferred election is ongoing.
 		///
 		/// The dispatch origin of this call must be signed.
 		///
@@ -376,9 +793,11 @@ pub mod pallet {
 			#[pallet::compact] candidate_count: u32,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_election_ongoing(), Error::<T>::ElectionOngoing);
 
 			let actual_count = <Candidates<T>>::decode_len().unwrap_or(0);
 			ensure!(actual_count as u32 <= candidate_count, Error::<T>::InvalidWitnessData);
+			ensure!((actual_count as u32) < T::MaxCandidates::get(), Error::<T>::TooManyCandidates);
 
 			let index = Self::is_candidate(&who).err().ok_or(Error::<T>::DuplicatedCandidate)?;
 
@@ -388,7 +807,11 @@ pub mod pallet {
 			T::Currency::reserve(&who, T::CandidacyBond::get())
 				.map_err(|_| Error::<T>::InsufficientCandidateFunds)?;
 
-			<Candidates<T>>::mutate(|c| c.insert(index, (who, T::CandidacyBond::get())));
+			<Candidates<T>>::mutate(|c| {
+				// can't fail: the number of candidates was checked above.
+				let _ok = c.try_insert(index, (who.clone(), T::CandidacyBond::get()));
+				debug_assert!(_ok.is_ok());
+			});
 			Ok(None.into())
 		}
 
@@ -401,9 +824,11 @@ pub mod pallet {
 		///   origin is removed as a runner-up.
 		/// - `origin` is a current member. In this case, the deposit is unreserved and origin is
 		///   removed as a member, consequently not being a candidate for the next round anymore.
-		///   Similar to [`remove_member`](Self::remove_member), if replacement runners exists,
-		///   they are immediately used. If the prime is renouncing, then no prime will exist until
-		///   the next round.
+		///   Similar to [`remove_member`](Self::remove_member), the seat is filled according to
+		///   [`Config::Succession`]. If the prime is renouncing, then no prime will exist until the
+		///   next round.
+		///
+		/// No candidacy can be renounced while a deferred election is ongoing.
 		///
 		/// The dispatch origin of this call must be signed, and have one of the above roles.
 		///
@@ -420,11 +845,12 @@ pub mod pallet {
 			renouncing: Renouncing,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_election_ongoing(), Error::<T>::ElectionOngoing);
 			match renouncing {
 				Renouncing::Member => {
-					let _ = Self::remove_and_replace_member(&who, false)
+					let (_, deposit) = Self::remove_and_replace_member(&who, false)
 						.map_err(|_| Error::<T>::InvalidRenouncing)?;
-					Self::deposit_event(Event::Renounced(who));
+					Self::deposit_event(Event::Renounced { candidate: who, deposit });
 				},
 				Renouncing::RunnerUp => {
 					<RunnersUp<T>>::try_mutate::<_, Error<T>, _>(|runners_up| {
@@ -436,7 +862,8 @@ pub mod pallet {
 						let SeatHolder { deposit, .. } = runners_up.remove(index);
 						let _remainder = T::Currency::unreserve(&who, deposit);
 						debug_assert!(_remainder.is_zero());
-						Self::deposit_event(Event::Renounced(who));
+						<CandidateMetadata<T>>::remove(&who);
+						Self::deposit_event(Event::Renounced { candidate: who, deposit });
 						Ok(())
 					})?;
 				},
@@ -449,7 +876,8 @@ pub mod pallet {
 						let (_removed, deposit) = candidates.remove(index);
 						let _remainder = T::Currency::unreserve(&who, deposit);
 						debug_assert!(_remainder.is_zero());
-						Self::deposit_event(Event::Renounced(who));
+						<CandidateMetadata<T>>::remove(&who);
+						Self::deposit_event(Event::Renounced { candidate: who, deposit });
 						Ok(())
 					})?;
 				},
@@ -460,8 +888,10 @@ pub mod pallet {
 		/// Remove a particular member from the set. This is effective immediately and the bond of
 		/// the outgoing member is slashed.
 		///
-		/// If a runner-up is available, then the best runner-up will be removed and replaces the
-		/// outgoing member. Otherwise, a new phragmen election is started.
+		/// The seat is filled according to [`Config::Succession`]. With
+		/// [`SuccessionPolicy::PromoteRunnerUp`], if a runner-up is available, then the best
+		/// runner-up will be removed and replaces the outgoing member. Otherwise, a new phragmen
+		/// election is started. No member can be removed while a deferred election is ongoing.
 		///
 		/// The dispatch origin of this call must be root.
 		///
@@ -469,7 +899,7 @@ pub mod pallet {
 		///
 		/// # <weight>
 		/// If we have a replacement, we use a small weight. Else, since this is a root call and
-		/// will go into phragmen, we assume full block for now.
+		/// may go into phragmen, we assume full block for now.
 		/// # </weight>
 		#[pallet::weight(if *has_replacement {
 			T::WeightInfo::remove_member_with_replacement()
@@ -483,8 +913,11 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			ensure_root(origin)?;
 			let who = T::Lookup::lookup(who)?;
+			ensure!(!Self::is_election_ongoing(), Error::<T>::ElectionOngoing);
 
-			let will_have_replacement = <RunnersUp<T>>::decode_len().map_or(false, |l| l > 0);
+			let succession = T::Succession::get();
+			let will_have_replacement = succession == SuccessionPolicy::PromoteRunnerUp &&
+				<RunnersUp<T>>::decode_len().map_or(false, |l| l > 0);
 			if will_have_replacement != has_replacement {
 				// In both cases, we will change more weight than need. Refund and abort.
 				return Err(Error::<T>::InvalidReplacement.with_weight(
@@ -493,11 +926,11 @@ pub mod pallet {
 				))
 			}
 
-			let had_replacement = Self::remove_and_replace_member(&who, true)?;
+			let (had_replacement, deposit) = Self::remove_and_replace_member(&who, true)?;
 			debug_assert_eq!(has_replacement, had_replacement);
-			Self::deposit_event(Event::MemberKicked(who.clone()));
+			Self::deposit_event(Event::MemberKicked { member: who.clone(), deposit });
 
-			if !had_replacement {
+			if !had_replacement && succession == SuccessionPolicy::PromoteRunnerUp {
 				Self::do_phragmen();
 			}
 
@@ -505,27 +938,299 @@ pub mod pallet {
 			Ok(None.into())
 		}
 
-		/// Clean all voters who are defunct (i.e. they do not serve any purpose at all). The
-		/// deposit of the removed voters are returned.
+		/// Clean voters who are defunct (i.e. they do not serve any purpose at all). The caller
+		/// is rewarded with [`Config::DefunctVoterReward`] of the deposit of each removed voter,
+		/// the rest of which is returned to the voter.
 		///
-		/// This is an root function to be used only for cleaning the state.
+		/// The voters are checked page by page: at most `num_voters` voters are checked, starting
+		/// after the last one checked by the previous call, and at most `num_defunct` of them are
+		/// removed. Once all voters are checked, the next call starts over. Voters cannot be
+		/// removed while the voters of a deferred election are snapshotted.
 		///
-		/// The dispatch origin of this call must be root.
+		/// The dispatch origin of this call must be signed.
 		///
 		/// # <weight>
-		/// The total number of voters and those that are defunct must be provided as witness data.
+		/// The maximum number of voters to check and to remove bound the weight, the unused part
+		/// of which is refunded.
 		/// # </weight>
-		#[pallet::weight(T::WeightInfo::clean_defunct_voters(*_num_voters, *_num_defunct))]
+		#[pallet::weight(T::WeightInfo::clean_defunct_voters(*num_voters, *num_defunct))]
 		pub fn clean_defunct_voters(
 			origin: OriginFor<T>,
-			_num_voters: u32,
-			_num_defunct: u32,
+			num_voters: u32,
+			num_defunct: u32,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_voting_frozen(), Error::<T>::VotingFrozen);
+
+			let mut voters = match <DefunctVoterCursor<T>>::get() {
+				Some(cursor) => <Voting<T>>::iter_from(cursor),
+				None => <Voting<T>>::iter(),
+			};
+			let mut checked = 0u32;
+			let mut removed = 0u32;
+			let mut exhausted = false;
+			while checked < num_voters && removed < num_defunct {
+				match voters.next() {
+					Some((voter, Voter { votes, .. })) => {
+						checked += 1;
+						if Self::is_defunct_voter(&votes) {
+							Self::do_remove_defunct_voter(&voter, &who);
+							removed += 1;
+						}
+					},
+					None => {
+						exhausted = true;
+						break
+					},
+				}
+			}
+
+			if exhausted {
+				<DefunctVoterCursor<T>>::kill();
+			} else {
+				<DefunctVoterCursor<T>>::put(voters.last_raw_key().to_vec());
+			}
+
+			Ok(Some(T::WeightInfo::clean_defunct_voters(checked, removed)).into())
+		}
+
+		/// Set the members and runners-up after an election failed with
+		/// [`FallbackStrategy::Emergency`].
+		///
+		/// `winners` are ranked from best to worst and must be candidates of the failed election.
+		/// The first [`Config::DesiredMembers`] of them become members, the rest runners-up. They
+		/// are backed by their approval stake, and those without any are dropped. The other
+		/// candidates are slashed, as after any election.
+		///
+		/// The dispatch origin of this call must be root.
+		#[pallet::weight(T::BlockWeights::get().max_block)]
+		pub fn set_members(
+			origin: OriginFor<T>,
+			winners: Vec<T::AccountId>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			ensure!(<Phase<T>>::get() == ElectionPhase::Emergency, Error::<T>::NoEmergency);
+
+			let candidates_and_deposit = <SnapshotCandidates<T>>::get();
+			let num_to_elect = T::DesiredMembers::get().saturating_add(T::DesiredRunnersUp::get());
+			ensure!(winners.len() as u32 <= num_to_elect, Error::<T>::InvalidMembers);
+			let mut unique = BTreeSet::new();
+			ensure!(
+				winners
+					.iter()
+					.all(|w| unique.insert(w) && candidates_and_deposit.iter().any(|(c, _)| c == w)),
+				Error::<T>::InvalidMembers,
+			);
+
+			<SnapshotCandidates<T>>::kill();
+			let voters_and_stakes = Self::take_snapshotted_voters();
+			<Phase<T>>::put(ElectionPhase::Off);
+
+			let approvals = Self::approval_stakes(&candidates_and_deposit, &voters_and_stakes);
+			let winners = winners
+				.into_iter()
+				.map(|w| {
+					let approval = approvals.get(&w).copied().unwrap_or_default();
+					(w, approval)
+				})
+				.collect::<Vec<_>>();
+			Self::new_term(&candidates_and_deposit, voters_and_stakes, winners);
+
+			Ok(None.into())
+		}
+
+		/// Submit a solution of the ongoing deferred election, computed off-chain. A fixed amount
+		/// of deposit is reserved.
+		///
+		/// The solution is only accepted during the signed phase, and if its claimed score is
+		/// better than the one of the best solution so far, or than [`MinimumUntrustedScore`] if
+		/// there is none. It is then checked against the snapshot and its score evaluated. Every
+		/// voter of the snapshot who voted for a winner must distribute their whole stake among
+		/// the winners they voted for:
+		///
+		/// - If it is valid, it replaces the best solution so far, whose submitter gets their
+		///   deposit back.
+		/// - Otherwise, the deposit is slashed.
+		///
+		/// The best solution is used once the signed phase is over, and its deposit is returned.
+		///
+		/// The dispatch origin of this call must be signed.
+		///
+		/// # <weight>
+		/// The whole snapshot is read to check the solution, hence a snapshot at the bounds of the
+		/// configuration is charged, or the edges of the solution if there are more.
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::submit_solution(
+			T::MaxCandidates::get(),
+			T::MaxVoters::get(),
+			T::MaxVoters::get()
+				.saturating_mul(T::MaxVotesPerVoter::get())
+				.max(solution.edge_count()),
+		))]
+		pub fn submit_solution(
+			origin: OriginFor<T>,
+			solution: RawSolution<T::AccountId>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				matches!(<Phase<T>>::get(), ElectionPhase::Signed(_)),
+				Error::<T>::SignedPhaseClosed,
+			);
+			let best = <BestSubmission<T>>::get();
+			let score_to_beat =
+				best.as_ref().map(|best| best.score).or_else(<MinimumUntrustedScore<T>>::get);
+			if let Some(score_to_beat) = score_to_beat {
+				ensure!(
+					is_score_better(solution.score, score_to_beat, Perbill::zero()),
+					Error::<T>::WeakSolution,
+				);
+			}
+
+			let deposit = T::SolutionDeposit::get();
+			T::Currency::reserve(&who, deposit)
+				.map_err(|_| Error::<T>::InsufficientSolutionFunds)?;
+
+			match Self::feasibility_check(&solution) {
+				Ok(winners) => {
+					if let Some(previous) = best {
+						T::Currency::unreserve(&previous.who, previous.deposit);
+					}
+					<BestSubmission<T>>::put(SignedSubmission {
+						who: who.clone(),
+						deposit,
+						score: solution.score,
+						winners,
+					});
+					Self::deposit_event(Event::SolutionStored { submitter: who, deposit });
+				},
+				Err(e) => {
+					log::warn!(
+						target: "runtime::elections-phragmen",
+						"Slashing infeasible solution of {:?} [{:?}].",
+						who,
+						e,
+					);
+					let (imbalance, remainder) = T::Currency::slash_reserved(&who, deposit);
+					T::BadSolution::on_unbalanced(imbalance);
+					Self::deposit_event(Event::SolutionSlashed {
+						submitter: who,
+						amount: deposit.saturating_sub(remainder),
+						deposit,
+					});
+				},
+			}
+
+			Ok(None.into())
+		}
+
+		/// Refund the part of the deposit of `origin` which its votes no longer require, e.g.
+		/// after [`Config::VotingBondBase`] or [`Config::VotingBondFactor`] were lowered.
+		///
+		/// The dispatch origin of this call must be signed and be a voter.
+		#[pallet::weight(T::WeightInfo::refund_voter_deposit())]
+		pub fn refund_voter_deposit(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_voter(&who), Error::<T>::MustBeVoter);
+
+			let mut voter = <Voting<T>>::get(&who);
+			let required = Self::deposit_of(voter.votes.len());
+			let excess = voter.deposit.saturating_sub(required);
+			ensure!(!excess.is_zero(), Error::<T>::NothingToRefund);
+
+			let _remainder = T::Currency::unreserve(&who, excess);
+			debug_assert!(_remainder.is_zero());
+			voter.deposit = required;
+			<Voting<T>>::insert(&who, voter);
+
+			Self::deposit_event(Event::VoterDepositRefunded { voter: who, amount: excess });
+			Ok(None.into())
+		}
+
+		/// Dissolve the current term: an election starts in the next block, regardless of the
+		/// term duration, and its winners replace the members and runners-up.
+		///
+		/// The current members and runners-up keep their seats until then, and are candidates of
+		/// the election as usual. No term can be dissolved while a deferred election is ongoing.
+		///
+		/// The dispatch origin of this call must be [`Config::TermOrigin`].
+		#[pallet::weight(T::WeightInfo::force_new_term())]
+		pub fn force_new_term(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			T::TermOrigin::ensure_origin(origin)?;
+			ensure!(!Self::is_election_ongoing(), Error::<T>::ElectionOngoing);
+
+			<ForceNewTerm<T>>::put(true);
+			Self::deposit_event(Event::TermDissolved);
+			Ok(None.into())
+		}
+
+		/// Set the term duration to `duration` blocks, overriding [`Config::TermDuration`].
+		///
+		/// The current term is rescheduled to end `duration` blocks from now, and the following
+		/// terms last `duration` blocks each. If zero, no elections are triggered anymore.
+		///
+		/// The dispatch origin of this call must be [`Config::TermOrigin`].
+		#[pallet::weight(T::WeightInfo::set_term_duration())]
+		pub fn set_term_duration(
+			origin: OriginFor<T>,
+			#[pallet::compact] duration: T::BlockNumber,
+		) -> DispatchResultWithPostInfo {
+			T::TermOrigin::ensure_origin(origin)?;
+
+			<TermDurationOverride<T>>::put(duration);
+			let now = T::BlockNumberProvider::current_block_number();
+			Self::schedule_next_election(now, None, duration);
+			Self::deposit_event(Event::TermDurationSet { duration });
+			Ok(None.into())
+		}
+
+		/// Describe the candidacy of `origin` to voters with `metadata`, e.g. with a name or a
+		/// link, replacing any previous one, or remove it if `metadata` is empty.
+		///
+		/// The metadata is kept in [`CandidateMetadata`] for as long as `origin` is a candidate or
+		/// holds a seat. It is at most [`Config::MaxCandidateMetadata`] bytes long.
+		///
+		/// The dispatch origin of this call must be signed and be a candidate, member or
+		/// runner-up.
+		#[pallet::weight(T::WeightInfo::set_candidate_metadata())]
+		pub fn set_candidate_metadata(
+			origin: OriginFor<T>,
+			metadata: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let metadata = BoundedVec::<_, T::MaxCandidateMetadata>::try_from(metadata)
+				.map_err(|_| Error::<T>::MetadataTooLong)?;
+			ensure!(
+				Self::is_candidate(&who).is_ok() ||
+					Self::is_member(&who) ||
+					Self::is_runner_up(&who),
+				Error::<T>::MustBeCandidateOrSeatHolder,
+			);
+
+			if metadata.is_empty() {
+				<CandidateMetadata<T>>::remove(&who);
+			} else {
+				<CandidateMetadata<T>>::insert(&who, metadata);
+			}
+			Self::deposit_event(Event::CandidateMetadataSet { candidate: who });
+			Ok(None.into())
+		}
+
+		/// Set the score which submitted solutions must beat, if no better solution was submitted
+		/// yet, or remove it if `None`.
+		///
+		/// Solutions are checked for feasibility only, hence without a minimum score, the first
+		/// solution of an election can elect any feasible set of winners.
+		///
+		/// The dispatch origin of this call must be [`Config::TermOrigin`].
+		#[pallet::weight(T::WeightInfo::set_minimum_untrusted_score())]
+		pub fn set_minimum_untrusted_score(
+			origin: OriginFor<T>,
+			score: Option<ElectionScore>,
 		) -> DispatchResultWithPostInfo {
-			let _ = ensure_root(origin)?;
-			<Voting<T>>::iter()
-				.filter(|(_, x)| Self::is_defunct_voter(&x.votes))
-				.for_each(|(dv, _)| Self::do_remove_voter(&dv));
+			T::TermOrigin::ensure_origin(origin)?;
 
+			<MinimumUntrustedScore<T>>::set(score);
+			Self::deposit_event(Event::MinimumUntrustedScoreSet { score });
 			Ok(None.into())
 		}
 	}
@@ -534,33 +1239,74 @@ pub mod pallet {
 	#[pallet::metadata(
 		<T as frame_system::Config>::AccountId = "AccountId",
 		BalanceOf<T> = "Balance",
+		<T as frame_system::Config>::BlockNumber = "BlockNumber",
 		Vec<(<T as frame_system::Config>::AccountId, BalanceOf<T>)> = "Vec<(AccountId, Balance)>",
 	)]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// A new term with \[new_members\]. This indicates that enough candidates existed to run
-		/// the election, not that enough have has been elected. The inner value must be examined
-		/// for this purpose. A `NewTerm(\[\])` indicates that some candidates got their bond
-		/// slashed and none were elected, whilst `EmptyTerm` means that no candidates existed to
-		/// begin with.
-		NewTerm(Vec<(<T as frame_system::Config>::AccountId, BalanceOf<T>)>),
-		/// No (or not enough) candidates existed for this round. This is different from
-		/// `NewTerm(\[\])`. See the description of `NewTerm`.
+		/// A new term with the new members and their backing stake. This indicates that enough
+		/// candidates existed to run the election, not that enough have has been elected. The
+		/// members must be examined for this purpose. A `NewTerm` without members indicates that
+		/// some candidates got their bond slashed and none were elected, whilst `EmptyTerm` means
+		/// that no candidates existed to begin with.
+		NewTerm { members_with_stake: Vec<(<T as frame_system::Config>::AccountId, BalanceOf<T>)> },
+		/// No (or not enough) candidates existed for this round. This is different from a
+		/// `NewTerm` without members. See the description of `NewTerm`.
 		EmptyTerm,
-		/// Internal error happened while trying to perform election.
-		ElectionError,
-		/// A \[member\] has been removed. This should always be followed by either `NewTerm` or
-		/// `EmptyTerm`.
-		MemberKicked(<T as frame_system::Config>::AccountId),
-		/// Someone has renounced their candidacy.
-		Renounced(<T as frame_system::Config>::AccountId),
-		/// A \[candidate\] was slashed by \[amount\] due to failing to obtain a seat as member or
-		/// runner-up.
+		/// The election failed for `reason`. This is always followed by the fallback, see
+		/// [`Config::Fallback`].
+		ElectionError { reason: ElectionErrorReason },
+		/// A `member` has been removed, their `deposit` being slashed, see `SeatHolderSlashed`.
+		/// This should always be followed by either `NewTerm` or `EmptyTerm`.
+		MemberKicked { member: <T as frame_system::Config>::AccountId, deposit: BalanceOf<T> },
+		/// A `candidate` has renounced their candidacy or seat, and got back their `deposit`.
+		Renounced { candidate: <T as frame_system::Config>::AccountId, deposit: BalanceOf<T> },
+		/// A `candidate` was slashed by `amount` out of their `deposit` due to failing to obtain a
+		/// seat as member or runner-up.
 		///
 		/// Note that old members and runners-up are also candidates.
-		CandidateSlashed(<T as frame_system::Config>::AccountId, BalanceOf<T>),
-		/// A \[seat holder\] was slashed by \[amount\] by being forcefully removed from the set.
-		SeatHolderSlashed(<T as frame_system::Config>::AccountId, BalanceOf<T>),
+		CandidateSlashed {
+			candidate: <T as frame_system::Config>::AccountId,
+			amount: BalanceOf<T>,
+			deposit: BalanceOf<T>,
+		},
+		/// A `seat_holder` was slashed by `amount` out of their `deposit` by being forcefully
+		/// removed from the set.
+		SeatHolderSlashed {
+			seat_holder: <T as frame_system::Config>::AccountId,
+			amount: BalanceOf<T>,
+			deposit: BalanceOf<T>,
+		},
+		/// A deferred election started. Its voters are snapshotted in the next blocks.
+		ElectionStarted,
+		/// The solution of `submitter` is the best one so far. They reserved `deposit` for it.
+		SolutionStored { submitter: <T as frame_system::Config>::AccountId, deposit: BalanceOf<T> },
+		/// A `submitter` was slashed by `amount` out of their `deposit` due to submitting an
+		/// invalid solution.
+		SolutionSlashed {
+			submitter: <T as frame_system::Config>::AccountId,
+			amount: BalanceOf<T>,
+			deposit: BalanceOf<T>,
+		},
+		/// The election failed, the members and runners-up are kept. See
+		/// [`FallbackStrategy::KeepMembers`].
+		FallbackMembersKept,
+		/// The election failed, the winners are elected by approval instead. This is always
+		/// followed by `NewTerm`. See [`FallbackStrategy::Approval`].
+		FallbackApprovalElection,
+		/// The election failed, the members and runners-up are kept until they are set by
+		/// [`Call::set_members`]. See [`FallbackStrategy::Emergency`].
+		FallbackEmergency,
+		/// A `voter` got back `amount` of their deposit which their votes no longer required.
+		VoterDepositRefunded { voter: <T as frame_system::Config>::AccountId, amount: BalanceOf<T> },
+		/// The current term was dissolved, an election starts in the next block.
+		TermDissolved,
+		/// The term duration was set to `duration` blocks.
+		TermDurationSet { duration: <T as frame_system::Config>::BlockNumber },
+		/// The metadata of the candidacy of `candidate` was set.
+		CandidateMetadataSet { candidate: <T as frame_system::Config>::AccountId },
+		/// The minimum score of submitted solutions was set to `score`, or removed if `None`.
+		MinimumUntrustedScoreSet { score: Option<ElectionScore> },
 	}
 
 	#[deprecated(note = "use `Event` instead")]
@@ -602,6 +1348,30 @@ pub mod pallet {
 		InvalidRenouncing,
 		/// Prediction regarding replacement after member removal is wrong.
 		InvalidReplacement,
+		/// Too many candidates have been created.
+		TooManyCandidates,
+		/// Too many voters exist already.
+		TooManyVoters,
+		/// Candidacies and seats are frozen while a deferred election is ongoing.
+		ElectionOngoing,
+		/// Solutions can only be submitted during the signed phase.
+		SignedPhaseClosed,
+		/// The claimed score is not better than the one of the best solution so far.
+		WeakSolution,
+		/// Submitter does not have enough funds for the solution deposit.
+		InsufficientSolutionFunds,
+		/// Members can only be set after an election failed with the emergency fallback.
+		NoEmergency,
+		/// The members to set are not distinct candidates of the failed election, or too many.
+		InvalidMembers,
+		/// The deposit of the voter is not more than what their votes require.
+		NothingToRefund,
+		/// The metadata of the candidacy is longer than [`Config::MaxCandidateMetadata`].
+		MetadataTooLong,
+		/// Votes cannot change while the voters of a deferred election are snapshotted.
+		VotingFrozen,
+		/// Only candidates, members and runners-up can describe their candidacy.
+		MustBeCandidateOrSeatHolder,
 	}
 
 	/// The current elected members.
@@ -609,8 +1379,11 @@ pub mod pallet {
 	/// Invariant: Always sorted based on account id.
 	#[pallet::storage]
 	#[pallet::getter(fn members)]
-	pub type Members<T: Config> =
-		StorageValue<_, Vec<SeatHolder<T::AccountId, BalanceOf<T>>>, ValueQuery>;
+	pub type Members<T: Config> = StorageValue<
+		_,
+		BoundedVec<SeatHolder<T::AccountId, BalanceOf<T>>, T::DesiredMembers>,
+		ValueQuery,
+	>;
 
 	/// The current reserved runners-up.
 	///
@@ -618,8 +1391,11 @@ pub mod pallet {
 	/// last (i.e. _best_) runner-up will be replaced.
 	#[pallet::storage]
 	#[pallet::getter(fn runners_up)]
-	pub type RunnersUp<T: Config> =
-		StorageValue<_, Vec<SeatHolder<T::AccountId, BalanceOf<T>>>, ValueQuery>;
+	pub type RunnersUp<T: Config> = StorageValue<
+		_,
+		BoundedVec<SeatHolder<T::AccountId, BalanceOf<T>>, T::DesiredRunnersUp>,
+		ValueQuery,
+	>;
 
 	/// The present candidate list. A current member or runner-up can never enter this vector
 	/// and is always implicitly assumed to be a candidate.
@@ -629,7 +1405,22 @@ pub mod pallet {
 	/// Invariant: Always sorted based on account id.
 	#[pallet::storage]
 	#[pallet::getter(fn candidates)]
-	pub type Candidates<T: Config> = StorageValue<_, Vec<(T::AccountId, BalanceOf<T>)>, ValueQuery>;
+	pub type Candidates<T: Config> =
+		StorageValue<_, BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxCandidates>, ValueQuery>;
+
+	/// The metadata attached by candidates to their candidacy, kept for as long as they are a
+	/// candidate, member or runner-up.
+	///
+	/// TWOX-NOTE: SAFE as `AccountId` is a crypto hash.
+	#[pallet::storage]
+	#[pallet::getter(fn candidate_metadata)]
+	pub type CandidateMetadata<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<u8, T::MaxCandidateMetadata>,
+		OptionQuery,
+	>;
 
 	/// The total number of vote rounds that have happened, excluding the upcoming one.
 	#[pallet::storage]
@@ -641,8 +1432,70 @@ pub mod pallet {
 	/// TWOX-NOTE: SAFE as `AccountId` is a crypto hash.
 	#[pallet::storage]
 	#[pallet::getter(fn voting)]
-	pub type Voting<T: Config> =
-		StorageMap<_, Twox64Concat, T::AccountId, Voter<T::AccountId, BalanceOf<T>>, ValueQuery>;
+	pub type Voting<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Voter<T::AccountId, BalanceOf<T>, T::MaxVotesPerVoter>,
+		ValueQuery,
+	>;
+
+	/// The number of voters in [`Voting`].
+	///
+	/// When updating [`Voting`], you must also update this counter.
+	#[pallet::storage]
+	pub type CounterForVoters<T> = StorageValue<_, u32, ValueQuery>;
+
+	/// The phase of the ongoing deferred election, if any.
+	#[pallet::storage]
+	#[pallet::getter(fn phase)]
+	pub type Phase<T: Config> = StorageValue<_, ElectionPhase<T::BlockNumber>, ValueQuery>;
+
+	/// The candidates of the ongoing deferred election, with their deposit.
+	#[pallet::storage]
+	pub type SnapshotCandidates<T: Config> =
+		StorageValue<_, Vec<(T::AccountId, BalanceOf<T>)>, ValueQuery>;
+
+	/// The voters of the ongoing deferred election snapshotted so far, with their stake and votes,
+	/// by page. Each page holds the voters snapshotted in one block.
+	#[pallet::storage]
+	pub type SnapshotVoters<T: Config> =
+		StorageMap<_, Twox64Concat, u32, Vec<VoterOf<T>>, ValueQuery>;
+
+	/// The number of pages in [`SnapshotVoters`].
+	#[pallet::storage]
+	pub type SnapshotVoterPages<T> = StorageValue<_, u32, ValueQuery>;
+
+	/// The raw key of [`Voting`] after which [`Call::clean_defunct_voters`] resumes, if any.
+	#[pallet::storage]
+	pub type DefunctVoterCursor<T> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+	/// The term duration set by [`Call::set_term_duration`], overriding [`Config::TermDuration`].
+	#[pallet::storage]
+	pub type TermDurationOverride<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	/// The block of [`Config::BlockNumberProvider`] from which the next term starts.
+	///
+	/// Nothing is scheduled while the term duration is zero.
+	#[pallet::storage]
+	pub type NextElectionAt<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	/// Whether the current term was dissolved by [`Call::force_new_term`], in which case an
+	/// election starts in the next block.
+	#[pallet::storage]
+	pub type ForceNewTerm<T> = StorageValue<_, bool, ValueQuery>;
+
+	/// The best solution submitted during the signed phase of the ongoing deferred election.
+	#[pallet::storage]
+	#[pallet::getter(fn best_submission)]
+	pub type BestSubmission<T: Config> =
+		StorageValue<_, SignedSubmission<T::AccountId, BalanceOf<T>>, OptionQuery>;
+
+	/// The score which a submitted solution must beat if no solution was submitted yet, set by
+	/// [`Call::set_minimum_untrusted_score`].
+	#[pallet::storage]
+	#[pallet::getter(fn minimum_untrusted_score)]
+	pub type MinimumUntrustedScore<T> = StorageValue<_, ElectionScore, OptionQuery>;
 
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
@@ -663,6 +1516,10 @@ pub mod pallet {
 				self.members.len() as u32 <= T::DesiredMembers::get(),
 				"Cannot accept more than DesiredMembers genesis member",
 			);
+			assert!(
+				self.members.len() as u32 <= T::MaxVoters::get(),
+				"Cannot accept more than MaxVoters genesis member",
+			);
 			let members = self
 				.members
 				.iter()
@@ -680,14 +1537,16 @@ pub mod pallet {
 						match members.binary_search_by(|m| m.who.cmp(member)) {
 							Ok(_) =>
 								panic!("Duplicate member in elections-phragmen genesis: {}", member),
-							Err(pos) => members.insert(
-								pos,
-								SeatHolder {
-									who: member.clone(),
-									stake: *stake,
-									deposit: Zero::zero(),
-								},
-							),
+							Err(pos) => members
+								.try_insert(
+									pos,
+									SeatHolder {
+										who: member.clone(),
+										stake: *stake,
+										deposit: Zero::zero(),
+									},
+								)
+								.expect("number of genesis members was checked above; qed"),
 						}
 					});
 
@@ -695,10 +1554,13 @@ pub mod pallet {
 					// they have any lock. NOTE: this means that we will still try to remove a lock once
 					// this genesis voter is removed, and for now it is okay because remove_lock is noop
 					// if lock is not there.
+					let votes = BoundedVec::try_from(vec![member.clone()])
+						.expect("MaxVotesPerVoter must allow at least one vote; qed");
 					<Voting<T>>::insert(
 						&member,
-						Voter { votes: vec![member.clone()], stake: *stake, deposit: Zero::zero() },
+						Voter { votes, stake: *stake, deposit: Zero::zero() },
 					);
+					CounterForVoters::<T>::mutate(|x| x.saturating_inc());
 
 					member.clone()
 				})
@@ -706,6 +1568,11 @@ pub mod pallet {
 
 			// report genesis members to upstream, if any.
 			T::InitializeMembers::initialize_members(&members);
+			T::RankedMembers::change_ranked_members(&<Pallet<T>>::ranked_members());
+
+			// the first term starts at genesis.
+			let now = T::BlockNumberProvider::current_block_number();
+			<Pallet<T>>::schedule_next_election(now, None, T::TermDuration::get());
 		}
 	}
 }
@@ -717,14 +1584,18 @@ impl<T: Config> Pallet<T> {
 			.saturating_add(T::VotingBondFactor::get().saturating_mul((count as u32).into()))
 	}
 
-	/// Attempts to remove a member `who`. If a runner-up exists, it is used as the replacement.
+	/// Attempts to remove a member `who`. If a runner-up exists and [`Config::Succession`] is
+	/// [`SuccessionPolicy::PromoteRunnerUp`], it is used as the replacement. With
+	/// [`SuccessionPolicy::ByElection`], an election is scheduled for the next block instead.
 	///
 	/// Returns:
 	///
-	/// - `Ok(true)` if the member was removed and a replacement was found.
-	/// - `Ok(false)` if the member was removed and but no replacement was found.
+	/// - `Ok((true, deposit))` if the member was removed and a replacement was found.
+	/// - `Ok((false, deposit))` if the member was removed and but no replacement was found.
 	/// - `Err(_)` if the member was no found.
 	///
+	/// `deposit` is the deposit of the removed member.
+	///
 	/// Both `Members` and `RunnersUp` storage is updated accordingly. `T::ChangeMember` is called
 	/// if needed. If `slash` is true, the deposit of the potentially removed member is slashed,
 	/// else, it is unreserved.
@@ -733,12 +1604,15 @@ impl<T: Config> Pallet<T> {
 	///
 	/// This function attempts to preserve the prime. If the removed members is not the prime, it is
 	/// set again via [`Config::ChangeMembers`].
-	fn remove_and_replace_member(who: &T::AccountId, slash: bool) -> Result<bool, DispatchError> {
+	fn remove_and_replace_member(
+		who: &T::AccountId,
+		slash: bool,
+	) -> Result<(bool, BalanceOf<T>), DispatchError> {
 		// closure will return:
 		// - `Ok(Option(replacement))` if member was removed and replacement was replaced.
 		// - `Ok(None)` if member was removed but no replacement was found
 		// - `Err(_)` if who is not a member.
-		let maybe_replacement = <Members<T>>::try_mutate::<_, Error<T>, _>(|members| {
+		let (maybe_replacement, deposit) = <Members<T>>::try_mutate::<_, Error<T>, _>(|members| {
 			let remove_index = members
 				.binary_search_by(|m| m.who.cmp(who))
 				.map_err(|_| Error::<T>::NotMember)?;
@@ -747,19 +1621,37 @@ impl<T: Config> Pallet<T> {
 
 			// slash or unreserve
 			if slash {
-				let (imbalance, _remainder) = T::Currency::slash_reserved(who, removed.deposit);
-				debug_assert!(_remainder.is_zero());
+				let (imbalance, remainder) = T::Currency::slash_reserved(who, removed.deposit);
+				debug_assert!(remainder.is_zero());
 				T::LoserCandidate::on_unbalanced(imbalance);
-				Self::deposit_event(Event::SeatHolderSlashed(who.clone(), removed.deposit));
+				Self::cap_voter_stake(who);
+				Self::deposit_event(Event::SeatHolderSlashed {
+					seat_holder: who.clone(),
+					amount: removed.deposit.saturating_sub(remainder),
+					deposit: removed.deposit,
+				});
 			} else {
 				T::Currency::unreserve(who, removed.deposit);
 			}
-
-			let maybe_next_best = <RunnersUp<T>>::mutate(|r| r.pop()).map(|next_best| {
+			<CandidateMetadata<T>>::remove(who);
+
+			// the best runner-up is the last one.
+			let maybe_best_runner_up = match T::Succession::get() {
+				SuccessionPolicy::PromoteRunnerUp =>
+					<RunnersUp<T>>::mutate(|r| r.len().checked_sub(1).map(|best| r.remove(best))),
+				SuccessionPolicy::HoldVacant => None,
+				SuccessionPolicy::ByElection => {
+					<ForceNewTerm<T>>::put(true);
+					None
+				},
+			};
+			let maybe_next_best = maybe_best_runner_up.map(|next_best| {
 				// defensive-only: Members and runners-up are disjoint. This will always be err and
 				// give us an index to insert.
 				if let Err(index) = members.binary_search_by(|m| m.who.cmp(&next_best.who)) {
-					members.insert(index, next_best.clone());
+					// can't fail: a member was removed above.
+					let _ok = members.try_insert(index, next_best.clone());
+					debug_assert!(_ok.is_ok());
 				} else {
 					// overlap. This can never happen. If so, it seems like our intended replacement
 					// is already a member, so not much more to do.
@@ -770,7 +1662,7 @@ impl<T: Config> Pallet<T> {
 				}
 				next_best
 			});
-			Ok(maybe_next_best)
+			Ok((maybe_next_best, removed.deposit))
 		})?;
 
 		let remaining_member_ids_sorted =
@@ -804,8 +1696,9 @@ impl<T: Config> Pallet<T> {
 				T::ChangeMembers::set_prime(Some(current_prime));
 			}
 		}
+		T::RankedMembers::change_ranked_members(&Self::ranked_members());
 
-		Ok(return_value)
+		Ok((return_value, deposit))
 	}
 
 	/// Check if `who` is a candidate. It returns the insert index if the element does not exists as
@@ -834,6 +1727,15 @@ impl<T: Config> Pallet<T> {
 		Self::members().into_iter().map(|m| m.who).collect::<Vec<T::AccountId>>()
 	}
 
+	/// Get the members with their backing stake, from the most to the least backed. Ties are
+	/// broken by account id.
+	pub fn ranked_members() -> Vec<(T::AccountId, BalanceOf<T>)> {
+		let mut ranked = Self::members().into_iter().map(|m| (m.who, m.stake)).collect::<Vec<_>>();
+		// members are sorted by account id, and the sort is stable.
+		ranked.sort_by(|(_, a), (_, b)| b.cmp(a));
+		ranked
+	}
+
 	/// Get a concatenation of previous members and runners-up and their deposits.
 	///
 	/// These accounts are essentially treated as candidates.
@@ -859,35 +1761,430 @@ impl<T: Config> Pallet<T> {
 
 	/// Remove a certain someone as a voter.
 	fn do_remove_voter(who: &T::AccountId) {
-		let Voter { deposit, .. } = <Voting<T>>::take(who);
-
-		// remove storage, lock and unreserve.
-		T::Currency::remove_lock(T::PalletId::get(), who);
-
+		let Voter { deposit, .. } = Self::take_voter(who);
 		// NOTE: we could check the deposit amount before removing and skip if zero, but it will be
 		// a noop anyhow.
 		let _remainder = T::Currency::unreserve(who, deposit);
 		debug_assert!(_remainder.is_zero());
 	}
 
-	/// Run the phragmen election with all required side processes and state updates, if election
-	/// succeeds. Else, it will emit an `ElectionError` event.
+	/// Remove the defunct voter `who`, paying [`Config::DefunctVoterReward`] of their deposit to
+	/// `reporter` and returning the rest.
+	fn do_remove_defunct_voter(who: &T::AccountId, reporter: &T::AccountId) {
+		let Voter { deposit, .. } = Self::take_voter(who);
+		let reward = T::DefunctVoterReward::get() * deposit;
+		// the reward can only be less than the deposit.
+		let _ = T::Currency::repatriate_reserved(who, reporter, reward, BalanceStatus::Free);
+		let _remainder = T::Currency::unreserve(who, deposit.saturating_sub(reward));
+		debug_assert!(_remainder.is_zero());
+	}
+
+	/// Remove the vote of `who` and its lock, and return it. Its deposit is left reserved.
+	fn take_voter(who: &T::AccountId) -> Voter<T::AccountId, BalanceOf<T>, T::MaxVotesPerVoter> {
+		let voter = <Voting<T>>::take(who);
+		CounterForVoters::<T>::mutate(|x| x.saturating_dec());
+
+		// remove storage and lock.
+		T::Currency::remove_lock(T::PalletId::get(), who);
+		voter
+	}
+
+	/// Run the phragmen election, or [`Config::ElectWinners`] if it elects the winners, with all
+	/// required side processes and state updates, if election succeeds. Else, it will emit an
+	/// `ElectionError` event.
 	///
 	/// Calls the appropriate [`ChangeMembers`] function variant internally.
 	fn do_phragmen() -> Weight {
-		let desired_seats = T::DesiredMembers::get() as usize;
-		let desired_runners_up = T::DesiredRunnersUp::get() as usize;
-		let num_to_elect = desired_runners_up + desired_seats;
+		let candidates_and_deposit = Self::candidates_and_deposit();
+		if candidates_and_deposit.len().is_zero() {
+			Self::deposit_event(Event::EmptyTerm);
+			return T::DbWeight::get().reads(5)
+		}
 
-		let mut candidates_and_deposit = Self::candidates();
-		// add all the previous members and runners-up as candidates as well.
-		candidates_and_deposit.append(&mut Self::implicit_candidates_with_deposit());
+		let voters_and_stakes = Voting::<T>::iter()
+			.map(|(voter, Voter { stake, votes, .. })| (voter, stake, votes.into_inner()))
+			.collect::<Vec<_>>();
+		match T::ElectWinners::elect_winners() {
+			Some(Ok((mut winners, weight))) => {
+				// the election might have been computed on older data: only the candidates of now
+				// can win, at most as many as there are seats.
+				let num_to_elect = (T::DesiredMembers::get() as usize)
+					.saturating_add(T::DesiredRunnersUp::get() as usize);
+				winners.retain(|(w, _)| candidates_and_deposit.iter().any(|(c, _)| c == w));
+				winners.truncate(num_to_elect);
+				weight.saturating_add(Self::apply_election(
+					candidates_and_deposit,
+					voters_and_stakes,
+					Ok(winners),
+				))
+			},
+			Some(Err(e)) => {
+				log::error!(
+					target: "runtime::elections-phragmen",
+					"Failed to elect the winners [{:?}].",
+					e,
+				);
+				let reason = ElectionErrorReason::ElectWinners;
+				Self::apply_election(candidates_and_deposit, voters_and_stakes, Err(reason))
+			},
+			None => Self::elect(candidates_and_deposit, voters_and_stakes),
+		}
+	}
 
+	/// Start a deferred election by snapshotting the candidates, see [`ElectionPhase`].
+	///
+	/// Emits `EmptyTerm` instead if there are no candidates.
+	fn start_election() -> Weight {
+		let candidates_and_deposit = Self::candidates_and_deposit();
 		if candidates_and_deposit.len().is_zero() {
 			Self::deposit_event(Event::EmptyTerm);
 			return T::DbWeight::get().reads(5)
 		}
 
+		<SnapshotCandidates<T>>::put(candidates_and_deposit);
+		<Phase<T>>::put(ElectionPhase::Snapshot(None));
+		Self::deposit_event(Event::ElectionStarted);
+		T::DbWeight::get().reads_writes(5, 2)
+	}
+
+	/// Snapshot the next [`Config::VoterSnapshotPerBlock`] voters after `last_key` as a new page,
+	/// moving to [`ElectionPhase::Signed`] once all voters are snapshotted.
+	///
+	/// The stake of each voter is capped by their balance in this block. Votes cannot change
+	/// until the snapshot is complete, hence it reflects the voters as of the block starting the
+	/// election.
+	fn snapshot_voters(last_key: Option<Vec<u8>>, now: T::BlockNumber) -> Weight {
+		// at least one voter per block, in case the configuration changed to zero meanwhile.
+		let page_size = T::VoterSnapshotPerBlock::get().max(1) as usize;
+		let mut voters = match last_key {
+			Some(last_key) => Voting::<T>::iter_from(last_key),
+			None => Voting::<T>::iter(),
+		};
+		let page = voters
+			.by_ref()
+			.take(page_size)
+			.map(|(voter, Voter { stake, votes, .. })| {
+				let stake = stake.min(T::Currency::total_balance(&voter));
+				(voter, stake, votes.into_inner())
+			})
+			.collect::<Vec<_>>();
+		let count = page.len();
+		if !page.is_empty() {
+			let index = <SnapshotVoterPages<T>>::mutate(|pages| {
+				let index = *pages;
+				pages.saturating_inc();
+				index
+			});
+			<SnapshotVoters<T>>::insert(index, page);
+		}
+
+		let next_phase = if count < page_size {
+			ElectionPhase::Signed(now.saturating_add(T::SignedPhase::get()))
+		} else {
+			ElectionPhase::Snapshot(Some(voters.last_raw_key().to_vec()))
+		};
+		<Phase<T>>::put(next_phase);
+		T::DbWeight::get().reads_writes(2 * count as Weight + 2, 3)
+	}
+
+	/// All the voters of the snapshot, in the order they were snapshotted.
+	fn snapshotted_voters() -> Vec<VoterOf<T>> {
+		(0..<SnapshotVoterPages<T>>::get())
+			.flat_map(|page| <SnapshotVoters<T>>::get(page))
+			.collect()
+	}
+
+	/// Remove the snapshot of the voters, returning them in the order they were snapshotted.
+	fn take_snapshotted_voters() -> Vec<VoterOf<T>> {
+		(0..<SnapshotVoterPages<T>>::take())
+			.flat_map(|page| <SnapshotVoters<T>>::take(page))
+			.collect()
+	}
+
+	/// Run the election of a deferred election from its snapshot, then clear the snapshot.
+	///
+	/// The best submitted solution is used if any, and its submitter gets their deposit back.
+	/// Otherwise the phragmen election is run.
+	fn elect_from_snapshot() -> Weight {
+		let candidates_and_deposit = <SnapshotCandidates<T>>::take();
+		let voters_and_stakes = Self::take_snapshotted_voters();
+		<Phase<T>>::put(ElectionPhase::Off);
+		let weight = match <BestSubmission<T>>::take() {
+			Some(SignedSubmission { who, deposit, winners, .. }) => {
+				T::Currency::unreserve(&who, deposit);
+				let candidates = candidates_and_deposit.len() as Weight;
+				Self::new_term(&candidates_and_deposit, voters_and_stakes, winners);
+				// unreserve, then slash the losers and rotate the seats.
+				T::DbWeight::get().reads_writes(candidates + 4, candidates + 6)
+			},
+			None => Self::elect(candidates_and_deposit, voters_and_stakes),
+		};
+		weight.saturating_add(T::DbWeight::get().reads_writes(3, 4))
+	}
+
+	/// Check `solution` against the snapshot of the ongoing deferred election, and return its
+	/// winners with their total support, ranked from best to worst.
+	fn feasibility_check(
+		solution: &RawSolution<T::AccountId>,
+	) -> Result<Vec<(T::AccountId, ExtendedBalance)>, FeasibilityError> {
+		let candidates = <SnapshotCandidates<T>>::get()
+			.into_iter()
+			.map(|(c, _)| c)
+			.collect::<BTreeSet<_>>();
+		let num_to_elect = (T::DesiredMembers::get() as usize)
+			.saturating_add(T::DesiredRunnersUp::get() as usize)
+			.min(candidates.len());
+		ensure!(solution.winners.len() == num_to_elect, FeasibilityError::WrongWinnerCount);
+		let mut winners = BTreeSet::new();
+		for winner in solution.winners.iter() {
+			ensure!(
+				candidates.contains(winner) && winners.insert(winner),
+				FeasibilityError::InvalidWinner,
+			);
+		}
+
+		// each voter distributes their whole stake once, to the winners they voted for, if any.
+		let total_issuance = T::Currency::total_issuance();
+		let stake_to_distribute = |stake: BalanceOf<T>, votes: &[T::AccountId]| {
+			if votes.iter().any(|v| winners.contains(v)) {
+				T::CurrencyToVote::to_vote(stake, total_issuance).into()
+			} else {
+				ExtendedBalance::zero()
+			}
+		};
+		let mut voters = Self::snapshotted_voters()
+			.into_iter()
+			.map(|(voter, stake, votes)| (voter, (stake, votes)))
+			.collect::<BTreeMap<_, _>>();
+		let assignments = solution
+			.assignments
+			.iter()
+			.map(|(who, distribution)| -> Result<_, FeasibilityError> {
+				let (stake, votes) = voters.remove(who).ok_or(FeasibilityError::InvalidVoter)?;
+				ensure!(
+					distribution.iter().all(|(target, _)| votes.contains(target)),
+					FeasibilityError::InvalidVote,
+				);
+				let distributed = distribution
+					.iter()
+					.fold(ExtendedBalance::zero(), |total, (_, w)| total.saturating_add(*w));
+				ensure!(
+					distributed == stake_to_distribute(stake, &votes),
+					FeasibilityError::InvalidStake,
+				);
+				Ok(StakedAssignment { who: who.clone(), distribution: distribution.clone() })
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		ensure!(
+			voters
+				.values()
+				.all(|(stake, votes)| stake_to_distribute(*stake, votes).is_zero()),
+			FeasibilityError::MissingVoter,
+		);
+
+		// stake can only be distributed to the winners.
+		let supports = to_supports(&solution.winners, &assignments)
+			.map_err(|_| FeasibilityError::InvalidVote)?;
+		ensure!((&supports).evaluate() == solution.score, FeasibilityError::InvalidScore);
+
+		let mut winners = supports
+			.into_iter()
+			.map(|(who, support)| (who, support.total))
+			.collect::<Vec<_>>();
+		winners.sort_by(|a, b| b.1.cmp(&a.1));
+		Ok(winners)
+	}
+
+	/// Predict the outcome of the next election as of the current state, or of the ongoing
+	/// deferred election once its snapshot is complete.
+	///
+	/// The best solution submitted so far is used if any. Otherwise phragmen is run, which is far
+	/// too expensive to do on-chain: this is meant to be called through the runtime API.
+	pub fn predict_result() -> ElectionPrediction<T::AccountId, BalanceOf<T>> {
+		let (candidates_and_deposit, voters_and_stakes) = match <Phase<T>>::get() {
+			ElectionPhase::Signed(_) | ElectionPhase::Emergency =>
+				(<SnapshotCandidates<T>>::get(), Self::snapshotted_voters()),
+			ElectionPhase::Off | ElectionPhase::Snapshot(_) => {
+				let voters_and_stakes = Voting::<T>::iter()
+					.map(|(voter, Voter { stake, votes, .. })| (voter, stake, votes.into_inner()))
+					.collect::<Vec<_>>();
+				(Self::candidates_and_deposit(), voters_and_stakes)
+			},
+		};
+
+		let winners = match <BestSubmission<T>>::get() {
+			Some(best) => Ok(best.winners),
+			None => Self::seq_phragmen(&candidates_and_deposit, &voters_and_stakes),
+		};
+		let (members, runners_up) = winners.map(Self::split_winners).unwrap_or_default();
+
+		let total_issuance = T::Currency::total_issuance();
+		let approvals = Self::approval_stakes(&candidates_and_deposit, &voters_and_stakes)
+			.into_iter()
+			.map(|(c, a)| (c, T::CurrencyToVote::to_currency(a, total_issuance)))
+			.collect::<Vec<_>>();
+
+		ElectionPrediction { members, runners_up, approvals }
+	}
+
+	/// The number of blocks of a term, set by [`Call::set_term_duration`] or else
+	/// [`Config::TermDuration`].
+	pub fn term_duration() -> T::BlockNumber {
+		<TermDurationOverride<T>>::get().unwrap_or_else(T::TermDuration::get)
+	}
+
+	/// The block of [`Config::BlockNumberProvider`] from which the next term starts, as of `now`.
+	///
+	/// `term_duration` must not be zero. If it was zero so far, the first term starts at `now`.
+	#[cfg(feature = "election-provider")]
+	pub(crate) fn next_election_at(
+		now: T::BlockNumber,
+		term_duration: T::BlockNumber,
+	) -> T::BlockNumber {
+		<NextElectionAt<T>>::get().unwrap_or_else(|| now.saturating_add(term_duration))
+	}
+
+	/// Cap the stake of `who`, if a voter, by their balance, which a slash might have reduced.
+	fn cap_voter_stake(who: &T::AccountId) {
+		if !Voting::<T>::contains_key(who) {
+			return
+		}
+		Voting::<T>::mutate(who, |voter| {
+			let balance = T::Currency::total_balance(who);
+			if voter.stake > balance {
+				voter.stake = balance;
+				T::Currency::set_lock(T::PalletId::get(), who, balance, WithdrawReasons::all());
+			}
+		});
+	}
+
+	/// Schedule the term after the one starting at `now`, which was scheduled to start at
+	/// `scheduled`, or at `now` if `None`.
+	///
+	/// The next term starts `term_duration` blocks after `scheduled`, or after the latest start
+	/// which `now` has already passed, if the block number jumped over several terms. Nothing is
+	/// scheduled if `term_duration` is zero.
+	fn schedule_next_election(
+		now: T::BlockNumber,
+		scheduled: Option<T::BlockNumber>,
+		term_duration: T::BlockNumber,
+	) {
+		if term_duration.is_zero() {
+			<NextElectionAt<T>>::kill();
+			return
+		}
+		let scheduled = scheduled.unwrap_or(now).min(now);
+		let missed_terms = (now - scheduled) / term_duration;
+		let next_election = scheduled
+			.saturating_add(term_duration.saturating_mul(missed_terms.saturating_add(One::one())));
+		<NextElectionAt<T>>::put(next_election);
+	}
+
+	/// Whether votes are frozen, while the voters of a deferred election are being snapshotted or
+	/// while the voters are being migrated.
+	fn is_voting_frozen() -> bool {
+		matches!(<Phase<T>>::get(), ElectionPhase::Snapshot(_)) || Self::is_migrating_voters()
+	}
+
+	/// Whether [`migrations::v6::BoundVoters`] is running, in which case the voters may not fit
+	/// into their bounds yet and are not counted.
+	fn is_migrating_voters() -> bool {
+		LazyMigrationExecutor::<migrations::v6::BoundVoters<T>, T::DbWeight>::is_running()
+	}
+
+	/// Whether a deferred election is ongoing, in which case candidacies and seats are frozen.
+	fn is_election_ongoing() -> bool {
+		<Phase<T>>::get() != ElectionPhase::Off
+	}
+
+	/// The candidates of the next election with their deposit, including the current members and
+	/// runners-up.
+	fn candidates_and_deposit() -> Vec<(T::AccountId, BalanceOf<T>)> {
+		let mut candidates_and_deposit = Self::candidates().into_inner();
+		// add all the previous members and runners-up as candidates as well.
+		candidates_and_deposit.append(&mut Self::implicit_candidates_with_deposit());
+		candidates_and_deposit
+	}
+
+	/// Run the phragmen election of `candidates_and_deposit` by `voters_and_stakes`, and update the
+	/// seats accordingly.
+	///
+	/// The election is refused if its projected weight is above [`Config::MaxElectionWeight`], in
+	/// which case [`Config::Fallback`] is applied.
+	///
+	/// `candidates_and_deposit` must not be empty.
+	fn elect(
+		candidates_and_deposit: Vec<(T::AccountId, BalanceOf<T>)>,
+		voters_and_stakes: Vec<VoterOf<T>>,
+	) -> Weight {
+		let weight_candidates = candidates_and_deposit.len() as u32;
+		let weight_voters = voters_and_stakes.len() as u32;
+		let weight_edges = voters_and_stakes
+			.iter()
+			.fold(0u32, |edges, (_, _, votes)| edges.saturating_add(votes.len() as u32));
+		let weight =
+			T::WeightInfo::election_phragmen(weight_candidates, weight_voters, weight_edges);
+		if let Some(ratio) = T::MaxElectionWeight::get() {
+			let max_weight = ratio * T::BlockWeights::get().max_block;
+			if weight > max_weight {
+				log::warn!(
+					target: "runtime::elections-phragmen",
+					"Refused election of projected weight {} above {}.",
+					weight,
+					max_weight,
+				);
+				let reason = ElectionErrorReason::TooHeavy { weight };
+				return Self::apply_election(candidates_and_deposit, voters_and_stakes, Err(reason))
+			}
+		}
+
+		let winners =
+			Self::seq_phragmen(&candidates_and_deposit, &voters_and_stakes).map_err(|e| {
+				log::error!(
+					target: "runtime::elections-phragmen",
+					"Failed to run election [{:?}].",
+					e,
+				);
+				ElectionErrorReason::Phragmen
+			});
+		weight.saturating_add(Self::apply_election(
+			candidates_and_deposit,
+			voters_and_stakes,
+			winners,
+		))
+	}
+
+	/// Start a new term with the `winners` of the election of `candidates_and_deposit` by
+	/// `voters_and_stakes`, ranked from best to worst with their backing stake, or apply
+	/// [`Config::Fallback`] if the election failed.
+	fn apply_election(
+		candidates_and_deposit: Vec<(T::AccountId, BalanceOf<T>)>,
+		voters_and_stakes: Vec<VoterOf<T>>,
+		winners: Result<Vec<(T::AccountId, ExtendedBalance)>, ElectionErrorReason>,
+	) -> Weight {
+		match winners {
+			Ok(winners) => {
+				Self::new_term(&candidates_and_deposit, voters_and_stakes, winners);
+				0
+			},
+			Err(reason) => {
+				Self::deposit_event(Event::ElectionError { reason });
+				Self::fallback(candidates_and_deposit, voters_and_stakes)
+			},
+		}
+	}
+
+	/// Run sequential phragmen to elect the members and runners-up among
+	/// `candidates_and_deposit` by `voters_and_stakes`, and return the winners with their backing
+	/// stake, ranked from best to worst.
+	fn seq_phragmen(
+		candidates_and_deposit: &[(T::AccountId, BalanceOf<T>)],
+		voters_and_stakes: &[VoterOf<T>],
+	) -> Result<Vec<(T::AccountId, ExtendedBalance)>, sp_npos_elections::Error> {
+		let desired_seats = T::DesiredMembers::get() as usize;
+		let desired_runners_up = T::DesiredRunnersUp::get() as usize;
+		let num_to_elect = desired_runners_up + desired_seats;
+
 		// All of the new winners that come out of phragmen will thus have a deposit recorded.
 		let candidate_ids =
 			candidates_and_deposit.iter().map(|(x, _)| x).cloned().collect::<Vec<_>>();
@@ -895,167 +2192,368 @@ impl<T: Config> Pallet<T> {
 		// helper closures to deal with balance/stake.
 		let total_issuance = T::Currency::total_issuance();
 		let to_votes = |b: BalanceOf<T>| T::CurrencyToVote::to_vote(b, total_issuance);
-		let to_balance = |e: ExtendedBalance| T::CurrencyToVote::to_currency(e, total_issuance);
 
-		let mut num_edges: u32 = 0;
-		// used for prime election.
-		let voters_and_stakes = Voting::<T>::iter()
-			.map(|(voter, Voter { stake, votes, .. })| (voter, stake, votes))
-			.collect::<Vec<_>>();
-		// used for phragmen.
+		// `voters_and_stakes` is used for prime election, this one for phragmen.
 		let voters_and_votes = voters_and_stakes
 			.iter()
 			.cloned()
-			.map(|(voter, stake, votes)| {
-				num_edges = num_edges.saturating_add(votes.len() as u32);
-				(voter, to_votes(stake), votes)
-			})
+			.map(|(voter, stake, votes)| (voter, to_votes(stake), votes))
 			.collect::<Vec<_>>();
 
-		let weight_candidates = candidates_and_deposit.len() as u32;
-		let weight_voters = voters_and_votes.len() as u32;
-		let weight_edges = num_edges;
-		let _ = sp_npos_elections::seq_phragmen::<T::AccountId, Perbill>(
+		sp_npos_elections::seq_phragmen::<T::AccountId, Perbill>(
 			num_to_elect,
 			candidate_ids,
-			voters_and_votes.clone(),
+			voters_and_votes,
 			None,
 		)
-		.map(|ElectionResult { winners, assignments: _ }| {
-			// this is already sorted by id.
-			let old_members_ids_sorted =
-				<Members<T>>::take().into_iter().map(|m| m.who).collect::<Vec<T::AccountId>>();
-			// this one needs a sort by id.
-			let mut old_runners_up_ids_sorted =
-				<RunnersUp<T>>::take().into_iter().map(|r| r.who).collect::<Vec<T::AccountId>>();
-			old_runners_up_ids_sorted.sort();
-
-			// filter out those who end up with no backing stake.
-			let mut new_set_with_stake = winners
-				.into_iter()
-				.filter_map(|(m, b)| if b.is_zero() { None } else { Some((m, to_balance(b))) })
-				.collect::<Vec<(T::AccountId, BalanceOf<T>)>>();
-
-			// OPTIMIZATION NOTE: we could bail out here if `new_set.len() == 0`. There isn't
-			// much left to do. Yet, re-arranging the code would require duplicating the
-			// slashing of exposed candidates, cleaning any previous members, and so on. For
-			// now, in favor of readability and veracity, we keep it simple.
-
-			// split new set into winners and runners up.
-			let split_point = desired_seats.min(new_set_with_stake.len());
-			let mut new_members_sorted_by_id =
-				new_set_with_stake.drain(..split_point).collect::<Vec<_>>();
-			new_members_sorted_by_id.sort_by(|i, j| i.0.cmp(&j.0));
-
-			// all the rest will be runners-up
-			new_set_with_stake.reverse();
-			let new_runners_up_sorted_by_rank = new_set_with_stake;
-			let mut new_runners_up_ids_sorted =
-				new_runners_up_sorted_by_rank.iter().map(|(r, _)| r.clone()).collect::<Vec<_>>();
-			new_runners_up_ids_sorted.sort();
-
-			// Now we select a prime member using a [Borda
-			// count](https://en.wikipedia.org/wiki/Borda_count). We weigh everyone's vote for
-			// that new member by a multiplier based on the order of the votes. i.e. the first
-			// person a voter votes for gets a 16x multiplier, the next person gets a 15x
-			// multiplier, an so on... (assuming `MAXIMUM_VOTE` = 16)
-			let mut prime_votes = new_members_sorted_by_id
-				.iter()
-				.map(|c| (&c.0, BalanceOf::<T>::zero()))
-				.collect::<Vec<_>>();
-			for (_, stake, votes) in voters_and_stakes.into_iter() {
-				for (vote_multiplier, who) in votes
-					.iter()
-					.enumerate()
-					.map(|(vote_position, who)| ((MAXIMUM_VOTE - vote_position) as u32, who))
-				{
-					if let Ok(i) = prime_votes.binary_search_by_key(&who, |k| k.0) {
-						prime_votes[i].1 = prime_votes[i]
-							.1
-							.saturating_add(stake.saturating_mul(vote_multiplier.into()));
-					}
+		.map(|ElectionResult { winners, assignments: _ }| winners)
+	}
+
+	/// Split the `winners` of an election, ranked from best to worst with their backing stake,
+	/// into the members sorted by account id and the runners-up sorted by rank (worse to best).
+	///
+	/// Winners without any backing stake are dropped.
+	fn split_winners(
+		winners: Vec<(T::AccountId, ExtendedBalance)>,
+	) -> (Vec<(T::AccountId, BalanceOf<T>)>, Vec<(T::AccountId, BalanceOf<T>)>) {
+		let desired_seats = T::DesiredMembers::get() as usize;
+		let total_issuance = T::Currency::total_issuance();
+		let to_balance = |e: ExtendedBalance| T::CurrencyToVote::to_currency(e, total_issuance);
+
+		// filter out those who end up with no backing stake.
+		let mut new_set_with_stake = winners
+			.into_iter()
+			.filter_map(|(m, b)| if b.is_zero() { None } else { Some((m, to_balance(b))) })
+			.collect::<Vec<(T::AccountId, BalanceOf<T>)>>();
+
+		// split new set into winners and runners up.
+		let split_point = desired_seats.min(new_set_with_stake.len());
+		let mut new_members_sorted_by_id =
+			new_set_with_stake.drain(..split_point).collect::<Vec<_>>();
+		new_members_sorted_by_id.sort_by(|i, j| i.0.cmp(&j.0));
+
+		// all the rest will be runners-up
+		new_set_with_stake.reverse();
+		(new_members_sorted_by_id, new_set_with_stake)
+	}
+
+	/// Apply [`Config::Fallback`] once the election of `candidates_and_deposit` by
+	/// `voters_and_stakes` failed.
+	fn fallback(
+		candidates_and_deposit: Vec<(T::AccountId, BalanceOf<T>)>,
+		voters_and_stakes: Vec<VoterOf<T>>,
+	) -> Weight {
+		match T::Fallback::get() {
+			FallbackStrategy::KeepMembers => {
+				Self::deposit_event(Event::FallbackMembersKept);
+				0
+			},
+			FallbackStrategy::Approval => {
+				let num_to_elect = (T::DesiredMembers::get() as usize)
+					.saturating_add(T::DesiredRunnersUp::get() as usize);
+				let mut winners =
+					Self::approval_stakes(&candidates_and_deposit, &voters_and_stakes)
+						.into_iter()
+						.collect::<Vec<_>>();
+				winners.sort_by(|a, b| b.1.cmp(&a.1));
+				winners.truncate(num_to_elect);
+
+				Self::deposit_event(Event::FallbackApprovalElection);
+				let candidates = candidates_and_deposit.len() as Weight;
+				Self::new_term(&candidates_and_deposit, voters_and_stakes, winners);
+				// slash the losers and rotate the seats.
+				T::DbWeight::get().reads_writes(candidates + 3, candidates + 5)
+			},
+			FallbackStrategy::Emergency => {
+				<SnapshotCandidates<T>>::put(candidates_and_deposit);
+				<SnapshotVoters<T>>::insert(0, voters_and_stakes);
+				<SnapshotVoterPages<T>>::put(1);
+				<Phase<T>>::put(ElectionPhase::Emergency);
+				Self::deposit_event(Event::FallbackEmergency);
+				T::DbWeight::get().writes(4)
+			},
+		}
+	}
+
+	/// The approval stake of each of `candidates_and_deposit`, i.e. the total stake of the
+	/// `voters_and_stakes` who voted for them, in vote units of [`Config::CurrencyToVote`].
+	fn approval_stakes(
+		candidates_and_deposit: &[(T::AccountId, BalanceOf<T>)],
+		voters_and_stakes: &[VoterOf<T>],
+	) -> BTreeMap<T::AccountId, ExtendedBalance> {
+		let total_issuance = T::Currency::total_issuance();
+		let mut approvals = candidates_and_deposit
+			.iter()
+			.map(|(c, _)| (c.clone(), ExtendedBalance::zero()))
+			.collect::<BTreeMap<_, _>>();
+		for (_, stake, votes) in voters_and_stakes.iter() {
+			let stake: ExtendedBalance = T::CurrencyToVote::to_vote(*stake, total_issuance).into();
+			// duplicate votes are only counted once.
+			let votes = votes.iter().collect::<BTreeSet<_>>();
+			for vote in votes {
+				if let Some(approval) = approvals.get_mut(vote) {
+					*approval = approval.saturating_add(stake);
 				}
 			}
-			// We then select the new member with the highest weighted stake. In the case of a tie,
-			// the last person in the list with the tied score is selected. This is the person with
-			// the "highest" account id based on the sort above.
-			let prime = prime_votes.into_iter().max_by_key(|x| x.1).map(|x| x.0.clone());
+		}
+		approvals
+	}
 
-			// new_members_sorted_by_id is sorted by account id.
-			let new_members_ids_sorted = new_members_sorted_by_id
+	/// Start a new term with the `winners` of an election, ranked from best to worst with their
+	/// total support, and update the seats accordingly.
+	///
+	/// The candidates of the election which did not win a seat are slashed.
+	fn new_term(
+		candidates_and_deposit: &[(T::AccountId, BalanceOf<T>)],
+		voters_and_stakes: Vec<VoterOf<T>>,
+		winners: Vec<(T::AccountId, ExtendedBalance)>,
+	) {
+		// this is already sorted by id.
+		let old_members_ids_sorted =
+			<Members<T>>::take().into_iter().map(|m| m.who).collect::<Vec<T::AccountId>>();
+		// this one needs a sort by id.
+		let mut old_runners_up_ids_sorted =
+			<RunnersUp<T>>::take().into_iter().map(|r| r.who).collect::<Vec<T::AccountId>>();
+		old_runners_up_ids_sorted.sort();
+
+		let (new_members_sorted_by_id, new_runners_up_sorted_by_rank) =
+			Self::split_winners(winners);
+
+		// OPTIMIZATION NOTE: we could bail out here if `new_set.len() == 0`. There isn't
+		// much left to do. Yet, re-arranging the code would require duplicating the
+		// slashing of exposed candidates, cleaning any previous members, and so on. For
+		// now, in favor of readability and veracity, we keep it simple.
+
+		let mut new_runners_up_ids_sorted =
+			new_runners_up_sorted_by_rank.iter().map(|(r, _)| r.clone()).collect::<Vec<_>>();
+		new_runners_up_ids_sorted.sort();
+
+		// Now we select a prime member using a [Borda
+		// count](https://en.wikipedia.org/wiki/Borda_count). We weigh everyone's vote for
+		// that new member by a multiplier based on the order of the votes. i.e. the first
+		// person a voter votes for gets a 16x multiplier, the next person gets a 15x
+		// multiplier, an so on... (assuming `MaxVotesPerVoter` = 16)
+		let max_votes = T::MaxVotesPerVoter::get() as usize;
+		let mut prime_votes = new_members_sorted_by_id
+			.iter()
+			.map(|c| (&c.0, BalanceOf::<T>::zero()))
+			.collect::<Vec<_>>();
+		for (_, stake, votes) in voters_and_stakes.into_iter() {
+			for (vote_multiplier, who) in votes
 				.iter()
-				.map(|(m, _)| m.clone())
-				.collect::<Vec<T::AccountId>>();
-
-			// report member changes. We compute diff because we need the outgoing list.
-			let (incoming, outgoing) = T::ChangeMembers::compute_members_diff_sorted(
-				&new_members_ids_sorted,
-				&old_members_ids_sorted,
-			);
-			T::ChangeMembers::change_members_sorted(&incoming, &outgoing, &new_members_ids_sorted);
-			T::ChangeMembers::set_prime(prime);
-
-			// All candidates/members/runners-up who are no longer retaining a position as a
-			// seat holder will lose their bond.
-			candidates_and_deposit.iter().for_each(|(c, d)| {
-				if new_members_ids_sorted.binary_search(c).is_err() &&
-					new_runners_up_ids_sorted.binary_search(c).is_err()
-				{
-					let (imbalance, _) = T::Currency::slash_reserved(c, *d);
-					T::LoserCandidate::on_unbalanced(imbalance);
-					Self::deposit_event(Event::CandidateSlashed(c.clone(), *d));
+				.enumerate()
+				.map(|(position, who)| (max_votes.saturating_sub(position) as u32, who))
+			{
+				if let Ok(i) = prime_votes.binary_search_by_key(&who, |k| k.0) {
+					prime_votes[i].1 = prime_votes[i]
+						.1
+						.saturating_add(stake.saturating_mul(vote_multiplier.into()));
 				}
-			});
-
-			// write final values to storage.
-			let deposit_of_candidate = |x: &T::AccountId| -> BalanceOf<T> {
-				// defensive-only. This closure is used against the new members and new runners-up,
-				// both of which are phragmen winners and thus must have deposit.
-				candidates_and_deposit
-					.iter()
-					.find_map(|(c, d)| if c == x { Some(*d) } else { None })
-					.unwrap_or_default()
-			};
-			// fetch deposits from the one recorded one. This will make sure that a candidate who
-			// submitted candidacy before a change to candidacy deposit will have the correct amount
-			// recorded.
-			<Members<T>>::put(
-				new_members_sorted_by_id
-					.iter()
-					.map(|(who, stake)| SeatHolder {
-						deposit: deposit_of_candidate(&who),
-						who: who.clone(),
-						stake: stake.clone(),
-					})
-					.collect::<Vec<_>>(),
-			);
-			<RunnersUp<T>>::put(
-				new_runners_up_sorted_by_rank
-					.into_iter()
-					.map(|(who, stake)| SeatHolder {
-						deposit: deposit_of_candidate(&who),
-						who,
-						stake,
-					})
-					.collect::<Vec<_>>(),
-			);
+			}
+		}
+		// We then select the new member with the highest weighted stake. In the case of a tie,
+		// the last person in the list with the tied score is selected. This is the person with
+		// the "highest" account id based on the sort above.
+		let prime = prime_votes.into_iter().max_by_key(|x| x.1).map(|x| x.0.clone());
 
-			// clean candidates.
-			<Candidates<T>>::kill();
+		// new_members_sorted_by_id is sorted by account id.
+		let new_members_ids_sorted = new_members_sorted_by_id
+			.iter()
+			.map(|(m, _)| m.clone())
+			.collect::<Vec<T::AccountId>>();
+
+		// report member changes. We compute diff because we need the outgoing list.
+		let (incoming, outgoing) = T::ChangeMembers::compute_members_diff_sorted(
+			&new_members_ids_sorted,
+			&old_members_ids_sorted,
+		);
+		T::ChangeMembers::change_members_sorted(&incoming, &outgoing, &new_members_ids_sorted);
+		T::ChangeMembers::set_prime(prime);
+
+		// All candidates/members/runners-up who are no longer retaining a position as a
+		// seat holder will lose their bond.
+		candidates_and_deposit.iter().for_each(|(c, d)| {
+			if new_members_ids_sorted.binary_search(c).is_err() &&
+				new_runners_up_ids_sorted.binary_search(c).is_err()
+			{
+				let (imbalance, remainder) = T::Currency::slash_reserved(c, *d);
+				T::LoserCandidate::on_unbalanced(imbalance);
+				Self::cap_voter_stake(c);
+				<CandidateMetadata<T>>::remove(c);
+				Self::deposit_event(Event::CandidateSlashed {
+					candidate: c.clone(),
+					amount: d.saturating_sub(remainder),
+					deposit: *d,
+				});
+			}
+		});
 
-			Self::deposit_event(Event::NewTerm(new_members_sorted_by_id));
-			<ElectionRounds<T>>::mutate(|v| *v += 1);
-		})
-		.map_err(|e| {
+		// write final values to storage.
+		let deposit_of_candidate = |x: &T::AccountId| -> BalanceOf<T> {
+			// defensive-only. This closure is used against the new members and new runners-up,
+			// both of which are phragmen winners and thus must have deposit.
+			candidates_and_deposit
+				.iter()
+				.find_map(|(c, d)| if c == x { Some(*d) } else { None })
+				.unwrap_or_default()
+		};
+		// fetch deposits from the one recorded one. This will make sure that a candidate who
+		// submitted candidacy before a change to candidacy deposit will have the correct amount
+		// recorded.
+		let new_members = new_members_sorted_by_id
+			.iter()
+			.map(|(who, stake)| SeatHolder {
+				deposit: deposit_of_candidate(&who),
+				who: who.clone(),
+				stake: stake.clone(),
+			})
+			.collect::<Vec<_>>();
+		// defensive-only: no more than `desired_seats` winners end up as members.
+		let new_members = BoundedVec::try_from(new_members).unwrap_or_else(|_| {
+			log::error!(
+				target: "runtime::elections-phragmen",
+				"More members than DesiredMembers were elected.",
+			);
+			Default::default()
+		});
+		<Members<T>>::put(new_members);
+		let new_runners_up = new_runners_up_sorted_by_rank
+			.into_iter()
+			.map(|(who, stake)| SeatHolder { deposit: deposit_of_candidate(&who), who, stake })
+			.collect::<Vec<_>>();
+		// defensive-only: no more than `desired_runners_up` of the winners are left.
+		let new_runners_up = BoundedVec::try_from(new_runners_up).unwrap_or_else(|_| {
 			log::error!(
 				target: "runtime::elections-phragmen",
-				"Failed to run election [{:?}].",
-				e,
+				"More runners-up than DesiredRunnersUp were elected.",
 			);
-			Self::deposit_event(Event::ElectionError);
+			Default::default()
 		});
+		<RunnersUp<T>>::put(new_runners_up);
+		T::RankedMembers::change_ranked_members(&Self::ranked_members());
 
-		T::WeightInfo::election_phragmen(weight_candidates, weight_voters, weight_edges)
+		// clean candidates.
+		<Candidates<T>>::kill();
+
+		Self::deposit_event(Event::NewTerm { members_with_stake: new_members_sorted_by_id });
+		<ElectionRounds<T>>::mutate(|v| *v += 1);
+	}
+
+	/// Ensure the correctness of the state of this pallet.
+	///
+	/// The following invariants are checked:
+	///
+	/// * `Members` and `Candidates` are sorted by account id.
+	/// * Members, runners-up and candidates are disjoint sets.
+	/// * There are no more members and runners-up than desired, nor candidates than
+	///   [`Config::MaxCandidates`].
+	/// * Every member and runner-up has their deposit reserved.
+	/// * [`CounterForVoters`] counts the voters, which are no more than [`Config::MaxVoters`],
+	///   unless they are being migrated.
+	/// * The stake of every voter is covered by their balance. The lock may overlap with the
+	///   reserved balance, hence the total balance is checked rather than the free one.
+	/// * No snapshot or solution exists outside of a deferred election.
+	#[cfg(feature = "try-runtime")]
+	pub fn do_try_state() -> Result<(), &'static str> {
+		ensure!(
+			<Candidates<T>>::decode_len().unwrap_or(0) <= T::MaxCandidates::get() as usize,
+			"More candidates than MaxCandidates.",
+		);
+
+		let members = Self::members_ids();
+		let runners_up = Self::runners_up().into_iter().map(|r| r.who).collect::<Vec<_>>();
+		let candidates = Self::candidates().into_iter().map(|(c, _)| c).collect::<Vec<_>>();
+
+		ensure!(members.windows(2).all(|w| w[0] < w[1]), "Members are not sorted.");
+		ensure!(candidates.windows(2).all(|w| w[0] < w[1]), "Candidates are not sorted.");
+
+		ensure!(
+			members.len() <= T::DesiredMembers::get() as usize,
+			"More members than DesiredMembers.",
+		);
+		ensure!(
+			runners_up.len() <= T::DesiredRunnersUp::get() as usize,
+			"More runners-up than DesiredRunnersUp.",
+		);
+
+		ensure!(
+			!runners_up.iter().any(|r| members.binary_search(r).is_ok()),
+			"A runner-up is also a member.",
+		);
+		ensure!(
+			!candidates
+				.iter()
+				.any(|c| members.binary_search(c).is_ok() || runners_up.contains(c)),
+			"A candidate is also a member or runner-up.",
+		);
+		ensure!(
+			Self::members()
+				.into_iter()
+				.chain(Self::runners_up().into_iter())
+				.all(|s| T::Currency::reserved_balance(&s.who) >= s.deposit),
+			"A member or runner-up does not have their deposit reserved.",
+		);
+		ensure!(
+			<CandidateMetadata<T>>::iter_keys().all(|who| {
+				candidates.contains(&who) ||
+					members.binary_search(&who).is_ok() ||
+					runners_up.contains(&who)
+			}),
+			"Metadata of an account which is neither a candidate nor a seat holder.",
+		);
+
+		if !Self::is_election_ongoing() {
+			ensure!(
+				<SnapshotCandidates<T>>::decode_len().unwrap_or(0) == 0 &&
+					<SnapshotVoterPages<T>>::get() == 0,
+				"A snapshot exists without an ongoing election.",
+			);
+			ensure!(
+				<BestSubmission<T>>::get().is_none(),
+				"A solution exists without an ongoing election.",
+			);
+		}
+		// the voters are only bounded and counted once they are migrated.
+		if !Self::is_migrating_voters() {
+			let mut voters = 0u32;
+			for (who, Voter { stake, .. }) in Voting::<T>::iter() {
+				ensure!(
+					stake <= T::Currency::total_balance(&who),
+					"A voter has more stake than balance.",
+				);
+				voters.saturating_inc();
+			}
+			ensure!(CounterForVoters::<T>::get() == voters, "CounterForVoters is not up to date.");
+			ensure!(voters <= T::MaxVoters::get(), "More voters than MaxVoters.");
+		}
+
+		Ok(())
+	}
+}
+
+impl<T: Config> CheckInvariants for Pallet<T> {
+	/// Check that the reserved balance of every member, runner-up, candidate, voter and solution
+	/// submitter covers all of the deposits recorded for it.
+	fn check_invariants() -> Vec<&'static str> {
+		let mut recorded = BTreeMap::<T::AccountId, BalanceOf<T>>::new();
+		let deposits = Self::implicit_candidates_with_deposit()
+			.into_iter()
+			.chain(Self::candidates().into_iter())
+			.chain(Voting::<T>::iter().map(|(who, voter)| (who, voter.deposit)))
+			.chain(<BestSubmission<T>>::get().map(|best| (best.who, best.deposit)));
+		for (who, deposit) in deposits {
+			let total = recorded.entry(who).or_insert_with(Zero::zero);
+			*total = total.saturating_add(deposit);
+		}
+
+		if recorded
+			.into_iter()
+			.any(|(who, deposit)| deposit > T::Currency::reserved_balance(&who))
+		{
+			vec!["Elections: reserved balance does not cover the recorded deposits."]
+		} else {
+			Vec::new()
+		}
 	}
 }
 
@@ -1080,7 +2578,10 @@ impl<T: Config> SortedMembers<T::AccountId> for Pallet<T> {
 	fn add(who: &T::AccountId) {
 		Members::<T>::mutate(|members| match members.binary_search_by(|m| m.who.cmp(who)) {
 			Ok(_) => (),
-			Err(pos) => members.insert(pos, SeatHolder { who: who.clone(), ..Default::default() }),
+			Err(pos) => {
+				let _ =
+					members.try_insert(pos, SeatHolder { who: who.clone(), ..Default::default() });
+			},
 		})
 	}
 }
@@ -1101,14 +2602,16 @@ mod tests {
 	use super::*;
 	use crate as elections_phragmen;
 	use frame_support::{
-		assert_noop, assert_ok, dispatch::DispatchResultWithPostInfo, parameter_types,
-		traits::OnInitialize,
+		assert_noop, assert_ok,
+		dispatch::DispatchResultWithPostInfo,
+		parameter_types,
+		traits::{OnIdle, OnInitialize, OnRuntimeUpgrade},
 	};
 	use frame_system::ensure_signed;
 	use sp_core::H256;
 	use sp_runtime::{
 		testing::Header,
-		traits::{BlakeTwo256, IdentityLookup},
+		traits::{BadOrigin, BlakeTwo256, IdentityLookup},
 		BuildStorage,
 	};
 	use substrate_test_utils::assert_eq_uvec;
@@ -1167,9 +2670,21 @@ mod tests {
 		pub static CandidacyBond: u64 = 3;
 		pub static DesiredMembers: u32 = 2;
 		pub static DesiredRunnersUp: u32 = 0;
+		pub static MaxCandidates: u32 = 1000;
+		pub const MaxCandidateMetadata: u32 = 8;
+		pub static MaxVoters: u32 = 1000;
+		pub static MaxVotesPerVoter: u32 = 16;
+		pub static VoterSnapshotPerBlock: u32 = 0;
+		pub static SignedPhase: u64 = 0;
+		pub static SolutionDeposit: u64 = 5;
+		pub static DefunctVoterReward: Perbill = Perbill::from_percent(50);
+		pub static Fallback: FallbackStrategy = FallbackStrategy::KeepMembers;
+		pub static MaxElectionWeight: Option<Perbill> = None;
+		pub static Succession: SuccessionPolicy = SuccessionPolicy::PromoteRunnerUp;
 		pub static TermDuration: u64 = 5;
 		pub static Members: Vec<u64> = vec![];
 		pub static Prime: Option<u64> = None;
+		pub static RankedMembers: Vec<(u64, u64)> = vec![];
 	}
 
 	pub struct TestChangeMembers;
@@ -1216,6 +2731,13 @@ mod tests {
 		}
 	}
 
+	pub struct TestChangeRankedMembers;
+	impl ChangeRankedMembers<u64, u64> for TestChangeRankedMembers {
+		fn change_ranked_members(ranked: &[(u64, u64)]) {
+			RANKED_MEMBERS.with(|m| *m.borrow_mut() = ranked.to_vec());
+		}
+	}
+
 	parameter_types! {
 		pub const ElectionsPhragmenPalletId: LockIdentifier = *b"phrelect";
 	}
@@ -1226,18 +2748,40 @@ mod tests {
 		type Currency = Balances;
 		type CurrencyToVote = frame_support::traits::SaturatingCurrencyToVote;
 		type ChangeMembers = TestChangeMembers;
+		type RankedMembers = TestChangeRankedMembers;
 		type InitializeMembers = ();
 		type CandidacyBond = CandidacyBond;
 		type VotingBondBase = VotingBondBase;
 		type VotingBondFactor = VotingBondFactor;
 		type TermDuration = TermDuration;
+		type TermOrigin = frame_system::EnsureRoot<u64>;
+		type BlockNumberProvider = System;
 		type DesiredMembers = DesiredMembers;
 		type DesiredRunnersUp = DesiredRunnersUp;
+		type MaxCandidates = MaxCandidates;
+		type MaxCandidateMetadata = MaxCandidateMetadata;
+		type MaxVoters = MaxVoters;
+		type MaxVotesPerVoter = MaxVotesPerVoter;
+		type VoterSnapshotPerBlock = VoterSnapshotPerBlock;
+		type SignedPhase = SignedPhase;
+		type SolutionDeposit = SolutionDeposit;
 		type LoserCandidate = ();
 		type KickedMember = ();
+		type BadSolution = ();
+		type DefunctVoterReward = DefunctVoterReward;
+		type Fallback = Fallback;
+		type MaxElectionWeight = MaxElectionWeight;
+		type ElectWinners = ();
+		type Succession = Succession;
 		type WeightInfo = ();
 	}
 
+	#[cfg(feature = "election-provider")]
+	impl election_provider::Config for Test {
+		const MAX_VOTES_PER_VOTER: u32 = 16;
+		type ElectionProvider = election_provider::SeqPhragmen<Test>;
+	}
+
 	pub type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
 	pub type UncheckedExtrinsic = sp_runtime::generic::UncheckedExtrinsic<u32, u64, Call, ()>;
 
@@ -1292,6 +2836,38 @@ mod tests {
 			DESIRED_MEMBERS.with(|m| *m.borrow_mut() = count);
 			self
 		}
+		pub fn max_candidates(self, count: u32) -> Self {
+			MAX_CANDIDATES.with(|m| *m.borrow_mut() = count);
+			self
+		}
+		pub fn max_voters(self, count: u32) -> Self {
+			MAX_VOTERS.with(|m| *m.borrow_mut() = count);
+			self
+		}
+		pub fn max_votes_per_voter(self, count: u32) -> Self {
+			MAX_VOTES_PER_VOTER.with(|m| *m.borrow_mut() = count);
+			self
+		}
+		pub fn voter_snapshot_per_block(self, count: u32) -> Self {
+			VOTER_SNAPSHOT_PER_BLOCK.with(|m| *m.borrow_mut() = count);
+			self
+		}
+		pub fn signed_phase(self, duration: u64) -> Self {
+			SIGNED_PHASE.with(|m| *m.borrow_mut() = duration);
+			self
+		}
+		pub fn fallback(self, strategy: FallbackStrategy) -> Self {
+			FALLBACK.with(|m| *m.borrow_mut() = strategy);
+			self
+		}
+		pub fn max_election_weight(self, ratio: Perbill) -> Self {
+			MAX_ELECTION_WEIGHT.with(|m| *m.borrow_mut() = Some(ratio));
+			self
+		}
+		pub fn succession(self, policy: SuccessionPolicy) -> Self {
+			SUCCESSION.with(|m| *m.borrow_mut() = policy);
+			self
+		}
 		pub fn balance_factor(mut self, factor: u64) -> Self {
 			self.balance_factor = factor;
 			self
@@ -1383,13 +2959,13 @@ mod tests {
 	}
 
 	fn ensure_members_sorted() {
-		let mut members = Elections::members().clone();
+		let mut members = Elections::members().into_inner();
 		members.sort_by_key(|m| m.who);
 		assert_eq!(Elections::members(), members);
 	}
 
 	fn ensure_candidates_sorted() {
-		let mut candidates = Elections::candidates().clone();
+		let mut candidates = Elections::candidates().into_inner();
 		candidates.sort_by_key(|(c, _)| *c);
 		assert_eq!(Elections::candidates(), candidates);
 	}
@@ -1407,6 +2983,10 @@ mod tests {
 			.all(|s| s.stake != u64::zero()));
 	}
 
+	fn ensure_voters_counted() {
+		assert_eq!(CounterForVoters::<Test>::get(), all_voters().len() as u32);
+	}
+
 	fn ensure_member_candidates_runners_up_disjoint() {
 		// members, candidates and runners-up must always be disjoint sets.
 		assert!(!intersects(&members_ids(), &candidate_ids()));
@@ -1426,6 +3006,9 @@ mod tests {
 		ensure_candidates_sorted();
 		ensure_member_candidates_runners_up_disjoint();
 		ensure_members_has_approval_stake();
+		ensure_voters_counted();
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(Elections::do_try_state());
 	}
 
 	fn submit_candidacy(origin: Origin) -> DispatchResultWithPostInfo {
@@ -1441,7 +3024,15 @@ mod tests {
 	}
 
 	fn votes_of(who: &u64) -> Vec<u64> {
-		Voting::<Test>::get(who).votes
+		Voting::<Test>::get(who).votes.into_inner()
+	}
+
+	/// Apply the fallback as if the election of the current candidates and voters failed.
+	fn fail_election() {
+		let voters = Voting::<Test>::iter()
+			.map(|(voter, Voter { stake, votes, .. })| (voter, stake, votes.into_inner()))
+			.collect::<Vec<_>>();
+		Elections::fallback(Elections::candidates_and_deposit(), voters);
 	}
 
 	#[test]
@@ -1483,11 +3074,19 @@ mod tests {
 
 				assert_eq!(
 					Elections::voting(1),
-					Voter { stake: 10u64, votes: vec![1], deposit: 0 }
+					Voter {
+						stake: 10u64,
+						votes: BoundedVec::try_from(vec![1]).unwrap(),
+						deposit: 0,
+					}
 				);
 				assert_eq!(
 					Elections::voting(2),
-					Voter { stake: 20u64, votes: vec![2], deposit: 0 }
+					Voter {
+						stake: 20u64,
+						votes: BoundedVec::try_from(vec![2]).unwrap(),
+						deposit: 0,
+					}
 				);
 
 				// they will persist since they have self vote.
@@ -1507,11 +3106,19 @@ mod tests {
 
 				assert_eq!(
 					Elections::voting(1),
-					Voter { stake: 10u64, votes: vec![1], deposit: 0 }
+					Voter {
+						stake: 10u64,
+						votes: BoundedVec::try_from(vec![1]).unwrap(),
+						deposit: 0,
+					}
 				);
 				assert_eq!(
 					Elections::voting(2),
-					Voter { stake: 20u64, votes: vec![2], deposit: 0 }
+					Voter {
+						stake: 20u64,
+						votes: BoundedVec::try_from(vec![2]).unwrap(),
+						deposit: 0,
+					}
 				);
 
 				assert_ok!(Elections::remove_voter(Origin::signed(1)));
@@ -1538,11 +3145,19 @@ mod tests {
 
 				assert_eq!(
 					Elections::voting(1),
-					Voter { stake: 10u64, votes: vec![1], deposit: 0 }
+					Voter {
+						stake: 10u64,
+						votes: BoundedVec::try_from(vec![1]).unwrap(),
+						deposit: 0,
+					}
 				);
 				assert_eq!(
 					Elections::voting(2),
-					Voter { stake: 20u64, votes: vec![2], deposit: 0 }
+					Voter {
+						stake: 20u64,
+						votes: BoundedVec::try_from(vec![2]).unwrap(),
+						deposit: 0,
+					}
 				);
 
 				// they will persist since they have self vote.
@@ -1601,13 +3216,66 @@ mod tests {
 	}
 
 	#[test]
-	fn simple_candidate_submission_should_work() {
+	fn elections_are_not_skipped_by_block_number_jumps() {
 		ExtBuilder::default().build_and_execute(|| {
-			assert_eq!(candidate_ids(), Vec::<u64>::new());
-			assert!(Elections::is_candidate(&1).is_err());
-			assert!(Elections::is_candidate(&2).is_err());
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
 
-			assert_eq!(balances(&1), (10, 0));
+			// the block number jumps over the end of the term at 5.
+			System::set_block_number(3);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::election_rounds(), 0);
+			System::set_block_number(7);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::election_rounds(), 1);
+			assert_eq!(members_ids(), vec![5]);
+
+			// the next term still ends at 10.
+			assert_eq!(NextElectionAt::<Test>::get(), Some(10));
+			System::set_block_number(9);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::election_rounds(), 1);
+			System::set_block_number(12);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::election_rounds(), 2);
+			assert_eq!(NextElectionAt::<Test>::get(), Some(15));
+		});
+	}
+
+	#[test]
+	fn set_term_duration_reschedules_the_next_election() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
+
+			System::set_block_number(5);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::election_rounds(), 1);
+			assert_eq!(NextElectionAt::<Test>::get(), Some(10));
+
+			// the current term ends 3 blocks from now.
+			System::set_block_number(6);
+			assert_ok!(Elections::set_term_duration(Origin::root(), 3));
+			assert_eq!(NextElectionAt::<Test>::get(), Some(9));
+
+			System::set_block_number(8);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::election_rounds(), 1);
+			System::set_block_number(9);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::election_rounds(), 2);
+			assert_eq!(NextElectionAt::<Test>::get(), Some(12));
+		});
+	}
+
+	#[test]
+	fn simple_candidate_submission_should_work() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_eq!(candidate_ids(), Vec::<u64>::new());
+			assert!(Elections::is_candidate(&1).is_err());
+			assert!(Elections::is_candidate(&2).is_err());
+
+			assert_eq!(balances(&1), (10, 0));
 			assert_ok!(submit_candidacy(Origin::signed(1)));
 			assert_eq!(balances(&1), (7, 3));
 
@@ -1685,6 +3353,315 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn candidacy_submission_is_limited_by_max_candidates() {
+		ExtBuilder::default().max_candidates(2).build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(1)));
+			assert_ok!(submit_candidacy(Origin::signed(2)));
+			assert_noop!(submit_candidacy(Origin::signed(3)), Error::<Test>::TooManyCandidates);
+
+			// a renounced candidacy frees a spot.
+			assert_ok!(Elections::renounce_candidacy(Origin::signed(1), Renouncing::Candidate(2)));
+			assert_ok!(submit_candidacy(Origin::signed(3)));
+			assert_eq!(candidate_ids(), vec![2, 3]);
+		});
+	}
+
+	#[test]
+	fn vote_is_limited_by_max_votes_per_voter() {
+		ExtBuilder::default().max_votes_per_voter(2).build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(4)));
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(submit_candidacy(Origin::signed(3)));
+
+			assert_noop!(
+				vote(Origin::signed(2), vec![3, 4, 5], 20),
+				Error::<Test>::MaximumVotesExceeded,
+			);
+			assert_ok!(vote(Origin::signed(2), vec![4, 5], 20));
+			assert_eq!(votes_of(&2), vec![4, 5]);
+		});
+	}
+
+	#[test]
+	fn vote_is_limited_by_max_voters() {
+		ExtBuilder::default().max_voters(2).build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(submit_candidacy(Origin::signed(4)));
+
+			assert_ok!(vote(Origin::signed(1), vec![5], 10));
+			assert_ok!(vote(Origin::signed(2), vec![5], 20));
+			assert_noop!(vote(Origin::signed(3), vec![5], 30), Error::<Test>::TooManyVoters);
+
+			// existing voters can still update their votes.
+			assert_ok!(vote(Origin::signed(1), vec![4, 5], 10));
+			assert_eq!(CounterForVoters::<Test>::get(), 2);
+
+			// a removed voter frees a spot.
+			assert_ok!(Elections::remove_voter(Origin::signed(1)));
+			assert_ok!(vote(Origin::signed(3), vec![5], 30));
+			assert_eq!(all_voters().len(), 2);
+			assert_eq!(CounterForVoters::<Test>::get(), 2);
+		});
+	}
+
+	#[test]
+	fn migration_to_v6_unreserves_deposits_out_of_bound() {
+		ExtBuilder::default()
+			.desired_runners_up(1)
+			.max_votes_per_voter(1)
+			.max_voters(1)
+			.voter_bond_factor(1)
+			.build_and_execute(|| {
+				use frame_support::storage::unhashed;
+
+				for who in 3..=4 {
+					assert_ok!(Balances::reserve(&who, 3));
+				}
+				let runners_up: Vec<SeatHolder<u64, u64>> = vec![
+					SeatHolder { who: 3, stake: 30, deposit: 3 },
+					SeatHolder { who: 4, stake: 40, deposit: 3 },
+				];
+				unhashed::put(&<RunnersUp<Test>>::hashed_key(), &runners_up);
+
+				// voters as stored before v6: votes, stake and a deposit of 2 + 1 per vote.
+				assert_ok!(Balances::reserve(&5, 4));
+				unhashed::put(&<Voting<Test>>::hashed_key_for(5u64), &(vec![3u64, 4], 50u64, 4u64));
+				assert_ok!(Balances::reserve(&6, 3));
+				unhashed::put(&<Voting<Test>>::hashed_key_for(6u64), &(vec![4u64], 60u64, 3u64));
+				StorageVersion::new(5).put::<Elections>();
+
+				<migrations::v6::MigrateV5ToV6<Test> as OnRuntimeUpgrade>::on_runtime_upgrade();
+				assert_eq!(StorageVersion::get::<Elections>(), 6);
+
+				// votes are frozen until the voters are migrated.
+				assert_noop!(vote(Origin::signed(2), vec![4], 20), Error::<Test>::VotingFrozen);
+				<Elections as OnIdle<u64>>::on_idle(1, Weight::max_value());
+				assert!(!Elections::is_migrating_voters());
+
+				// the worst runner-up is dropped.
+				assert_eq!(runners_up_ids(), vec![4]);
+				assert_eq!(balances(&3), (30, 0));
+
+				// one voter is left, paying for one vote only. The other one got their deposit back.
+				let kept = all_voters();
+				assert_eq!(kept.len(), 1);
+				assert_eq!(balances(&5), if kept == vec![5] { (47, 3) } else { (50, 0) });
+				assert_eq!(balances(&6), if kept == vec![6] { (57, 3) } else { (60, 0) });
+				if kept == vec![5] {
+					assert_eq!(votes_of(&5), vec![3]);
+				}
+			});
+	}
+
+	#[test]
+	fn migration_to_v6_schedules_the_end_of_the_current_term() {
+		ExtBuilder::default().build_and_execute(|| {
+			// before v6, the term ended at the next multiple of the term duration.
+			<NextElectionAt<Test>>::kill();
+			System::set_block_number(6);
+			StorageVersion::new(5).put::<Elections>();
+
+			<migrations::v6::MigrateV5ToV6<Test> as OnRuntimeUpgrade>::on_runtime_upgrade();
+			assert_eq!(NextElectionAt::<Test>::get(), Some(10));
+		});
+	}
+
+	#[test]
+	fn migration_to_v6_releases_undecodable_voters() {
+		ExtBuilder::default().build_and_execute(|| {
+			use frame_support::storage::unhashed;
+
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(vote(Origin::signed(2), vec![5], 20));
+			assert_eq!(balances(&2), (18, 2));
+			assert_eq!(locked_stake_of(&2), 20);
+
+			// a voter claiming more votes than it holds, followed by its stake and deposit.
+			let mut raw = codec::Compact(100u32).encode();
+			raw.extend((5u64, 20u64, 2u64).encode());
+			unhashed::put_raw(&<Voting<Test>>::hashed_key_for(2u64), &raw);
+			StorageVersion::new(5).put::<Elections>();
+
+			<migrations::v6::MigrateV5ToV6<Test> as OnRuntimeUpgrade>::on_runtime_upgrade();
+			<Elections as OnIdle<u64>>::on_idle(1, Weight::max_value());
+
+			assert!(all_voters().is_empty());
+			assert_eq!(balances(&2), (20, 0));
+			assert!(Balances::locks(&2).is_empty());
+		});
+	}
+
+	#[test]
+	fn migration_to_v5_unreserves_deposits_out_of_bound() {
+		ExtBuilder::default().max_candidates(2).build_and_execute(|| {
+			for who in 1..=3 {
+				assert_ok!(Balances::reserve(&who, 3));
+			}
+			let candidates: Vec<(u64, u64)> = vec![(1, 3), (2, 3), (3, 3)];
+			frame_support::storage::unhashed::put(&<Candidates<Test>>::hashed_key(), &candidates);
+			StorageVersion::new(4).put::<Elections>();
+
+			<migrations::v5::MigrateV4ToV5<Test> as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+			assert_eq!(StorageVersion::get::<Elections>(), 5);
+			assert_eq!(candidate_ids(), vec![1, 2]);
+			assert_eq!(balances(&2), (17, 3));
+			assert_eq!(balances(&3), (30, 0));
+		});
+	}
+
+	#[test]
+	fn check_invariants_reports_uncovered_deposits() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
+			assert_ok!(vote(Origin::signed(2), vec![5], 20));
+			assert!(Elections::check_invariants().is_empty());
+
+			// the candidacy bond and the voting bond of 5 are both reserved.
+			Balances::unreserve(&5, 2);
+			assert_eq!(
+				Elections::check_invariants(),
+				vec!["Elections: reserved balance does not cover the recorded deposits."],
+			);
+		});
+	}
+
+	#[test]
+	fn candidate_metadata_is_kept_while_candidate_or_seat_holder() {
+		ExtBuilder::default().desired_runners_up(1).build_and_execute(|| {
+			let set = |who: u64, metadata: &[u8]| {
+				Elections::set_candidate_metadata(Origin::signed(who), metadata.to_vec())
+			};
+			let metadata_of = |who: u64| Elections::candidate_metadata(who).map(|m| m.into_inner());
+
+			assert_noop!(set(5, b"five"), Error::<Test>::MustBeCandidateOrSeatHolder);
+			for who in 1..=5 {
+				assert_ok!(submit_candidacy(Origin::signed(who)));
+			}
+			assert_noop!(set(5, b"too long name"), Error::<Test>::MetadataTooLong);
+			assert_ok!(set(5, b"five"));
+			System::assert_last_event(Event::Elections(super::Event::CandidateMetadataSet {
+				candidate: 5,
+			}));
+			assert_ok!(set(4, b"four"));
+			assert_ok!(set(3, b"three"));
+			assert_ok!(set(2, b"two"));
+			assert_ok!(set(1, b"one"));
+			assert_ok!(set(1, b""));
+			assert_eq!(metadata_of(5), Some(b"five".to_vec()));
+			assert_eq!(metadata_of(1), None);
+
+			assert_ok!(Elections::renounce_candidacy(Origin::signed(2), Renouncing::Candidate(5)));
+			assert_eq!(metadata_of(2), None);
+
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
+			assert_ok!(vote(Origin::signed(4), vec![4], 40));
+			assert_ok!(vote(Origin::signed(3), vec![3], 30));
+
+			System::set_block_number(5);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(members_ids(), vec![4, 5]);
+			assert_eq!(runners_up_ids(), vec![3]);
+
+			// seat holders keep their metadata across terms, and can change it.
+			assert_eq!(metadata_of(5), Some(b"five".to_vec()));
+			assert_eq!(metadata_of(4), Some(b"four".to_vec()));
+			assert_eq!(metadata_of(3), Some(b"three".to_vec()));
+			assert_ok!(set(3, b"3"));
+			assert_eq!(metadata_of(3), Some(b"3".to_vec()));
+			assert_noop!(set(1, b"one"), Error::<Test>::MustBeCandidateOrSeatHolder);
+
+			assert_ok!(Elections::remove_member(Origin::root(), 4, true));
+			assert_eq!(members_ids(), vec![3, 5]);
+			assert_eq!(metadata_of(4), None);
+
+			assert_ok!(Elections::renounce_candidacy(Origin::signed(5), Renouncing::Member));
+			assert_eq!(metadata_of(5), None);
+			assert_eq!(metadata_of(3), Some(b"3".to_vec()));
+		});
+	}
+
+	#[test]
+	#[cfg(feature = "try-runtime")]
+	fn try_state_detects_unreserved_seat_deposit() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
+
+			System::set_block_number(5);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(members_ids(), vec![5]);
+			assert_ok!(Elections::do_try_state());
+
+			// the candidacy bond and the voting bond of 5 are both reserved. The unreserved funds
+			// cannot be reserved again while locked, hence the rollback.
+			frame_support::storage::with_transaction(|| {
+				Balances::unreserve(&5, 5);
+				assert_eq!(
+					Elections::do_try_state(),
+					Err("A member or runner-up does not have their deposit reserved."),
+				);
+				frame_support::storage::TransactionOutcome::Rollback(())
+			});
+			assert_ok!(Elections::do_try_state());
+		});
+	}
+
+	#[test]
+	fn slashed_voter_stake_is_capped_by_balance() {
+		ExtBuilder::default()
+			.desired_members(1)
+			.desired_runners_up(0)
+			.build_and_execute(|| {
+				assert_ok!(submit_candidacy(Origin::signed(5)));
+				assert_ok!(submit_candidacy(Origin::signed(2)));
+				assert_ok!(vote(Origin::signed(5), vec![5], 50));
+				assert_ok!(vote(Origin::signed(2), vec![2], 20));
+
+				System::set_block_number(5);
+				Elections::on_initialize(System::block_number());
+				assert_eq!(members_ids(), vec![5]);
+
+				// 2 lost their candidacy bond, and can only stake what is left.
+				assert_eq!(Balances::total_balance(&2), 17);
+				assert_eq!(locked_stake_of(&2), 17);
+				assert_eq!(has_lock(&2), 17);
+				assert_ok!(Elections::do_try_state());
+			});
+	}
+
+	#[test]
+	#[cfg(feature = "try-runtime")]
+	fn upgrade_hooks_detect_unsorted_members() {
+		ExtBuilder::default().desired_runners_up(0).build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(submit_candidacy(Origin::signed(4)));
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
+			assert_ok!(vote(Origin::signed(4), vec![4], 40));
+
+			System::set_block_number(5);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(members_ids(), vec![4, 5]);
+			assert_ok!(<Elections as OnRuntimeUpgrade>::pre_upgrade());
+			assert_ok!(<Elections as OnRuntimeUpgrade>::post_upgrade());
+
+			crate::Members::<Test>::mutate(|members| members.as_mut().reverse());
+			assert_eq!(
+				<Elections as OnRuntimeUpgrade>::pre_upgrade(),
+				Err("Members are not sorted.")
+			);
+			assert_eq!(
+				<Elections as OnRuntimeUpgrade>::post_upgrade(),
+				Err("Members are not sorted.")
+			);
+
+			// restore the order for the checks run after each test.
+			crate::Members::<Test>::mutate(|members| members.as_mut().reverse());
+		});
+	}
+
 	#[test]
 	fn member_candidacy_submission_should_not_work() {
 		// critically important to make sure that outgoing candidates and losers are not mixed up.
@@ -1808,6 +3785,41 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn refund_voter_deposit_works() {
+		ExtBuilder::default().voter_bond_factor(1).build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(submit_candidacy(Origin::signed(4)));
+
+			assert_ok!(vote(Origin::signed(2), vec![5, 4], 10));
+			assert_eq!(balances(&2), (16, 4));
+
+			assert_noop!(
+				Elections::refund_voter_deposit(Origin::signed(2)),
+				Error::<Test>::NothingToRefund,
+			);
+			assert_noop!(
+				Elections::refund_voter_deposit(Origin::signed(3)),
+				Error::<Test>::MustBeVoter,
+			);
+
+			// a runtime upgrade removes the per-vote bond.
+			VOTING_BOND_FACTOR.with(|v| *v.borrow_mut() = 0);
+
+			assert_ok!(Elections::refund_voter_deposit(Origin::signed(2)));
+			System::assert_last_event(Event::Elections(super::Event::VoterDepositRefunded {
+				voter: 2,
+				amount: 2,
+			}));
+			assert_eq!(balances(&2), (18, 2));
+			assert_eq!(voter_deposit(&2), 2);
+			assert_eq!(votes_of(&2), vec![5, 4]);
+
+			assert_ok!(Elections::remove_voter(Origin::signed(2)));
+			assert_eq!(balances(&2), (20, 0));
+		});
+	}
+
 	#[test]
 	fn voting_reserves_bond_per_vote() {
 		ExtBuilder::default().voter_bond_factor(1).build_and_execute(|| {
@@ -2012,117 +4024,628 @@ mod tests {
 		})
 	}
 
-	#[test]
-	fn can_vote_for_more_than_total_balance_but_moot() {
-		ExtBuilder::default().build_and_execute(|| {
-			assert_ok!(submit_candidacy(Origin::signed(5)));
-			assert_ok!(submit_candidacy(Origin::signed(4)));
+	#[test]
+	fn can_vote_for_more_than_total_balance_but_moot() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(submit_candidacy(Origin::signed(4)));
+
+			assert_ok!(vote(Origin::signed(2), vec![4, 5], 30));
+			// you can lie but won't get away with it.
+			assert_eq!(locked_stake_of(&2), 20);
+			assert_eq!(has_lock(&2), 20);
+		});
+	}
+
+	#[test]
+	fn remove_voter_should_work() {
+		ExtBuilder::default().voter_bond(8).build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+
+			assert_ok!(vote(Origin::signed(2), vec![5], 20));
+			assert_ok!(vote(Origin::signed(3), vec![5], 30));
+
+			assert_eq_uvec!(all_voters(), vec![2, 3]);
+			assert_eq!(locked_stake_of(&2), 20);
+			assert_eq!(locked_stake_of(&3), 30);
+			assert_eq!(votes_of(&2), vec![5]);
+			assert_eq!(votes_of(&3), vec![5]);
+
+			assert_ok!(Elections::remove_voter(Origin::signed(2)));
+
+			assert_eq_uvec!(all_voters(), vec![3]);
+			assert!(votes_of(&2).is_empty());
+			assert_eq!(locked_stake_of(&2), 0);
+
+			assert_eq!(balances(&2), (20, 0));
+			assert_eq!(Balances::locks(&2).len(), 0);
+		});
+	}
+
+	#[test]
+	fn non_voter_remove_should_not_work() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_noop!(Elections::remove_voter(Origin::signed(3)), Error::<Test>::MustBeVoter);
+		});
+	}
+
+	#[test]
+	fn dupe_remove_should_fail() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(vote(Origin::signed(2), vec![5], 20));
+
+			assert_ok!(Elections::remove_voter(Origin::signed(2)));
+			assert!(all_voters().is_empty());
+
+			assert_noop!(Elections::remove_voter(Origin::signed(2)), Error::<Test>::MustBeVoter);
+		});
+	}
+
+	#[test]
+	fn removed_voter_should_not_be_counted() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(submit_candidacy(Origin::signed(4)));
+			assert_ok!(submit_candidacy(Origin::signed(3)));
+
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
+			assert_ok!(vote(Origin::signed(4), vec![4], 40));
+			assert_ok!(vote(Origin::signed(3), vec![3], 30));
+
+			assert_ok!(Elections::remove_voter(Origin::signed(4)));
+
+			System::set_block_number(5);
+			Elections::on_initialize(System::block_number());
+
+			assert_eq!(members_ids(), vec![3, 5]);
+		});
+	}
+
+	#[test]
+	fn simple_voting_rounds_should_work() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(submit_candidacy(Origin::signed(4)));
+			assert_ok!(submit_candidacy(Origin::signed(3)));
+
+			assert_ok!(vote(Origin::signed(2), vec![5], 20));
+			assert_ok!(vote(Origin::signed(4), vec![4], 15));
+			assert_ok!(vote(Origin::signed(3), vec![3], 30));
+
+			assert_eq_uvec!(all_voters(), vec![2, 3, 4]);
+
+			assert_eq!(votes_of(&2), vec![5]);
+			assert_eq!(votes_of(&3), vec![3]);
+			assert_eq!(votes_of(&4), vec![4]);
+
+			assert_eq!(candidate_ids(), vec![3, 4, 5]);
+			assert_eq!(<Candidates<Test>>::decode_len().unwrap(), 3);
+
+			assert_eq!(Elections::election_rounds(), 0);
+
+			System::set_block_number(5);
+			Elections::on_initialize(System::block_number());
+
+			assert_eq!(members_and_stake(), vec![(3, 30), (5, 20)]);
+			assert!(Elections::runners_up().is_empty());
+
+			assert_eq_uvec!(all_voters(), vec![2, 3, 4]);
+			assert!(candidate_ids().is_empty());
+			assert_eq!(<Candidates<Test>>::decode_len(), None);
+
+			assert_eq!(Elections::election_rounds(), 1);
+		});
+	}
+
+	#[test]
+	fn deferred_election_snapshots_voters_over_several_blocks() {
+		ExtBuilder::default().voter_snapshot_per_block(2).build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(submit_candidacy(Origin::signed(4)));
+			assert_ok!(submit_candidacy(Origin::signed(3)));
+
+			assert_ok!(vote(Origin::signed(2), vec![5], 20));
+			assert_ok!(vote(Origin::signed(4), vec![4], 15));
+			assert_ok!(vote(Origin::signed(3), vec![3], 30));
+
+			System::set_block_number(5);
+			Elections::on_initialize(System::block_number());
+			System::assert_last_event(Event::Elections(super::Event::ElectionStarted));
+			assert_eq!(Elections::phase(), ElectionPhase::Snapshot(None));
+			assert_eq!(<SnapshotCandidates<Test>>::decode_len(), Some(3));
+
+			// candidacies and seats are frozen until the election completes.
+			assert_noop!(submit_candidacy(Origin::signed(1)), Error::<Test>::ElectionOngoing);
+			assert_noop!(
+				Elections::renounce_candidacy(Origin::signed(3), Renouncing::Candidate(3)),
+				Error::<Test>::ElectionOngoing,
+			);
+
+			// two voters are snapshotted per block.
+			System::set_block_number(6);
+			Elections::on_initialize(System::block_number());
+			assert!(matches!(Elections::phase(), ElectionPhase::Snapshot(Some(_))));
+			assert_eq!(<SnapshotVoterPages<Test>>::get(), 1);
+			assert_eq!(<SnapshotVoters<Test>>::decode_len(0), Some(2));
+
+			System::set_block_number(7);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::phase(), ElectionPhase::Signed(7));
+			assert_eq!(<SnapshotVoterPages<Test>>::get(), 2);
+			assert_eq!(<SnapshotVoters<Test>>::decode_len(1), Some(1));
+			assert!(members_ids().is_empty());
+			assert_eq!(Elections::election_rounds(), 0);
+
+			System::set_block_number(8);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(members_and_stake(), vec![(3, 30), (5, 20)]);
+			assert!(candidate_ids().is_empty());
+			assert_eq!(Elections::election_rounds(), 1);
+
+			assert_eq!(Elections::phase(), ElectionPhase::Off);
+			assert_eq!(<SnapshotCandidates<Test>>::decode_len(), None);
+			assert_eq!(<SnapshotVoterPages<Test>>::get(), 0);
+			assert_eq!(<SnapshotVoters<Test>>::iter().count(), 0);
+			assert_ok!(submit_candidacy(Origin::signed(1)));
+		});
+	}
+
+	#[test]
+	fn deferred_election_snapshots_voters_as_of_its_start() {
+		ExtBuilder::default().voter_snapshot_per_block(1).build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(submit_candidacy(Origin::signed(4)));
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
+			assert_ok!(vote(Origin::signed(4), vec![4], 40));
+
+			System::set_block_number(5);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::phase(), ElectionPhase::Snapshot(None));
+
+			// votes are frozen while the voters are snapshotted.
+			assert_noop!(vote(Origin::signed(4), vec![5], 40), Error::<Test>::VotingFrozen);
+			assert_noop!(Elections::remove_voter(Origin::signed(4)), Error::<Test>::VotingFrozen);
+			assert_noop!(
+				Elections::clean_defunct_voters(Origin::signed(1), 2, 2),
+				Error::<Test>::VotingFrozen,
+			);
+
+			// the stake is capped by the balance at the time of the snapshot.
+			let _ = Balances::slash(&4, 30);
+			let _ = Balances::slash(&5, 30);
+			for n in 6..=8 {
+				System::set_block_number(n);
+				Elections::on_initialize(System::block_number());
+			}
+			assert_eq!(Elections::phase(), ElectionPhase::Signed(8));
+			let mut snapshot = Elections::snapshotted_voters();
+			snapshot.sort();
+			assert_eq!(snapshot, vec![(4, 10, vec![4]), (5, 20, vec![5])]);
+
+			// votes can change again once the snapshot is complete.
+			assert_ok!(vote(Origin::signed(4), vec![5], 10));
+			assert_ok!(vote(Origin::signed(5), vec![5], 20));
+		});
+	}
+
+	#[test]
+	fn signed_solution_is_used_in_deferred_election() {
+		ExtBuilder::default()
+			.voter_snapshot_per_block(2)
+			.signed_phase(2)
+			.build_and_execute(|| {
+				assert_ok!(submit_candidacy(Origin::signed(5)));
+				assert_ok!(submit_candidacy(Origin::signed(4)));
+				assert_ok!(submit_candidacy(Origin::signed(3)));
+
+				assert_ok!(vote(Origin::signed(2), vec![5], 20));
+				assert_ok!(vote(Origin::signed(4), vec![4], 15));
+				assert_ok!(vote(Origin::signed(3), vec![3], 30));
+
+				// a solution electing 3 and 4, worse than the one of phragmen, yet valid.
+				let solution = RawSolution {
+					winners: vec![4, 3],
+					assignments: vec![(3, vec![(3, 30)]), (4, vec![(4, 15)])],
+					score: [15, 45, 30 * 30 + 15 * 15],
+				};
+				assert_noop!(
+					Elections::submit_solution(Origin::signed(6), solution.clone()),
+					Error::<Test>::SignedPhaseClosed,
+				);
+
+				for n in 5..=7 {
+					System::set_block_number(n);
+					Elections::on_initialize(System::block_number());
+				}
+				assert_eq!(Elections::phase(), ElectionPhase::Signed(9));
+
+				assert_ok!(Elections::submit_solution(Origin::signed(6), solution.clone()));
+				System::assert_last_event(Event::Elections(super::Event::SolutionStored {
+					submitter: 6,
+					deposit: 5,
+				}));
+				assert_eq!(balances(&6), (55, 5));
+				assert_eq!(
+					Elections::best_submission().map(|best| best.winners),
+					Some(vec![(3, 30), (4, 15)]),
+				);
+				assert_noop!(
+					Elections::submit_solution(Origin::signed(1), solution),
+					Error::<Test>::WeakSolution,
+				);
+
+				// the election runs after the signed phase.
+				System::set_block_number(9);
+				Elections::on_initialize(System::block_number());
+				assert!(members_ids().is_empty());
+
+				System::set_block_number(10);
+				Elections::on_initialize(System::block_number());
+				assert_eq!(members_and_stake(), vec![(3, 30), (4, 15)]);
+				assert_eq!(balances(&6), (60, 0));
+				assert_eq!(balances(&5), (47, 0));
+				assert_eq!(Elections::phase(), ElectionPhase::Off);
+				assert_eq!(Elections::best_submission(), None);
+			});
+	}
+
+	#[test]
+	fn infeasible_solutions_are_slashed() {
+		ExtBuilder::default()
+			.voter_snapshot_per_block(2)
+			.signed_phase(2)
+			.build_and_execute(|| {
+				assert_ok!(submit_candidacy(Origin::signed(5)));
+				assert_ok!(submit_candidacy(Origin::signed(4)));
+				assert_ok!(submit_candidacy(Origin::signed(3)));
+
+				assert_ok!(vote(Origin::signed(2), vec![5], 20));
+				assert_ok!(vote(Origin::signed(4), vec![4], 15));
+				assert_ok!(vote(Origin::signed(3), vec![3], 30));
+
+				for n in 5..=7 {
+					System::set_block_number(n);
+					Elections::on_initialize(System::block_number());
+				}
+
+				let check =
+					|solution: RawSolution<u64>, error: FeasibilityError| {
+						assert_eq!(Elections::feasibility_check(&solution), Err(error));
+						let (free, reserved) = balances(&6);
+						assert_ok!(Elections::submit_solution(Origin::signed(6), solution));
+						System::assert_last_event(Event::Elections(
+							super::Event::SolutionSlashed { submitter: 6, amount: 5, deposit: 5 },
+						));
+						assert_eq!(balances(&6), (free - 5, reserved));
+					};
+
+				// too few winners.
+				check(
+					RawSolution { winners: vec![3], assignments: vec![], score: [0, 0, 0] },
+					FeasibilityError::WrongWinnerCount,
+				);
+				// not a candidate.
+				check(
+					RawSolution { winners: vec![3, 1], assignments: vec![], score: [0, 0, 0] },
+					FeasibilityError::InvalidWinner,
+				);
+				// 4 votes for 4, not 3.
+				check(
+					RawSolution {
+						winners: vec![3, 5],
+						assignments: vec![(4, vec![(3, 15)])],
+						score: [0, 15, 225],
+					},
+					FeasibilityError::InvalidVote,
+				);
+				// 3 only has 30.
+				check(
+					RawSolution {
+						winners: vec![3, 5],
+						assignments: vec![(3, vec![(3, 31)])],
+						score: [0, 31, 961],
+					},
+					FeasibilityError::InvalidStake,
+				);
+				// 3 distributes their stake twice.
+				check(
+					RawSolution {
+						winners: vec![3, 5],
+						assignments: vec![(3, vec![(3, 30)]), (3, vec![(3, 30)])],
+						score: [0, 60, 3600],
+					},
+					FeasibilityError::InvalidVoter,
+				);
+				// 3 keeps part of their stake.
+				check(
+					RawSolution {
+						winners: vec![3, 5],
+						assignments: vec![(3, vec![(3, 10)]), (2, vec![(5, 20)])],
+						score: [10, 30, 500],
+					},
+					FeasibilityError::InvalidStake,
+				);
+				// 3 and 2 voted for the winners, yet are left out.
+				check(
+					RawSolution { winners: vec![3, 5], assignments: vec![], score: [0, 0, 0] },
+					FeasibilityError::MissingVoter,
+				);
+				check(
+					RawSolution {
+						winners: vec![3, 5],
+						assignments: vec![(3, vec![(3, 30)])],
+						score: [0, 30, 900],
+					},
+					FeasibilityError::MissingVoter,
+				);
+				// the score is overstated.
+				check(
+					RawSolution {
+						winners: vec![3, 5],
+						assignments: vec![(3, vec![(3, 30)]), (2, vec![(5, 20)])],
+						score: [30, 50, 1300],
+					},
+					FeasibilityError::InvalidScore,
+				);
+				assert_eq!(Elections::best_submission(), None);
+
+				// phragmen runs without any valid solution.
+				System::set_block_number(10);
+				Elections::on_initialize(System::block_number());
+				assert_eq!(members_and_stake(), vec![(3, 30), (5, 20)]);
+			});
+	}
+
+	#[test]
+	fn solutions_must_beat_the_minimum_untrusted_score() {
+		ExtBuilder::default()
+			.voter_snapshot_per_block(2)
+			.signed_phase(2)
+			.build_and_execute(|| {
+				assert_ok!(submit_candidacy(Origin::signed(5)));
+				assert_ok!(submit_candidacy(Origin::signed(4)));
+				assert_ok!(submit_candidacy(Origin::signed(3)));
+
+				assert_ok!(vote(Origin::signed(2), vec![5], 20));
+				assert_ok!(vote(Origin::signed(4), vec![4], 15));
+				assert_ok!(vote(Origin::signed(3), vec![3], 30));
+
+				assert_noop!(
+					Elections::set_minimum_untrusted_score(Origin::signed(1), Some([20, 0, 0])),
+					BadOrigin,
+				);
+				assert_ok!(Elections::set_minimum_untrusted_score(
+					Origin::root(),
+					Some([20, 0, 0])
+				));
+				assert_eq!(Elections::minimum_untrusted_score(), Some([20, 0, 0]));
+				System::assert_last_event(Event::Elections(
+					super::Event::MinimumUntrustedScoreSet { score: Some([20, 0, 0]) },
+				));
+
+				for n in 5..=7 {
+					System::set_block_number(n);
+					Elections::on_initialize(System::block_number());
+				}
+
+				// electing 3 and 4 is feasible, but its minimal support is below the minimum.
+				let weak = RawSolution {
+					winners: vec![4, 3],
+					assignments: vec![(3, vec![(3, 30)]), (4, vec![(4, 15)])],
+					score: [15, 45, 30 * 30 + 15 * 15],
+				};
+				assert_noop!(
+					Elections::submit_solution(Origin::signed(6), weak),
+					Error::<Test>::WeakSolution,
+				);
+
+				let solution = RawSolution {
+					winners: vec![3, 5],
+					assignments: vec![(3, vec![(3, 30)]), (2, vec![(5, 20)])],
+					score: [20, 50, 30 * 30 + 20 * 20],
+				};
+				assert_ok!(Elections::submit_solution(Origin::signed(6), solution));
+				assert_eq!(
+					Elections::best_submission().map(|best| best.winners),
+					Some(vec![(3, 30), (5, 20)]),
+				);
+
+				assert_ok!(Elections::set_minimum_untrusted_score(Origin::root(), None));
+				assert_eq!(Elections::minimum_untrusted_score(), None);
+			});
+	}
+
+	#[test]
+	fn fallback_keeps_members() {
+		ExtBuilder::default()
+			.genesis_members(vec![(1, 10), (2, 20)])
+			.build_and_execute(|| {
+				assert_ok!(submit_candidacy(Origin::signed(3)));
+				assert_ok!(vote(Origin::signed(3), vec![3], 30));
+
+				fail_election();
+
+				System::assert_last_event(Event::Elections(super::Event::FallbackMembersKept));
+				assert_eq!(members_and_stake(), vec![(1, 10), (2, 20)]);
+				assert_eq!(candidate_ids(), vec![3]);
+				assert_eq!(Elections::election_rounds(), 0);
+			});
+	}
+
+	#[test]
+	fn fallback_elects_by_approval() {
+		ExtBuilder::default()
+			.fallback(FallbackStrategy::Approval)
+			.build_and_execute(|| {
+				assert_ok!(submit_candidacy(Origin::signed(5)));
+				assert_ok!(submit_candidacy(Origin::signed(4)));
+				assert_ok!(submit_candidacy(Origin::signed(3)));
+
+				assert_ok!(vote(Origin::signed(2), vec![5, 4, 4], 20));
+				assert_ok!(vote(Origin::signed(3), vec![3], 30));
+				assert_ok!(vote(Origin::signed(4), vec![4], 15));
+
+				fail_election();
 
-			assert_ok!(vote(Origin::signed(2), vec![4, 5], 30));
-			// you can lie but won't get away with it.
-			assert_eq!(locked_stake_of(&2), 20);
-			assert_eq!(has_lock(&2), 20);
-		});
+				System::assert_has_event(Event::Elections(super::Event::FallbackApprovalElection));
+				// 4 is approved by 2 and 4, duplicate votes are counted once.
+				assert_eq!(members_and_stake(), vec![(3, 30), (4, 35)]);
+				assert!(candidate_ids().is_empty());
+				assert_eq!(balances(&5), (47, 0));
+				assert_eq!(Elections::election_rounds(), 1);
+			});
 	}
 
 	#[test]
-	fn remove_voter_should_work() {
-		ExtBuilder::default().voter_bond(8).build_and_execute(|| {
-			assert_ok!(submit_candidacy(Origin::signed(5)));
+	fn fallback_emergency_waits_for_set_members() {
+		ExtBuilder::default()
+			.genesis_members(vec![(1, 10), (2, 20)])
+			.fallback(FallbackStrategy::Emergency)
+			.build_and_execute(|| {
+				assert_noop!(
+					Elections::set_members(Origin::root(), vec![1]),
+					Error::<Test>::NoEmergency,
+				);
 
-			assert_ok!(vote(Origin::signed(2), vec![5], 20));
-			assert_ok!(vote(Origin::signed(3), vec![5], 30));
+				assert_ok!(submit_candidacy(Origin::signed(5)));
+				assert_ok!(submit_candidacy(Origin::signed(3)));
+				assert_ok!(vote(Origin::signed(5), vec![5], 50));
+				assert_ok!(vote(Origin::signed(3), vec![3], 30));
 
-			assert_eq_uvec!(all_voters(), vec![2, 3]);
-			assert_eq!(locked_stake_of(&2), 20);
-			assert_eq!(locked_stake_of(&3), 30);
-			assert_eq!(votes_of(&2), vec![5]);
-			assert_eq!(votes_of(&3), vec![5]);
+				fail_election();
 
-			assert_ok!(Elections::remove_voter(Origin::signed(2)));
+				System::assert_last_event(Event::Elections(super::Event::FallbackEmergency));
+				assert_eq!(Elections::phase(), ElectionPhase::Emergency);
+				assert_eq!(members_and_stake(), vec![(1, 10), (2, 20)]);
 
-			assert_eq_uvec!(all_voters(), vec![3]);
-			assert!(votes_of(&2).is_empty());
-			assert_eq!(locked_stake_of(&2), 0);
+				// seats are frozen, and no election happens.
+				assert_noop!(submit_candidacy(Origin::signed(4)), Error::<Test>::ElectionOngoing);
+				System::set_block_number(5);
+				Elections::on_initialize(System::block_number());
+				assert_eq!(members_and_stake(), vec![(1, 10), (2, 20)]);
 
-			assert_eq!(balances(&2), (20, 0));
-			assert_eq!(Balances::locks(&2).len(), 0);
-		});
+				assert_noop!(Elections::set_members(Origin::signed(1), vec![5]), BadOrigin);
+				assert_noop!(
+					Elections::set_members(Origin::root(), vec![5, 4]),
+					Error::<Test>::InvalidMembers,
+				);
+				assert_noop!(
+					Elections::set_members(Origin::root(), vec![5, 5]),
+					Error::<Test>::InvalidMembers,
+				);
+				assert_noop!(
+					Elections::set_members(Origin::root(), vec![5, 3, 2]),
+					Error::<Test>::InvalidMembers,
+				);
+
+				assert_ok!(Elections::set_members(Origin::root(), vec![5, 1]));
+				assert_eq!(members_and_stake(), vec![(1, 10), (5, 50)]);
+				assert_eq!(balances(&3), (25, 2));
+				assert_eq!(Elections::phase(), ElectionPhase::Off);
+				assert_eq!(<SnapshotCandidates<Test>>::decode_len(), None);
+				assert_eq!(<SnapshotVoterPages<Test>>::get(), 0);
+			});
 	}
 
 	#[test]
-	fn non_voter_remove_should_not_work() {
-		ExtBuilder::default().build_and_execute(|| {
-			assert_noop!(Elections::remove_voter(Origin::signed(3)), Error::<Test>::MustBeVoter);
-		});
+	fn too_heavy_election_is_refused() {
+		ExtBuilder::default()
+			.max_election_weight(Perbill::from_percent(50))
+			.fallback(FallbackStrategy::Approval)
+			.build_and_execute(|| {
+				assert_ok!(submit_candidacy(Origin::signed(5)));
+				assert_ok!(submit_candidacy(Origin::signed(4)));
+				assert_ok!(vote(Origin::signed(5), vec![5], 50));
+				assert_ok!(vote(Origin::signed(4), vec![4], 40));
+
+				System::set_block_number(5);
+				Elections::on_initialize(System::block_number());
+
+				// any election is heavier than half of the block of the mock.
+				let weight = <() as WeightInfo>::election_phragmen(2, 2, 2);
+				System::assert_has_event(Event::Elections(super::Event::ElectionError {
+					reason: ElectionErrorReason::TooHeavy { weight },
+				}));
+				System::assert_has_event(Event::Elections(super::Event::FallbackApprovalElection));
+				assert_eq!(members_and_stake(), vec![(4, 40), (5, 50)]);
+			});
 	}
 
 	#[test]
-	fn dupe_remove_should_fail() {
-		ExtBuilder::default().build_and_execute(|| {
-			assert_ok!(submit_candidacy(Origin::signed(5)));
-			assert_ok!(vote(Origin::signed(2), vec![5], 20));
-
-			assert_ok!(Elections::remove_voter(Origin::signed(2)));
-			assert!(all_voters().is_empty());
+	fn deferred_election_with_no_candidates_is_an_empty_term() {
+		ExtBuilder::default().voter_snapshot_per_block(2).build_and_execute(|| {
+			System::set_block_number(5);
+			Elections::on_initialize(System::block_number());
 
-			assert_noop!(Elections::remove_voter(Origin::signed(2)), Error::<Test>::MustBeVoter);
+			System::assert_last_event(Event::Elections(super::Event::EmptyTerm));
+			assert_eq!(Elections::phase(), ElectionPhase::Off);
 		});
 	}
 
 	#[test]
-	fn removed_voter_should_not_be_counted() {
-		ExtBuilder::default().build_and_execute(|| {
+	fn predict_result_works() {
+		ExtBuilder::default().desired_runners_up(1).build_and_execute(|| {
 			assert_ok!(submit_candidacy(Origin::signed(5)));
 			assert_ok!(submit_candidacy(Origin::signed(4)));
 			assert_ok!(submit_candidacy(Origin::signed(3)));
+			assert_ok!(submit_candidacy(Origin::signed(2)));
 
 			assert_ok!(vote(Origin::signed(5), vec![5], 50));
 			assert_ok!(vote(Origin::signed(4), vec![4], 40));
-			assert_ok!(vote(Origin::signed(3), vec![3], 30));
+			assert_ok!(vote(Origin::signed(3), vec![3, 4], 30));
 
-			assert_ok!(Elections::remove_voter(Origin::signed(4)));
+			let prediction = Elections::predict_result();
+			let ids = |seats: &[(u64, u64)]| seats.iter().map(|(who, _)| *who).collect::<Vec<_>>();
+			assert_eq!(ids(&prediction.members), vec![4, 5]);
+			assert_eq!(ids(&prediction.runners_up), vec![3]);
+			assert_eq!(prediction.approvals, vec![(2, 0), (3, 30), (4, 70), (5, 50)]);
 
 			System::set_block_number(5);
 			Elections::on_initialize(System::block_number());
 
-			assert_eq!(members_ids(), vec![3, 5]);
+			assert_eq!(members_and_stake(), prediction.members);
+			assert_eq!(runners_up_and_stake(), prediction.runners_up);
 		});
 	}
 
+	#[cfg(feature = "election-provider")]
 	#[test]
-	fn simple_voting_rounds_should_work() {
-		ExtBuilder::default().build_and_execute(|| {
+	fn election_provider_adapter_elects_as_built_in() {
+		use frame_election_provider_support::ElectionDataProvider;
+
+		ExtBuilder::default().desired_runners_up(1).build_and_execute(|| {
 			assert_ok!(submit_candidacy(Origin::signed(5)));
 			assert_ok!(submit_candidacy(Origin::signed(4)));
 			assert_ok!(submit_candidacy(Origin::signed(3)));
+			assert_ok!(submit_candidacy(Origin::signed(2)));
 
-			assert_ok!(vote(Origin::signed(2), vec![5], 20));
-			assert_ok!(vote(Origin::signed(4), vec![4], 15));
-			assert_ok!(vote(Origin::signed(3), vec![3], 30));
-
-			assert_eq_uvec!(all_voters(), vec![2, 3, 4]);
-
-			assert_eq!(votes_of(&2), vec![5]);
-			assert_eq!(votes_of(&3), vec![3]);
-			assert_eq!(votes_of(&4), vec![4]);
-
-			assert_eq!(candidate_ids(), vec![3, 4, 5]);
-			assert_eq!(<Candidates<Test>>::decode_len().unwrap(), 3);
-
-			assert_eq!(Elections::election_rounds(), 0);
-
-			System::set_block_number(5);
-			Elections::on_initialize(System::block_number());
-
-			assert_eq!(members_and_stake(), vec![(3, 30), (5, 20)]);
-			assert!(Elections::runners_up().is_empty());
-
-			assert_eq_uvec!(all_voters(), vec![2, 3, 4]);
-			assert!(candidate_ids().is_empty());
-			assert_eq!(<Candidates<Test>>::decode_len(), None);
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
+			assert_ok!(vote(Origin::signed(4), vec![4], 40));
+			assert_ok!(vote(Origin::signed(3), vec![3, 4], 30));
 
-			assert_eq!(Elections::election_rounds(), 1);
+			assert_eq!(Elections::targets(None).unwrap().0, vec![2, 3, 4, 5]);
+			assert_eq!(Elections::targets(Some(3)), Err("Target snapshot too big"));
+			assert_eq_uvec!(
+				Elections::voters(None).unwrap().0,
+				vec![(3, 30, vec![3, 4]), (4, 40, vec![4]), (5, 50, vec![5])]
+			);
+			assert_eq!(Elections::desired_targets().unwrap().0, 3);
+
+			let (winners, _) = election_provider::ElectionProviderAdapter::<Test>::elect_winners()
+				.unwrap()
+				.unwrap();
+			let (members, runners_up) = Elections::split_winners(winners);
+			let prediction = Elections::predict_result();
+			// stakes might differ by rounding, the supports being computed with `Perbill`.
+			let ids = |seats: &[(u64, u64)]| seats.iter().map(|(who, _)| *who).collect::<Vec<_>>();
+			assert_eq!(ids(&members), ids(&prediction.members));
+			assert_eq!(ids(&runners_up), ids(&prediction.runners_up));
+
+			// the term ends in block 5, or in the next one once dissolved.
+			assert_eq!(Elections::next_election_prediction(1), 5);
+			assert_ok!(Elections::force_new_term(Origin::root()));
+			assert_eq!(Elections::next_election_prediction(1), 2);
 		});
 	}
 
@@ -2137,6 +4660,32 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn slash_events_report_amount_and_deposit() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(submit_candidacy(Origin::signed(4)));
+			assert_ok!(submit_candidacy(Origin::signed(3)));
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
+			assert_ok!(vote(Origin::signed(4), vec![4], 40));
+
+			System::set_block_number(5);
+			Elections::on_initialize(System::block_number());
+			System::assert_has_event(Event::Elections(super::Event::CandidateSlashed {
+				candidate: 3,
+				amount: 3,
+				deposit: 3,
+			}));
+
+			assert_ok!(Elections::remove_member(Origin::root(), 4, false));
+			System::assert_has_event(Event::Elections(super::Event::SeatHolderSlashed {
+				seat_holder: 4,
+				amount: 3,
+				deposit: 3,
+			}));
+		});
+	}
+
 	#[test]
 	fn all_outgoing() {
 		ExtBuilder::default().build_and_execute(|| {
@@ -2149,10 +4698,9 @@ mod tests {
 			System::set_block_number(5);
 			Elections::on_initialize(System::block_number());
 
-			System::assert_last_event(Event::Elections(super::Event::NewTerm(vec![
-				(4, 40),
-				(5, 50),
-			])));
+			System::assert_last_event(Event::Elections(super::Event::NewTerm {
+				members_with_stake: vec![(4, 40), (5, 50)],
+			}));
 
 			assert_eq!(members_and_stake(), vec![(4, 40), (5, 50)]);
 			assert_eq!(runners_up_and_stake(), vec![]);
@@ -2163,7 +4711,9 @@ mod tests {
 			System::set_block_number(10);
 			Elections::on_initialize(System::block_number());
 
-			System::assert_last_event(Event::Elections(super::Event::NewTerm(vec![])));
+			System::assert_last_event(Event::Elections(super::Event::NewTerm {
+				members_with_stake: vec![],
+			}));
 
 			// outgoing have lost their bond.
 			assert_eq!(balances(&4), (37, 0));
@@ -2233,7 +4783,9 @@ mod tests {
 			assert_eq!(Elections::election_rounds(), 1);
 			assert!(members_ids().is_empty());
 
-			System::assert_last_event(Event::Elections(super::Event::NewTerm(vec![])));
+			System::assert_last_event(Event::Elections(super::Event::NewTerm {
+				members_with_stake: vec![],
+			}));
 		});
 	}
 
@@ -2457,6 +5009,10 @@ mod tests {
 			assert_ok!(vote(Origin::signed(3), vec![3], 30));
 
 			assert_ok!(Elections::remove_member(Origin::root(), 4, false));
+			System::assert_has_event(Event::Elections(super::Event::MemberKicked {
+				member: 4,
+				deposit: 3,
+			}));
 
 			assert_eq!(balances(&4), (35, 2)); // slashed
 			assert_eq!(Elections::election_rounds(), 2); // new election round
@@ -2585,10 +5141,9 @@ mod tests {
 			// 5 is an outgoing loser. will also get slashed.
 			assert_eq!(balances(&5), (45, 2));
 
-			System::assert_has_event(Event::Elections(super::Event::NewTerm(vec![
-				(4, 40),
-				(5, 50),
-			])));
+			System::assert_has_event(Event::Elections(super::Event::NewTerm {
+				members_with_stake: vec![(4, 40), (5, 50)],
+			}));
 		})
 	}
 
@@ -2672,6 +5227,10 @@ mod tests {
 			assert_eq!(runners_up_ids(), vec![2, 3]);
 
 			assert_ok!(Elections::renounce_candidacy(Origin::signed(4), Renouncing::Member));
+			System::assert_has_event(Event::Elections(super::Event::Renounced {
+				candidate: 4,
+				deposit: 3,
+			}));
 			assert_eq!(balances(&4), (38, 2)); // 2 is voting bond.
 
 			assert_eq!(members_ids(), vec![3, 5]);
@@ -2762,6 +5321,10 @@ mod tests {
 			assert_eq!(candidate_ids(), vec![5]);
 
 			assert_ok!(Elections::renounce_candidacy(Origin::signed(5), Renouncing::Candidate(1)));
+			System::assert_last_event(Event::Elections(super::Event::Renounced {
+				candidate: 5,
+				deposit: 3,
+			}));
 			assert_eq!(balances(&5), (50, 0));
 			assert!(candidate_ids().is_empty());
 		})
@@ -3003,7 +5566,7 @@ mod tests {
 		// member removed, replacement found.
 		ExtBuilder::default().desired_runners_up(1).build_and_execute(|| {
 			setup();
-			assert_eq!(Elections::remove_and_replace_member(&4, false), Ok(true));
+			assert_eq!(Elections::remove_and_replace_member(&4, false), Ok((true, 3)));
 
 			assert_eq!(members_ids(), vec![3, 5]);
 			assert_eq!(runners_up_ids().len(), 0);
@@ -3013,7 +5576,7 @@ mod tests {
 		ExtBuilder::default().desired_runners_up(1).build_and_execute(|| {
 			setup();
 			assert_ok!(Elections::renounce_candidacy(Origin::signed(3), Renouncing::RunnerUp));
-			assert_eq!(Elections::remove_and_replace_member(&4, false), Ok(false));
+			assert_eq!(Elections::remove_and_replace_member(&4, false), Ok((false, 3)));
 
 			assert_eq!(members_ids(), vec![5]);
 			assert_eq!(runners_up_ids().len(), 0);
@@ -3026,6 +5589,98 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn succession_can_hold_seat_vacant() {
+		ExtBuilder::default()
+			.desired_runners_up(1)
+			.succession(SuccessionPolicy::HoldVacant)
+			.build_and_execute(|| {
+				assert_ok!(submit_candidacy(Origin::signed(5)));
+				assert_ok!(submit_candidacy(Origin::signed(4)));
+				assert_ok!(submit_candidacy(Origin::signed(3)));
+
+				assert_ok!(vote(Origin::signed(5), vec![5], 50));
+				assert_ok!(vote(Origin::signed(4), vec![4], 40));
+				assert_ok!(vote(Origin::signed(3), vec![3], 30));
+
+				System::set_block_number(5);
+				Elections::on_initialize(System::block_number());
+				assert_eq!(members_ids(), vec![4, 5]);
+				assert_eq!(runners_up_ids(), vec![3]);
+
+				// the runner-up is not a replacement.
+				assert!(Elections::remove_member(Origin::root(), 4, true).is_err());
+				assert_ok!(Elections::remove_member(Origin::root(), 4, false));
+				assert_eq!(members_ids(), vec![5]);
+				assert_eq!(runners_up_ids(), vec![3]);
+				assert_eq!(Elections::election_rounds(), 1);
+
+				assert_ok!(Elections::renounce_candidacy(Origin::signed(5), Renouncing::Member));
+				assert!(members_ids().is_empty());
+				assert_eq!(runners_up_ids(), vec![3]);
+
+				System::set_block_number(6);
+				Elections::on_initialize(System::block_number());
+				assert!(members_ids().is_empty());
+				assert_eq!(Elections::election_rounds(), 1);
+			});
+	}
+
+	#[test]
+	fn succession_can_trigger_by_election() {
+		ExtBuilder::default()
+			.desired_runners_up(1)
+			.succession(SuccessionPolicy::ByElection)
+			.build_and_execute(|| {
+				assert_ok!(submit_candidacy(Origin::signed(5)));
+				assert_ok!(submit_candidacy(Origin::signed(4)));
+				assert_ok!(submit_candidacy(Origin::signed(3)));
+
+				assert_ok!(vote(Origin::signed(5), vec![5], 50));
+				assert_ok!(vote(Origin::signed(4), vec![4], 40));
+				assert_ok!(vote(Origin::signed(3), vec![3], 30));
+
+				System::set_block_number(5);
+				Elections::on_initialize(System::block_number());
+				assert_eq!(members_ids(), vec![4, 5]);
+				assert_eq!(runners_up_ids(), vec![3]);
+
+				assert_ok!(Elections::renounce_candidacy(Origin::signed(4), Renouncing::Member));
+				assert_eq!(members_ids(), vec![5]);
+				assert_eq!(runners_up_ids(), vec![3]);
+
+				System::set_block_number(6);
+				Elections::on_initialize(System::block_number());
+				assert_eq!(members_ids(), vec![3, 5]);
+				assert!(runners_up_ids().is_empty());
+				assert_eq!(Elections::election_rounds(), 2);
+			});
+	}
+
+	#[test]
+	fn ranked_members_are_reported() {
+		ExtBuilder::default().desired_runners_up(1).build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(submit_candidacy(Origin::signed(4)));
+			assert_ok!(submit_candidacy(Origin::signed(3)));
+
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
+			assert_ok!(vote(Origin::signed(4), vec![4], 40));
+			assert_ok!(vote(Origin::signed(3), vec![3], 30));
+
+			System::set_block_number(5);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(members_ids(), vec![4, 5]);
+			assert_eq!(Elections::ranked_members(), vec![(5, 50), (4, 40)]);
+			assert_eq!(RANKED_MEMBERS.with(|m| m.borrow().clone()), vec![(5, 50), (4, 40)]);
+
+			// the promoted runner-up keeps their backing.
+			assert_ok!(Elections::remove_member(Origin::root(), 5, true));
+			assert_eq!(Elections::ranked_members(), vec![(4, 40), (3, 30)]);
+			assert_eq!(RANKED_MEMBERS.with(|m| m.borrow().clone()), vec![(4, 40), (3, 30)]);
+		});
+	}
+
 	#[test]
 	fn no_desired_members() {
 		// not interested in anything
@@ -3129,6 +5784,79 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn force_new_term_starts_election_in_next_block() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(submit_candidacy(Origin::signed(4)));
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
+			assert_ok!(vote(Origin::signed(4), vec![4], 40));
+
+			assert_noop!(Elections::force_new_term(Origin::signed(1)), BadOrigin);
+			assert_ok!(Elections::force_new_term(Origin::root()));
+			System::assert_last_event(Event::Elections(super::Event::TermDissolved));
+
+			System::set_block_number(2);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(members_ids(), vec![4, 5]);
+			assert_eq!(Elections::election_rounds(), 1);
+
+			// the new term lasts a whole term duration.
+			System::set_block_number(5);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::election_rounds(), 1);
+
+			System::set_block_number(7);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::election_rounds(), 2);
+		});
+	}
+
+	#[test]
+	fn force_new_term_is_rejected_during_deferred_election() {
+		ExtBuilder::default().voter_snapshot_per_block(1).build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
+
+			System::set_block_number(5);
+			Elections::on_initialize(System::block_number());
+			assert!(Elections::is_election_ongoing());
+
+			assert_noop!(Elections::force_new_term(Origin::root()), Error::<Test>::ElectionOngoing);
+		});
+	}
+
+	#[test]
+	fn set_term_duration_overrides_config() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(vote(Origin::signed(5), vec![5], 50));
+			assert_eq!(Elections::term_duration(), 5);
+
+			assert_noop!(Elections::set_term_duration(Origin::signed(1), 3), BadOrigin);
+			assert_ok!(Elections::set_term_duration(Origin::root(), 3));
+			System::assert_last_event(Event::Elections(super::Event::TermDurationSet {
+				duration: 3,
+			}));
+			assert_eq!(Elections::term_duration(), 3);
+
+			System::set_block_number(3);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::election_rounds(), 0);
+
+			System::set_block_number(4);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::election_rounds(), 1);
+
+			// passive mode.
+			assert_ok!(Elections::set_term_duration(Origin::root(), 0));
+			assert_eq!(NextElectionAt::<Test>::get(), None);
+			System::set_block_number(7);
+			Elections::on_initialize(System::block_number());
+			assert_eq!(Elections::election_rounds(), 1);
+		});
+	}
+
 	#[test]
 	fn remove_defunct_voter_works() {
 		ExtBuilder::default().build_and_execute(|| {
@@ -3147,9 +5875,56 @@ mod tests {
 
 			assert_ok!(Elections::renounce_candidacy(Origin::signed(5), Renouncing::Candidate(3)));
 			assert_ok!(Elections::renounce_candidacy(Origin::signed(4), Renouncing::Candidate(2)));
-			assert_ok!(Elections::renounce_candidacy(Origin::signed(3), Renouncing::Candidate(1)));
 
-			assert_ok!(Elections::clean_defunct_voters(Origin::root(), 4, 2));
+			assert_noop!(Elections::clean_defunct_voters(Origin::root(), 4, 4), BadOrigin);
+			assert_ok!(Elections::clean_defunct_voters(Origin::signed(1), 4, 4));
+
+			assert_eq_uvec!(all_voters(), vec![2, 3]);
+			assert_eq!(CounterForVoters::<Test>::get(), 2);
+			// half of each deposit rewards the caller, the other half is returned.
+			assert_eq!(balances(&1), (12, 0));
+			assert_eq!(balances(&4), (39, 0));
+			assert_eq!(has_lock(&4), 0);
+
+			// all voters were checked, the next call finds none left and starts over.
+			assert!(<DefunctVoterCursor<Test>>::get().is_some());
+			assert_ok!(Elections::clean_defunct_voters(Origin::signed(1), 4, 4));
+			assert_eq!(<DefunctVoterCursor<Test>>::get(), None);
+			assert_eq_uvec!(all_voters(), vec![2, 3]);
+		})
+	}
+
+	#[test]
+	fn remove_defunct_voter_is_paginated() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(submit_candidacy(Origin::signed(5)));
+			assert_ok!(submit_candidacy(Origin::signed(4)));
+
+			// all defunct
+			assert_ok!(vote(Origin::signed(5), vec![5], 5));
+			assert_ok!(vote(Origin::signed(4), vec![4], 5));
+			assert_ok!(vote(Origin::signed(3), vec![5, 4], 5));
+			assert_ok!(vote(Origin::signed(2), vec![4], 5));
+
+			assert_ok!(Elections::renounce_candidacy(Origin::signed(5), Renouncing::Candidate(2)));
+			assert_ok!(Elections::renounce_candidacy(Origin::signed(4), Renouncing::Candidate(1)));
+
+			// no voter is checked.
+			assert_ok!(Elections::clean_defunct_voters(Origin::signed(1), 0, 4));
+			assert_eq!(all_voters().len(), 4);
+
+			// at most one voter is removed per call, each call resuming after the previous one.
+			for remaining in (1..4).rev() {
+				assert_ok!(Elections::clean_defunct_voters(Origin::signed(1), 4, 1));
+				assert_eq!(all_voters().len(), remaining);
+				assert!(<DefunctVoterCursor<Test>>::get().is_some());
+			}
+
+			// the last voter is removed, and all voters are checked.
+			assert_ok!(Elections::clean_defunct_voters(Origin::signed(1), 4, 4));
+			assert!(all_voters().is_empty());
+			assert_eq!(<DefunctVoterCursor<Test>>::get(), None);
+			assert_eq!(balances(&1), (14, 0));
 		})
 	}
 }
@@ -21,3 +21,7 @@
 pub mod v3;
 /// Version 4.
 pub mod v4;
+/// Version 5.
+pub mod v5;
+/// Version 6.
+pub mod v6;
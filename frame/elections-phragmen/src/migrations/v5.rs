@@ -0,0 +1,101 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Migrations to version [`5.0.0`], as denoted by the changelog.
+
+use crate::{BalanceOf, Candidates, Config, Members, Pallet, SeatHolder};
+use frame_support::{
+	migrations::{migrate_to_bounded_vec, OnBoundedOverflow, VersionedMigration},
+	traits::{Get, OnRuntimeUpgrade, ReservableCurrency},
+	weights::{RuntimeDbWeight, Weight},
+};
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// Overflow policy unreserving the deposit of the members and candidates which do not fit into
+/// their new bound.
+///
+/// Dropped members are not reported to [`Config::ChangeMembers`], they are replaced in the next
+/// election.
+pub struct UnreserveDeposits<T>(PhantomData<T>);
+
+impl<T: Config> OnBoundedOverflow<SeatHolder<T::AccountId, BalanceOf<T>>> for UnreserveDeposits<T> {
+	fn on_overflow(
+		excess: Vec<SeatHolder<T::AccountId, BalanceOf<T>>>,
+		db_weight: &RuntimeDbWeight,
+	) -> Result<Weight, &'static str> {
+		let count = excess.len() as Weight;
+		for SeatHolder { who, deposit, .. } in excess {
+			T::Currency::unreserve(&who, deposit);
+		}
+		Ok(db_weight.reads_writes(count, count))
+	}
+}
+
+impl<T: Config> OnBoundedOverflow<(T::AccountId, BalanceOf<T>)> for UnreserveDeposits<T> {
+	fn on_overflow(
+		excess: Vec<(T::AccountId, BalanceOf<T>)>,
+		db_weight: &RuntimeDbWeight,
+	) -> Result<Weight, &'static str> {
+		let count = excess.len() as Weight;
+		for (who, deposit) in excess {
+			T::Currency::unreserve(&who, deposit);
+		}
+		Ok(db_weight.reads_writes(count, count))
+	}
+}
+
+/// Bound the members by [`Config::DesiredMembers`] and the candidates by
+/// [`Config::MaxCandidates`], unreserving the deposit of everyone who does not fit, regardless of
+/// the storage version. Use [`MigrateV4ToV5`] instead.
+pub struct InnerMigrateV4ToV5<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for InnerMigrateV4ToV5<T> {
+	fn on_runtime_upgrade() -> Weight {
+		let db_weight = T::DbWeight::get();
+		// `UnreserveDeposits` never fails, hence neither does the migration of both values.
+		let members_weight =
+			migrate_to_bounded_vec::<Members<T>, _, _, UnreserveDeposits<T>>(&db_weight)
+				.unwrap_or_default();
+		let candidates_weight =
+			migrate_to_bounded_vec::<Candidates<T>, _, _, UnreserveDeposits<T>>(&db_weight)
+				.unwrap_or_default();
+
+		members_weight.saturating_add(candidates_weight)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			<Members<T>>::decode_len().unwrap_or(0) <= T::DesiredMembers::get() as usize,
+			"Too many members.",
+		);
+		frame_support::ensure!(
+			<Candidates<T>>::decode_len().unwrap_or(0) <= T::MaxCandidates::get() as usize,
+			"Too many candidates.",
+		);
+		Ok(())
+	}
+}
+
+/// Migrate the storage version 4 to 5, see [`InnerMigrateV4ToV5`].
+pub type MigrateV4ToV5<T> = VersionedMigration<
+	4,
+	5,
+	InnerMigrateV4ToV5<T>,
+	Pallet<T>,
+	<T as frame_system::Config>::DbWeight,
+>;
@@ -0,0 +1,251 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Migrations to version [`6.0.0`], as denoted by the changelog.
+
+use crate::{
+	BalanceOf, Config, CounterForVoters, NextElectionAt, Pallet, RunnersUp, SeatHolder, Voter,
+	Voting,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	migrations::{LazyMigration, StartLazyMigration, VersionedMigration},
+	storage::unhashed,
+	traits::{BlockNumberProvider, Get, LockableCurrency, OnRuntimeUpgrade, ReservableCurrency},
+	weights::Weight,
+	BoundedVec,
+};
+use sp_runtime::traits::{Saturating, Zero};
+use sp_std::{convert::TryFrom, marker::PhantomData, prelude::*};
+
+/// A voter, as stored before version 6.
+#[derive(Encode, Decode)]
+struct OldVoter<AccountId, Balance> {
+	votes: Vec<AccountId>,
+	stake: Balance,
+	deposit: Balance,
+}
+
+/// Bound the runners-up by [`Config::DesiredRunnersUp`], regardless of the storage version.
+///
+/// The runners-up are sorted from worst to best, hence the worst ones are dropped. They get their
+/// deposit back.
+pub struct BoundRunnersUp<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for BoundRunnersUp<T> {
+	fn on_runtime_upgrade() -> Weight {
+		let db_weight = T::DbWeight::get();
+		let runners_up_key = <RunnersUp<T>>::hashed_key();
+		let mut runners_up =
+			match unhashed::get::<Vec<SeatHolder<T::AccountId, BalanceOf<T>>>>(&runners_up_key) {
+				Some(runners_up) => runners_up,
+				None => return db_weight.reads(1),
+			};
+
+		let excess = runners_up.len().saturating_sub(T::DesiredRunnersUp::get() as usize);
+		if excess == 0 {
+			return db_weight.reads(1)
+		}
+		log::warn!(
+			target: "runtime::elections-phragmen",
+			"Dropping {} runners-up beyond DesiredRunnersUp.",
+			excess,
+		);
+		for SeatHolder { who, deposit, .. } in runners_up.drain(..excess) {
+			T::Currency::unreserve(&who, deposit);
+		}
+		let runners_up = BoundedVec::<_, T::DesiredRunnersUp>::try_from(runners_up)
+			.expect("excess runners-up were dropped above; qed");
+		<RunnersUp<T>>::put(runners_up);
+
+		db_weight.reads_writes(excess as Weight + 1, excess as Weight + 1)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			<RunnersUp<T>>::decode_len().unwrap_or(0) <= T::DesiredRunnersUp::get() as usize,
+			"Too many runners-up.",
+		);
+		Ok(())
+	}
+}
+
+/// Schedule the end of the current term in [`NextElectionAt`], regardless of the storage version.
+///
+/// Before version 6 the terms started in the blocks whose number is a multiple of the term
+/// duration, so the current term keeps ending at the first such block.
+pub struct ScheduleNextElection<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for ScheduleNextElection<T> {
+	fn on_runtime_upgrade() -> Weight {
+		let term_duration = Pallet::<T>::term_duration();
+		if term_duration.is_zero() {
+			return T::DbWeight::get().reads(2)
+		}
+
+		let now = T::BlockNumberProvider::current_block_number();
+		let term_progress = now % term_duration;
+		let next_election = if term_progress.is_zero() {
+			now
+		} else {
+			now.saturating_add(term_duration - term_progress)
+		};
+		<NextElectionAt<T>>::put(next_election);
+		T::DbWeight::get().reads_writes(3, 1)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			Pallet::<T>::term_duration().is_zero() || <NextElectionAt<T>>::exists(),
+			"The next election is not scheduled.",
+		);
+		Ok(())
+	}
+}
+
+/// Bound the votes of each voter by [`Config::MaxVotesPerVoter`] and the number of voters by
+/// [`Config::MaxVoters`], and count the voters into [`CounterForVoters`], as many voters per step
+/// as the weight limit allows.
+///
+/// This is a [`LazyMigration`] driven by the `on_idle` hook of the pallet. Voters keep their first
+/// votes and get back the deposit of the votes they lose. The voters beyond the bound are removed,
+/// their lock and deposit are released. Votes are frozen and elections are deferred until the
+/// migration is complete.
+pub struct BoundVoters<T>(PhantomData<T>);
+
+impl<T: Config> BoundVoters<T> {
+	/// The weight of migrating a voter: reading it, and writing it back or removing it along with
+	/// its lock and deposit.
+	fn weight_per_voter() -> Weight {
+		T::DbWeight::get().reads_writes(3, 3)
+	}
+
+	/// Bound the votes of `who`, or remove it if `kept` voters already fill [`Config::MaxVoters`].
+	///
+	/// Returns whether the voter is kept.
+	fn migrate_voter(
+		who: T::AccountId,
+		old: OldVoter<T::AccountId, BalanceOf<T>>,
+		kept: u32,
+	) -> bool {
+		let OldVoter { mut votes, stake, deposit } = old;
+		if kept >= T::MaxVoters::get() {
+			<Voting<T>>::remove(&who);
+			T::Currency::remove_lock(T::PalletId::get(), &who);
+			T::Currency::unreserve(&who, deposit);
+			return false
+		}
+
+		let max_votes = T::MaxVotesPerVoter::get() as usize;
+		if votes.len() > max_votes {
+			votes.truncate(max_votes);
+			let new_deposit = Pallet::<T>::deposit_of(votes.len()).min(deposit);
+			T::Currency::unreserve(&who, deposit.saturating_sub(new_deposit));
+			let votes = BoundedVec::try_from(votes).expect("votes were truncated above; qed");
+			<Voting<T>>::insert(&who, Voter { votes, stake, deposit: new_deposit });
+		}
+		true
+	}
+
+	/// Remove the voter `who`, whose stored value `raw` cannot be decoded, along with its lock.
+	///
+	/// The deposit is encoded last, so it is still unreserved if the end of `raw` decodes to a
+	/// balance.
+	fn remove_undecodable_voter(who: T::AccountId, raw: &[u8]) {
+		<Voting<T>>::remove(&who);
+		T::Currency::remove_lock(T::PalletId::get(), &who);
+		let deposit = raw
+			.len()
+			.checked_sub(BalanceOf::<T>::zero().encoded_size())
+			.and_then(|start| BalanceOf::<T>::decode(&mut &raw[start..]).ok())
+			.unwrap_or_else(Zero::zero);
+		T::Currency::unreserve(&who, deposit);
+		log::warn!(
+			target: "runtime::elections-phragmen",
+			"Removed the undecodable voter {:?}, unreserving a deposit of {:?}.",
+			who,
+			deposit,
+		);
+	}
+}
+
+impl<T: Config> LazyMigration for BoundVoters<T> {
+	const ID: &'static [u8] = b"pallet-elections-phragmen::v6::BoundVoters";
+	/// The raw storage key of the last voter migrated, and the number of voters kept so far.
+	type Cursor = (Vec<u8>, u32);
+
+	fn step(cursor: Option<(Vec<u8>, u32)>, limit: Weight) -> (Weight, Option<(Vec<u8>, u32)>) {
+		let per_voter = Self::weight_per_voter();
+		if per_voter > limit {
+			// A migration which has not started yet continues from the prefix of the map, as
+			// `None` would complete it.
+			let cursor =
+				cursor.unwrap_or_else(|| (<Voting<T>>::iter_keys().last_raw_key().to_vec(), 0));
+			return (0, Some(cursor))
+		}
+
+		let (mut voters, mut kept) = match cursor {
+			Some((last_key, kept)) => (<Voting<T>>::iter_keys_from(last_key), kept),
+			None => (<Voting<T>>::iter_keys(), 0),
+		};
+		let mut consumed: Weight = 0;
+		while consumed.saturating_add(per_voter) <= limit {
+			let who = match voters.next() {
+				Some(who) => who,
+				None => {
+					log::info!(
+						target: "runtime::elections-phragmen",
+						"Migrated the voters, {} of which are kept.",
+						kept,
+					);
+					CounterForVoters::<T>::put(kept);
+					return (consumed.saturating_add(T::DbWeight::get().writes(1)), None)
+				},
+			};
+			// Voters before version 6 encode like the new ones, but may exceed the new bounds.
+			let raw = unhashed::get_raw(voters.last_raw_key()).unwrap_or_default();
+			match OldVoter::<T::AccountId, BalanceOf<T>>::decode(&mut &raw[..]) {
+				Ok(old) =>
+					if Self::migrate_voter(who, old, kept) {
+						kept.saturating_inc();
+					},
+				Err(_) => Self::remove_undecodable_voter(who, &raw),
+			}
+			consumed = consumed.saturating_add(per_voter);
+		}
+		(consumed, Some((voters.last_raw_key().to_vec(), kept)))
+	}
+}
+
+/// Bound the runners-up, schedule the next election and start [`BoundVoters`], regardless of the
+/// storage version. Use [`MigrateV5ToV6`] instead.
+pub type InnerMigrateV5ToV6<T> = (
+	BoundRunnersUp<T>,
+	ScheduleNextElection<T>,
+	StartLazyMigration<BoundVoters<T>, <T as frame_system::Config>::DbWeight>,
+);
+
+/// Migrate the storage version 5 to 6, see [`InnerMigrateV5ToV6`].
+pub type MigrateV5ToV6<T> = VersionedMigration<
+	5,
+	6,
+	InnerMigrateV5ToV6<T>,
+	Pallet<T>,
+	<T as frame_system::Config>::DbWeight,
+>;
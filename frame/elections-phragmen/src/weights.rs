@@ -35,6 +35,10 @@
 // --output=./frame/elections-phragmen/src/weights.rs
 // --template=./.maintain/frame-weight-template.hbs
 
+// The weights of `submit_solution`, `refund_voter_deposit`, `force_new_term`,
+// `set_term_duration`, `set_candidate_metadata` and `set_minimum_untrusted_score` were not
+// generated by the command above, they are hand-written estimates to be replaced by running it
+// again.
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -57,6 +61,12 @@ pub trait WeightInfo {
 	fn remove_member_wrong_refund() -> Weight;
 	fn clean_defunct_voters(v: u32, d: u32, ) -> Weight;
 	fn election_phragmen(c: u32, v: u32, e: u32, ) -> Weight;
+	fn submit_solution(c: u32, v: u32, e: u32, ) -> Weight;
+	fn refund_voter_deposit() -> Weight;
+	fn force_new_term() -> Weight;
+	fn set_term_duration() -> Weight;
+	fn set_candidate_metadata() -> Weight;
+	fn set_minimum_untrusted_score() -> Weight;
 }
 
 /// Weights for pallet_elections_phragmen using the Substrate node and recommended hardware.
@@ -141,6 +151,38 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(v as Weight)))
 			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
 	}
+	fn submit_solution(c: u32, v: u32, e: u32, ) -> Weight {
+		(58_000_000 as Weight)
+			.saturating_add((1_200_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((14_000_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add((900_000 as Weight).saturating_mul(e as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn refund_voter_deposit() -> Weight {
+		(41_530_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn force_new_term() -> Weight {
+		(14_882_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_term_duration() -> Weight {
+		(12_207_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_candidate_metadata() -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_minimum_untrusted_score() -> Weight {
+		(12_207_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
@@ -224,4 +266,36 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(v as Weight)))
 			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
 	}
+	fn submit_solution(c: u32, v: u32, e: u32, ) -> Weight {
+		(58_000_000 as Weight)
+			.saturating_add((1_200_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((14_000_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add((900_000 as Weight).saturating_mul(e as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn refund_voter_deposit() -> Weight {
+		(41_530_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn force_new_term() -> Weight {
+		(14_882_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_term_duration() -> Weight {
+		(12_207_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_candidate_metadata() -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_minimum_untrusted_score() -> Weight {
+		(12_207_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
 }
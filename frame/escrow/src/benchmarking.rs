@@ -0,0 +1,106 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Escrow pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::dispatch::UnfilteredDispatchable;
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, Saturating};
+
+use crate::Pallet as Escrow;
+
+const SEED: u32 = 0;
+
+/// The amount put in escrow by the benchmarks.
+fn escrow_amount<T: Config>() -> BalanceOf<T> {
+	T::MinEscrowAmount::get().max(T::Currency::minimum_balance().saturating_mul(100u32.into()))
+}
+
+/// Create a vested escrow from a funded `depositor` to `beneficiary`, releasable after block 1.
+fn create_vested_escrow<T: Config>(
+	depositor: &T::AccountId,
+	beneficiary: &T::AccountId,
+) -> Result<EscrowId, &'static str> {
+	T::Currency::make_free_balance_be(depositor, BalanceOf::<T>::max_value());
+	let id = Escrow::<T>::next_escrow_id();
+	Escrow::<T>::create_escrow(
+		RawOrigin::Signed(depositor.clone()).into(),
+		T::Lookup::unlookup(beneficiary.clone()),
+		escrow_amount::<T>(),
+		1u32.into(),
+		Some(10u32.into()),
+	)?;
+	Ok(id)
+}
+
+benchmarks! {
+	create_escrow {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+		let beneficiary_lookup = T::Lookup::unlookup(beneficiary);
+		let amount = escrow_amount::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), beneficiary_lookup, amount, 1u32.into(), Some(10u32.into()))
+	verify {
+		assert_eq!(T::Currency::reserved_balance(&caller), amount);
+	}
+
+	approve {
+		let depositor: T::AccountId = account("depositor", 0, SEED);
+		let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+		let id = create_vested_escrow::<T>(&depositor, &beneficiary)?;
+		let origin = T::ApproveOrigin::successful_origin();
+		let call = Call::<T>::approve(id);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(Escrow::<T>::escrow(id).unwrap().approved, "Escrow was not approved");
+	}
+
+	release {
+		let caller: T::AccountId = whitelisted_caller();
+		let depositor: T::AccountId = account("depositor", 0, SEED);
+		let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+		// the worst case is a beneficiary without an account, which is created by a transfer.
+		let id = create_vested_escrow::<T>(&depositor, &beneficiary)?;
+		Escrows::<T>::mutate(id, |escrow| escrow.as_mut().unwrap().approved = true);
+		T::BlockNumberProvider::set_block_number(1u32.into());
+	}: _(RawOrigin::Signed(caller), id)
+	verify {
+		assert_eq!(T::Currency::free_balance(&beneficiary), escrow_amount::<T>());
+		assert_eq!(
+			T::VestingSchedule::vesting_balance(&beneficiary),
+			Some(escrow_amount::<T>()),
+			"Release was not vested",
+		);
+	}
+
+	cancel {
+		let caller: T::AccountId = whitelisted_caller();
+		let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+		let id = create_vested_escrow::<T>(&caller, &beneficiary)?;
+	}: _(RawOrigin::Signed(caller.clone()), id)
+	verify {
+		assert!(T::Currency::reserved_balance(&caller).is_zero(), "Funds were not unreserved");
+	}
+}
+
+impl_benchmark_test_suite!(Escrow, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,361 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Escrow Pallet
+//!
+//! - [`Config`]
+//! - [`Call`]
+//!
+//! ## Overview
+//!
+//! A pallet for holding funds in escrow until both a point in time has passed and a condition,
+//! attested by [`Config::ApproveOrigin`], has been met. The approving origin is typically an
+//! oracle or a governance body, which makes escrows suitable for milestone-based grant payouts:
+//! one escrow per milestone, approved once the milestone was delivered.
+//!
+//! Funds are reserved on the depositor's account while in escrow. Upon release they are moved to
+//! the beneficiary, either as free balance or, if the escrow specifies a vesting length, locked
+//! under a vesting schedule created through the [`VestingSchedule`] trait.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `create_escrow` - Reserve funds for a beneficiary, releasable after a given block.
+//! - `approve` - Mark the condition of an escrow as met.
+//! - `release` - Move the funds of an approved and due escrow to its beneficiary.
+//! - `cancel` - Return the funds of an escrow to its depositor.
+//!
+//! ## Weights
+//!
+//! The weights in [`weights::SubstrateWeight`] were not benchmarked but borrowed from those of
+//! `pallet_vesting`. Do not use them in production before benchmarking the pallet against your
+//! runtime.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub mod weights;
+
+use codec::{Decode, Encode};
+use frame_support::{
+	ensure,
+	pallet_prelude::*,
+	traits::{
+		BalanceStatus, BlockNumberProvider, Currency, EnsureOrigin, ExistenceRequirement,
+		ReservableCurrency, VestingSchedule,
+	},
+	transactional,
+};
+use frame_system::{ensure_signed, pallet_prelude::*};
+pub use pallet::*;
+use sp_runtime::{
+	traits::{Convert, Saturating, StaticLookup, Zero},
+	RuntimeDebug,
+};
+pub use weights::WeightInfo;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Identifier of an escrow.
+pub type EscrowId = u32;
+
+/// Funds held in escrow for a beneficiary.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct EscrowInfo<AccountId, Balance, BlockNumber> {
+	/// The account the funds are reserved on.
+	pub depositor: AccountId,
+	/// The account the funds are released to.
+	pub beneficiary: AccountId,
+	/// The amount held in escrow.
+	pub amount: Balance,
+	/// The block from which on the escrow can be released.
+	pub release_after: BlockNumber,
+	/// Whether the release condition was approved by `ApproveOrigin`.
+	pub approved: bool,
+	/// If set, the released funds vest over this number of blocks, starting at the release.
+	pub vesting_length: Option<BlockNumber>,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency trait.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The vesting schedule used to deliver vested releases.
+		type VestingSchedule: VestingSchedule<
+			Self::AccountId,
+			Moment = Self::BlockNumber,
+			Currency = Self::Currency,
+		>;
+
+		/// Convert the block number into a balance.
+		type BlockNumberToBalance: Convert<Self::BlockNumber, BalanceOf<Self>>;
+
+		/// Provider for the block number against which `release_after` is checked and from which
+		/// vested releases start vesting.
+		///
+		/// Should be the provider of `VestingSchedule`, otherwise vested releases start vesting
+		/// at a block of another clock.
+		type BlockNumberProvider: BlockNumberProvider<BlockNumber = Self::BlockNumber>;
+
+		/// The origin attesting that the release condition of an escrow is met, e.g. an oracle
+		/// or a governance collective. It may also cancel escrows.
+		type ApproveOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The minimum amount which can be put in escrow.
+		#[pallet::constant]
+		type MinEscrowAmount: Get<BalanceOf<Self>>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// All escrows which were neither released nor cancelled.
+	#[pallet::storage]
+	#[pallet::getter(fn escrow)]
+	pub type Escrows<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		EscrowId,
+		EscrowInfo<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	>;
+
+	/// The identifier of the next escrow to be created.
+	#[pallet::storage]
+	#[pallet::getter(fn next_escrow_id)]
+	pub type NextEscrowId<T: Config> = StorageValue<_, EscrowId, ValueQuery>;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
+	pub enum Event<T: Config> {
+		/// Funds were put in escrow. \[id, depositor, beneficiary, amount\]
+		EscrowCreated(EscrowId, T::AccountId, T::AccountId, BalanceOf<T>),
+		/// The release condition of an escrow was approved. \[id\]
+		EscrowApproved(EscrowId),
+		/// The funds of an escrow were released to its beneficiary. \[id, beneficiary, amount\]
+		EscrowReleased(EscrowId, T::AccountId, BalanceOf<T>),
+		/// An escrow was cancelled and its funds returned. \[id, depositor, amount\]
+		EscrowCancelled(EscrowId, T::AccountId, BalanceOf<T>),
+	}
+
+	/// Error for the escrow pallet.
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The given escrow does not exist.
+		UnknownEscrow,
+		/// The amount to put in escrow is below `MinEscrowAmount`.
+		AmountLow,
+		/// The depositor cannot be the beneficiary.
+		SelfEscrow,
+		/// Vested releases must vest over at least one block.
+		InvalidVestingLength,
+		/// The escrow was already approved.
+		AlreadyApproved,
+		/// The release condition of the escrow was not approved yet.
+		NotApproved,
+		/// The escrow cannot be released before `release_after`.
+		NotDue,
+		/// The origin may not cancel the escrow.
+		NotAllowed,
+		/// No more escrows can be created.
+		NoAvailableEscrowId,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Put `amount` in escrow for `beneficiary`.
+		///
+		/// The dispatch origin for this call must be _Signed_. `amount` is reserved on the sender's
+		/// account until the escrow is released or cancelled.
+		///
+		/// - `beneficiary`: The account to release the funds to.
+		/// - `amount`: The amount to put in escrow, at least `MinEscrowAmount`.
+		/// - `release_after`: The block of `BlockNumberProvider` from which on the escrow can be
+		///   released.
+		/// - `vesting_length`: If set, released funds vest over this number of blocks.
+		///
+		/// Emits `EscrowCreated`.
+		#[pallet::weight(T::WeightInfo::create_escrow())]
+		pub fn create_escrow(
+			origin: OriginFor<T>,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+			amount: BalanceOf<T>,
+			release_after: T::BlockNumber,
+			vesting_length: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			let depositor = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+			ensure!(depositor != beneficiary, Error::<T>::SelfEscrow);
+			ensure!(amount >= T::MinEscrowAmount::get(), Error::<T>::AmountLow);
+			ensure!(
+				vesting_length.map_or(true, |length| !length.is_zero()),
+				Error::<T>::InvalidVestingLength
+			);
+
+			let id = Self::next_escrow_id();
+			let next_id = id.checked_add(1).ok_or(Error::<T>::NoAvailableEscrowId)?;
+
+			T::Currency::reserve(&depositor, amount)?;
+
+			NextEscrowId::<T>::put(next_id);
+			Escrows::<T>::insert(
+				id,
+				EscrowInfo {
+					depositor: depositor.clone(),
+					beneficiary: beneficiary.clone(),
+					amount,
+					release_after,
+					approved: false,
+					vesting_length,
+				},
+			);
+
+			Self::deposit_event(Event::<T>::EscrowCreated(id, depositor, beneficiary, amount));
+			Ok(())
+		}
+
+		/// Approve the release condition of escrow `id`.
+		///
+		/// The dispatch origin for this call must be `ApproveOrigin`.
+		///
+		/// Emits `EscrowApproved`.
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn approve(origin: OriginFor<T>, id: EscrowId) -> DispatchResult {
+			T::ApproveOrigin::ensure_origin(origin)?;
+
+			Escrows::<T>::try_mutate(id, |maybe_escrow| -> DispatchResult {
+				let escrow = maybe_escrow.as_mut().ok_or(Error::<T>::UnknownEscrow)?;
+				ensure!(!escrow.approved, Error::<T>::AlreadyApproved);
+				escrow.approved = true;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::EscrowApproved(id));
+			Ok(())
+		}
+
+		/// Release the funds of escrow `id` to its beneficiary.
+		///
+		/// The dispatch origin for this call must be _Signed_. The escrow must be approved and
+		/// `release_after` must have passed.
+		///
+		/// If the escrow has a vesting length, the released funds vest linearly over it, starting
+		/// at the current block of `BlockNumberProvider`. They are merged into the vesting schedule
+		/// of the beneficiary, if any.
+		///
+		/// Only what is still reserved on the depositor's account is released, if their reserve
+		/// was slashed. Releasing to a beneficiary without an account fails if the released funds
+		/// are below the existential deposit.
+		///
+		/// Emits `EscrowReleased`.
+		#[pallet::weight(T::WeightInfo::release())]
+		#[transactional]
+		pub fn release(origin: OriginFor<T>, id: EscrowId) -> DispatchResult {
+			ensure_signed(origin)?;
+			let escrow = Self::escrow(id).ok_or(Error::<T>::UnknownEscrow)?;
+			ensure!(escrow.approved, Error::<T>::NotApproved);
+			let now = T::BlockNumberProvider::current_block_number();
+			ensure!(now >= escrow.release_after, Error::<T>::NotDue);
+
+			// The reserve of the depositor might have been slashed in the meantime.
+			let released = if T::Currency::total_balance(&escrow.beneficiary).is_zero() {
+				// Reserved funds cannot be repatriated to an account which does not exist.
+				let remaining = T::Currency::unreserve(&escrow.depositor, escrow.amount);
+				let released = escrow.amount.saturating_sub(remaining);
+				T::Currency::transfer(
+					&escrow.depositor,
+					&escrow.beneficiary,
+					released,
+					ExistenceRequirement::AllowDeath,
+				)?;
+				released
+			} else {
+				let remaining = T::Currency::repatriate_reserved(
+					&escrow.depositor,
+					&escrow.beneficiary,
+					escrow.amount,
+					BalanceStatus::Free,
+				)?;
+				escrow.amount.saturating_sub(remaining)
+			};
+			if let Some(length) = escrow.vesting_length {
+				let per_block = T::VestingSchedule::per_block_for(
+					released,
+					T::BlockNumberToBalance::convert(length),
+				);
+				T::VestingSchedule::merge_vesting_schedule(
+					&escrow.beneficiary,
+					released,
+					per_block,
+					now,
+				)?;
+			}
+
+			Escrows::<T>::remove(id);
+			Self::deposit_event(Event::<T>::EscrowReleased(id, escrow.beneficiary, released));
+			Ok(())
+		}
+
+		/// Cancel escrow `id` and return its funds to the depositor.
+		///
+		/// The dispatch origin for this call must be `ApproveOrigin`, or _Signed_ by the
+		/// depositor as long as the escrow was not approved yet.
+		///
+		/// Only what is still reserved on the depositor's account is returned, if their reserve
+		/// was slashed.
+		///
+		/// Emits `EscrowCancelled`.
+		#[pallet::weight(T::WeightInfo::cancel())]
+		pub fn cancel(origin: OriginFor<T>, id: EscrowId) -> DispatchResult {
+			let escrow = Self::escrow(id).ok_or(Error::<T>::UnknownEscrow)?;
+			match T::ApproveOrigin::try_origin(origin) {
+				Ok(_) => {},
+				Err(origin) => {
+					let who = ensure_signed(origin)?;
+					ensure!(who == escrow.depositor && !escrow.approved, Error::<T>::NotAllowed);
+				},
+			}
+
+			// The reserve of the depositor might have been slashed in the meantime.
+			let remaining = T::Currency::unreserve(&escrow.depositor, escrow.amount);
+			let returned = escrow.amount.saturating_sub(remaining);
+
+			Escrows::<T>::remove(id);
+			Self::deposit_event(Event::<T>::EscrowCancelled(id, escrow.depositor, returned));
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,280 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::{assert_noop, assert_ok, traits::VestingSchedule as _};
+use sp_runtime::traits::BadOrigin;
+
+use super::*;
+use crate::mock::{new_test_ext, Balances, Escrow, Event as TestEvent, System, Test, Vesting};
+
+const ORACLE: u64 = 10;
+
+#[test]
+fn create_escrow_reserves_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Escrow::create_escrow(Some(1).into(), 2, 300, 5, None));
+
+		assert_eq!(
+			Escrow::escrow(0),
+			Some(EscrowInfo {
+				depositor: 1,
+				beneficiary: 2,
+				amount: 300,
+				release_after: 5,
+				approved: false,
+				vesting_length: None,
+			})
+		);
+		assert_eq!(Escrow::next_escrow_id(), 1);
+		assert_eq!(Balances::reserved_balance(&1), 300);
+		assert_eq!(Balances::free_balance(&1), 700);
+		System::assert_last_event(TestEvent::Escrow(Event::EscrowCreated(0, 1, 2, 300)));
+	});
+}
+
+#[test]
+fn create_escrow_checks_parameters() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Escrow::create_escrow(Some(1).into(), 1, 300, 5, None),
+			Error::<Test>::SelfEscrow
+		);
+		assert_noop!(
+			Escrow::create_escrow(Some(1).into(), 2, 49, 5, None),
+			Error::<Test>::AmountLow
+		);
+		assert_noop!(
+			Escrow::create_escrow(Some(1).into(), 2, 300, 5, Some(0)),
+			Error::<Test>::InvalidVestingLength,
+		);
+		assert_noop!(
+			Escrow::create_escrow(Some(1).into(), 2, 1_001, 5, None),
+			pallet_balances::Error::<Test, _>::InsufficientBalance,
+		);
+	});
+}
+
+#[test]
+fn approve_requires_approve_origin() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Escrow::create_escrow(Some(1).into(), 2, 300, 5, None));
+
+		assert_noop!(Escrow::approve(Some(1).into(), 0), BadOrigin);
+		assert_noop!(Escrow::approve(Some(ORACLE).into(), 1), Error::<Test>::UnknownEscrow);
+		assert_ok!(Escrow::approve(Some(ORACLE).into(), 0));
+		assert!(Escrow::escrow(0).unwrap().approved);
+		System::assert_last_event(TestEvent::Escrow(Event::EscrowApproved(0)));
+
+		assert_noop!(Escrow::approve(Some(ORACLE).into(), 0), Error::<Test>::AlreadyApproved);
+	});
+}
+
+#[test]
+fn release_requires_approval_and_time() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Escrow::create_escrow(Some(1).into(), 2, 300, 5, None));
+
+		System::set_block_number(5);
+		assert_noop!(Escrow::release(Some(3).into(), 0), Error::<Test>::NotApproved);
+
+		System::set_block_number(4);
+		assert_ok!(Escrow::approve(Some(ORACLE).into(), 0));
+		assert_noop!(Escrow::release(Some(3).into(), 0), Error::<Test>::NotDue);
+
+		System::set_block_number(5);
+		assert_ok!(Escrow::release(Some(3).into(), 0));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 700);
+		assert_eq!(Balances::free_balance(&2), 310);
+		assert_eq!(Vesting::vesting_balance(&2), None);
+		assert!(Escrow::escrow(0).is_none());
+		System::assert_last_event(TestEvent::Escrow(Event::EscrowReleased(0, 2, 300)));
+	});
+}
+
+#[test]
+fn release_can_be_vested() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Escrow::create_escrow(Some(1).into(), 2, 300, 5, Some(30)));
+		assert_ok!(Escrow::approve(Some(ORACLE).into(), 0));
+
+		System::set_block_number(10);
+		assert_ok!(Escrow::release(Some(3).into(), 0));
+		assert_eq!(Balances::free_balance(&2), 310);
+		assert_eq!(
+			Vesting::vesting(&2),
+			Some(pallet_vesting::VestingInfo {
+				locked: 300,
+				per_block: 10,
+				starting_block: 10,
+				cliff_block: None,
+				remainder: 0,
+			})
+		);
+		assert_eq!(Vesting::vesting_balance(&2), Some(300));
+
+		System::set_block_number(25);
+		assert_eq!(Vesting::vesting_balance(&2), Some(150));
+	});
+}
+
+#[test]
+fn vested_release_is_unlocked_after_vesting_length() {
+	new_test_ext().execute_with(|| {
+		// 300 does not divide into 40 blocks, the amount per block is rounded up.
+		assert_ok!(Escrow::create_escrow(Some(1).into(), 2, 300, 5, Some(40)));
+		assert_ok!(Escrow::approve(Some(ORACLE).into(), 0));
+
+		System::set_block_number(10);
+		assert_ok!(Escrow::release(Some(3).into(), 0));
+		assert_eq!(Vesting::vesting(&2).map(|schedule| schedule.per_block), Some(8));
+
+		System::set_block_number(47);
+		assert_eq!(Vesting::vesting_balance(&2), Some(4));
+		System::set_block_number(50);
+		assert_eq!(Vesting::vesting_balance(&2), Some(0));
+	});
+}
+
+#[test]
+fn vested_release_merges_into_existing_schedule() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Escrow::create_escrow(Some(1).into(), 2, 300, 5, Some(30)));
+		assert_ok!(Escrow::approve(Some(ORACLE).into(), 0));
+		assert_ok!(Vesting::add_vesting_schedule(&2, 10, 1, 1));
+
+		// 1 of the existing schedule is still locked at block 10.
+		System::set_block_number(10);
+		assert_ok!(Escrow::release(Some(3).into(), 0));
+		assert_eq!(
+			Vesting::vesting(&2),
+			Some(pallet_vesting::VestingInfo {
+				locked: 301,
+				per_block: 10,
+				starting_block: 10,
+				cliff_block: None,
+				remainder: 1,
+			})
+		);
+	});
+}
+
+#[test]
+fn release_is_limited_by_slashed_reserve() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Escrow::create_escrow(Some(1).into(), 2, 300, 5, Some(30)));
+		assert_ok!(Escrow::approve(Some(ORACLE).into(), 0));
+		assert_eq!(Balances::slash_reserved(&1, 100).1, 0);
+
+		System::set_block_number(10);
+		assert_ok!(Escrow::release(Some(3).into(), 0));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&2), 210);
+		// Only what was released vests, 200 over 30 blocks rounded up.
+		assert_eq!(
+			Vesting::vesting(&2),
+			Some(pallet_vesting::VestingInfo {
+				locked: 200,
+				per_block: 7,
+				starting_block: 10,
+				cliff_block: None,
+				remainder: 0,
+			})
+		);
+		System::assert_last_event(TestEvent::Escrow(Event::EscrowReleased(0, 2, 200)));
+	});
+}
+
+#[test]
+fn release_creates_beneficiary_account() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Escrow::create_escrow(Some(1).into(), 5, 300, 5, None));
+		assert_ok!(Escrow::create_escrow(Some(1).into(), 6, 60, 5, None));
+		assert_ok!(Escrow::approve(Some(ORACLE).into(), 0));
+		assert_ok!(Escrow::approve(Some(ORACLE).into(), 1));
+
+		System::set_block_number(5);
+		assert_ok!(Escrow::release(Some(3).into(), 0));
+		assert_eq!(Balances::free_balance(&5), 300);
+		assert_eq!(Balances::reserved_balance(&1), 60);
+
+		// What is left of the escrow after a slash is below the existential deposit.
+		assert_eq!(Balances::slash_reserved(&1, 55).1, 0);
+		assert_noop!(
+			Escrow::release(Some(3).into(), 1),
+			pallet_balances::Error::<Test, _>::ExistentialDeposit,
+		);
+		assert!(Escrow::escrow(1).is_some());
+		assert_eq!(Balances::reserved_balance(&1), 5);
+	});
+}
+
+#[test]
+fn failed_vesting_keeps_escrow() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Escrow::create_escrow(Some(1).into(), 2, 300, 5, Some(30)));
+		assert_ok!(Escrow::approve(Some(ORACLE).into(), 0));
+
+		// The vesting schedule would end after the last block number.
+		System::set_block_number(u64::MAX - 5);
+		assert_noop!(
+			Escrow::release(Some(3).into(), 0),
+			pallet_vesting::Error::<Test>::ScheduleEndsOverflow,
+		);
+		assert!(Escrow::escrow(0).is_some());
+		assert_eq!(Balances::reserved_balance(&1), 300);
+		assert_eq!(Balances::free_balance(&2), 10);
+	});
+}
+
+#[test]
+fn cancel_returns_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Escrow::create_escrow(Some(1).into(), 2, 300, 5, None));
+		assert_ok!(Escrow::create_escrow(Some(1).into(), 2, 200, 5, None));
+
+		// The depositor can cancel as long as the escrow is not approved.
+		assert_noop!(Escrow::cancel(Some(2).into(), 0), Error::<Test>::NotAllowed);
+		assert_ok!(Escrow::cancel(Some(1).into(), 0));
+		assert_eq!(Balances::reserved_balance(&1), 200);
+		assert!(Escrow::escrow(0).is_none());
+		System::assert_last_event(TestEvent::Escrow(Event::EscrowCancelled(0, 1, 300)));
+
+		// Afterwards, only the approving origin can.
+		assert_ok!(Escrow::approve(Some(ORACLE).into(), 1));
+		assert_noop!(Escrow::cancel(Some(1).into(), 1), Error::<Test>::NotAllowed);
+		assert_ok!(Escrow::cancel(Some(ORACLE).into(), 1));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 1_000);
+
+		assert_noop!(Escrow::cancel(Some(1).into(), 1), Error::<Test>::UnknownEscrow);
+	});
+}
+
+#[test]
+fn cancel_is_limited_by_slashed_reserve() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Escrow::create_escrow(Some(1).into(), 2, 300, 5, None));
+		assert_eq!(Balances::slash_reserved(&1, 100).1, 0);
+
+		assert_ok!(Escrow::cancel(Some(1).into(), 0));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 900);
+		// Only what was still reserved is reported as returned.
+		System::assert_last_event(TestEvent::Escrow(Event::EscrowCancelled(0, 1, 200)));
+	});
+}
@@ -0,0 +1,27 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration tests for FRAME pallets.
+//!
+//! The tests run multi-block scenarios on a small runtime composing balances, vesting,
+//! elections-phragmen and the merkle mountain range pallet, to catch bugs in the interaction of
+//! pallets which the mocks of the individual pallets miss. See the `tests` module.
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,227 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::{
+	parameter_types,
+	traits::{LockIdentifier, OnInitialize, WithdrawReasons},
+};
+use pallet_mmr_primitives::LeafDataProvider;
+use sp_core::{
+	offchain::{testing::TestOffchainExt, OffchainDbExt, OffchainWorkerExt},
+	H256,
+};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, Header as _, Identity, IdentityLookup, Keccak256},
+	BuildStorage,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Vesting: pallet_vesting::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Elections: pallet_elections_phragmen::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Mmr: pallet_mmr::{Pallet, Storage},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+	type AccountData = pallet_balances::AccountData<u64>;
+	type AccountId = u64;
+	type BaseCallFilter = frame_support::traits::AllowAll;
+	type BlockHashCount = BlockHashCount;
+	type BlockLength = ();
+	type BlockNumber = u64;
+	type BlockWeights = ();
+	type Call = Call;
+	type DbWeight = ();
+	type Event = Event;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type Header = Header;
+	type Index = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type OnKilledAccount = ();
+	type OnNewAccount = ();
+	type OnSetCode = ();
+	type Origin = Origin;
+	type PalletInfo = PalletInfo;
+	type SS58Prefix = ();
+	type SystemWeightInfo = ();
+	type Version = ();
+}
+parameter_types! {
+	pub const MaxLocks: u32 = 10;
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type MaxLocks = MaxLocks;
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const MinVestedTransfer: u64 = 10;
+	pub UnvestedFundsAllowedWithdrawReasons: WithdrawReasons =
+		WithdrawReasons::except(WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE);
+}
+impl pallet_vesting::Config for Test {
+	type BlockNumberProvider = System;
+	type BlockNumberToBalance = Identity;
+	type Currency = Balances;
+	type Event = Event;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type MaxAutoVestsPerBlock = ();
+	type MinVestedTransfer = MinVestedTransfer;
+	type PauseOrigin = frame_system::EnsureRoot<u64>;
+	type UnvestedFundsAllowedWithdrawReasons = UnvestedFundsAllowedWithdrawReasons;
+	type VestOtherReward = ();
+	type VestingHooks = ();
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const ElectionsPhragmenPalletId: LockIdentifier = *b"phrelect";
+	pub const CandidacyBond: u64 = 3;
+	pub const VotingBondBase: u64 = 2;
+	pub const VotingBondFactor: u64 = 0;
+	pub const TermDuration: u64 = 10;
+	pub const DesiredMembers: u32 = 1;
+	pub const DesiredRunnersUp: u32 = 0;
+	pub const MaxCandidates: u32 = 10;
+	pub const MaxVoters: u32 = 100;
+	pub const MaxVotesPerVoter: u32 = 16;
+}
+impl pallet_elections_phragmen::Config for Test {
+	type BadSolution = ();
+	type BlockNumberProvider = System;
+	type CandidacyBond = CandidacyBond;
+	type ChangeMembers = ();
+	type Currency = Balances;
+	type CurrencyToVote = frame_support::traits::SaturatingCurrencyToVote;
+	type DefunctVoterReward = ();
+	type DesiredMembers = DesiredMembers;
+	type DesiredRunnersUp = DesiredRunnersUp;
+	type Event = Event;
+	type Fallback = ();
+	type MaxElectionWeight = ();
+	type ElectWinners = ();
+	type InitializeMembers = ();
+	type KickedMember = ();
+	type LoserCandidate = ();
+	type MaxCandidateMetadata = ();
+	type MaxCandidates = MaxCandidates;
+	type MaxVoters = MaxVoters;
+	type MaxVotesPerVoter = MaxVotesPerVoter;
+	type PalletId = ElectionsPhragmenPalletId;
+	type RankedMembers = ();
+	type SignedPhase = ();
+	type SolutionDeposit = ();
+	type Succession = ();
+	type TermDuration = TermDuration;
+	type TermOrigin = frame_system::EnsureRoot<u64>;
+	type VoterSnapshotPerBlock = ();
+	type VotingBondBase = VotingBondBase;
+	type VotingBondFactor = VotingBondFactor;
+	type WeightInfo = ();
+}
+impl pallet_mmr::Config for Test {
+	const INDEXING_PREFIX: &'static [u8] = b"mmr-";
+
+	type Hash = H256;
+	type Hashing = Keccak256;
+	type LeafData = (frame_system::Pallet<Test>, ElectedMembers);
+	type OnNewRoot = ();
+	type PruningDepth = ();
+	type LeafSource = ();
+	type WeightInfo = ();
+}
+
+/// Commits the current members of the elections pallet to the MMR leaf of each block.
+pub struct ElectedMembers;
+impl LeafDataProvider for ElectedMembers {
+	type LeafData = Vec<u64>;
+
+	fn leaf_data() -> Self::LeafData {
+		members()
+	}
+}
+
+/// The leaf data added to the MMR in each block.
+pub type Leaf = <<Test as pallet_mmr::Config>::LeafData as LeafDataProvider>::LeafData;
+
+/// The accounts of the current members of the elections pallet.
+pub fn members() -> Vec<u64> {
+	Elections::members().into_iter().map(|m| m.who).collect()
+}
+
+/// Account `10` holds `100` units, which vest over 20 blocks starting at genesis.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	GenesisConfig {
+		system: Default::default(),
+		balances: pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(1, 100), (2, 100), (3, 100), (10, 100)],
+		},
+		vesting: pallet_vesting::GenesisConfig::<Test> {
+			vesting: vec![(10, 0, 20, 0)],
+			schedules: vec![],
+		},
+		elections: Default::default(),
+	}
+	.build_storage()
+	.unwrap()
+	.into()
+}
+
+/// Make the indexed MMR leaves available to the off-chain MMR storage, which is required to
+/// generate proofs.
+pub fn register_offchain_ext(ext: &mut sp_io::TestExternalities) {
+	ext.persist_offchain_overlay();
+	let (offchain, _offchain_state) = TestOffchainExt::with_offchain_db(ext.offchain_db());
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+}
+
+/// Finalize the current block and initialize all blocks up to and including `n`.
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		let parent_hash = System::finalize().hash();
+		let number = System::block_number() + 1;
+		System::initialize(
+			&number,
+			&parent_hash,
+			&Default::default(),
+			frame_system::InitKind::Full,
+		);
+		AllPalletsWithSystem::on_initialize(number);
+	}
+}
@@ -0,0 +1,152 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::{assert_noop, assert_ok, traits::Currency};
+use pallet_mmr_primitives::DataOrHash;
+use sp_runtime::traits::Keccak256;
+
+use crate::mock::*;
+
+fn submit_candidacy(who: u64) {
+	let candidate_count = Elections::candidates().len() as u32;
+	assert_ok!(Elections::submit_candidacy(Origin::signed(who), candidate_count));
+}
+
+#[test]
+fn vesting_locked_voters_can_vote() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		submit_candidacy(1);
+		submit_candidacy(2);
+
+		// The voting bond cannot be reserved from funds locked by vesting.
+		assert_noop!(
+			Elections::vote(Origin::signed(10), vec![1], 100),
+			pallet_elections_phragmen::Error::<Test>::UnableToPayBond,
+		);
+
+		// Once some funds vested, the bond can be paid, while still voting with the full balance.
+		assert_ok!(Vesting::vest(Origin::signed(10)));
+		assert_eq!(Balances::locks(&10)[0].amount, 95);
+		assert_ok!(Elections::vote(Origin::signed(10), vec![1], 100));
+		assert_eq!(Balances::reserved_balance(&10), 2);
+		assert_eq!(Elections::voting(&10).stake, 100);
+
+		run_to_block(10);
+		assert_eq!(members(), vec![1]);
+		assert_eq!(Elections::members()[0].stake, 100);
+	});
+}
+
+#[test]
+fn election_lock_outlives_vesting() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		submit_candidacy(1);
+		assert_ok!(Vesting::vest(Origin::signed(10)));
+		assert_ok!(Elections::vote(Origin::signed(10), vec![1], 100));
+
+		// Fully vested funds are still locked for voting.
+		run_to_block(20);
+		assert_ok!(Vesting::vest(Origin::signed(10)));
+		assert_eq!(Vesting::vesting(&10), None);
+		assert_noop!(
+			Balances::transfer(Origin::signed(10), 3, 50),
+			pallet_balances::Error::<Test, _>::LiquidityRestrictions,
+		);
+
+		// Removing the voter releases the lock and the bond.
+		assert_ok!(Elections::remove_voter(Origin::signed(10)));
+		assert_eq!(Balances::free_balance(&10), 100);
+		assert_ok!(Balances::transfer(Origin::signed(10), 3, 100));
+	});
+}
+
+#[test]
+fn mmr_leaves_commit_to_members_across_terms() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		run_to_block(1);
+		submit_candidacy(1);
+		submit_candidacy(2);
+		assert_ok!(Elections::vote(Origin::signed(3), vec![1], 50));
+
+		run_to_block(15);
+		assert_eq!(members(), vec![1]);
+
+		// Candidate 2 runs again and wins the next term.
+		submit_candidacy(2);
+		assert_ok!(Elections::vote(Origin::signed(3), vec![2], 50));
+		run_to_block(25);
+		assert_eq!(members(), vec![2]);
+	});
+
+	register_offchain_ext(&mut ext);
+	ext.execute_with(|| {
+		let root = Mmr::mmr_root_hash();
+		assert_eq!(Mmr::mmr_leaves(), 25);
+
+		// The leaf of block `n` has index `n - 1` and commits to the members at the block.
+		for (block, expected_members) in vec![(5, vec![]), (15, vec![1]), (25, vec![2])] {
+			let (leaf, proof) = Mmr::generate_proof(block - 1).unwrap();
+			let ((parent_number, parent_hash), leaf_members): Leaf = leaf.clone();
+			assert_eq!(parent_number, block - 1);
+			assert_eq!(parent_hash, System::block_hash(block - 1));
+			assert_eq!(leaf_members, expected_members);
+
+			assert_ok!(Mmr::verify_leaf(leaf.clone(), proof.clone()));
+			assert_ok!(pallet_mmr::verify_leaf_proof::<Keccak256, _>(
+				root,
+				DataOrHash::Data(leaf),
+				proof,
+			));
+		}
+
+		// A leaf cannot be proven with the proof of another block.
+		let (leaf, _) = Mmr::generate_proof(4).unwrap();
+		let (_, proof) = Mmr::generate_proof(14).unwrap();
+		assert!(Mmr::verify_leaf(leaf, proof).is_err());
+	});
+}
+
+#[test]
+fn candidacy_bond_cannot_be_paid_from_vesting_funds() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		let schedule = pallet_vesting::VestingInfo {
+			locked: 50,
+			per_block: 5,
+			starting_block: 1,
+			cliff_block: None,
+			remainder: 0,
+		};
+		assert_ok!(Vesting::vested_transfer(Origin::signed(1), 20, schedule));
+		assert_noop!(
+			Elections::submit_candidacy(Origin::signed(20), 0),
+			pallet_elections_phragmen::Error::<Test>::InsufficientCandidateFunds,
+		);
+
+		// Liquid funds can back the bond, while the vesting funds stay locked.
+		let _ = Balances::deposit_creating(&20, 3);
+		submit_candidacy(20);
+		assert_eq!(Balances::reserved_balance(&20), 3);
+		assert_noop!(
+			Balances::transfer(Origin::signed(20), 3, 1),
+			pallet_balances::Error::<Test, _>::LiquidityRestrictions,
+		);
+	});
+}
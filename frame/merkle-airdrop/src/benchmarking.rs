@@ -0,0 +1,116 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Merkle airdrop pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+use sp_std::prelude::*;
+
+use crate::Pallet as MerkleAirdrop;
+
+const SEED: u32 = 0;
+const MAX_PROOF_ITEMS: u32 = 32;
+
+/// Build an MMR of `2^p` leaves and return its root and the proof for its first leaf, which is
+/// the allocation of `amount` to `who`.
+///
+/// An MMR with a power of two leaves is a single perfect binary tree, hence the proof consists of
+/// the `p` siblings on the path of the first leaf, which are arbitrary here.
+fn allocation_proof<T: Config>(
+	who: T::AccountId,
+	amount: BalanceOf<T>,
+	p: u32,
+) -> (HashOf<T>, Proof<HashOf<T>>) {
+	let mut root = DataOrHash::<<T as Config>::Hashing, _>::Data((who, amount)).hash();
+	let items = (0..p).map(|i| <T as Config>::Hashing::hash_of(&i)).collect::<Vec<_>>();
+	for item in &items {
+		let mut concat = root.as_ref().to_vec();
+		concat.extend_from_slice(item.as_ref());
+		root = <T as Config>::Hashing::hash(&concat);
+	}
+	(root, Proof { leaf_index: 0, leaf_count: 1 << p, items })
+}
+
+/// Create a vested airdrop funded by a fresh account and return its id.
+fn create_funded_airdrop<T: Config>(
+	merkle_root: HashOf<T>,
+	leaf_count: u64,
+	total: BalanceOf<T>,
+) -> Result<AirdropId, &'static str> {
+	let creator: T::AccountId = account("creator", 0, SEED);
+	T::Currency::make_free_balance_be(&creator, total.saturating_mul(2u32.into()));
+	let id = MerkleAirdrop::<T>::next_airdrop_id();
+	MerkleAirdrop::<T>::create_airdrop(
+		RawOrigin::Signed(creator).into(),
+		merkle_root,
+		leaf_count,
+		total,
+		Some(VestingParams { starting_block: 1u32.into(), length: 10u32.into() }),
+	)?;
+	Ok(id)
+}
+
+benchmarks! {
+	create_airdrop {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let total = T::Currency::minimum_balance().saturating_mul(1_000u32.into());
+		let vesting = Some(VestingParams { starting_block: 1u32.into(), length: 10u32.into() });
+	}: _(RawOrigin::Signed(caller.clone()), Default::default(), 1_000, total, vesting)
+	verify {
+		assert_eq!(T::Currency::free_balance(&MerkleAirdrop::<T>::account_id(0)), total);
+	}
+
+	claim {
+		let p in 1 .. MAX_PROOF_ITEMS;
+
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = T::Currency::minimum_balance().saturating_mul(100u32.into()).max(100u32.into());
+		let (root, proof) = allocation_proof::<T>(caller.clone(), amount, p);
+		let id = create_funded_airdrop::<T>(root, proof.leaf_count, amount)?;
+	}: _(RawOrigin::Signed(caller.clone()), id, amount, proof)
+	verify {
+		assert!(MerkleAirdrop::<T>::is_claimed(id, 0), "Allocation was not marked as claimed");
+		assert_eq!(
+			T::VestingSchedule::vesting_balance(&caller),
+			Some(amount),
+			"Claim was not vested",
+		);
+	}
+
+	close_airdrop {
+		let c in 0 .. T::MaxClaimsRemoved::get();
+
+		let total = T::Currency::minimum_balance().saturating_mul(1_000u32.into());
+		let id = create_funded_airdrop::<T>(Default::default(), 1_000, total)?;
+		for leaf_index in 0 .. c {
+			Claimed::<T>::insert(id, leaf_index as u64, true);
+		}
+		let creator: T::AccountId = account("creator", 0, SEED);
+	}: _(RawOrigin::Signed(creator), id)
+	verify {
+		assert!(MerkleAirdrop::<T>::airdrop(id).is_none(), "Airdrop was not removed");
+	}
+}
+
+impl_benchmark_test_suite!(MerkleAirdrop, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,386 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Merkle Airdrop Pallet
+//!
+//! - [`Config`]
+//! - [`Call`]
+//!
+//! ## Overview
+//!
+//! A pallet for distributing funds to a large list of accounts without putting the list on-chain.
+//!
+//! The creator of an airdrop commits to a list of `(account, amount)` allocations with a single
+//! root hash and deposits the total amount into an account owned by this pallet. The root is the
+//! root of a Merkle Mountain Range whose leaves are the SCALE-encoded allocations, hashed with
+//! [`Config::Hashing`]. It is built exactly as `pallet_mmr` builds its own tree, hence any MMR
+//! tooling can be used to produce the root and the proofs, and verification is done with
+//! [`pallet_mmr::verify_leaf_proof`].
+//!
+//! Each account then claims its allocation by providing the leaf proof. Every leaf can only be
+//! claimed once. Depending on the airdrop, claimed funds are either liquid, or delivered as a
+//! vesting schedule through the [`VestingSchedule`] trait.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `create_airdrop` - Commit to an allocation list and deposit the funds.
+//! - `claim` - Claim an allocation by providing its MMR proof.
+//! - `close_airdrop` - Return the unclaimed funds to the creator and remove the airdrop, over as
+//!   many calls as needed to remove its claims.
+//!
+//! ## Weights
+//!
+//! The weights in [`weights::SubstrateWeight`] were not benchmarked but estimated from those of
+//! `pallet_vesting`, including the cost of verifying a proof. Do not use them in production before
+//! benchmarking the pallet against your runtime.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub mod weights;
+
+use codec::{Decode, Encode};
+use frame_support::{
+	ensure,
+	pallet_prelude::*,
+	traits::{Currency, ExistenceRequirement, Get, VestingSchedule},
+	transactional, PalletId,
+};
+use frame_system::{ensure_signed, pallet_prelude::*};
+pub use pallet::*;
+use pallet_mmr::primitives::{DataOrHash, Proof};
+use sp_runtime::{
+	traits::{AccountIdConversion, Convert, Hash, Saturating, Zero},
+	RuntimeDebug,
+};
+pub use weights::WeightInfo;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type HashOf<T> = <<T as Config>::Hashing as Hash>::Output;
+
+/// Identifier of an airdrop.
+pub type AirdropId = u32;
+
+/// How the claimed funds of an airdrop are vested.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct VestingParams<BlockNumber> {
+	/// The block at which claimed funds start vesting.
+	pub starting_block: BlockNumber,
+	/// The number of blocks over which claimed funds vest.
+	pub length: BlockNumber,
+}
+
+/// An airdrop committed to by a merkle root.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Airdrop<AccountId, Balance, BlockNumber, Hash> {
+	/// The account which created the airdrop and receives the unclaimed funds upon closing.
+	pub creator: AccountId,
+	/// The MMR root of the allocation list.
+	pub merkle_root: Hash,
+	/// The number of allocations, i.e. MMR leaves, committed to by `merkle_root`.
+	pub leaf_count: u64,
+	/// The funds which were not claimed yet.
+	pub remaining: Balance,
+	/// If set, claims are delivered as vesting schedules with these parameters.
+	pub vesting: Option<VestingParams<BlockNumber>>,
+	/// Whether the airdrop was closed, and its claims are being removed.
+	pub closed: bool,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency trait.
+		type Currency: Currency<Self::AccountId>;
+
+		/// The vesting schedule used to deliver vested claims.
+		type VestingSchedule: VestingSchedule<
+			Self::AccountId,
+			Moment = Self::BlockNumber,
+			Currency = Self::Currency,
+		>;
+
+		/// Convert the block number into a balance.
+		type BlockNumberToBalance: Convert<Self::BlockNumber, BalanceOf<Self>>;
+
+		/// The hashing used to build the MMR of allocations.
+		///
+		/// Use the same hasher as the off-chain tooling producing the roots, e.g. `Keccak256` to
+		/// stay compatible with Ethereum-side tooling.
+		type Hashing: Hash;
+
+		/// The pallet id, used to derive the accounts holding the funds of each airdrop.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The maximal number of claims of a closed airdrop removed by a single `close_airdrop`.
+		#[pallet::constant]
+		type MaxClaimsRemoved: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// All active airdrops.
+	#[pallet::storage]
+	#[pallet::getter(fn airdrop)]
+	pub type Airdrops<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		AirdropId,
+		Airdrop<T::AccountId, BalanceOf<T>, T::BlockNumber, HashOf<T>>,
+	>;
+
+	/// The identifier of the next airdrop to be created.
+	#[pallet::storage]
+	#[pallet::getter(fn next_airdrop_id)]
+	pub type NextAirdropId<T: Config> = StorageValue<_, AirdropId, ValueQuery>;
+
+	/// The leaves of each airdrop which were already claimed.
+	#[pallet::storage]
+	#[pallet::getter(fn is_claimed)]
+	pub type Claimed<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AirdropId, Twox64Concat, u64, bool, ValueQuery>;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
+	pub enum Event<T: Config> {
+		/// An airdrop was created. \[id, creator, total\]
+		AirdropCreated(AirdropId, T::AccountId, BalanceOf<T>),
+		/// An allocation was claimed. \[id, who, amount\]
+		Claimed(AirdropId, T::AccountId, BalanceOf<T>),
+		/// An airdrop was closed and the unclaimed funds returned to its creator.
+		/// \[id, returned\]
+		AirdropClosed(AirdropId, BalanceOf<T>),
+		/// All claims of a closed airdrop were removed, and so was the airdrop. \[id\]
+		AirdropRemoved(AirdropId),
+	}
+
+	/// Error for the merkle airdrop pallet.
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The given airdrop does not exist.
+		UnknownAirdrop,
+		/// An airdrop must commit to at least one allocation.
+		NoAllocations,
+		/// Vested claims must vest over at least one block.
+		InvalidVestingParams,
+		/// The allocation was already claimed.
+		AlreadyClaimed,
+		/// The proof is not valid for the airdrop's root and the claimed allocation.
+		InvalidProof,
+		/// The airdrop does not hold enough funds to pay the claim.
+		InsufficientFunds,
+		/// The origin is not the creator of the airdrop.
+		NotCreator,
+		/// No more airdrops can be created.
+		NoAvailableAirdropId,
+		/// The airdrop was closed.
+		AirdropClosed,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Create an airdrop of `total` funds, allocated according to the list committed to by
+		/// `merkle_root`.
+		///
+		/// The dispatch origin for this call must be _Signed_. `total` is transferred from the
+		/// sender to the airdrop's account.
+		///
+		/// - `merkle_root`: The MMR root of the SCALE-encoded `(AccountId, Balance)` allocations.
+		/// - `leaf_count`: The number of allocations.
+		/// - `total`: The amount of funds to deposit.
+		/// - `vesting`: If set, claims are delivered as vesting schedules.
+		///
+		/// Emits `AirdropCreated`.
+		#[pallet::weight(T::WeightInfo::create_airdrop())]
+		pub fn create_airdrop(
+			origin: OriginFor<T>,
+			merkle_root: HashOf<T>,
+			leaf_count: u64,
+			total: BalanceOf<T>,
+			vesting: Option<VestingParams<T::BlockNumber>>,
+		) -> DispatchResult {
+			let creator = ensure_signed(origin)?;
+			ensure!(leaf_count > 0, Error::<T>::NoAllocations);
+			if let Some(params) = vesting {
+				ensure!(!params.length.is_zero(), Error::<T>::InvalidVestingParams);
+			}
+
+			let id = Self::next_airdrop_id();
+			let next_id = id.checked_add(1).ok_or(Error::<T>::NoAvailableAirdropId)?;
+
+			T::Currency::transfer(
+				&creator,
+				&Self::account_id(id),
+				total,
+				ExistenceRequirement::KeepAlive,
+			)?;
+
+			NextAirdropId::<T>::put(next_id);
+			Airdrops::<T>::insert(
+				id,
+				Airdrop {
+					creator: creator.clone(),
+					merkle_root,
+					leaf_count,
+					remaining: total,
+					vesting,
+					closed: false,
+				},
+			);
+
+			Self::deposit_event(Event::<T>::AirdropCreated(id, creator, total));
+			Ok(())
+		}
+
+		/// Claim `amount` from airdrop `id`.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the account the allocation is
+		/// for. `proof` must be the MMR proof of the leaf `(origin, amount)`.
+		///
+		/// If the airdrop is vested, the claimed funds are locked under a vesting schedule, merged
+		/// into the existing vesting schedule of the claimant, if any.
+		///
+		/// Emits `Claimed`.
+		#[pallet::weight(T::WeightInfo::claim(proof.items.len() as u32))]
+		#[transactional]
+		pub fn claim(
+			origin: OriginFor<T>,
+			id: AirdropId,
+			amount: BalanceOf<T>,
+			proof: Proof<HashOf<T>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut airdrop = Self::airdrop(id).ok_or(Error::<T>::UnknownAirdrop)?;
+			ensure!(!airdrop.closed, Error::<T>::AirdropClosed);
+			ensure!(!Self::is_claimed(id, proof.leaf_index), Error::<T>::AlreadyClaimed);
+			ensure!(airdrop.remaining >= amount, Error::<T>::InsufficientFunds);
+
+			Self::verify_allocation(&airdrop, who.clone(), amount, proof.clone())?;
+
+			T::Currency::transfer(
+				&Self::account_id(id),
+				&who,
+				amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			if let Some(VestingParams { starting_block, length }) = airdrop.vesting {
+				let per_block = T::VestingSchedule::per_block_for(
+					amount,
+					T::BlockNumberToBalance::convert(length),
+				);
+				T::VestingSchedule::merge_vesting_schedule(
+					&who,
+					amount,
+					per_block,
+					starting_block,
+				)?;
+			}
+
+			Claimed::<T>::insert(id, proof.leaf_index, true);
+			airdrop.remaining = airdrop.remaining.saturating_sub(amount);
+			Airdrops::<T>::insert(id, airdrop);
+
+			Self::deposit_event(Event::<T>::Claimed(id, who, amount));
+			Ok(())
+		}
+
+		/// Close airdrop `id`, return all unclaimed funds to its creator and remove its claims.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the creator of the airdrop.
+		///
+		/// At most [`Config::MaxClaimsRemoved`] claims are removed by a call. If more remain, the
+		/// airdrop stays closed, and the call must be repeated to remove the rest.
+		///
+		/// Emits `AirdropClosed` when the airdrop is closed, and `AirdropRemoved` once all its
+		/// claims are removed.
+		#[pallet::weight(T::WeightInfo::close_airdrop(T::MaxClaimsRemoved::get()))]
+		pub fn close_airdrop(origin: OriginFor<T>, id: AirdropId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let mut airdrop = Self::airdrop(id).ok_or(Error::<T>::UnknownAirdrop)?;
+			ensure!(airdrop.creator == who, Error::<T>::NotCreator);
+
+			if !airdrop.closed {
+				let account = Self::account_id(id);
+				let returned = T::Currency::free_balance(&account);
+				T::Currency::transfer(&account, &who, returned, ExistenceRequirement::AllowDeath)?;
+				airdrop.remaining = Zero::zero();
+				airdrop.closed = true;
+				Self::deposit_event(Event::<T>::AirdropClosed(id, returned));
+			}
+
+			let removed = match Claimed::<T>::remove_prefix(id, Some(T::MaxClaimsRemoved::get())) {
+				sp_io::KillStorageResult::AllRemoved(removed) => {
+					Airdrops::<T>::remove(id);
+					Self::deposit_event(Event::<T>::AirdropRemoved(id));
+					removed
+				},
+				sp_io::KillStorageResult::SomeRemaining(removed) => {
+					Airdrops::<T>::insert(id, airdrop);
+					removed
+				},
+			};
+			Ok(Some(T::WeightInfo::close_airdrop(removed)).into())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account holding the funds of airdrop `id`.
+	pub fn account_id(id: AirdropId) -> T::AccountId {
+		T::PalletId::get().into_sub_account(id)
+	}
+
+	/// Check that `proof` proves the allocation of `amount` to `who` in `airdrop`.
+	fn verify_allocation(
+		airdrop: &Airdrop<T::AccountId, BalanceOf<T>, T::BlockNumber, HashOf<T>>,
+		who: T::AccountId,
+		amount: BalanceOf<T>,
+		proof: Proof<HashOf<T>>,
+	) -> DispatchResult {
+		ensure!(
+			proof.leaf_count == airdrop.leaf_count && proof.leaf_index < proof.leaf_count,
+			Error::<T>::InvalidProof
+		);
+		pallet_mmr::verify_leaf_proof::<<T as Config>::Hashing, _>(
+			airdrop.merkle_root,
+			DataOrHash::Data((who, amount)),
+			proof,
+		)
+		.map_err(|_| Error::<T>::InvalidProof.into())
+	}
+}
@@ -0,0 +1,161 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::{parameter_types, traits::WithdrawReasons, PalletId};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, Identity, IdentityLookup, Keccak256},
+};
+
+use super::*;
+use crate as pallet_merkle_airdrop;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Vesting: pallet_vesting::{Pallet, Call, Storage, Event<T>},
+		MerkleAirdrop: pallet_merkle_airdrop::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+	type AccountData = pallet_balances::AccountData<u64>;
+	type AccountId = u64;
+	type BaseCallFilter = frame_support::traits::AllowAll;
+	type BlockHashCount = BlockHashCount;
+	type BlockLength = ();
+	type BlockNumber = u64;
+	type BlockWeights = ();
+	type Call = Call;
+	type DbWeight = ();
+	type Event = Event;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type Header = Header;
+	type Index = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type OnKilledAccount = ();
+	type OnNewAccount = ();
+	type OnSetCode = ();
+	type Origin = Origin;
+	type PalletInfo = PalletInfo;
+	type SS58Prefix = ();
+	type SystemWeightInfo = ();
+	type Version = ();
+}
+parameter_types! {
+	pub const MaxLocks: u32 = 10;
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type MaxLocks = MaxLocks;
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const MinVestedTransfer: u64 = 10;
+	pub UnvestedFundsAllowedWithdrawReasons: WithdrawReasons =
+		WithdrawReasons::except(WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE);
+}
+impl pallet_vesting::Config for Test {
+	type BlockNumberProvider = System;
+	type BlockNumberToBalance = Identity;
+	type Currency = Balances;
+	type Event = Event;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type MaxAutoVestsPerBlock = ();
+	type MinVestedTransfer = MinVestedTransfer;
+	type PauseOrigin = frame_system::EnsureRoot<u64>;
+	type UnvestedFundsAllowedWithdrawReasons = UnvestedFundsAllowedWithdrawReasons;
+	type VestOtherReward = ();
+	type VestingHooks = ();
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const MerkleAirdropPalletId: PalletId = PalletId(*b"py/drops");
+	pub static MaxClaimsRemoved: u32 = 2;
+}
+impl Config for Test {
+	type BlockNumberToBalance = Identity;
+	type Currency = Balances;
+	type Event = Event;
+	type Hashing = Keccak256;
+	type MaxClaimsRemoved = MaxClaimsRemoved;
+	type PalletId = MerkleAirdropPalletId;
+	type VestingSchedule = Vesting;
+	type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 1_000), (2, 10), (3, 10)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Merges MMR nodes the same way `pallet_mmr` does.
+struct KeccakMerge;
+impl mmr_lib::Merge for KeccakMerge {
+	type Item = H256;
+
+	fn merge(left: &H256, right: &H256) -> H256 {
+		let mut concat = left.as_ref().to_vec();
+		concat.extend_from_slice(right.as_ref());
+		<Keccak256 as Hash>::hash(&concat)
+	}
+}
+
+/// Build the MMR of `allocations` off-chain and return its root and the proof of each leaf.
+pub fn build_airdrop(allocations: &[(u64, u64)]) -> (H256, Vec<Proof<H256>>) {
+	let mut mmr = mmr_lib::util::MemMMR::<H256, KeccakMerge>::default();
+	let positions = allocations
+		.iter()
+		.map(|allocation| mmr.push(<Keccak256 as Hash>::hash_of(allocation)).unwrap())
+		.collect::<Vec<_>>();
+	let root = mmr.get_root().unwrap();
+	let proofs = positions
+		.into_iter()
+		.enumerate()
+		.map(|(leaf_index, position)| Proof {
+			leaf_index: leaf_index as u64,
+			leaf_count: allocations.len() as u64,
+			items: mmr.gen_proof(vec![position]).unwrap().proof_items().to_vec(),
+		})
+		.collect();
+	(root, proofs)
+}
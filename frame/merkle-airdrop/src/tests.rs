@@ -0,0 +1,263 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::{assert_noop, assert_ok, traits::VestingSchedule as _};
+use sp_core::H256;
+
+use super::*;
+use crate::mock::{
+	build_airdrop, new_test_ext, Balances, Event as TestEvent, MerkleAirdrop, System, Test, Vesting,
+};
+
+const ALLOCATIONS: [(u64, u64); 5] = [(2, 100), (3, 200), (4, 300), (5, 50), (6, 150)];
+
+fn create(vesting: Option<VestingParams<u64>>) -> Vec<Proof<H256>> {
+	let (root, proofs) = build_airdrop(&ALLOCATIONS);
+	assert_ok!(MerkleAirdrop::create_airdrop(Some(1).into(), root, 5, 800, vesting));
+	proofs
+}
+
+#[test]
+fn create_airdrop_deposits_funds() {
+	new_test_ext().execute_with(|| {
+		let (root, _) = build_airdrop(&ALLOCATIONS);
+		assert_ok!(MerkleAirdrop::create_airdrop(Some(1).into(), root, 5, 800, None));
+
+		let airdrop = MerkleAirdrop::airdrop(0).unwrap();
+		assert_eq!(airdrop.creator, 1);
+		assert_eq!(airdrop.merkle_root, root);
+		assert_eq!(airdrop.remaining, 800);
+		assert_eq!(MerkleAirdrop::next_airdrop_id(), 1);
+		assert_eq!(Balances::free_balance(&1), 200);
+		assert_eq!(Balances::free_balance(&MerkleAirdrop::account_id(0)), 800);
+		System::assert_last_event(TestEvent::MerkleAirdrop(Event::AirdropCreated(0, 1, 800)));
+	});
+}
+
+#[test]
+fn create_airdrop_checks_parameters() {
+	new_test_ext().execute_with(|| {
+		let (root, _) = build_airdrop(&ALLOCATIONS);
+		assert_noop!(
+			MerkleAirdrop::create_airdrop(Some(1).into(), root, 0, 800, None),
+			Error::<Test>::NoAllocations,
+		);
+		assert_noop!(
+			MerkleAirdrop::create_airdrop(
+				Some(1).into(),
+				root,
+				5,
+				800,
+				Some(VestingParams { starting_block: 1, length: 0 }),
+			),
+			Error::<Test>::InvalidVestingParams,
+		);
+		assert_noop!(
+			MerkleAirdrop::create_airdrop(Some(1).into(), root, 5, 1_000, None),
+			pallet_balances::Error::<Test, _>::KeepAlive,
+		);
+	});
+}
+
+#[test]
+fn claim_pays_out_allocations() {
+	new_test_ext().execute_with(|| {
+		let proofs = create(None);
+
+		for (proof, &(who, amount)) in proofs.into_iter().zip(ALLOCATIONS.iter()) {
+			let before = Balances::free_balance(&who);
+			assert_ok!(MerkleAirdrop::claim(Some(who).into(), 0, amount, proof.clone()));
+			assert_eq!(Balances::free_balance(&who), before + amount);
+			assert!(MerkleAirdrop::is_claimed(0, proof.leaf_index));
+			System::assert_last_event(TestEvent::MerkleAirdrop(Event::Claimed(0, who, amount)));
+		}
+
+		assert_eq!(MerkleAirdrop::airdrop(0).unwrap().remaining, 0);
+		assert_eq!(Balances::free_balance(&MerkleAirdrop::account_id(0)), 0);
+	});
+}
+
+#[test]
+fn claim_cannot_be_repeated() {
+	new_test_ext().execute_with(|| {
+		let proofs = create(None);
+		assert_ok!(MerkleAirdrop::claim(Some(3).into(), 0, 200, proofs[1].clone()));
+		assert_noop!(
+			MerkleAirdrop::claim(Some(3).into(), 0, 200, proofs[1].clone()),
+			Error::<Test>::AlreadyClaimed,
+		);
+	});
+}
+
+#[test]
+fn claim_rejects_invalid_proofs() {
+	new_test_ext().execute_with(|| {
+		let proofs = create(None);
+
+		// Wrong claimer.
+		assert_noop!(
+			MerkleAirdrop::claim(Some(7).into(), 0, 100, proofs[0].clone()),
+			Error::<Test>::InvalidProof,
+		);
+		// Wrong amount.
+		assert_noop!(
+			MerkleAirdrop::claim(Some(2).into(), 0, 101, proofs[0].clone()),
+			Error::<Test>::InvalidProof,
+		);
+		// Proof of another leaf.
+		assert_noop!(
+			MerkleAirdrop::claim(Some(2).into(), 0, 100, proofs[1].clone()),
+			Error::<Test>::InvalidProof,
+		);
+		// Proof for a different number of leaves.
+		let (_, other_proofs) = build_airdrop(&ALLOCATIONS[..4]);
+		assert_noop!(
+			MerkleAirdrop::claim(Some(2).into(), 0, 100, other_proofs[0].clone()),
+			Error::<Test>::InvalidProof,
+		);
+		// Unknown airdrop.
+		assert_noop!(
+			MerkleAirdrop::claim(Some(2).into(), 1, 100, proofs[0].clone()),
+			Error::<Test>::UnknownAirdrop,
+		);
+	});
+}
+
+#[test]
+fn claim_can_be_vested() {
+	new_test_ext().execute_with(|| {
+		let proofs = create(Some(VestingParams { starting_block: 10, length: 20 }));
+
+		assert_ok!(MerkleAirdrop::claim(Some(4).into(), 0, 300, proofs[2].clone()));
+		assert_eq!(Balances::free_balance(&4), 300);
+		assert_eq!(Vesting::vesting_balance(&4), Some(300));
+		assert_eq!(
+			Vesting::vesting(&4),
+			Some(pallet_vesting::VestingInfo {
+				locked: 300,
+				per_block: 15,
+				starting_block: 10,
+				cliff_block: None,
+				remainder: 0,
+			})
+		);
+
+		System::set_block_number(20);
+		assert_eq!(Vesting::vesting_balance(&4), Some(150));
+		System::set_block_number(30);
+		assert_eq!(Vesting::vesting_balance(&4), Some(0));
+	});
+}
+
+#[test]
+fn vested_claim_is_unlocked_after_the_vesting_length() {
+	new_test_ext().execute_with(|| {
+		let proofs = create(Some(VestingParams { starting_block: 10, length: 20 }));
+
+		// 50 does not divide by 20, the amount per block is rounded up.
+		assert_ok!(MerkleAirdrop::claim(Some(5).into(), 0, 50, proofs[3].clone()));
+		assert_eq!(Vesting::vesting(&5).unwrap().per_block, 3);
+
+		System::set_block_number(29);
+		assert_eq!(Vesting::vesting_balance(&5), Some(0));
+	});
+}
+
+#[test]
+fn vested_claim_merges_into_existing_schedule() {
+	new_test_ext().execute_with(|| {
+		let proofs = create(Some(VestingParams { starting_block: 10, length: 20 }));
+		assert_ok!(Vesting::add_vesting_schedule(&2, 10, 1, 1));
+
+		assert_ok!(MerkleAirdrop::claim(Some(2).into(), 0, 100, proofs[0].clone()));
+		assert_eq!(
+			Vesting::vesting(&2),
+			Some(pallet_vesting::VestingInfo {
+				locked: 110,
+				per_block: 5,
+				starting_block: 10,
+				cliff_block: None,
+				remainder: 10,
+			})
+		);
+		System::set_block_number(30);
+		assert_eq!(Vesting::vesting_balance(&2), Some(0));
+	});
+}
+
+#[test]
+fn close_airdrop_refunds_creator() {
+	new_test_ext().execute_with(|| {
+		let proofs = create(None);
+		assert_ok!(MerkleAirdrop::claim(Some(2).into(), 0, 100, proofs[0].clone()));
+
+		assert_noop!(MerkleAirdrop::close_airdrop(Some(2).into(), 0), Error::<Test>::NotCreator);
+		assert_ok!(MerkleAirdrop::close_airdrop(Some(1).into(), 0));
+
+		assert_eq!(Balances::free_balance(&1), 900);
+		assert!(MerkleAirdrop::airdrop(0).is_none());
+		assert!(!MerkleAirdrop::is_claimed(0, 0));
+		System::assert_has_event(TestEvent::MerkleAirdrop(Event::AirdropClosed(0, 700)));
+		System::assert_last_event(TestEvent::MerkleAirdrop(Event::AirdropRemoved(0)));
+
+		assert_noop!(
+			MerkleAirdrop::claim(Some(3).into(), 0, 200, proofs[1].clone()),
+			Error::<Test>::UnknownAirdrop,
+		);
+	});
+}
+
+#[test]
+fn close_airdrop_removes_claims_over_several_calls() {
+	let mut ext = new_test_ext();
+	let proofs = ext.execute_with(|| {
+		let proofs = create(None);
+		assert_ok!(MerkleAirdrop::claim(Some(2).into(), 0, 100, proofs[0].clone()));
+		assert_ok!(MerkleAirdrop::claim(Some(3).into(), 0, 200, proofs[1].clone()));
+		assert_ok!(MerkleAirdrop::claim(Some(5).into(), 0, 50, proofs[3].clone()));
+		proofs
+	});
+	// The removal limit only applies to the keys committed by previous blocks.
+	ext.commit_all().unwrap();
+
+	ext.execute_with(|| {
+		// At most 2 claims are removed by a call.
+		assert_ok!(MerkleAirdrop::close_airdrop(Some(1).into(), 0));
+		assert_eq!(Balances::free_balance(&1), 650);
+		System::assert_last_event(TestEvent::MerkleAirdrop(Event::AirdropClosed(0, 450)));
+		let airdrop = MerkleAirdrop::airdrop(0).unwrap();
+		assert!(airdrop.closed);
+		assert_eq!(airdrop.remaining, 0);
+		assert_eq!(Claimed::<Test>::iter_prefix(0).count(), 1);
+
+		// No claim is accepted while the claims are removed.
+		assert_noop!(
+			MerkleAirdrop::claim(Some(4).into(), 0, 300, proofs[2].clone()),
+			Error::<Test>::AirdropClosed,
+		);
+	});
+	ext.commit_all().unwrap();
+
+	ext.execute_with(|| {
+		// The next call removes the rest, without returning any funds twice.
+		assert_ok!(MerkleAirdrop::close_airdrop(Some(1).into(), 0));
+		assert_eq!(Balances::free_balance(&1), 650);
+		assert!(MerkleAirdrop::airdrop(0).is_none());
+		assert_eq!(Claimed::<Test>::iter_prefix(0).count(), 0);
+		System::assert_last_event(TestEvent::MerkleAirdrop(Event::AirdropRemoved(0)));
+	});
+}
@@ -0,0 +1,212 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![warn(missing_docs)]
+
+//! Canonicalize the MMR nodes of the finalized blocks in the offchain database.
+//!
+//! `pallet-mmr` indexes the nodes appended by each block under a key derived from the parent hash
+//! of the block, so that the nodes of competing forks do not overwrite each other. The
+//! [`MmrGadget`] follows the finalized blocks of a full node and moves their nodes to their
+//! canonical key, derived from the position of the node only.
+//!
+//! The nodes of the blocks which are never finalized are left under their temporary key.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::{Decode, Encode};
+use futures::StreamExt;
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	offchain::{OffchainStorage, STORAGE_PREFIX},
+	storage::StorageKey,
+	twox_128,
+};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, One},
+};
+
+/// The offchain database key of the number of the last block whose nodes were canonicalized.
+const CANONICALIZED_KEY: &[u8] = b"pallet-mmr-gadget::canonicalized";
+
+/// An instance of the MMR pallet in the runtime.
+struct MmrInstance {
+	/// The `INDEXING_PREFIX` of the instance.
+	indexing_prefix: Vec<u8>,
+	/// The storage key of the `NumberOfLeaves` value of the instance.
+	leaves_key: StorageKey,
+}
+
+/// Moves the MMR nodes appended by each finalized block to their canonical key.
+pub struct MmrGadget<Block, Client, B, S> {
+	client: Arc<Client>,
+	storage: S,
+	instances: Vec<MmrInstance>,
+	_phantom: PhantomData<(Block, B)>,
+}
+
+impl<Block, Client, B, S> MmrGadget<Block, Client, B, S>
+where
+	Block: BlockT,
+	Client: BlockchainEvents<Block> + HeaderBackend<Block> + StorageProvider<Block, B>,
+	B: Backend<Block>,
+	S: OffchainStorage,
+{
+	/// Create a gadget canonicalizing the nodes in `storage`.
+	pub fn new(client: Arc<Client>, storage: S) -> Self {
+		Self { client, storage, instances: Vec::new(), _phantom: PhantomData }
+	}
+
+	/// Also canonicalize the nodes of the MMR pallet instance named `pallet` in the runtime,
+	/// configured with `indexing_prefix` as its `INDEXING_PREFIX`.
+	pub fn with_instance(mut self, pallet: &str, indexing_prefix: &[u8]) -> Self {
+		let mut leaves_key = twox_128(pallet.as_bytes()).to_vec();
+		leaves_key.extend_from_slice(&twox_128(b"NumberOfLeaves"));
+		self.instances.push(MmrInstance {
+			indexing_prefix: indexing_prefix.to_vec(),
+			leaves_key: StorageKey(leaves_key),
+		});
+		self
+	}
+
+	/// Canonicalize the nodes of the finalized blocks, until the client stops.
+	///
+	/// Canonicalization resumes after the last canonicalized block, if any, or otherwise starts at
+	/// the first block finalized after the gadget is started.
+	pub async fn run(mut self) {
+		let mut finality_notifications = self.client.finality_notification_stream();
+		while let Some(notification) = finality_notifications.next().await {
+			if let Err(e) = self.canonicalize_up_to(*notification.header.number()) {
+				log::error!(target: "mmr-gadget", "Failed to canonicalize MMR nodes: {}", e);
+			}
+		}
+	}
+
+	fn canonicalize_up_to(&mut self, finalized: NumberFor<Block>) -> Result<(), String> {
+		let mut number = self
+			.storage
+			.get(STORAGE_PREFIX, CANONICALIZED_KEY)
+			.and_then(|last| NumberFor::<Block>::decode(&mut &last[..]).ok())
+			.map_or(finalized, |last| last + One::one());
+		while number <= finalized {
+			self.canonicalize_block(number)?;
+			self.storage.set(STORAGE_PREFIX, CANONICALIZED_KEY, &number.encode());
+			number += One::one();
+		}
+		Ok(())
+	}
+
+	fn canonicalize_block(&mut self, number: NumberFor<Block>) -> Result<(), String> {
+		let header = self
+			.client
+			.hash(number)
+			.and_then(|hash| hash.map_or(Ok(None), |hash| self.client.header(BlockId::Hash(hash))))
+			.map_err(|e| e.to_string())?
+			.ok_or_else(|| format!("Finalized block #{} not found", number))?;
+		let parent_hash = *header.parent_hash();
+
+		let Self { client, storage, instances, .. } = self;
+		for instance in instances.iter() {
+			let leaves = |hash| -> Result<u64, String> {
+				let leaves = client
+					.storage(&BlockId::Hash(hash), &instance.leaves_key)
+					.map_err(|e| e.to_string())?;
+				match leaves {
+					Some(leaves) => u64::decode(&mut &leaves.0[..]).map_err(|e| {
+						format!("Failed to decode the MMR leaves of block #{}: {:?}", number, e)
+					}),
+					None => Ok(0),
+				}
+			};
+			let (leaves_before, leaves_after) = (leaves(parent_hash)?, leaves(header.hash())?);
+			canonicalize_nodes(
+				storage,
+				&instance.indexing_prefix,
+				parent_hash,
+				leaves_before,
+				leaves_after,
+			);
+		}
+		Ok(())
+	}
+}
+
+/// The number of nodes of a MMR of `leaves` leaves.
+fn mmr_size(leaves: u64) -> u64 {
+	2 * leaves - u64::from(leaves.count_ones())
+}
+
+/// Move the nodes appended by the block whose parent is `parent_hash`, which took the MMR of
+/// `indexing_prefix` from `leaves_before` to `leaves_after` leaves, to their canonical key.
+///
+/// A node is written to its canonical key before it is removed from its temporary key, so that
+/// the canonicalization of a block can be repeated if it is interrupted.
+pub fn canonicalize_nodes<S: OffchainStorage, Hash: Encode>(
+	storage: &mut S,
+	indexing_prefix: &[u8],
+	parent_hash: Hash,
+	leaves_before: u64,
+	leaves_after: u64,
+) {
+	for pos in mmr_size(leaves_before)..mmr_size(leaves_after) {
+		let temp_key = (indexing_prefix, &parent_hash, pos).encode();
+		if let Some(node) = storage.get(STORAGE_PREFIX, &temp_key) {
+			storage.set(STORAGE_PREFIX, &(indexing_prefix, pos).encode(), &node);
+			storage.remove(STORAGE_PREFIX, &temp_key);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{offchain::storage::InMemOffchainStorage, H256};
+
+	#[test]
+	fn nodes_of_the_finalized_block_are_canonicalized() {
+		let mut storage = InMemOffchainStorage::default();
+		let temp_key =
+			|parent: u8, pos: u64| (&b"mmr"[..], H256::repeat_byte(parent), pos).encode();
+		let canon_key = |pos: u64| (&b"mmr"[..], pos).encode();
+		// given the leaf 2 appended by the child of the block `[1; 32]`, and the leaf 3 with its
+		// two parents appended by the child of the block `[2; 32]`, while a fork appended another
+		// leaf 3
+		storage.set(STORAGE_PREFIX, &temp_key(1, 3), b"leaf 2");
+		storage.set(STORAGE_PREFIX, &temp_key(2, 4), b"leaf 3");
+		storage.set(STORAGE_PREFIX, &temp_key(2, 5), b"node 5");
+		storage.set(STORAGE_PREFIX, &temp_key(2, 6), b"node 6");
+		storage.set(STORAGE_PREFIX, &temp_key(0xf2, 4), b"fork leaf 3");
+
+		// when
+		canonicalize_nodes(&mut storage, b"mmr", H256::repeat_byte(2), 3, 4);
+
+		// then
+		for (pos, node) in vec![(4, b"leaf 3"), (5, b"node 5"), (6, b"node 6")] {
+			assert_eq!(storage.get(STORAGE_PREFIX, &canon_key(pos)), Some(node.to_vec()));
+			assert_eq!(storage.get(STORAGE_PREFIX, &temp_key(2, pos)), None);
+		}
+		assert_eq!(storage.get(STORAGE_PREFIX, &canon_key(3)), None);
+		assert_eq!(storage.get(STORAGE_PREFIX, &temp_key(1, 3)), Some(b"leaf 2".to_vec()));
+		assert_eq!(storage.get(STORAGE_PREFIX, &temp_key(0xf2, 4)), Some(b"fork leaf 3".to_vec()));
+
+		// and canonicalizing the block again is a no-op
+		canonicalize_nodes(&mut storage, b"mmr", H256::repeat_byte(2), 3, 4);
+		assert_eq!(storage.get(STORAGE_PREFIX, &canon_key(4)), Some(b"leaf 3".to_vec()));
+	}
+}
@@ -21,11 +21,19 @@
 #![warn(missing_docs)]
 
 use frame_support::RuntimeDebug;
-use sp_runtime::traits::{self, One, Saturating};
+use sp_runtime::traits::{self, One, Saturating, Zero};
 use sp_std::fmt;
 #[cfg(not(feature = "std"))]
 use sp_std::prelude::Vec;
 
+pub mod utils;
+
+/// The Keccak-256 hasher, to build MMRs which can be verified by Ethereum contracts.
+///
+/// Leaves are hashed as the Keccak-256 of their compact encoding (see [FullLeaf]), inner nodes as
+/// the Keccak-256 of the concatenation of the hashes of their children.
+pub use sp_runtime::traits::Keccak256;
+
 /// A provider of the MMR's leaf data.
 pub trait LeafDataProvider {
 	/// A type that should end up in the leaf of MMR.
@@ -62,6 +70,45 @@ impl<T: frame_system::Config> LeafDataProvider for frame_system::Pallet<T> {
 	}
 }
 
+/// A provider of the leaf data added to the MMR by past blocks.
+///
+/// This allows to repair the leaves missing from the Off-chain DB, for instance because offchain
+/// indexing was disabled when they were added.
+pub trait LeafDataSource<BlockNumber, LeafData> {
+	/// The leaf data added by the block `block_number`, if it can still be derived.
+	fn leaf_data_at(block_number: BlockNumber) -> Option<LeafData>;
+}
+
+/// No past leaf data is available.
+impl<BlockNumber, LeafData> LeafDataSource<BlockNumber, LeafData> for () {
+	fn leaf_data_at(_block_number: BlockNumber) -> Option<LeafData> {
+		None
+	}
+}
+
+/// The leaf data of [frame_system::Pallet] can be derived for the blocks whose parent hash is
+/// still in [frame_system::BlockHash], i.e. the last `BlockHashCount` blocks.
+impl<T: frame_system::Config>
+	LeafDataSource<
+		<T as frame_system::Config>::BlockNumber,
+		(<T as frame_system::Config>::BlockNumber, <T as frame_system::Config>::Hash),
+	> for frame_system::Pallet<T>
+{
+	fn leaf_data_at(
+		block_number: <T as frame_system::Config>::BlockNumber,
+	) -> Option<(<T as frame_system::Config>::BlockNumber, <T as frame_system::Config>::Hash)> {
+		if block_number.is_zero() {
+			return None
+		}
+		let parent_number = block_number - One::one();
+		let parent_hash = Self::block_hash(parent_number);
+		if parent_hash == Default::default() {
+			return None
+		}
+		Some((parent_number, parent_hash))
+	}
+}
+
 /// New MMR root notification hook.
 pub trait OnNewRoot<Hash> {
 	/// Function called by the pallet in case new MMR root has been computed.
@@ -213,6 +260,18 @@ macro_rules! impl_leaf_data_for_tuple {
 			}
 		}
 
+		impl<H, $( $name ),+> Compact<H, ( $( DataOrHash<H, $name>, )+ )> where
+			H: traits::Hash,
+			$( $name: FullLeaf ),+
+		{
+			/// Retrieve a hash of this item in it's compact form.
+			///
+			/// This is the hash the leaf has in the MMR, whichever elements are stored in full.
+			pub fn hash(&self) -> H::Output {
+				self.using_encoded(<H as traits::Hash>::hash, true)
+			}
+		}
+
 		/// [LeafDataProvider] implementation for `Compact<H, (DataOrHash<H, Tuple>, ...)>`
 		///
 		/// This provides a compact-form encoding for tuples wrapped in [Compact].
@@ -248,20 +307,42 @@ macro_rules! impl_leaf_data_for_tuple {
 				)
 			}
 		}
-	}
-}
 
-/// Test functions implementation for `Compact<H, (DataOrHash<H, Tuple>, ...)>`
-#[cfg(test)]
-impl<H, A, B> Compact<H, (DataOrHash<H, A>, DataOrHash<H, B>)>
-where
-	H: traits::Hash,
-	A: FullLeaf,
-	B: FullLeaf,
-{
-	/// Retrieve a hash of this item in it's compact form.
-	pub fn hash(&self) -> H::Output {
-		self.using_encoded(<H as traits::Hash>::hash, true)
+		/// [LeafDataSource] implementation for `Compact<H, (DataOrHash<H, Tuple>, ...)>`
+		///
+		/// The past leaf data is available as long as every element of the tuple can derive it.
+		impl<BlockNumber, H, $( $name ),+> LeafDataSource<
+			BlockNumber,
+			Compact<H, ( $( DataOrHash<H, $name::LeafData>, )+ )>,
+		> for Compact<H, ( $( $name, )+ )> where
+			BlockNumber: Clone,
+			H: traits::Hash,
+			$( $name: LeafDataProvider + LeafDataSource<BlockNumber, $name::LeafData> ),+
+		{
+			fn leaf_data_at(
+				block_number: BlockNumber,
+			) -> Option<Compact<H, ( $( DataOrHash<H, $name::LeafData>, )+ )>> {
+				let tuple = (
+					$( DataOrHash::Data($name::leaf_data_at(block_number.clone())?), )+
+				);
+				Some(Compact::new(tuple))
+			}
+		}
+
+		/// [LeafDataSource] implementation for `(Tuple, ...)`
+		impl<BlockNumber, $( $name ),+> LeafDataSource<
+			BlockNumber,
+			( $( $name::LeafData, )+ ),
+		> for ( $( $name, )+ ) where
+			BlockNumber: Clone,
+			$( $name: LeafDataProvider + LeafDataSource<BlockNumber, $name::LeafData> ),+
+		{
+			fn leaf_data_at(block_number: BlockNumber) -> Option<( $( $name::LeafData, )+ )> {
+				Some((
+					$( $name::leaf_data_at(block_number.clone())?, )+
+				))
+			}
+		}
 	}
 }
 
@@ -282,6 +363,33 @@ pub struct Proof<Hash> {
 	pub items: Vec<Hash>,
 }
 
+/// A MMR proof data for a group of leaves.
+#[derive(codec::Encode, codec::Decode, RuntimeDebug, Clone, PartialEq, Eq)]
+pub struct BatchProof<Hash> {
+	/// The indices of the leaves the proof is for, sorted and without duplicates.
+	pub leaf_indices: Vec<u64>,
+	/// Number of leaves in MMR, when the proof was generated.
+	pub leaf_count: u64,
+	/// Proof elements (hashes of siblings of inner nodes on the paths to the leaves).
+	pub items: Vec<Hash>,
+}
+
+/// A proof that the MMR of `prev_leaf_count` leaves is a prefix of the MMR of `leaf_count` leaves.
+///
+/// This allows to trust the root of a later MMR given the root of an earlier one.
+#[derive(codec::Encode, codec::Decode, RuntimeDebug, Clone, PartialEq, Eq)]
+pub struct AncestryProof<Hash> {
+	/// The peaks of the MMR of `prev_leaf_count` leaves, from left to right.
+	pub prev_peaks: Vec<Hash>,
+	/// Number of leaves in the earlier MMR.
+	pub prev_leaf_count: u64,
+	/// Number of leaves in MMR, when the proof was generated.
+	pub leaf_count: u64,
+	/// Hashes of the subtrees added since, required to compute the peaks of the later MMR from
+	/// `prev_peaks`, in depth-first order from left to right.
+	pub items: Vec<Hash>,
+}
+
 /// Merkle Mountain Range operation error.
 #[derive(RuntimeDebug, codec::Encode, codec::Decode, PartialEq, Eq)]
 pub enum Error {
@@ -400,6 +508,7 @@ impl EncodableOpaqueLeaf {
 
 sp_api::decl_runtime_apis! {
 	/// API to interact with MMR pallet.
+	#[api_version(2)]
 	pub trait MmrApi<Hash: codec::Codec> {
 		/// Generate MMR proof for a leaf under given index.
 		fn generate_proof(leaf_index: u64) -> Result<(EncodableOpaqueLeaf, Proof<Hash>), Error>;
@@ -419,6 +528,55 @@ sp_api::decl_runtime_apis! {
 		/// The leaf data is expected to be encoded in it's compact form.
 		fn verify_proof_stateless(root: Hash, leaf: EncodableOpaqueLeaf, proof: Proof<Hash>)
 			-> Result<(), Error>;
+
+		/// Generate MMR proof for a leaf under given index, against the MMR root when the MMR had
+		/// `leaves_count` leaves.
+		///
+		/// The proof can be verified against that root with [Self::verify_proof_stateless].
+		fn generate_historical_proof(leaf_index: u64, leaves_count: u64)
+			-> Result<(EncodableOpaqueLeaf, Proof<Hash>), Error>;
+
+		/// Generate a proof that the MMR of `prev_leaf_count` leaves is a prefix of the current
+		/// MMR.
+		fn generate_ancestry_proof(prev_leaf_count: u64) -> Result<AncestryProof<Hash>, Error>;
+
+		/// Verify a proof that the MMR root `prev_root` is an ancestor of the MMR root `root`.
+		///
+		/// Note this function does not require any on-chain storage.
+		fn verify_ancestry_proof_stateless(
+			root: Hash,
+			prev_root: Hash,
+			proof: AncestryProof<Hash>,
+		) -> Result<(), Error>;
+
+		/// Generate a single MMR proof for the leaves under given indices.
+		///
+		/// The indices are sorted and de-duplicated, and the leaves are returned in that order.
+		fn generate_batch_proof(leaf_indices: Vec<u64>)
+			-> Result<(Vec<EncodableOpaqueLeaf>, BatchProof<Hash>), Error>;
+
+		/// Generate a single MMR proof for the leaves from `first_leaf` to `last_leaf`, both
+		/// included.
+		///
+		/// The proof can be verified like the proofs of [Self::generate_batch_proof].
+		fn generate_proof_range(first_leaf: u64, last_leaf: u64)
+			-> Result<(Vec<EncodableOpaqueLeaf>, BatchProof<Hash>), Error>;
+
+		/// Verify MMR proof of a batch of leaves against on-chain MMR.
+		///
+		/// The leaves are expected in the order of the proof's leaf indices.
+		/// See [Self::verify_batch_proof_stateless] for a stateless verifier.
+		fn verify_batch_proof(leaves: Vec<EncodableOpaqueLeaf>, proof: BatchProof<Hash>)
+			-> Result<(), Error>;
+
+		/// Verify MMR proof of a batch of leaves against given root hash.
+		///
+		/// The leaves data is expected to be encoded in its compact form.
+		fn verify_batch_proof_stateless(
+			root: Hash,
+			leaves: Vec<EncodableOpaqueLeaf>,
+			proof: BatchProof<Hash>,
+		) -> Result<(), Error>;
 	}
 }
 
@@ -428,7 +586,6 @@ mod tests {
 
 	use codec::Decode;
 	use sp_core::H256;
-	use sp_runtime::traits::Keccak256;
 
 	pub(crate) fn hex(s: &str) -> H256 {
 		s.parse().unwrap()
@@ -459,6 +616,26 @@ mod tests {
 		assert_eq!(decoded, Ok(proof));
 	}
 
+	#[test]
+	fn should_encode_decode_batch_proof() {
+		// given
+		let proof: BatchProof<<Keccak256 as traits::Hash>::Output> = BatchProof {
+			leaf_indices: vec![1, 5],
+			leaf_count: 10,
+			items: vec![
+				hex("c3e7ba6b511162fead58f2c8b5764ce869ed1118011ac37392522ed16720bbcd"),
+				hex("d3e7ba6b511162fead58f2c8b5764ce869ed1118011ac37392522ed16720bbcd"),
+			],
+		};
+
+		// when
+		let encoded = codec::Encode::encode(&proof);
+		let decoded = BatchProof::decode(&mut &*encoded);
+
+		// then
+		assert_eq!(decoded, Ok(proof));
+	}
+
 	#[test]
 	fn should_encode_decode_correctly_if_no_compact() {
 		// given
@@ -550,6 +727,77 @@ mod tests {
 		assert_eq!(decoded_compact, vec![Ok(d.clone()), Ok(d.clone())]);
 	}
 
+	#[test]
+	fn should_compose_leaf_data_providers() {
+		// given
+		struct ParentNumberAndHash;
+		impl LeafDataProvider for ParentNumberAndHash {
+			type LeafData = (u64, H256);
+			fn leaf_data() -> Self::LeafData {
+				(4, H256::repeat_byte(4))
+			}
+		}
+		impl LeafDataSource<u64, (u64, H256)> for ParentNumberAndHash {
+			fn leaf_data_at(block_number: u64) -> Option<(u64, H256)> {
+				let parent = block_number.checked_sub(1)?;
+				Some((parent, H256::repeat_byte(parent as u8)))
+			}
+		}
+		struct AuthoritySet;
+		impl LeafDataProvider for AuthoritySet {
+			type LeafData = Vec<u64>;
+			fn leaf_data() -> Self::LeafData {
+				vec![1, 2, 3]
+			}
+		}
+		impl LeafDataSource<u64, Vec<u64>> for AuthoritySet {
+			fn leaf_data_at(_block_number: u64) -> Option<Vec<u64>> {
+				Some(vec![1, 2, 3])
+			}
+		}
+		struct CustomData;
+		impl LeafDataProvider for CustomData {
+			type LeafData = String;
+			fn leaf_data() -> Self::LeafData {
+				"Hello World!".into()
+			}
+		}
+		type Providers = (ParentNumberAndHash, AuthoritySet, CustomData);
+		type CompactProviders = Compact<Keccak256, Providers>;
+
+		// when
+		let leaf = CompactProviders::leaf_data();
+		let (number_and_hash, authorities, custom) = leaf.tuple.clone();
+		let compacted: <CompactProviders as LeafDataProvider>::LeafData = Compact::new((
+			number_and_hash.clone(),
+			DataOrHash::Hash(authorities.hash()),
+			DataOrHash::Hash(custom.hash()),
+		));
+		let encoded = leaf.using_encoded(|x| x.to_vec(), false);
+		let decoded = <CompactProviders as LeafDataProvider>::LeafData::decode(&mut &*encoded);
+
+		// then
+		assert_eq!(
+			Providers::leaf_data(),
+			((4, H256::repeat_byte(4)), vec![1, 2, 3], "Hello World!".into())
+		);
+		assert_eq!(number_and_hash, DataOrHash::Data((4, H256::repeat_byte(4))));
+		assert_eq!(leaf.hash(), compacted.hash());
+		assert_eq!(decoded, Ok(leaf));
+		assert_eq!(
+			<Compact<Keccak256, (ParentNumberAndHash, AuthoritySet)>>::leaf_data_at(5),
+			Some(Compact::new((
+				DataOrHash::Data((4, H256::repeat_byte(4))),
+				DataOrHash::Data(vec![1, 2, 3]),
+			))),
+		);
+		assert_eq!(
+			<(ParentNumberAndHash, AuthoritySet)>::leaf_data_at(5),
+			Some(((4, H256::repeat_byte(4)), vec![1, 2, 3])),
+		);
+		assert_eq!(<(ParentNumberAndHash, AuthoritySet)>::leaf_data_at(0), None);
+	}
+
 	#[test]
 	fn opaque_leaves_should_be_full_leaf_compatible() {
 		// given
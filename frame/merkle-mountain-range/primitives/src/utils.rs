@@ -0,0 +1,181 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Merkle Mountain Range utilities.
+//!
+//! Nodes are numbered by their position, in the order in which they are appended to the MMR:
+//! every leaf is followed by the inner nodes it completes.
+
+#[cfg(not(feature = "std"))]
+use sp_std::prelude::Vec;
+
+/// MMR nodes & size -related utilities.
+pub struct NodesUtils {
+	no_of_leaves: u64,
+}
+
+impl NodesUtils {
+	/// Create new instance of MMR nodes utilities for given number of leaves.
+	pub fn new(no_of_leaves: u64) -> Self {
+		Self { no_of_leaves }
+	}
+
+	/// Calculate number of peaks in the MMR.
+	pub fn number_of_peaks(&self) -> u64 {
+		self.number_of_leaves().count_ones() as u64
+	}
+
+	/// Return the number of leaves in the MMR.
+	pub fn number_of_leaves(&self) -> u64 {
+		self.no_of_leaves
+	}
+
+	/// Calculate the total size of MMR (number of nodes).
+	pub fn size(&self) -> u64 {
+		2 * self.no_of_leaves - self.number_of_peaks()
+	}
+
+	/// Calculate the positions and heights of the peaks of the MMR, from left to right.
+	pub fn peaks(&self) -> Vec<(u64, u32)> {
+		let mut peaks = Vec::new();
+		let mut offset = 0;
+		for height in (0..64).rev() {
+			if self.no_of_leaves & (1 << height) != 0 {
+				// A perfect binary tree with `2^height` leaves.
+				let size = (2 << height) - 1;
+				peaks.push((offset + size - 1, height));
+				offset += size;
+			}
+		}
+		peaks
+	}
+
+	/// Calculate maximal depth of the MMR.
+	pub fn depth(&self) -> u32 {
+		if self.no_of_leaves == 0 {
+			return 0
+		}
+
+		64 - self.no_of_leaves.next_power_of_two().leading_zeros()
+	}
+
+	/// Calculate the number of leaves of the MMR of `size` nodes.
+	///
+	/// Returns `None` if no MMR has that many nodes.
+	pub fn leaf_count_from_size(size: u64) -> Option<u64> {
+		let mut leaves = 0;
+		let mut remaining = size;
+		// The MMR is made of perfect binary trees of decreasing heights, each larger than all the
+		// smaller ones together, so that they can be found from the largest one.
+		for height in (0..63).rev() {
+			let tree_size = (2 << height) - 1;
+			if tree_size <= remaining {
+				remaining -= tree_size;
+				leaves += 1 << height;
+			}
+		}
+		if remaining == 0 {
+			Some(leaves)
+		} else {
+			None
+		}
+	}
+}
+
+/// Calculate the position of the leaf at `leaf_index` in the MMR.
+///
+/// The leaf is appended right after all the nodes of the MMR of `leaf_index` leaves.
+pub fn leaf_index_to_pos(leaf_index: u64) -> u64 {
+	NodesUtils::new(leaf_index).size()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_calculate_number_of_leaves_correctly() {
+		assert_eq!(
+			vec![0, 1, 2, 3, 4, 9, 15, 21]
+				.into_iter()
+				.map(|n| NodesUtils::new(n).depth())
+				.collect::<Vec<_>>(),
+			vec![0, 1, 2, 3, 3, 5, 5, 6]
+		);
+	}
+
+	#[test]
+	fn should_calculate_depth_correclty() {
+		assert_eq!(
+			vec![0, 1, 2, 3, 4, 9, 15, 21]
+				.into_iter()
+				.map(|n| NodesUtils::new(n).number_of_leaves())
+				.collect::<Vec<_>>(),
+			vec![0, 1, 2, 3, 4, 9, 15, 21]
+		);
+	}
+
+	#[test]
+	fn should_calculate_number_of_peaks_correctly() {
+		assert_eq!(
+			vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 21]
+				.into_iter()
+				.map(|n| NodesUtils::new(n).number_of_peaks())
+				.collect::<Vec<_>>(),
+			vec![0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4, 3]
+		);
+	}
+
+	#[test]
+	fn should_calculate_peaks_correctly() {
+		assert_eq!(NodesUtils::new(0).peaks(), vec![]);
+		assert_eq!(NodesUtils::new(1).peaks(), vec![(0, 0)]);
+		assert_eq!(NodesUtils::new(7).peaks(), vec![(6, 2), (9, 1), (10, 0)]);
+		assert_eq!(NodesUtils::new(8).peaks(), vec![(14, 3)]);
+		assert_eq!(NodesUtils::new(21).peaks(), vec![(30, 4), (37, 2), (38, 0)]);
+	}
+
+	#[test]
+	fn should_calculate_the_size_correctly() {
+		let leaves = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 21];
+		let sizes = vec![0, 1, 3, 4, 7, 8, 10, 11, 15, 16, 18, 19, 22, 23, 25, 26, 39];
+		assert_eq!(leaves.iter().map(|n| NodesUtils::new(*n).size()).collect::<Vec<_>>(), sizes);
+		assert_eq!(
+			sizes.iter().map(|s| NodesUtils::leaf_count_from_size(*s)).collect::<Vec<_>>(),
+			leaves.into_iter().map(Some).collect::<Vec<_>>(),
+		);
+	}
+
+	#[test]
+	fn should_reject_sizes_of_no_mmr() {
+		assert_eq!(
+			vec![2, 5, 6, 9, 12, 13, 14, 17]
+				.into_iter()
+				.map(NodesUtils::leaf_count_from_size)
+				.collect::<Vec<_>>(),
+			vec![None; 8]
+		);
+	}
+
+	#[test]
+	fn should_calculate_leaf_positions_correctly() {
+		assert_eq!(
+			(0..8).map(leaf_index_to_pos).collect::<Vec<_>>(),
+			vec![0, 1, 3, 4, 7, 8, 10, 11]
+		);
+	}
+}
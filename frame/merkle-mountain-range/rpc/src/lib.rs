@@ -21,12 +21,12 @@
 
 use std::sync::Arc;
 
-use codec::{Codec, Encode};
+use codec::{Codec, Decode, Encode};
 use jsonrpc_core::{Error, ErrorCode, Result};
 use jsonrpc_derive::rpc;
 use serde::{Deserialize, Serialize};
 
-use pallet_mmr_primitives::{Error as MmrError, Proof};
+use pallet_mmr_primitives::{BatchProof, EncodableOpaqueLeaf, Error as MmrError, Proof};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_core::Bytes;
@@ -57,6 +57,33 @@ impl<BlockHash> LeafProof<BlockHash> {
 	}
 }
 
+/// Retrieved MMR leaves and their proof.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LeafBatchProof<BlockHash> {
+	/// Block hash the proof was generated for.
+	pub block_hash: BlockHash,
+	/// SCALE-encoded vector of leaves data, in the order of the proof's leaf indices.
+	pub leaves: Bytes,
+	/// SCALE-encoded proof data. See [pallet_mmr_primitives::BatchProof].
+	pub proof: Bytes,
+}
+
+impl<BlockHash> LeafBatchProof<BlockHash> {
+	/// Create new `LeafBatchProof` from given concrete `leaves` and `proof`.
+	pub fn new<Leaf, MmrHash>(
+		block_hash: BlockHash,
+		leaves: Vec<Leaf>,
+		proof: BatchProof<MmrHash>,
+	) -> Self
+	where
+		Leaf: Encode,
+		MmrHash: Encode,
+	{
+		Self { block_hash, leaves: Bytes(leaves.encode()), proof: Bytes(proof.encode()) }
+	}
+}
+
 /// MMR RPC methods.
 #[rpc]
 pub trait MmrApi<BlockHash> {
@@ -74,6 +101,37 @@ pub trait MmrApi<BlockHash> {
 		leaf_index: u64,
 		at: Option<BlockHash>,
 	) -> Result<LeafProof<BlockHash>>;
+
+	/// Generate a single MMR proof for the leaves at given `leaf_indices`.
+	///
+	/// The indices are sorted and de-duplicated by the runtime, which returns the (full) leaves
+	/// in that order along with the proof. Optionally, a block hash at which the runtime should
+	/// be queried can be specified.
+	#[rpc(name = "mmr_generateBatchProof")]
+	fn generate_batch_proof(
+		&self,
+		leaf_indices: Vec<u64>,
+		at: Option<BlockHash>,
+	) -> Result<LeafBatchProof<BlockHash>>;
+
+	/// Generate a single MMR proof for the leaves from `first_leaf` to `last_leaf`, both included.
+	///
+	/// This is much smaller than a proof for each leaf of the range, and can be verified like the
+	/// proofs of [`Self::generate_batch_proof`]. Optionally, a block hash at which the runtime
+	/// should be queried can be specified.
+	#[rpc(name = "mmr_generateProofRange")]
+	fn generate_proof_range(
+		&self,
+		first_leaf: u64,
+		last_leaf: u64,
+		at: Option<BlockHash>,
+	) -> Result<LeafBatchProof<BlockHash>>;
+
+	/// Verify a MMR proof of a batch of leaves against the MMR at the proof's block hash.
+	///
+	/// Returns `false` if the proof is not valid for the given leaves.
+	#[rpc(name = "mmr_verifyProof")]
+	fn verify_proof(&self, proof: LeafBatchProof<BlockHash>) -> Result<bool>;
 }
 
 /// An implementation of MMR specific RPC methods.
@@ -117,6 +175,70 @@ where
 
 		Ok(LeafProof::new(block_hash, leaf, proof))
 	}
+
+	fn generate_batch_proof(
+		&self,
+		leaf_indices: Vec<u64>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<LeafBatchProof<<Block as BlockT>::Hash>> {
+		let api = self.client.runtime_api();
+		let block_hash = at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash);
+
+		let (leaves, proof) = api
+			.generate_batch_proof_with_context(
+				&BlockId::hash(block_hash),
+				sp_core::ExecutionContext::OffchainCall(None),
+				leaf_indices,
+			)
+			.map_err(runtime_error_into_rpc_error)?
+			.map_err(mmr_error_into_rpc_error)?;
+
+		Ok(LeafBatchProof::new(block_hash, leaves, proof))
+	}
+
+	fn generate_proof_range(
+		&self,
+		first_leaf: u64,
+		last_leaf: u64,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<LeafBatchProof<<Block as BlockT>::Hash>> {
+		let api = self.client.runtime_api();
+		let block_hash = at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash);
+
+		let (leaves, proof) = api
+			.generate_proof_range_with_context(
+				&BlockId::hash(block_hash),
+				sp_core::ExecutionContext::OffchainCall(None),
+				first_leaf,
+				last_leaf,
+			)
+			.map_err(runtime_error_into_rpc_error)?
+			.map_err(mmr_error_into_rpc_error)?;
+
+		Ok(LeafBatchProof::new(block_hash, leaves, proof))
+	}
+
+	fn verify_proof(&self, proof: LeafBatchProof<<Block as BlockT>::Hash>) -> Result<bool> {
+		let api = self.client.runtime_api();
+		let LeafBatchProof { block_hash, leaves, proof } = proof;
+		let leaves = Vec::<EncodableOpaqueLeaf>::decode(&mut &leaves[..])
+			.map_err(|e| invalid_params("Unable to decode the leaves", e))?;
+		let proof = BatchProof::<MmrHash>::decode(&mut &proof[..])
+			.map_err(|e| invalid_params("Unable to decode the proof", e))?;
+
+		match api
+			.verify_batch_proof(&BlockId::hash(block_hash), leaves, proof)
+			.map_err(runtime_error_into_rpc_error)?
+		{
+			Ok(()) => Ok(true),
+			Err(MmrError::Verify) => Ok(false),
+			Err(e) => Err(mmr_error_into_rpc_error(e)),
+		}
+	}
 }
 
 const RUNTIME_ERROR: i64 = 8000;
@@ -143,6 +265,15 @@ fn mmr_error_into_rpc_error(err: MmrError) -> Error {
 	}
 }
 
+/// Converts a failure to decode a parameter into an RPC error.
+fn invalid_params(message: &str, err: codec::Error) -> Error {
+	Error {
+		code: ErrorCode::InvalidParams,
+		message: message.into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
+
 /// Converts a runtime trap into an RPC error.
 fn runtime_error_into_rpc_error(err: impl std::fmt::Debug) -> Error {
 	Error {
@@ -179,6 +310,28 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn should_serialize_leaf_batch_proof() {
+		// given
+		let leaves = vec![vec![1_u8, 2, 3, 4]];
+		let proof = BatchProof {
+			leaf_indices: vec![1, 5],
+			leaf_count: 9,
+			items: vec![H256::repeat_byte(1), H256::repeat_byte(2)],
+		};
+
+		let leaf_batch_proof = LeafBatchProof::new(H256::repeat_byte(0), leaves, proof);
+
+		// when
+		let actual = serde_json::to_string(&leaf_batch_proof).unwrap();
+
+		// then
+		assert_eq!(
+			actual,
+			r#"{"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","leaves":"0x041001020304","proof":"0x080100000000000000050000000000000009000000000000000801010101010101010101010101010101010101010101010101010101010101010202020202020202020202020202020202020202020202020202020202020202"}"#
+		);
+	}
+
 	#[test]
 	fn should_deserialize_leaf_proof() {
 		// given
@@ -24,16 +24,22 @@ use frame_benchmarking::{benchmarks_instance_pallet, impl_benchmark_test_suite};
 use frame_support::traits::OnInitialize;
 
 benchmarks_instance_pallet! {
+	// Appending a leaf to a MMR of `2^p - 1` leaves merges it with all of its `p` peaks, which is
+	// the worst case for a MMR of that size. `2^48` leaves are more than any chain adds, while
+	// the MMR library overflows on sizes close to `2^64` nodes.
 	on_initialize {
-		let x in 1 .. 1_000;
+		let p in 1 .. 48;
 
-		let leaves = x as u64;
-	}: {
-		for b in 0..leaves {
-			Pallet::<T, I>::on_initialize((b as u32).into());
+		let leaves = (1u64 << p) - 1;
+		for (pos, _) in mmr::utils::NodesUtils::new(leaves).peaks() {
+			crate::Nodes::<T, I>::insert(pos, <T as Config<I>>::Hash::default());
 		}
+		crate::NumberOfLeaves::<T, I>::put(leaves);
+	}: {
+		Pallet::<T, I>::on_initialize(1u32.into());
 	} verify {
-		assert_eq!(crate::NumberOfLeaves::<T, I>::get(), leaves);
+		assert_eq!(crate::NumberOfLeaves::<T, I>::get(), leaves + 1);
+		assert_eq!(mmr::utils::NodesUtils::new(leaves + 1).number_of_peaks(), 1);
 	}
 }
 
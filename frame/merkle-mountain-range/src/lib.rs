@@ -29,7 +29,7 @@
 //! well) to the Off-chain DB, so that the data is available for Off-chain workers.
 //! Hashing used for MMR is configurable independently from the rest of the runtime (i.e. not using
 //! `frame_system::Hashing`) so something compatible with external chains can be used (like
-//! Keccak256 for Ethereum compatibility).
+//! [`primitives::Keccak256`] for Ethereum compatibility).
 //!
 //! Depending on the usage context (off-chain vs on-chain) the pallet is able to:
 //! - verify MMR leaf proofs (on-chain)
@@ -53,28 +53,42 @@
 //! in time to provide a MMR proof about some past block hash, while this data can be safely pruned
 //! from on-chain storage.
 //!
-//! NOTE This pallet is experimental and not proven to work in production.
+//! ## Instances
+//!
+//! The pallet is instantiable, so that independent subsystems (for instance bridging and block
+//! commitments) can each keep their own MMR, with separate on-chain storage. Each instance must be
+//! configured with its own [`Config::INDEXING_PREFIX`] to keep their nodes apart in the
+//! Off-chain DB.
+//!
+//! NOTE This pallet is experimental and not proven to work in production. Its weights are
+//! hand-written estimates, not benchmark results, and must be benchmarked before it is used on a
+//! production chain.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::Encode;
-use frame_support::weights::Weight;
-use sp_runtime::traits;
+use codec::{Decode, Encode};
+use frame_support::{traits::Get, weights::Weight};
+use sp_runtime::traits::{self, One, Saturating, UniqueSaturatedInto, Zero};
 
 #[cfg(any(feature = "runtime-benchmarks", test))]
 mod benchmarking;
-mod default_weights;
+pub mod migrations;
 mod mmr;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
+pub mod weights;
 
 pub use pallet::*;
 pub use pallet_mmr_primitives as primitives;
+pub use weights::WeightInfo;
 
-pub trait WeightInfo {
-	fn on_initialize(peaks: u64) -> Weight;
-}
+/// The current storage version.
+const STORAGE_VERSION: frame_support::traits::StorageVersion =
+	frame_support::traits::StorageVersion::new(1);
+
+/// The maximal number of leaves repaired by the offchain worker in a single block.
+const MAX_REPAIRED_LEAVES_PER_BLOCK: u64 = 1_000;
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -84,6 +98,7 @@ pub mod pallet {
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
 	/// This pallet's configuration trait
@@ -96,8 +111,13 @@ pub mod pallet {
 		/// and some of the inner mmr nodes might be pruned from on-chain storage.
 		/// The latter will contain all the entries in their full form.
 		///
-		/// Each node is stored in the Off-chain DB under key derived from the [`Self::INDEXING_PREFIX`] and
-		/// it's in-tree index (MMR position).
+		/// Each node is stored in the Off-chain DB under key derived from the [`Self::INDEXING_PREFIX`],
+		/// the parent hash of the block which added it and it's in-tree index (MMR position).
+		/// Once the block is finalized, the `pallet-mmr-gadget` of the node moves the node to a key
+		/// derived from the prefix and the position only.
+		///
+		/// Every instance of the pallet in a runtime must use a distinct prefix, so that their
+		/// nodes do not overwrite each other in the Off-chain DB.
 		const INDEXING_PREFIX: &'static [u8];
 
 		/// A hasher type for MMR.
@@ -146,6 +166,23 @@ pub mod pallet {
 		/// Hook complexity should be `O(1)`.
 		type OnNewRoot: primitives::OnNewRoot<<Self as Config<I>>::Hash>;
 
+		/// The number of most recent leaves whose nodes are kept in on-chain storage.
+		///
+		/// Older nodes are pruned from on-chain storage once their parent is older than this
+		/// window, and are then only available in the Off-chain DB. The peaks of the MMR at any
+		/// size within the window are kept, so that proofs generated for such a size can still be
+		/// verified on-chain.
+		#[pallet::constant]
+		type PruningDepth: Get<u64>;
+
+		/// A source of the leaf data added by past blocks.
+		///
+		/// It is used to rebuild the leaves missing from the Off-chain DB, see
+		/// [`Pallet::rebuild_offchain`]. Use `()` if the leaf data cannot be derived anymore.
+		/// Sources compose like the [LeafData](Config::LeafData) providers, e.g.
+		/// `Compact<H, (frame_system::Pallet<Self>, MyData)>`.
+		type LeafSource: primitives::LeafDataSource<Self::BlockNumber, LeafOf<Self, I>>;
+
 		/// Weights for this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -163,8 +200,9 @@ pub mod pallet {
 
 	/// Hashes of the nodes in the MMR.
 	///
-	/// Note this collection only contains MMR peaks, the inner nodes (and leaves)
-	/// are pruned and only stored in the Offchain DB.
+	/// Note this collection only contains the nodes added for the last
+	/// [`Config::PruningDepth`] leaves and the peaks of the MMR within that window,
+	/// the older inner nodes (and leaves) are pruned and only stored in the Offchain DB.
 	#[pallet::storage]
 	#[pallet::getter(fn mmr_peak)]
 	pub type Nodes<T: Config<I>, I: 'static = ()> =
@@ -174,11 +212,12 @@ pub mod pallet {
 	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
 		fn on_initialize(_n: T::BlockNumber) -> Weight {
 			use primitives::LeafDataProvider;
-			let leaves = Self::mmr_leaves();
-			let peaks_before = mmr::utils::NodesUtils::new(leaves).number_of_peaks();
+			let leaves_before = Self::mmr_leaves();
+			let peaks_before = mmr::utils::NodesUtils::new(leaves_before).number_of_peaks();
 			let data = T::LeafData::leaf_data();
 			// append new leaf to MMR
-			let mut mmr: ModuleMmr<mmr::storage::RuntimeStorage, T, I> = mmr::Mmr::new(leaves);
+			let mut mmr: ModuleMmr<mmr::storage::RuntimeStorage, T, I> =
+				mmr::Mmr::new(leaves_before);
 			mmr.push(data).expect("MMR push never fails.");
 
 			// update the size
@@ -188,8 +227,38 @@ pub mod pallet {
 			<NumberOfLeaves<T, I>>::put(leaves);
 			<RootHash<T, I>>::put(root);
 
+			let depth = T::PruningDepth::get();
+			let pruned = Self::prune_nodes(
+				leaves_before.saturating_sub(depth)..leaves.saturating_sub(depth),
+			);
+
 			let peaks_after = mmr::utils::NodesUtils::new(leaves).number_of_peaks();
-			T::WeightInfo::on_initialize(peaks_before.max(peaks_after))
+			T::WeightInfo::on_initialize(peaks_before.max(peaks_after) as u32)
+				.saturating_add(T::DbWeight::get().writes(pruned))
+		}
+
+		/// Prune the nodes left over by storage version 0 within `remaining_weight`.
+		fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			frame_support::migrations::LazyMigrationExecutor::<
+				migrations::v1::PruneNodes<T, I>,
+				T::DbWeight,
+			>::execute(remaining_weight)
+		}
+
+		fn offchain_worker(_n: T::BlockNumber) {
+			if let Err(e) = Self::repair_offchain(MAX_REPAIRED_LEAVES_PER_BLOCK) {
+				log::warn!(target: "runtime::mmr", "Failed to repair the Off-chain DB: {:?}", e);
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
 		}
 	}
 }
@@ -226,11 +295,289 @@ where
 	}
 }
 
+/// Stateless MMR proof verification for a batch of leaves.
+///
+/// This function can be used to verify received MMR proof (`proof`)
+/// for given leaves data (`leaves`) against a known MMR root hash (`root`).
+/// The leaves are expected in the order of `proof.leaf_indices`.
+///
+/// The verification does not require any storage access.
+pub fn verify_leaves_proof<H, L>(
+	root: H::Output,
+	leaves: sp_std::prelude::Vec<mmr::Node<H, L>>,
+	proof: primitives::BatchProof<H::Output>,
+) -> Result<(), primitives::Error>
+where
+	H: traits::Hash,
+	L: primitives::FullLeaf,
+{
+	let is_valid = mmr::verify_leaves_proof::<H, L>(root, leaves, proof)?;
+	if is_valid {
+		Ok(())
+	} else {
+		Err(primitives::Error::Verify.log_debug(("The proof is incorrect.", root)))
+	}
+}
+
+/// Stateless MMR ancestry proof verification.
+///
+/// This function can be used to verify that the MMR root `prev_root` is an ancestor of a known
+/// MMR root hash (`root`), i.e. that every leaf proven against `prev_root` is also in the MMR of
+/// `root`.
+///
+/// The verification does not require any storage access.
+pub fn verify_ancestry_proof<H>(
+	root: H::Output,
+	prev_root: H::Output,
+	proof: primitives::AncestryProof<H::Output>,
+) -> Result<(), primitives::Error>
+where
+	H: traits::Hash,
+{
+	let is_valid = mmr::verify_ancestry_proof::<H>(root, prev_root, proof)?;
+	if is_valid {
+		Ok(())
+	} else {
+		Err(primitives::Error::Verify.log_debug(("The proof is incorrect.", root, prev_root)))
+	}
+}
+
+impl<T: Config<I>, I: 'static> frame_support::traits::CheckInvariants for Pallet<T, I> {
+	/// Check that the number of nodes stored on-chain matches the size of an MMR with
+	/// [`NumberOfLeaves`] leaves, once the nodes older than [`Config::PruningDepth`] are pruned.
+	///
+	/// The check is skipped while [`migrations::v1::PruneNodes`] is running.
+	fn check_invariants() -> sp_std::prelude::Vec<&'static str> {
+		if frame_support::migrations::LazyMigrationExecutor::<
+			migrations::v1::PruneNodes<T, I>,
+			T::DbWeight,
+		>::is_running()
+		{
+			return sp_std::prelude::Vec::new()
+		}
+
+		let leaves = Self::mmr_leaves();
+		let pruned_leaves = leaves.saturating_sub(T::PruningDepth::get());
+		// Every inner node of the MMR before the window has both of its children pruned.
+		let pruned_size = mmr::utils::NodesUtils::new(pruned_leaves).size();
+		let size = mmr::utils::NodesUtils::new(leaves).size() - 2 * (pruned_size - pruned_leaves);
+		if Nodes::<T, I>::iter().count() as u64 != size {
+			return sp_std::vec!["Mmr: number of nodes does not match the number of leaves."]
+		}
+		sp_std::prelude::Vec::new()
+	}
+}
+
 impl<T: Config<I>, I: 'static> Pallet<T, I> {
-	fn offchain_key(pos: u64) -> sp_std::prelude::Vec<u8> {
+	/// The Off-chain DB key of the node at `pos`, once it is canonicalized.
+	fn node_canon_offchain_key(pos: u64) -> sp_std::prelude::Vec<u8> {
 		(T::INDEXING_PREFIX, pos).encode()
 	}
 
+	/// The Off-chain DB key of the node at `pos`, added by the child of the block `parent_hash`.
+	///
+	/// Nodes are indexed under this key first, so that the nodes at the same position on
+	/// different forks do not overwrite each other.
+	fn node_temp_offchain_key(
+		pos: u64,
+		parent_hash: <T as frame_system::Config>::Hash,
+	) -> sp_std::prelude::Vec<u8> {
+		(T::INDEXING_PREFIX, parent_hash, pos).encode()
+	}
+
+	/// The number of the block which added the leaf at `leaf_index`, in the current chain.
+	fn leaf_block_number(leaf_index: u64) -> T::BlockNumber {
+		// A leaf is added by every block, the last one by the current block.
+		let age = Self::mmr_leaves().saturating_sub(1).saturating_sub(leaf_index);
+		<frame_system::Pallet<T>>::block_number().saturating_sub(age.unique_saturated_into())
+	}
+
+	/// The parent hash of the block which added the leaf at `leaf_index`, if it is still known.
+	fn leaf_parent_hash(leaf_index: u64) -> Option<<T as frame_system::Config>::Hash> {
+		let block_number = Self::leaf_block_number(leaf_index);
+		if block_number.is_zero() {
+			return None
+		}
+		let parent_hash = <frame_system::Pallet<T>>::block_hash(block_number - One::one());
+		if parent_hash == Default::default() {
+			return None
+		}
+		Some(parent_hash)
+	}
+
+	/// The Off-chain DB key of the range of leaves which remains to be repaired.
+	///
+	/// The range is stored as a SCALE-encoded `(u64, u64)` of the first leaf to repair and the
+	/// leaf after the last one. Setting it, for instance with the `offchain_localStorageSet` RPC
+	/// on the `PERSISTENT` storage, lets the offchain worker repair the range in the background.
+	pub fn repair_offchain_key() -> sp_std::prelude::Vec<u8> {
+		(T::INDEXING_PREFIX, b"repair").encode()
+	}
+
+	/// Rebuild the nodes of the leaves from `from` to `to` (excluded) which are missing from the
+	/// Off-chain DB, for instance because offchain indexing was disabled when they were added.
+	///
+	/// The leaf data is derived from [`Config::LeafSource`], and the rebuilt nodes are checked
+	/// against the ones still stored on-chain. The blocks which added the leaves must be final,
+	/// since the nodes are stored under their canonical key. The range which remains to be
+	/// repaired, replacing any pending one, is stored under [`Self::repair_offchain_key`] as the
+	/// repair progresses, so that the offchain worker resumes it if it fails.
+	///
+	/// Note this method can only be used from an off-chain context.
+	pub fn rebuild_offchain(from: u64, to: u64) -> Result<(), primitives::Error> {
+		use sp_core::offchain::StorageKind;
+
+		let key = Self::repair_offchain_key();
+		sp_io::offchain::local_storage_set(StorageKind::PERSISTENT, &key, &(from, to).encode());
+		Self::repair_offchain(u64::MAX)
+	}
+
+	/// Repair at most `limit` leaves of the range stored under [`Self::repair_offchain_key`].
+	fn repair_offchain(limit: u64) -> Result<(), primitives::Error> {
+		use sp_core::offchain::StorageKind;
+		use sp_io::offchain::{local_storage_clear, local_storage_get, local_storage_set};
+
+		let key = Self::repair_offchain_key();
+		let (from, to) = match local_storage_get(StorageKind::PERSISTENT, &key)
+			.and_then(|v| <(u64, u64)>::decode(&mut &*v).ok())
+		{
+			Some(range) => range,
+			None => return Ok(()),
+		};
+
+		let to = to.min(Self::mmr_leaves());
+		let store =
+			mmr::storage::Storage::<mmr::storage::OffchainStorage, T, I, LeafOf<T, I>>::default();
+		let mut next = from;
+		while next < to && next - from < limit {
+			Self::rebuild_leaf_offchain(&store, next)?;
+			next += 1;
+			local_storage_set(StorageKind::PERSISTENT, &key, &(next, to).encode());
+		}
+
+		if next >= to {
+			log::info!(target: "runtime::mmr", "Repaired the MMR leaves {} to {}.", from, to);
+			local_storage_clear(StorageKind::PERSISTENT, &key);
+		}
+		Ok(())
+	}
+
+	/// Rebuild the nodes appended with the leaf at `leaf_index` which are missing from the
+	/// Off-chain DB.
+	fn rebuild_leaf_offchain(
+		store: &mmr::storage::Storage<mmr::storage::OffchainStorage, T, I, LeafOf<T, I>>,
+		leaf_index: u64,
+	) -> Result<(), primitives::Error> {
+		use mmr_lib::{MMRStore, Merge};
+		use primitives::LeafDataSource;
+
+		let get = |pos| match store.get_elem(pos) {
+			Ok(node) => node,
+			Err(e) => {
+				primitives::Error::LeafNotFound.log_debug(e);
+				None
+			},
+		};
+
+		let first = mmr::utils::NodesUtils::new(leaf_index).size();
+		let last = mmr::utils::NodesUtils::new(leaf_index + 1).size();
+		// The inner nodes appended with a leaf follow it, with increasing heights.
+		for (height, pos) in (first..last).enumerate() {
+			if get(pos).is_some() {
+				continue
+			}
+
+			let node = if height == 0 {
+				let block_number = Self::leaf_block_number(leaf_index);
+				let leaf = T::LeafSource::leaf_data_at(block_number).ok_or_else(|| {
+					primitives::Error::LeafNotFound
+						.log_debug(("The leaf data cannot be derived.", leaf_index))
+				})?;
+				mmr::Node::Data(leaf)
+			} else {
+				let left = get(pos - (1 << height));
+				let right = get(pos - 1);
+				match (left, right) {
+					(Some(left), Some(right)) =>
+						mmr::Hasher::<HashingOf<T, I>, LeafOf<T, I>>::merge(&left, &right),
+					_ =>
+						return Err(primitives::Error::LeafNotFound.log_debug((
+							"The children of the node are missing, repair the earlier leaves first.",
+							pos,
+						))),
+				}
+			};
+
+			if let Some(hash) = Nodes::<T, I>::get(pos) {
+				if hash != node.hash() {
+					return Err(primitives::Error::Verify
+						.log_error(("The rebuilt node does not match the on-chain one.", pos)))
+				}
+			}
+			node.using_encoded(|node| {
+				sp_io::offchain::local_storage_set(
+					sp_core::offchain::StorageKind::PERSISTENT,
+					&Self::node_canon_offchain_key(pos),
+					node,
+				)
+			});
+		}
+		Ok(())
+	}
+
+	/// Prune from on-chain storage the children of the inner nodes appended with the leaves in
+	/// `leaves`, which have just become older than [`Config::PruningDepth`].
+	///
+	/// Returns the number of removed nodes.
+	fn prune_nodes(leaves: sp_std::ops::Range<u64>) -> u64 {
+		let mut pruned = 0;
+		for leaf_index in leaves {
+			let leaf_position = mmr_lib::leaf_index_to_pos(leaf_index);
+			// The inner nodes appended with a leaf follow it, with increasing heights.
+			for height in 1..=(leaf_index + 1).trailing_zeros() {
+				let position = leaf_position + height as u64;
+				<Nodes<T, I>>::remove(position - 1);
+				<Nodes<T, I>>::remove(position - (1 << height));
+				pruned += 2;
+			}
+		}
+		pruned
+	}
+
+	/// Check that a proof for an MMR of `leaf_count` leaves can be verified against the
+	/// on-chain peaks.
+	fn ensure_within_pruning_window(leaf_count: u64) -> Result<(), primitives::Error> {
+		let leaves = Self::mmr_leaves();
+		if leaf_count < leaves.saturating_sub(T::PruningDepth::get()) {
+			return Err(primitives::Error::Verify.log_debug((
+				"The proof is older than the pruning window.",
+				leaf_count,
+				leaves,
+			)))
+		}
+		Ok(())
+	}
+
+	/// Ensure the correctness of the state of this pallet.
+	///
+	/// Re-computes the MMR root out of the on-chain peaks and checks that it matches the stored
+	/// [`RootHash`]. This is run by the `try-runtime` hooks both before and after a runtime upgrade.
+	#[cfg(feature = "try-runtime")]
+	pub fn do_try_state() -> Result<(), &'static str> {
+		let leaves = Self::mmr_leaves();
+		if leaves == 0 {
+			return Ok(())
+		}
+
+		let mmr: ModuleMmr<mmr::storage::RuntimeStorage, T, I> = mmr::Mmr::new(leaves);
+		let root = mmr.root().map_err(|_| "MMR peaks are missing from the on-chain storage.")?;
+		frame_support::ensure!(
+			root == Self::mmr_root_hash(),
+			"MMR root does not match the one computed from on-chain peaks.",
+		);
+		Ok(())
+	}
+
 	/// Generate a MMR proof for the given `leaf_index`.
 	///
 	/// Note this method can only be used from an off-chain context
@@ -244,11 +591,118 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		mmr.generate_proof(leaf_index)
 	}
 
+	/// Convert a `block_number` into the index of the leaf added by that block.
+	///
+	/// A leaf is added by every block since the pallet was activated, so this fails with
+	/// [`primitives::Error::LeafNotFound`] for the blocks before the activation and the blocks
+	/// which are not imported yet.
+	pub fn block_num_to_leaf_index(block_number: T::BlockNumber) -> Result<u64, primitives::Error> {
+		let leaves = Self::mmr_leaves();
+		let current = <frame_system::Pallet<T>>::block_number();
+		if leaves == 0 || block_number > current {
+			return Err(primitives::Error::LeafNotFound
+				.log_debug(("The block did not add a leaf yet.", block_number)))
+		}
+
+		let age: u64 = (current - block_number).unique_saturated_into();
+		if age >= leaves {
+			return Err(primitives::Error::LeafNotFound
+				.log_debug(("The block precedes the activation of the MMR.", block_number)))
+		}
+		Ok(leaves - 1 - age)
+	}
+
+	/// Generate a MMR proof for the leaf added by the block `block_number`.
+	///
+	/// Returns the index of that leaf along with the leaf and the proof, see
+	/// [`Self::block_num_to_leaf_index`] and [`Self::generate_proof`].
+	pub fn generate_proof_for_block(
+		block_number: T::BlockNumber,
+	) -> Result<(u64, LeafOf<T, I>, primitives::Proof<<T as Config<I>>::Hash>), primitives::Error>
+	{
+		let leaf_index = Self::block_num_to_leaf_index(block_number)?;
+		Self::generate_proof(leaf_index).map(|(leaf, proof)| (leaf_index, leaf, proof))
+	}
+
+	/// Generate a MMR proof for the given `leaf_index`, against the root of the MMR when it had
+	/// `leaves_count` leaves.
+	///
+	/// Like [`Self::generate_proof`], this can only be used from an off-chain context. The proof
+	/// can be verified against that earlier root with [`verify_leaf_proof`], or on-chain with
+	/// [`Self::verify_leaf`] as long as the MMR of that size is within [`Config::PruningDepth`].
+	pub fn generate_historical_proof(
+		leaf_index: u64,
+		leaves_count: u64,
+	) -> Result<(LeafOf<T, I>, primitives::Proof<<T as Config<I>>::Hash>), primitives::Error> {
+		if leaves_count > Self::mmr_leaves() {
+			return Err(primitives::Error::GenerateProof
+				.log_debug(("The MMR never had that many leaves.", leaves_count)))
+		}
+		if leaf_index >= leaves_count {
+			return Err(primitives::Error::LeafNotFound
+				.log_debug(("The leaf was not in the MMR yet.", leaf_index, leaves_count)))
+		}
+
+		let mmr: ModuleMmr<mmr::storage::OffchainStorage, T, I> = mmr::Mmr::new(leaves_count);
+		mmr.generate_proof(leaf_index)
+	}
+
+	/// Generate a single MMR proof for the given `leaf_indices`.
+	///
+	/// The indices are sorted and de-duplicated first, and the leaves are returned in that order.
+	/// Like [`Self::generate_proof`], this can only be used from an off-chain context.
+	pub fn generate_batch_proof(
+		mut leaf_indices: sp_std::prelude::Vec<u64>,
+	) -> Result<
+		(sp_std::prelude::Vec<LeafOf<T, I>>, primitives::BatchProof<<T as Config<I>>::Hash>),
+		primitives::Error,
+	> {
+		leaf_indices.sort_unstable();
+		leaf_indices.dedup();
+		let mmr: ModuleMmr<mmr::storage::OffchainStorage, T, I> = mmr::Mmr::new(Self::mmr_leaves());
+		mmr.generate_batch_proof(leaf_indices)
+	}
+
+	/// Generate a single MMR proof for the contiguous range of leaves from `first_leaf` to
+	/// `last_leaf`, both included.
+	///
+	/// The proof shares the inner nodes of the range, so it is much smaller than a proof for each
+	/// leaf, and it can be verified like any batch proof with [`Self::verify_leaves`] or
+	/// [`verify_leaves_proof`]. Like [`Self::generate_proof`], this can only be used from an
+	/// off-chain context.
+	pub fn generate_proof_range(
+		first_leaf: u64,
+		last_leaf: u64,
+	) -> Result<
+		(sp_std::prelude::Vec<LeafOf<T, I>>, primitives::BatchProof<<T as Config<I>>::Hash>),
+		primitives::Error,
+	> {
+		if first_leaf > last_leaf {
+			return Err(primitives::Error::GenerateProof
+				.log_debug(("The range of leaves is empty.", first_leaf, last_leaf)))
+		}
+
+		let mmr: ModuleMmr<mmr::storage::OffchainStorage, T, I> = mmr::Mmr::new(Self::mmr_leaves());
+		mmr.generate_batch_proof((first_leaf..=last_leaf).collect())
+	}
+
+	/// Generate a proof that the MMR of `prev_leaf_count` leaves is a prefix of the current MMR.
+	///
+	/// Like [`Self::generate_proof`], this can only be used from an off-chain context. The proof
+	/// can be verified with [`verify_ancestry_proof`], given the roots of both MMRs.
+	pub fn generate_ancestry_proof(
+		prev_leaf_count: u64,
+	) -> Result<primitives::AncestryProof<<T as Config<I>>::Hash>, primitives::Error> {
+		let mmr: ModuleMmr<mmr::storage::OffchainStorage, T, I> = mmr::Mmr::new(Self::mmr_leaves());
+		mmr.generate_ancestry_proof(prev_leaf_count)
+	}
+
 	/// Verify MMR proof for given `leaf`.
 	///
 	/// This method is safe to use within the runtime code.
 	/// It will return `Ok(())` if the proof is valid
-	/// and an `Err(..)` if MMR is inconsistent (some leaves are missing)
+	/// and an `Err(..)` if MMR is inconsistent (some leaves are missing),
+	/// the proof is for an MMR older than [`Config::PruningDepth`] leaves
 	/// or the proof is invalid.
 	pub fn verify_leaf(
 		leaf: LeafOf<T, I>,
@@ -262,6 +716,8 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				.log_debug("The proof has incorrect number of leaves or proof items."))
 		}
 
+		Self::ensure_within_pruning_window(proof.leaf_count)?;
+
 		let mmr: ModuleMmr<mmr::storage::RuntimeStorage, T, I> = mmr::Mmr::new(proof.leaf_count);
 		let is_valid = mmr.verify_leaf_proof(leaf, proof)?;
 		if is_valid {
@@ -270,4 +726,33 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 			Err(primitives::Error::Verify.log_debug("The proof is incorrect."))
 		}
 	}
+
+	/// Verify MMR proof for given `leaves`, in the order of `proof.leaf_indices`.
+	///
+	/// This method is safe to use within the runtime code, see [`Self::verify_leaf`].
+	pub fn verify_leaves(
+		leaves: sp_std::prelude::Vec<LeafOf<T, I>>,
+		proof: primitives::BatchProof<<T as Config<I>>::Hash>,
+	) -> Result<(), primitives::Error> {
+		let depth = mmr::utils::NodesUtils::new(proof.leaf_count).depth() as usize;
+		let sorted = proof.leaf_indices.windows(2).all(|w| w[0] < w[1]);
+		if proof.leaf_count > Self::mmr_leaves() ||
+			proof.leaf_indices.last().map_or(true, |last| *last >= proof.leaf_count) ||
+			!sorted ||
+			proof.items.len() > depth.saturating_mul(proof.leaf_indices.len())
+		{
+			return Err(primitives::Error::Verify
+				.log_debug("The proof has incorrect leaf indices or number of proof items."))
+		}
+
+		Self::ensure_within_pruning_window(proof.leaf_count)?;
+
+		let mmr: ModuleMmr<mmr::storage::RuntimeStorage, T, I> = mmr::Mmr::new(proof.leaf_count);
+		let is_valid = mmr.verify_leaves_proof(leaves, proof)?;
+		if is_valid {
+			Ok(())
+		} else {
+			Err(primitives::Error::Verify.log_debug("The proof is incorrect."))
+		}
+	}
 }
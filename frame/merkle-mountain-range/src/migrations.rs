@@ -0,0 +1,128 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the MMR pallet.
+
+use super::*;
+
+/// Migration to version 1, pruning the nodes older than [`Config::PruningDepth`].
+pub mod v1 {
+	use super::*;
+	use frame_support::{
+		migrations::{LazyMigration, StartLazyMigration, VersionedMigration},
+		traits::PalletInfoAccess,
+	};
+
+	/// Prune from on-chain storage the nodes which are not kept within the pruning window, as
+	/// many leaves per step as the weight limit allows.
+	///
+	/// Up to version 0 every node was kept on-chain, so on long running chains this is a
+	/// [`LazyMigration`] driven by the `on_idle` hook of the pallet. The leaves which leave the
+	/// window in the meantime are pruned by `on_initialize` as usual.
+	pub struct PruneNodes<T, I = ()>(sp_std::marker::PhantomData<(T, I)>);
+
+	impl<T: Config<I>, I: 'static> LazyMigration for PruneNodes<T, I> {
+		const ID: &'static [u8] = b"pallet-mmr::v1::PruneNodes";
+		/// The index of the next leaf to prune the nodes of.
+		type Cursor = u64;
+
+		fn id() -> Vec<u8> {
+			[Self::ID, b":", Pallet::<T, I>::name().as_bytes()].concat()
+		}
+
+		fn step(cursor: Option<u64>, limit: Weight) -> (Weight, Option<u64>) {
+			let db_weight = T::DbWeight::get();
+			let mut leaf_index = cursor.unwrap_or(0);
+			// Reading the number of leaves.
+			let mut consumed = db_weight.reads(1);
+			if consumed > limit {
+				return (0, Some(leaf_index))
+			}
+
+			let end = Pallet::<T, I>::mmr_leaves().saturating_sub(T::PruningDepth::get());
+			while leaf_index < end {
+				// The children of each inner node appended with the leaf are removed.
+				let weight = db_weight.writes(2 * (leaf_index + 1).trailing_zeros() as Weight);
+				if consumed.saturating_add(weight) > limit {
+					return (consumed, Some(leaf_index))
+				}
+				Pallet::<T, I>::prune_nodes(leaf_index..leaf_index + 1);
+				consumed = consumed.saturating_add(weight);
+				leaf_index += 1;
+			}
+			log::info!(target: "runtime::mmr", "Pruned the MMR nodes of {} leaves for v1", end);
+			(consumed, None)
+		}
+	}
+
+	/// Migrate the storage version 0 to 1 by starting [`PruneNodes`].
+	pub type MigrateV0ToV1<T, I = ()> = VersionedMigration<
+		0,
+		1,
+		StartLazyMigration<PruneNodes<T, I>, <T as frame_system::Config>::DbWeight>,
+		Pallet<T, I>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::{mock::*, tests::new_test_ext};
+		use frame_support::{
+			instances::Instance1,
+			migrations::LazyMigrationExecutor,
+			traits::{CheckInvariants, OnIdle, OnRuntimeUpgrade, StorageVersion},
+			weights::constants::RocksDbWeight,
+		};
+
+		#[test]
+		fn migration_prunes_old_nodes() {
+			new_test_ext().execute_with(|| {
+				PruningDepth::set(u64::MAX);
+				for _ in 0..7 {
+					crate::tests::new_block();
+				}
+				assert_eq!(Nodes::<Test>::iter().count(), 11);
+
+				StorageVersion::new(0).put::<Pallet<Test>>();
+				PruningDepth::set(3);
+				MigrateV0ToV1::<Test>::on_runtime_upgrade();
+				assert_eq!(StorageVersion::get::<Pallet<Test>>(), 1);
+				assert!(LazyMigrationExecutor::<PruneNodes<Test>, ()>::is_running());
+				assert!(!LazyMigrationExecutor::<PruneNodes<Test, Instance1>, ()>::is_running());
+				assert!(MMR::check_invariants().is_empty());
+
+				// The limit allows for pruning the two children of the node appended with the
+				// second leaf, but not the four nodes below the ones appended with the fourth.
+				<MMR as OnIdle<u64>>::on_idle(7, RocksDbWeight::get().reads_writes(2, 4));
+				assert_eq!(Nodes::<Test>::iter().count(), 9);
+				assert!(LazyMigrationExecutor::<PruneNodes<Test>, ()>::is_running());
+
+				<MMR as OnIdle<u64>>::on_idle(7, Weight::max_value());
+				assert!(!LazyMigrationExecutor::<PruneNodes<Test>, ()>::is_running());
+
+				// The nodes of the last 3 leaves and the peak of the first 4 leaves are kept.
+				let mut positions = Nodes::<Test>::iter_keys().collect::<Vec<_>>();
+				positions.sort();
+				assert_eq!(positions, vec![6, 7, 8, 9, 10]);
+				let mmr: ModuleMmr<mmr::storage::RuntimeStorage, Test, ()> = mmr::Mmr::new(7);
+				assert_eq!(mmr.root(), Ok(MMR::mmr_root_hash()));
+				assert!(MMR::check_invariants().is_empty());
+			});
+		}
+	}
+}
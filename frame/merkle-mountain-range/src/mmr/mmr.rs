@@ -24,8 +24,9 @@ use crate::{
 	primitives::{self, Error},
 	Config, HashingOf,
 };
+use sp_std::collections::btree_map::BTreeMap;
 #[cfg(not(feature = "std"))]
-use sp_std::vec;
+use sp_std::{vec, vec::Vec};
 
 /// Stateless verification of the leaf proof.
 pub fn verify_leaf_proof<H, L>(
@@ -48,6 +49,149 @@ where
 		.map_err(|e| Error::Verify.log_debug(e))
 }
 
+/// Stateless verification of the proof for a batch of leaves.
+///
+/// The `leaves` are expected in the order of `proof.leaf_indices`.
+pub fn verify_leaves_proof<H, L>(
+	root: H::Output,
+	leaves: Vec<Node<H, L>>,
+	proof: primitives::BatchProof<H::Output>,
+) -> Result<bool, Error>
+where
+	H: sp_runtime::traits::Hash,
+	L: primitives::FullLeaf,
+{
+	if leaves.len() != proof.leaf_indices.len() {
+		return Err(Error::Verify.log_debug("The number of leaves does not match the proof."))
+	}
+
+	let size = NodesUtils::new(proof.leaf_count).size();
+	let leaves_and_positions = proof
+		.leaf_indices
+		.into_iter()
+		.map(mmr_lib::leaf_index_to_pos)
+		.zip(leaves.into_iter())
+		.collect();
+
+	let p = mmr_lib::MerkleProof::<Node<H, L>, Hasher<H, L>>::new(
+		size,
+		proof.items.into_iter().map(Node::Hash).collect(),
+	);
+	p.verify(Node::Hash(root), leaves_and_positions)
+		.map_err(|e| Error::Verify.log_debug(e))
+}
+
+/// Stateless verification of the proof that the MMR root `prev_root` is an ancestor of `root`.
+pub fn verify_ancestry_proof<H>(
+	root: H::Output,
+	prev_root: H::Output,
+	proof: primitives::AncestryProof<H::Output>,
+) -> Result<bool, Error>
+where
+	H: sp_runtime::traits::Hash,
+{
+	let prev_peaks_positions = NodesUtils::new(proof.prev_leaf_count).peaks();
+	if proof.prev_leaf_count == 0 ||
+		proof.prev_leaf_count > proof.leaf_count ||
+		prev_peaks_positions.len() != proof.prev_peaks.len()
+	{
+		return Err(Error::Verify.log_debug("The proof has incorrect number of leaves or peaks."))
+	}
+
+	let prev_peaks = prev_peaks_positions
+		.into_iter()
+		.map(|(pos, _)| pos)
+		.zip(proof.prev_peaks.iter().cloned())
+		.collect();
+	if root_of_peaks::<H>(proof.prev_leaf_count, prev_peaks)? != prev_root {
+		return Ok(false)
+	}
+
+	let mut prev_peaks = proof.prev_peaks.into_iter();
+	let mut items = proof.items.into_iter();
+	let peaks =
+		ancestry_peaks::<H>(proof.prev_leaf_count, proof.leaf_count, &mut |_, is_prev_peak| {
+			let hash = if is_prev_peak { prev_peaks.next() } else { items.next() };
+			hash.ok_or_else(|| Error::Verify.log_debug("The proof has too few items."))
+		})?;
+	if prev_peaks.next().is_some() || items.next().is_some() {
+		return Err(Error::Verify.log_debug("The proof has too many items."))
+	}
+
+	Ok(root_of_peaks::<H>(proof.leaf_count, peaks)? == root)
+}
+
+/// Compute the peaks of the MMR of `leaf_count` leaves, from the peaks of the MMR of
+/// `prev_leaf_count` leaves.
+///
+/// `resolve` is called with the position of each node whose hash is required, and whether it is
+/// a peak of the earlier MMR or the root of a subtree added since, in depth-first order from left
+/// to right. Returns the positions and hashes of the peaks.
+fn ancestry_peaks<H: sp_runtime::traits::Hash>(
+	prev_leaf_count: u64,
+	leaf_count: u64,
+	resolve: &mut dyn FnMut(u64, bool) -> Result<H::Output, Error>,
+) -> Result<Vec<(u64, H::Output)>, Error> {
+	let prev_size = NodesUtils::new(prev_leaf_count).size();
+	NodesUtils::new(leaf_count)
+		.peaks()
+		.into_iter()
+		.map(|(pos, height)| {
+			ancestry_node::<H>(pos, height, prev_size, &mut *resolve).map(|hash| (pos, hash))
+		})
+		.collect()
+}
+
+/// Compute the hash of the node at `pos` of given `height`, walking down to the peaks of the MMR
+/// of `prev_size` nodes. See [`ancestry_peaks`].
+fn ancestry_node<H: sp_runtime::traits::Hash>(
+	pos: u64,
+	height: u32,
+	prev_size: u64,
+	resolve: &mut dyn FnMut(u64, bool) -> Result<H::Output, Error>,
+) -> Result<H::Output, Error> {
+	// The nodes of the earlier MMR below a newer node are peaks of the earlier MMR.
+	if pos < prev_size {
+		return resolve(pos, true)
+	}
+	// The position of the first node of the subtree.
+	let first = pos + 2 - (2 << height);
+	if first >= prev_size {
+		return resolve(pos, false)
+	}
+
+	let left = ancestry_node::<H>(pos - (1 << height), height - 1, prev_size, &mut *resolve)?;
+	let right = ancestry_node::<H>(pos - 1, height - 1, prev_size, &mut *resolve)?;
+	let parent = <Hasher<H, ()> as mmr_lib::Merge>::merge(&Node::Hash(left), &Node::Hash(right));
+	Ok(parent.hash())
+}
+
+/// Compute the root of the MMR of `leaf_count` leaves given the positions and hashes of its peaks.
+fn root_of_peaks<H: sp_runtime::traits::Hash>(
+	leaf_count: u64,
+	peaks: Vec<(u64, H::Output)>,
+) -> Result<H::Output, Error> {
+	let store =
+		PeaksStore::<H>(peaks.into_iter().map(|(pos, hash)| (pos, Node::Hash(hash))).collect());
+	mmr_lib::MMR::<Node<H, ()>, Hasher<H, ()>, _>::new(NodesUtils::new(leaf_count).size(), store)
+		.get_root()
+		.map(|root| root.hash())
+		.map_err(|e| Error::Verify.log_debug(e))
+}
+
+/// An in-memory store of the peaks of a MMR, enough to compute its root.
+struct PeaksStore<H: sp_runtime::traits::Hash>(BTreeMap<u64, Node<H, ()>>);
+
+impl<H: sp_runtime::traits::Hash> mmr_lib::MMRStore<Node<H, ()>> for PeaksStore<H> {
+	fn get_elem(&self, pos: u64) -> mmr_lib::Result<Option<Node<H, ()>>> {
+		Ok(self.0.get(&pos).cloned())
+	}
+
+	fn append(&mut self, _: u64, _: Vec<Node<H, ()>>) -> mmr_lib::Result<()> {
+		Err(mmr_lib::Error::InconsistentStore)
+	}
+}
+
 /// A wrapper around a MMR library to expose limited functionality.
 ///
 /// Available functions depend on the storage kind ([Runtime](crate::mmr::storage::RuntimeStorage)
@@ -92,6 +236,38 @@ where
 			.map_err(|e| Error::Verify.log_debug(e))
 	}
 
+	/// Verify proof of a batch of leaves, given in the order of `proof.leaf_indices`.
+	pub fn verify_leaves_proof(
+		&self,
+		leaves: Vec<L>,
+		proof: primitives::BatchProof<<T as Config<I>>::Hash>,
+	) -> Result<bool, Error> {
+		if leaves.len() != proof.leaf_indices.len() {
+			return Err(Error::Verify.log_debug("The number of leaves does not match the proof."))
+		}
+
+		let p = mmr_lib::MerkleProof::<NodeOf<T, I, L>, Hasher<HashingOf<T, I>, L>>::new(
+			self.mmr.mmr_size(),
+			proof.items.into_iter().map(Node::Hash).collect(),
+		);
+		let leaves_and_positions = proof
+			.leaf_indices
+			.into_iter()
+			.map(mmr_lib::leaf_index_to_pos)
+			.zip(leaves.into_iter().map(Node::Data))
+			.collect();
+		let root = self.mmr.get_root().map_err(|e| Error::GetRoot.log_error(e))?;
+		p.verify(root, leaves_and_positions).map_err(|e| Error::Verify.log_debug(e))
+	}
+
+	/// Calculate the root hash of the MMR without altering the underlying storage.
+	pub fn root(&self) -> Result<<T as Config<I>>::Hash, Error> {
+		self.mmr
+			.get_root()
+			.map(|root| root.hash())
+			.map_err(|e| Error::GetRoot.log_error(e))
+	}
+
 	/// Return the internal size of the MMR (number of nodes).
 	#[cfg(test)]
 	pub fn size(&self) -> u64 {
@@ -159,4 +335,85 @@ where
 			})
 			.map(|p| (leaf, p))
 	}
+	/// Generate a proof for the given leaf indices.
+	///
+	/// The indices are expected to be sorted and without duplicates. Like
+	/// [`Self::generate_proof`], this requires all the nodes to be available in the storage.
+	pub fn generate_batch_proof(
+		&self,
+		leaf_indices: Vec<u64>,
+	) -> Result<(Vec<L>, primitives::BatchProof<<T as Config<I>>::Hash>), Error> {
+		let store = <Storage<OffchainStorage, T, I, L>>::default();
+		let positions = leaf_indices
+			.iter()
+			.map(|index| mmr_lib::leaf_index_to_pos(*index))
+			.collect::<Vec<_>>();
+		let leaves = positions
+			.iter()
+			.zip(leaf_indices.iter())
+			.map(|(position, index)| {
+				if *index >= self.leaves {
+					return Err(Error::LeafNotFound.log_debug(("Leaf index out of range.", index)))
+				}
+				match mmr_lib::MMRStore::get_elem(&store, *position) {
+					Ok(Some(Node::Data(leaf))) => Ok(leaf),
+					e => Err(Error::LeafNotFound.log_debug(e)),
+				}
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let leaf_count = self.leaves;
+		self.mmr
+			.gen_proof(positions)
+			.map_err(|e| Error::GenerateProof.log_error(e))
+			.map(|p| primitives::BatchProof {
+				leaf_indices,
+				leaf_count,
+				items: p.proof_items().iter().map(|x| x.hash()).collect(),
+			})
+			.map(|p| (leaves, p))
+	}
+
+	/// Generate a proof that the MMR of `prev_leaf_count` leaves is a prefix of this MMR.
+	///
+	/// This requires the peaks of the earlier MMR and the nodes added since to be available in
+	/// the storage.
+	pub fn generate_ancestry_proof(
+		&self,
+		prev_leaf_count: u64,
+	) -> Result<primitives::AncestryProof<<T as Config<I>>::Hash>, Error> {
+		if prev_leaf_count == 0 || prev_leaf_count > self.leaves {
+			return Err(Error::GenerateProof.log_debug((
+				"The earlier MMR must have between 1 and the current number of leaves.",
+				prev_leaf_count,
+			)))
+		}
+
+		let store = <Storage<OffchainStorage, T, I, L>>::default();
+		let mut prev_peaks = Vec::new();
+		let mut items = Vec::new();
+		ancestry_peaks::<HashingOf<T, I>>(
+			prev_leaf_count,
+			self.leaves,
+			&mut |pos, is_prev_peak| {
+				let hash = match mmr_lib::MMRStore::get_elem(&store, pos) {
+					Ok(Some(node)) => node.hash(),
+					e => return Err(Error::GenerateProof.log_debug(e)),
+				};
+				if is_prev_peak {
+					prev_peaks.push(hash);
+				} else {
+					items.push(hash);
+				}
+				Ok(hash)
+			},
+		)?;
+
+		Ok(primitives::AncestryProof {
+			prev_peaks,
+			prev_leaf_count,
+			leaf_count: self.leaves,
+			items,
+		})
+	}
 }
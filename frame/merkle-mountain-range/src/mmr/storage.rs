@@ -20,9 +20,10 @@
 use codec::Encode;
 #[cfg(not(feature = "std"))]
 use sp_std::prelude::Vec;
+use sp_std::{cell::RefCell, collections::btree_map::BTreeMap};
 
 use crate::{
-	mmr::{Node, NodeOf},
+	mmr::{utils::NodesUtils, Node, NodeOf},
 	primitives, Config, Nodes, NumberOfLeaves, Pallet,
 };
 
@@ -40,17 +41,65 @@ pub struct RuntimeStorage;
 /// Allows proof generation and verification, but does not support appending new items.
 /// MMR nodes are assumed to be stored in the Off-Chain DB. Note this storage type
 /// DOES NOT support adding new items to the MMR.
+///
+/// Nodes are looked up under their canonical key first, and otherwise under the key of the
+/// block which added them, if it is not finalized yet.
 pub struct OffchainStorage;
 
 /// A storage layer for MMR.
 ///
 /// There are two different implementations depending on the use case.
 /// See docs for [RuntimeStorage] and [OffchainStorage].
-pub struct Storage<StorageType, T, I, L>(sp_std::marker::PhantomData<(StorageType, T, I, L)>);
+pub struct Storage<StorageType, T: frame_system::Config, I, L> {
+	/// The parent hashes of the blocks which added the nodes looked up so far under their
+	/// temporary key, by the first position of their nodes, along with the position after their
+	/// last node. Only used by [OffchainStorage].
+	parent_hashes: RefCell<BTreeMap<u64, (u64, Option<T::Hash>)>>,
+	_phantom: sp_std::marker::PhantomData<(StorageType, I, L)>,
+}
 
-impl<StorageType, T, I, L> Default for Storage<StorageType, T, I, L> {
+impl<StorageType, T: frame_system::Config, I, L> Default for Storage<StorageType, T, I, L> {
 	fn default() -> Self {
-		Self(Default::default())
+		Self { parent_hashes: Default::default(), _phantom: Default::default() }
+	}
+}
+
+impl<T, I, L> Storage<OffchainStorage, T, I, L>
+where
+	T: Config<I>,
+	I: 'static,
+{
+	/// The parent hash of the block which added the node at `pos`, if it is still known.
+	///
+	/// All the nodes added by a block share its parent hash, so it is looked up once per block.
+	fn node_parent_hash(&self, pos: u64) -> Option<<T as frame_system::Config>::Hash> {
+		let cached = self.parent_hashes.borrow().range(..=pos).next_back().map(|(_, v)| *v);
+		if let Some((end, parent_hash)) = cached {
+			if pos < end {
+				return parent_hash
+			}
+		}
+
+		let leaves = NumberOfLeaves::<T, I>::get();
+		if pos >= NodesUtils::new(leaves).size() {
+			return None
+		}
+
+		// Search for the first leaf with which the MMR includes the node.
+		let (mut low, mut high) = (0, leaves - 1);
+		while low < high {
+			let mid = low + (high - low) / 2;
+			if NodesUtils::new(mid + 1).size() > pos {
+				high = mid;
+			} else {
+				low = mid + 1;
+			}
+		}
+
+		let parent_hash = Pallet::<T, I>::leaf_parent_hash(low);
+		let (first, end) = (NodesUtils::new(low).size(), NodesUtils::new(low + 1).size());
+		self.parent_hashes.borrow_mut().insert(first, (end, parent_hash));
+		parent_hash
 	}
 }
 
@@ -61,10 +110,16 @@ where
 	L: primitives::FullLeaf + codec::Decode,
 {
 	fn get_elem(&self, pos: u64) -> mmr_lib::Result<Option<NodeOf<T, I, L>>> {
-		let key = Pallet::<T, I>::offchain_key(pos);
-		// Retrieve the element from Off-chain DB.
-		Ok(sp_io::offchain::local_storage_get(sp_core::offchain::StorageKind::PERSISTENT, &key)
-			.and_then(|v| codec::Decode::decode(&mut &*v).ok()))
+		let get = |key: &[u8]| {
+			sp_io::offchain::local_storage_get(sp_core::offchain::StorageKind::PERSISTENT, key)
+		};
+		// Retrieve the element from Off-chain DB, where it is either canonical already or still
+		// stored under the parent hash of the block which added it.
+		let elem = get(&Pallet::<T, I>::node_canon_offchain_key(pos)).or_else(|| {
+			let parent_hash = self.node_parent_hash(pos)?;
+			get(&Pallet::<T, I>::node_temp_offchain_key(pos, parent_hash))
+		});
+		Ok(elem.and_then(|v| codec::Decode::decode(&mut &*v).ok()))
 	}
 
 	fn append(&mut self, _: u64, _: Vec<NodeOf<T, I, L>>) -> mmr_lib::Result<()> {
@@ -84,16 +139,20 @@ where
 
 	fn append(&mut self, pos: u64, elems: Vec<NodeOf<T, I, L>>) -> mmr_lib::Result<()> {
 		let mut leaves = crate::NumberOfLeaves::<T, I>::get();
-		let mut size = crate::mmr::utils::NodesUtils::new(leaves).size();
+		let mut size = NodesUtils::new(leaves).size();
 		if pos != size {
 			return Err(mmr_lib::Error::InconsistentStore)
 		}
 
+		// Nodes are indexed under the parent hash, so that forks do not overwrite each other's
+		// nodes before they are canonicalized.
+		let parent_hash = <frame_system::Pallet<T>>::parent_hash();
+
 		for elem in elems {
 			// on-chain we only store the hash (even if it's a leaf)
 			<Nodes<T, I>>::insert(size, elem.hash());
 			// Indexing API is used to store the full leaf content.
-			let key = Pallet::<T, I>::offchain_key(size);
+			let key = Pallet::<T, I>::node_temp_offchain_key(size, parent_hash);
 			elem.using_encoded(|elem| sp_io::offchain_index::set(&key, elem));
 			size += 1;
 
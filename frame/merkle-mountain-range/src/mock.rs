@@ -20,7 +20,7 @@ use crate::*;
 
 use codec::{Decode, Encode};
 use frame_support::parameter_types;
-use pallet_mmr_primitives::{Compact, LeafDataProvider};
+use pallet_mmr_primitives::{Compact, LeafDataProvider, LeafDataSource};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
@@ -39,11 +39,13 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		MMR: pallet_mmr::{Pallet, Storage},
+		MMR2: pallet_mmr::<Instance1>::{Pallet, Storage},
 	}
 );
 
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
+	pub static PruningDepth: u64 = u64::MAX;
 }
 impl frame_system::Config for Test {
 	type BaseCallFilter = frame_support::traits::AllowAll;
@@ -58,7 +60,7 @@ impl frame_system::Config for Test {
 	type Header = Header;
 	type Event = Event;
 	type BlockHashCount = BlockHashCount;
-	type DbWeight = ();
+	type DbWeight = frame_support::weights::constants::RocksDbWeight;
 	type BlockWeights = ();
 	type BlockLength = ();
 	type Version = ();
@@ -78,6 +80,20 @@ impl Config for Test {
 	type Hash = H256;
 	type LeafData = Compact<Keccak256, (frame_system::Pallet<Test>, LeafData)>;
 	type OnNewRoot = ();
+	type PruningDepth = PruningDepth;
+	type LeafSource = Compact<Keccak256, (frame_system::Pallet<Test>, LeafData)>;
+	type WeightInfo = ();
+}
+
+impl Config<Instance1> for Test {
+	const INDEXING_PREFIX: &'static [u8] = b"mmr2-";
+
+	type Hashing = BlakeTwo256;
+	type Hash = H256;
+	type LeafData = frame_system::Pallet<Test>;
+	type OnNewRoot = ();
+	type PruningDepth = PruningDepth;
+	type LeafSource = frame_system::Pallet<Test>;
 	type WeightInfo = ();
 }
 
@@ -104,3 +120,10 @@ impl LeafDataProvider for LeafData {
 		LEAF_DATA.with(|r| r.borrow().clone())
 	}
 }
+
+/// The leaf data of past blocks, as provided by [`LeafData`] to the blocks of `new_block`.
+impl LeafDataSource<u64, LeafData> for LeafData {
+	fn leaf_data_at(block_number: u64) -> Option<LeafData> {
+		Some(LeafData::new(block_number))
+	}
+}
@@ -18,9 +18,9 @@
 use crate::{mock::*, *};
 
 use frame_support::traits::OnInitialize;
-use pallet_mmr_primitives::{Compact, Proof};
+use pallet_mmr_primitives::{BatchProof, Compact, DataOrHash, Proof};
 use sp_core::{
-	offchain::{testing::TestOffchainExt, OffchainDbExt, OffchainWorkerExt},
+	offchain::{testing::TestOffchainExt, OffchainDbExt, OffchainWorkerExt, StorageKind},
 	H256,
 };
 
@@ -34,7 +34,7 @@ fn register_offchain_ext(ext: &mut sp_io::TestExternalities) {
 	ext.register_extension(OffchainWorkerExt::new(offchain));
 }
 
-fn new_block() -> u64 {
+pub(crate) fn new_block() -> u64 {
 	let number = frame_system::Pallet::<Test>::block_number() + 1;
 	let hash = H256::repeat_byte(number as u8);
 	LEAF_DATA.with(|r| r.borrow_mut().a = number);
@@ -70,6 +70,19 @@ fn decode_node(
 	}
 }
 
+fn new_fork_block(parent_hash: H256, fork: u8) -> u64 {
+	let number = frame_system::Pallet::<Test>::block_number() + 1;
+	LEAF_DATA.with(|r| *r.borrow_mut() = LeafData { a: number, b: vec![fork] });
+
+	frame_system::Pallet::<Test>::initialize(
+		&number,
+		&parent_hash,
+		&Default::default(),
+		frame_system::InitKind::Full,
+	);
+	MMR::on_initialize(number)
+}
+
 fn init_chain(blocks: usize) {
 	// given
 	for _ in 0..blocks {
@@ -77,6 +90,28 @@ fn init_chain(blocks: usize) {
 	}
 }
 
+/// Move the nodes of the leaves in `leaves` to their canonical key in the Off-chain DB, as the
+/// `pallet-mmr-gadget` does once the blocks which added them are finalized.
+fn canonicalize(ext: &mut sp_io::TestExternalities, leaves: std::ops::Range<u64>) {
+	use sp_io::offchain::{local_storage_clear, local_storage_get, local_storage_set};
+
+	ext.execute_with(|| {
+		for leaf_index in leaves {
+			let parent_hash = MMR::leaf_parent_hash(leaf_index).unwrap();
+			let first = mmr::utils::NodesUtils::new(leaf_index).size();
+			let last = mmr::utils::NodesUtils::new(leaf_index + 1).size();
+			for pos in first..last {
+				let temp_key = MMR::node_temp_offchain_key(pos, parent_hash);
+				if let Some(node) = local_storage_get(StorageKind::PERSISTENT, &temp_key) {
+					let canon_key = MMR::node_canon_offchain_key(pos);
+					local_storage_set(StorageKind::PERSISTENT, &canon_key, &node);
+					local_storage_clear(StorageKind::PERSISTENT, &temp_key);
+				}
+			}
+		}
+	});
+}
+
 #[test]
 fn should_start_empty() {
 	let _ = env_logger::try_init();
@@ -137,24 +172,31 @@ fn should_append_to_mmr_when_on_initialize_is_called() {
 		);
 	});
 
-	// make sure the leaves end up in the offchain DB
+	// make sure the leaves end up in the offchain DB, under the parent hash of their block
 	ext.persist_offchain_overlay();
 	let offchain_db = ext.offchain_db();
 	assert_eq!(
-		offchain_db.get(&MMR::offchain_key(0)).map(decode_node),
+		offchain_db
+			.get(&MMR::node_temp_offchain_key(0, H256::repeat_byte(1)))
+			.map(decode_node),
 		Some(mmr::Node::Data(((0, H256::repeat_byte(1)), LeafData::new(1),)))
 	);
 	assert_eq!(
-		offchain_db.get(&MMR::offchain_key(1)).map(decode_node),
+		offchain_db
+			.get(&MMR::node_temp_offchain_key(1, H256::repeat_byte(2)))
+			.map(decode_node),
 		Some(mmr::Node::Data(((1, H256::repeat_byte(2)), LeafData::new(2),)))
 	);
 	assert_eq!(
-		offchain_db.get(&MMR::offchain_key(2)).map(decode_node),
+		offchain_db
+			.get(&MMR::node_temp_offchain_key(2, H256::repeat_byte(2)))
+			.map(decode_node),
 		Some(mmr::Node::Hash(hex(
 			"672c04a9cd05a644789d769daa552d35d8de7c33129f8a7cbf49e595234c4854"
 		)))
 	);
-	assert_eq!(offchain_db.get(&MMR::offchain_key(3)), None);
+	assert_eq!(offchain_db.get(&MMR::node_temp_offchain_key(3, H256::repeat_byte(2))), None);
+	assert_eq!(offchain_db.get(&MMR::node_canon_offchain_key(0)), None);
 }
 
 #[test]
@@ -181,6 +223,21 @@ fn should_construct_larger_mmr_correctly() {
 	});
 }
 
+#[test]
+fn should_compute_root_from_on_chain_peaks() {
+	let _ = env_logger::try_init();
+	new_test_ext().execute_with(|| {
+		// given
+		init_chain(7);
+
+		// when
+		let mmr: ModuleMmr<mmr::storage::RuntimeStorage, Test, ()> = mmr::Mmr::new(7);
+
+		// then
+		assert_eq!(mmr.root(), Ok(crate::RootHash::<Test>::get()));
+	});
+}
+
 #[test]
 fn should_generate_proofs_correctly() {
 	let _ = env_logger::try_init();
@@ -247,6 +304,129 @@ fn should_generate_proofs_correctly() {
 	});
 }
 
+#[test]
+fn should_generate_batch_proofs_correctly() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given
+	ext.execute_with(|| init_chain(7));
+	ext.persist_offchain_overlay();
+
+	// Try to generate proofs now. This requires the offchain extensions to be present
+	// to retrieve full leaf data.
+	register_offchain_ext(&mut ext);
+	ext.execute_with(|| {
+		// when generate proofs for a few sets of leaves
+		let leaf_sets: Vec<Vec<u64>> =
+			vec![vec![0], vec![4], vec![0, 4], vec![1, 5, 6], (0..7).collect()];
+		for leaf_indices in leaf_sets {
+			let (leaves, proof) =
+				crate::Pallet::<Test>::generate_batch_proof(leaf_indices.clone()).unwrap();
+
+			// then
+			let single_leaves = leaf_indices
+				.iter()
+				.map(|index| crate::Pallet::<Test>::generate_proof(*index).unwrap().0)
+				.collect::<Vec<_>>();
+			assert_eq!(leaves, single_leaves);
+			assert_eq!(proof.leaf_indices, leaf_indices);
+			assert_eq!(proof.leaf_count, 7);
+			assert_eq!(crate::Pallet::<Test>::verify_leaves(leaves, proof), Ok(()));
+		}
+
+		// a proof of a single leaf is the same as the regular proof
+		let (_, proof) = crate::Pallet::<Test>::generate_batch_proof(vec![4]).unwrap();
+		let (_, single_proof) = crate::Pallet::<Test>::generate_proof(4).unwrap();
+		assert_eq!(proof.items, single_proof.items);
+
+		// the leaves of the whole MMR are proven by the peaks only
+		let (_, proof) = crate::Pallet::<Test>::generate_batch_proof((0..7).collect()).unwrap();
+		assert_eq!(proof.items, Vec::<H256>::new());
+	});
+}
+
+#[test]
+fn batch_proof_indices_are_sorted_and_deduplicated() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given
+	ext.execute_with(|| init_chain(7));
+	ext.persist_offchain_overlay();
+	register_offchain_ext(&mut ext);
+
+	ext.execute_with(|| {
+		// when
+		let unsorted = crate::Pallet::<Test>::generate_batch_proof(vec![5, 1, 5]).unwrap();
+		let sorted = crate::Pallet::<Test>::generate_batch_proof(vec![1, 5]).unwrap();
+
+		// then
+		assert_eq!(unsorted, sorted);
+		assert_eq!(sorted.1.leaf_indices, vec![1, 5]);
+		assert_eq!(
+			crate::Pallet::<Test>::generate_batch_proof(vec![2, 7]),
+			Err(primitives::Error::LeafNotFound),
+		);
+		assert_eq!(
+			crate::Pallet::<Test>::generate_batch_proof(vec![]),
+			Err(primitives::Error::GenerateProof),
+		);
+	});
+}
+
+#[test]
+fn should_verify_batch_proofs() {
+	let _ = env_logger::try_init();
+
+	// Start off with chain initialisation and storing indexing data off-chain
+	// (MMR Leafs)
+	let mut ext = new_test_ext();
+	ext.execute_with(|| init_chain(7));
+	ext.persist_offchain_overlay();
+
+	// Try to generate proof now. This requires the offchain extensions to be present
+	// to retrieve full leaf data.
+	register_offchain_ext(&mut ext);
+	let (leaves, proof) = ext.execute_with(|| {
+		// when
+		crate::Pallet::<Test>::generate_batch_proof(vec![1, 5]).unwrap()
+	});
+	let root = ext.execute_with(|| crate::Pallet::<Test>::mmr_root_hash());
+
+	// Verify the proof both against the on-chain MMR and without relying on any on-chain data.
+	let mut ext2 = new_test_ext();
+	ext2.execute_with(|| {
+		init_chain(7);
+		// then
+		assert_eq!(crate::Pallet::<Test>::verify_leaves(leaves.clone(), proof.clone()), Ok(()));
+
+		// leaves in the wrong order
+		let swapped = vec![leaves[1].clone(), leaves[0].clone()];
+		assert_eq!(
+			crate::Pallet::<Test>::verify_leaves(swapped, proof.clone()),
+			Err(primitives::Error::Verify),
+		);
+
+		// missing leaves
+		assert_eq!(
+			crate::Pallet::<Test>::verify_leaves(vec![leaves[0].clone()], proof.clone()),
+			Err(primitives::Error::Verify),
+		);
+
+		// unsorted indices
+		let unsorted = BatchProof { leaf_indices: vec![5, 1], ..proof.clone() };
+		assert_eq!(
+			crate::Pallet::<Test>::verify_leaves(leaves.clone(), unsorted),
+			Err(primitives::Error::Verify),
+		);
+	});
+
+	let leaves = leaves.into_iter().map(crate::primitives::DataOrHash::Data).collect();
+	assert_eq!(
+		crate::verify_leaves_proof::<<Test as Config>::Hashing, _>(root, leaves, proof),
+		Ok(())
+	);
+}
+
 #[test]
 fn should_verify() {
 	let _ = env_logger::try_init();
@@ -303,6 +483,58 @@ fn verification_should_be_stateless() {
 	);
 }
 
+#[test]
+fn should_generate_historical_proofs() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given the root of the MMR of 5 leaves
+	let historical_root = ext.execute_with(|| {
+		init_chain(5);
+		let root = crate::Pallet::<Test>::mmr_root_hash();
+		init_chain(2);
+		root
+	});
+	ext.persist_offchain_overlay();
+	register_offchain_ext(&mut ext);
+
+	ext.execute_with(|| {
+		// when
+		let (leaf, proof) = crate::Pallet::<Test>::generate_historical_proof(2, 5).unwrap();
+
+		// then
+		assert_eq!(proof.leaf_count, 5);
+		assert_eq!(leaf, crate::Pallet::<Test>::generate_proof(2).unwrap().0);
+		assert_eq!(crate::Pallet::<Test>::verify_leaf(leaf.clone(), proof.clone()), Ok(()));
+		let node = DataOrHash::Data(leaf);
+		assert_eq!(
+			crate::verify_leaf_proof::<<Test as Config>::Hashing, _>(
+				historical_root,
+				node.clone(),
+				proof.clone()
+			),
+			Ok(())
+		);
+		assert_eq!(
+			crate::verify_leaf_proof::<<Test as Config>::Hashing, _>(
+				crate::Pallet::<Test>::mmr_root_hash(),
+				node,
+				proof
+			),
+			Err(primitives::Error::Verify)
+		);
+
+		// the leaf must be in the MMR of that size, and the MMR must have had that size
+		assert_eq!(
+			crate::Pallet::<Test>::generate_historical_proof(5, 5),
+			Err(primitives::Error::LeafNotFound)
+		);
+		assert_eq!(
+			crate::Pallet::<Test>::generate_historical_proof(2, 8),
+			Err(primitives::Error::GenerateProof)
+		);
+	});
+}
+
 #[test]
 fn should_verify_on_the_next_block_since_there_is_no_pruning_yet() {
 	let _ = env_logger::try_init();
@@ -322,3 +554,439 @@ fn should_verify_on_the_next_block_since_there_is_no_pruning_yet() {
 		assert_eq!(crate::Pallet::<Test>::verify_leaf(leaf, proof5), Ok(()));
 	});
 }
+
+#[test]
+fn check_invariants_reports_missing_nodes() {
+	use frame_support::traits::CheckInvariants;
+
+	new_test_ext().execute_with(|| {
+		init_chain(7);
+		assert!(MMR::check_invariants().is_empty());
+
+		crate::Nodes::<Test>::remove(3);
+		assert_eq!(
+			MMR::check_invariants(),
+			vec!["Mmr: number of nodes does not match the number of leaves."]
+		);
+	});
+}
+
+#[test]
+fn should_prune_nodes_older_than_the_pruning_window() {
+	use frame_support::traits::CheckInvariants;
+
+	let _ = env_logger::try_init();
+	let unpruned_root = new_test_ext().execute_with(|| {
+		init_chain(8);
+		crate::RootHash::<Test>::get()
+	});
+
+	new_test_ext().execute_with(|| {
+		// given
+		PruningDepth::set(3);
+
+		// when
+		init_chain(7);
+
+		// then the nodes of the last 3 leaves and the peak of the first 4 leaves are kept.
+		let positions = || {
+			let mut positions = crate::Nodes::<Test>::iter_keys().collect::<Vec<_>>();
+			positions.sort();
+			positions
+		};
+		assert_eq!(positions(), vec![6, 7, 8, 9, 10]);
+		assert!(MMR::check_invariants().is_empty());
+
+		// the MMR is still extended from the on-chain peaks
+		new_block();
+		assert_eq!(positions(), vec![6, 7, 8, 9, 10, 11, 12, 13, 14]);
+		assert!(MMR::check_invariants().is_empty());
+		assert_eq!(crate::RootHash::<Test>::get(), unpruned_root);
+
+		// leaves are pruned once their parent is older than the window
+		new_block();
+		new_block();
+		assert_eq!(positions(), vec![6, 9, 10, 11, 12, 13, 14, 15, 16, 17]);
+		assert!(MMR::check_invariants().is_empty());
+	});
+}
+
+#[test]
+fn should_verify_proofs_within_the_pruning_window_only() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given
+	ext.execute_with(|| {
+		PruningDepth::set(3);
+		init_chain(7);
+	});
+	ext.persist_offchain_overlay();
+	register_offchain_ext(&mut ext);
+
+	ext.execute_with(|| {
+		// proofs are generated from the Off-chain DB, even for pruned leaves
+		let (leaf, proof) = crate::Pallet::<Test>::generate_proof(0).unwrap();
+
+		// when the MMR of 7 leaves is still within the window
+		new_block();
+		new_block();
+
+		// then
+		assert_eq!(crate::Pallet::<Test>::verify_leaf(leaf.clone(), proof.clone()), Ok(()));
+
+		// when it is not anymore
+		new_block();
+		new_block();
+
+		// then
+		assert_eq!(crate::Pallet::<Test>::verify_leaf(leaf, proof), Err(primitives::Error::Verify));
+	});
+}
+
+#[test]
+fn should_read_canonical_and_temporary_offchain_nodes() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given
+	ext.execute_with(|| init_chain(7));
+	ext.persist_offchain_overlay();
+	register_offchain_ext(&mut ext);
+
+	// when the first 5 blocks are finalized
+	canonicalize(&mut ext, 0..5);
+
+	// then the nodes of the first 5 leaves are moved to their canonical key
+	let offchain_db = ext.offchain_db();
+	for pos in 0..8 {
+		assert!(offchain_db.get(&MMR::node_canon_offchain_key(pos)).is_some());
+	}
+	assert_eq!(offchain_db.get(&MMR::node_temp_offchain_key(0, H256::repeat_byte(1))), None);
+	assert_eq!(offchain_db.get(&MMR::node_canon_offchain_key(8)), None);
+	assert!(offchain_db.get(&MMR::node_temp_offchain_key(8, H256::repeat_byte(6))).is_some());
+
+	// and proofs can be generated from canonical and non-canonical nodes alike
+	ext.execute_with(|| {
+		for leaf_index in vec![0, 4, 5, 6] {
+			let (leaf, proof) = crate::Pallet::<Test>::generate_proof(leaf_index).unwrap();
+			assert_eq!(crate::Pallet::<Test>::verify_leaf(leaf, proof), Ok(()));
+		}
+	});
+}
+
+#[test]
+fn should_keep_the_nodes_of_forks_apart() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given a chain of 3 blocks
+	ext.execute_with(|| init_chain(3));
+	ext.persist_offchain_overlay();
+
+	// and a fork which replaces the last 2 blocks
+	let mut fork = new_test_ext();
+	fork.execute_with(|| {
+		new_block();
+		new_fork_block(H256::repeat_byte(2), 0);
+		new_fork_block(H256::repeat_byte(0xf3), 1);
+	});
+	fork.persist_offchain_overlay();
+
+	// when the nodes of the fork end up in the same Off-chain DB
+	let fork_key = MMR::node_temp_offchain_key(3, H256::repeat_byte(0xf3));
+	let fork_leaf = fork.offchain_db().get(&fork_key).unwrap();
+	register_offchain_ext(&mut ext);
+	ext.execute_with(|| {
+		sp_io::offchain::local_storage_set(StorageKind::PERSISTENT, &fork_key, &fork_leaf)
+	});
+
+	// then they do not overwrite the nodes of the chain
+	let canonical_key = MMR::node_temp_offchain_key(3, H256::repeat_byte(3));
+	let canonical_leaf = ext.offchain_db().get(&canonical_key).unwrap();
+	assert_ne!(canonical_leaf, fork_leaf);
+
+	ext.execute_with(|| {
+		let (leaf, proof) = crate::Pallet::<Test>::generate_proof(2).unwrap();
+		assert_eq!(leaf.tuple.1, DataOrHash::Data(LeafData::new(3)));
+		assert_eq!(crate::Pallet::<Test>::verify_leaf(leaf, proof), Ok(()));
+	});
+
+	// once the chain is finalized, its nodes are canonicalized, and not the fork's
+	canonicalize(&mut ext, 0..3);
+
+	let offchain_db = ext.offchain_db();
+	assert_eq!(offchain_db.get(&MMR::node_canon_offchain_key(3)), Some(canonical_leaf));
+	assert_eq!(offchain_db.get(&canonical_key), None);
+	assert_eq!(offchain_db.get(&fork_key), Some(fork_leaf));
+}
+
+#[test]
+fn should_generate_and_verify_ancestry_proofs() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given the roots of the MMR after each block
+	let roots = ext.execute_with(|| {
+		(0..7)
+			.map(|_| {
+				new_block();
+				crate::Pallet::<Test>::mmr_root_hash()
+			})
+			.collect::<Vec<_>>()
+	});
+	ext.persist_offchain_overlay();
+	register_offchain_ext(&mut ext);
+
+	ext.execute_with(|| {
+		let root = roots[6];
+		for prev_leaf_count in 1..=7u64 {
+			// when
+			let proof = crate::Pallet::<Test>::generate_ancestry_proof(prev_leaf_count).unwrap();
+
+			// then
+			let prev_root = roots[prev_leaf_count as usize - 1];
+			assert_eq!(proof.leaf_count, 7);
+			assert_eq!(
+				crate::verify_ancestry_proof::<<Test as Config>::Hashing>(
+					root,
+					prev_root,
+					proof.clone()
+				),
+				Ok(())
+			);
+			// the proof does not hold for any other earlier root
+			let other_root = roots[prev_leaf_count as usize % 7];
+			assert_eq!(
+				crate::verify_ancestry_proof::<<Test as Config>::Hashing>(root, other_root, proof),
+				Err(primitives::Error::Verify)
+			);
+		}
+
+		// tampering with the subtrees added since invalidates the proof
+		let mut proof = crate::Pallet::<Test>::generate_ancestry_proof(2).unwrap();
+		assert_eq!(proof.items.len(), 3);
+		proof.items[0] = roots[0];
+		assert_eq!(
+			crate::verify_ancestry_proof::<<Test as Config>::Hashing>(
+				root,
+				roots[1],
+				proof.clone()
+			),
+			Err(primitives::Error::Verify)
+		);
+		proof.items.pop();
+		assert_eq!(
+			crate::verify_ancestry_proof::<<Test as Config>::Hashing>(root, roots[1], proof),
+			Err(primitives::Error::Verify)
+		);
+
+		// the earlier MMR must not be empty nor larger than the current one
+		assert_eq!(
+			crate::Pallet::<Test>::generate_ancestry_proof(0),
+			Err(primitives::Error::GenerateProof)
+		);
+		assert_eq!(
+			crate::Pallet::<Test>::generate_ancestry_proof(8),
+			Err(primitives::Error::GenerateProof)
+		);
+	});
+}
+
+#[test]
+fn should_keep_the_instances_apart() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given a block which only feeds the first instance
+	ext.execute_with(|| {
+		new_block();
+		// and two instances fed on every block since
+		for _ in 0..3 {
+			new_block();
+			MMR2::on_initialize(frame_system::Pallet::<Test>::block_number());
+		}
+
+		// then each instance has its own tree
+		assert_eq!(crate::NumberOfLeaves::<Test>::get(), 4);
+		assert_eq!(crate::NumberOfLeaves::<Test, Instance1>::get(), 3);
+		assert_eq!(crate::Nodes::<Test>::iter().count(), 7);
+		assert_eq!(crate::Nodes::<Test, Instance1>::iter().count(), 4);
+		assert_ne!(crate::RootHash::<Test>::get(), crate::RootHash::<Test, Instance1>::get());
+	});
+	ext.persist_offchain_overlay();
+	register_offchain_ext(&mut ext);
+
+	// and their nodes are stored under distinct keys off-chain
+	let offchain_db = ext.offchain_db();
+	let parent_hash = H256::repeat_byte(3);
+	assert_ne!(
+		MMR::node_temp_offchain_key(3, parent_hash),
+		MMR2::node_temp_offchain_key(1, parent_hash)
+	);
+	assert!(offchain_db.get(&MMR::node_temp_offchain_key(3, parent_hash)).is_some());
+	assert!(offchain_db.get(&MMR2::node_temp_offchain_key(1, parent_hash)).is_some());
+
+	ext.execute_with(|| {
+		let (leaf, proof) = MMR2::generate_proof(1).unwrap();
+		assert_eq!(leaf, (2, H256::repeat_byte(3)));
+		assert_eq!(MMR2::verify_leaf(leaf, proof), Ok(()));
+	});
+}
+
+#[test]
+fn should_generate_proofs_for_blocks() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given a MMR activated at block 5
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(4);
+		init_chain(3);
+	});
+	ext.persist_offchain_overlay();
+	register_offchain_ext(&mut ext);
+
+	ext.execute_with(|| {
+		// then
+		assert_eq!(crate::Pallet::<Test>::block_num_to_leaf_index(5), Ok(0));
+		assert_eq!(crate::Pallet::<Test>::block_num_to_leaf_index(7), Ok(2));
+		assert_eq!(
+			crate::Pallet::<Test>::block_num_to_leaf_index(4),
+			Err(primitives::Error::LeafNotFound)
+		);
+		assert_eq!(
+			crate::Pallet::<Test>::block_num_to_leaf_index(8),
+			Err(primitives::Error::LeafNotFound)
+		);
+
+		// when
+		let (leaf_index, leaf, proof) = crate::Pallet::<Test>::generate_proof_for_block(6).unwrap();
+
+		// then
+		assert_eq!(leaf_index, 1);
+		assert_eq!(
+			(leaf.clone(), proof.clone()),
+			crate::Pallet::<Test>::generate_proof(1).unwrap()
+		);
+		assert_eq!(crate::Pallet::<Test>::verify_leaf(leaf, proof), Ok(()));
+		assert_eq!(
+			crate::Pallet::<Test>::generate_proof_for_block(4),
+			Err(primitives::Error::LeafNotFound)
+		);
+	});
+}
+
+#[test]
+fn should_verify_keccak_mmr_proof_test_vector() {
+	use primitives::{Keccak256, OpaqueLeaf};
+
+	let _ = env_logger::try_init();
+	// given a MMR of 5 opaque leaves `[i; 32]`, hashed as `keccak256(leaf)`, its inner nodes as
+	// `keccak256(left ++ right)` and its peaks bagged from the right, as an Ethereum verifier would.
+	let root = hex("55da8f70fe2cb4ab6ee5586fc70ea6363dbb6d222e4f282a55dd078f44f4cd48");
+	let proof = Proof {
+		leaf_index: 2,
+		leaf_count: 5,
+		items: vec![
+			hex("4a7a4de37def8e10861261f58e1003e6086df449b615bb411c39669548e19dba"),
+			hex("2b639e0b5097f601aeeec60e2063037fbc5980a82967535d9fb8e08520e233e4"),
+			hex("b8bf1e33d35d228e8bf8fabdd5a4f9014038829dd90dbf59127ca095ad65c7cc"),
+		],
+	};
+	let leaf = |byte| DataOrHash::<Keccak256, _>::Data(OpaqueLeaf(vec![byte; 32]));
+
+	// then
+	assert_eq!(crate::verify_leaf_proof::<Keccak256, _>(root, leaf(2), proof.clone()), Ok(()));
+	assert_eq!(
+		crate::verify_leaf_proof::<Keccak256, _>(root, leaf(3), proof),
+		Err(primitives::Error::Verify)
+	);
+}
+
+#[test]
+fn should_generate_and_verify_range_proofs() {
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given
+	ext.execute_with(|| init_chain(7));
+	ext.persist_offchain_overlay();
+	register_offchain_ext(&mut ext);
+
+	ext.execute_with(|| {
+		// when
+		let (leaves, proof) = crate::Pallet::<Test>::generate_proof_range(1, 5).unwrap();
+
+		// then
+		assert_eq!(
+			(leaves.clone(), proof.clone()),
+			crate::Pallet::<Test>::generate_batch_proof((1..=5).collect()).unwrap()
+		);
+		// only the sibling of leaf 1 and the last peak are needed, against 3 items for each leaf
+		assert_eq!(proof.items.len(), 2);
+		assert_eq!(
+			crate::verify_leaves_proof::<<Test as Config>::Hashing, _>(
+				crate::Pallet::<Test>::mmr_root_hash(),
+				leaves.iter().cloned().map(DataOrHash::Data).collect(),
+				proof.clone()
+			),
+			Ok(())
+		);
+		assert_eq!(crate::Pallet::<Test>::verify_leaves(leaves, proof), Ok(()));
+
+		// the range must be within the MMR and not empty
+		assert_eq!(
+			crate::Pallet::<Test>::generate_proof_range(5, 7),
+			Err(primitives::Error::LeafNotFound)
+		);
+		assert_eq!(
+			crate::Pallet::<Test>::generate_proof_range(5, 4),
+			Err(primitives::Error::GenerateProof)
+		);
+	});
+}
+
+#[test]
+fn should_rebuild_missing_offchain_nodes() {
+	use frame_support::traits::OffchainWorker;
+
+	let _ = env_logger::try_init();
+	let mut ext = new_test_ext();
+	// given a chain whose leaves 2 and 3 were not indexed
+	ext.execute_with(|| init_chain(5));
+	ext.persist_offchain_overlay();
+	register_offchain_ext(&mut ext);
+
+	ext.execute_with(|| {
+		for (pos, parent_hash) in vec![(3, 3), (4, 4), (5, 4), (6, 4)] {
+			let key = MMR::node_temp_offchain_key(pos, H256::repeat_byte(parent_hash));
+			assert!(sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, &key).is_some());
+			sp_io::offchain::local_storage_clear(StorageKind::PERSISTENT, &key);
+		}
+		assert!(crate::Pallet::<Test>::generate_proof(2).is_err());
+
+		// when repairing the leaf 3 first, whose inner nodes need the missing leaf 2
+		assert_eq!(
+			crate::Pallet::<Test>::rebuild_offchain(3, 4),
+			Err(primitives::Error::LeafNotFound)
+		);
+
+		// then the range remains to be repaired
+		let key = crate::Pallet::<Test>::repair_offchain_key();
+		let pending = sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, &key);
+		assert_eq!(pending, Some((3u64, 4u64).encode()));
+
+		// and the offchain worker repairs a range requested through the Off-chain DB
+		sp_io::offchain::local_storage_set(StorageKind::PERSISTENT, &key, &(2u64, 4u64).encode());
+		MMR::offchain_worker(5);
+		assert_eq!(sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, &key), None);
+		for leaf_index in 0..5 {
+			let (leaf, proof) = crate::Pallet::<Test>::generate_proof(leaf_index).unwrap();
+			assert_eq!(crate::Pallet::<Test>::verify_leaf(leaf, proof), Ok(()));
+		}
+
+		// rebuilding nodes which are present is a no-op
+		assert_eq!(crate::Pallet::<Test>::rebuild_offchain(0, 5), Ok(()));
+	});
+
+	// the rebuilt nodes are stored under their canonical key
+	let offchain_db = ext.offchain_db();
+	for pos in 3..7 {
+		assert!(offchain_db.get(&MMR::node_canon_offchain_key(pos)).is_some());
+	}
+	assert_eq!(offchain_db.get(&MMR::node_canon_offchain_key(2)), None);
+}
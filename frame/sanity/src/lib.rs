@@ -0,0 +1,112 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Sanity Pallet
+//!
+//! - [`Config`]
+//!
+//! ## Overview
+//!
+//! A pallet which periodically checks the invariants of other pallets, meant for development and
+//! test chains.
+//!
+//! Pallets describe the invariants of their state by implementing [`CheckInvariants`]. The
+//! runtime registers the pallets to check as a tuple in [`Config::Invariants`], for instance
+//! `(Vesting, Elections, Mmr)`. They are checked in `on_idle` of every [`Config::CheckPeriod`]-th
+//! block, consuming all of the weight left in that block, since the cost of a check is not known
+//! in advance. Every violated invariant is reported with an [`Event::InvariantViolated`].
+//!
+//! ## Interface
+//!
+//! This pallet has no dispatchable functions.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{CheckInvariants, Get},
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use sp_runtime::traits::Zero;
+use sp_std::prelude::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The invariants to check, usually a tuple of pallets.
+		type Invariants: CheckInvariants;
+
+		/// The number of blocks between two checks of the invariants. If set to zero, the
+		/// invariants are never checked.
+		#[pallet::constant]
+		type CheckPeriod: Get<Self::BlockNumber>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event {
+		/// An invariant was found to be violated. \[description\]
+		InvariantViolated(Vec<u8>),
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Check the invariants if `n` is a multiple of [`Config::CheckPeriod`].
+		fn on_idle(n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let period = T::CheckPeriod::get();
+			if period.is_zero() || !(n % period).is_zero() {
+				return 0
+			}
+
+			Self::check();
+			remaining_weight
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Check all invariants, depositing an event for each violated one.
+	///
+	/// Returns the number of violated invariants.
+	pub fn check() -> u32 {
+		let violations = T::Invariants::check_invariants();
+		for violation in violations.iter() {
+			log::error!(target: "runtime::sanity", "Invariant violated: {}", violation);
+			Self::deposit_event(Event::InvariantViolated(violation.as_bytes().to_vec()));
+		}
+		violations.len() as u32
+	}
+}
@@ -0,0 +1,74 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::traits::OnIdle;
+
+use super::*;
+use crate::mock::{new_test_ext, Event as TestEvent, Sanity, System, Test, BALANCE, LOCKED};
+
+fn violations() -> Vec<Vec<u8>> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			TestEvent::Sanity(Event::InvariantViolated(violation)) => Some(violation),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn holding_invariants_are_not_reported() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Sanity::check(), 0);
+		assert!(violations().is_empty());
+	});
+}
+
+#[test]
+fn every_violated_invariant_is_reported() {
+	new_test_ext().execute_with(|| {
+		LOCKED.with(|v| *v.borrow_mut() = 20);
+		assert_eq!(Sanity::check(), 1);
+		assert_eq!(violations(), vec![b"Test: more locked than available.".to_vec()]);
+
+		BALANCE.with(|v| *v.borrow_mut() = 0);
+		assert_eq!(Sanity::check(), 2);
+		assert_eq!(
+			violations()[1..].to_vec(),
+			vec![b"Test: zero balance.".to_vec(), b"Test: more locked than available.".to_vec()],
+		);
+	});
+}
+
+#[test]
+fn invariants_are_checked_every_check_period() {
+	new_test_ext().execute_with(|| {
+		BALANCE.with(|v| *v.borrow_mut() = 0);
+
+		// Nothing is checked or consumed outside of the check period.
+		assert_eq!(<Sanity as OnIdle<u64>>::on_idle(4, 1_000), 0);
+		assert!(violations().is_empty());
+
+		// A check consumes all of the remaining weight.
+		assert_eq!(<Sanity as OnIdle<u64>>::on_idle(5, 1_000), 1_000);
+		assert_eq!(violations().len(), 1);
+
+		assert_eq!(<Sanity as OnIdle<u64>>::on_idle(6, 1_000), 0);
+		assert_eq!(<Sanity as OnIdle<u64>>::on_idle(10, 1_000), 1_000);
+		assert_eq!(violations().len(), 2);
+	});
+}
@@ -0,0 +1,107 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Streaming payments pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+use crate::Pallet as StreamingPayments;
+
+const SEED: u32 = 0;
+
+/// Open a stream from a funded `payer` to `payee`, starting at block 1.
+///
+/// The stream releases the existential deposit every block, so that anything accrued can always
+/// be transferred to a fresh payee account.
+fn open_funded_stream<T: Config>(
+	payer: &T::AccountId,
+	payee: &T::AccountId,
+) -> Result<StreamId, &'static str> {
+	T::Currency::make_free_balance_be(payer, BalanceOf::<T>::max_value());
+	T::BlockNumberProvider::set_block_number(0u32.into());
+	let per_block = T::Currency::minimum_balance().max(1u32.into());
+	let deposit = T::MinStreamDeposit::get().max(per_block.saturating_mul(100u32.into()));
+	let id = StreamingPayments::<T>::next_stream_id();
+	StreamingPayments::<T>::open_stream(
+		RawOrigin::Signed(payer.clone()).into(),
+		T::Lookup::unlookup(payee.clone()),
+		deposit,
+		per_block,
+		1u32.into(),
+	)?;
+	Ok(id)
+}
+
+benchmarks! {
+	open_stream {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let payee: T::AccountId = account("payee", 0, SEED);
+		let payee_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(payee);
+		let deposit = T::MinStreamDeposit::get();
+	}: _(RawOrigin::Signed(caller.clone()), payee_lookup, deposit, 10u32.into(), 1u32.into())
+	verify {
+		assert_eq!(StreamingPayments::<T>::outstanding(&caller), deposit);
+	}
+
+	top_up {
+		let caller: T::AccountId = whitelisted_caller();
+		let payee: T::AccountId = account("payee", 0, SEED);
+		let id = open_funded_stream::<T>(&caller, &payee)?;
+		// Worst case: something accrued and has to be paid out first.
+		T::BlockNumberProvider::set_block_number(10u32.into());
+	}: _(RawOrigin::Signed(caller), id, 100u32.into())
+	verify {
+		assert!(!T::Currency::free_balance(&payee).is_zero(), "Accrued funds were not paid out");
+	}
+
+	withdraw {
+		let caller: T::AccountId = whitelisted_caller();
+		let payer: T::AccountId = account("payer", 0, SEED);
+		let id = open_funded_stream::<T>(&payer, &caller)?;
+		T::BlockNumberProvider::set_block_number(10u32.into());
+	}: _(RawOrigin::Signed(caller.clone()), id)
+	verify {
+		assert!(!T::Currency::free_balance(&caller).is_zero(), "Accrued funds were not paid out");
+	}
+
+	close_stream {
+		let caller: T::AccountId = whitelisted_caller();
+		let payee: T::AccountId = account("payee", 0, SEED);
+		let id = open_funded_stream::<T>(&caller, &payee)?;
+		T::BlockNumberProvider::set_block_number(10u32.into());
+	}: _(RawOrigin::Signed(caller.clone()), id)
+	verify {
+		assert!(StreamingPayments::<T>::stream(id).is_none(), "Stream was not removed");
+		assert!(
+			StreamingPayments::<T>::outstanding(&caller).is_zero(),
+			"Deposit was not unreserved",
+		);
+	}
+}
+
+impl_benchmark_test_suite!(
+	StreamingPayments,
+	crate::mock::ExtBuilder::default().build(),
+	crate::mock::Test,
+);
@@ -0,0 +1,399 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Streaming Payments Pallet
+//!
+//! - [`Config`]
+//! - [`Call`]
+//!
+//! ## Overview
+//!
+//! A pallet for continuous payments from a payer to a payee, such as salaries or subscriptions.
+//!
+//! Opening a stream reserves a deposit on the payer's account. The deposit is then released to
+//! the payee linearly, a fixed amount per block, following exactly the same curve as a
+//! [`pallet_vesting::VestingInfo`]. The funds stay reserved on the payer's account until the payee
+//! withdraws them; at any time only the amount which is still owed is reserved. Unlike a lock,
+//! a reserve cannot overlap with the locks of other pallets, such as vesting or staking. It is
+//! not guaranteed to be paid out though: reserves can be slashed, e.g. by another pallet slashing
+//! the payer, in which case the payee is only paid what is left of the reserve.
+//!
+//! Every operation on a stream first _settles_ it: everything accrued so far is transferred to
+//! the payee and the stream is restarted from the current block with the remaining amount. This
+//! makes top-ups after a stream ran dry behave as expected, i.e. the payee is never owed anything
+//! for the blocks during which the stream was empty. A payee without an account is only paid
+//! once the accrued funds reach the existential deposit, until then they stay reserved.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `open_stream` - Reserve a deposit and start streaming it to a payee.
+//! - `top_up` - Add more funds to an existing stream.
+//! - `withdraw` - Transfer everything accrued so far to the payee.
+//! - `close_stream` - Settle the accrued amount and release the remainder back to the payer.
+//!
+//! ## Weights
+//!
+//! The weights in [`weights::SubstrateWeight`] were not benchmarked but borrowed from those of
+//! `pallet_vesting`. Do not use them in production before benchmarking the pallet against your
+//! runtime.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub mod weights;
+
+use codec::{Decode, Encode};
+use frame_support::{
+	ensure,
+	pallet_prelude::*,
+	traits::{BlockNumberProvider, Currency, ExistenceRequirement, Get, ReservableCurrency},
+	transactional,
+};
+use frame_system::{ensure_signed, pallet_prelude::*};
+pub use pallet::*;
+use pallet_vesting::VestingInfo;
+use sp_runtime::{
+	traits::{Convert, Saturating, StaticLookup, Zero},
+	RuntimeDebug,
+};
+use sp_std::mem;
+pub use weights::WeightInfo;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Identifier of a stream.
+pub type StreamId = u32;
+
+/// A payment stream from `payer` to `payee`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Stream<AccountId, Balance, BlockNumber> {
+	/// The account paying into the stream. The funds still owed are reserved on this account.
+	pub payer: AccountId,
+	/// The account receiving the stream.
+	pub payee: AccountId,
+	/// The funds still owed to the payee since the stream was last settled, and the rate at which
+	/// they are released.
+	pub schedule: VestingInfo<Balance, BlockNumber>,
+	/// The funds which accrued to the payee but were not paid out yet, because the payee has no
+	/// account and they are below the existential deposit. They stay reserved on the payer's
+	/// account.
+	pub unpaid: Balance,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency trait.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Convert the block number into a balance.
+		type BlockNumberToBalance: Convert<Self::BlockNumber, BalanceOf<Self>>;
+
+		/// Provider for the block number against which streams accrue.
+		///
+		/// Should be the provider of `pallet_vesting`, so that streams follow the same clock as
+		/// vesting schedules.
+		type BlockNumberProvider: BlockNumberProvider<BlockNumber = Self::BlockNumber>;
+
+		/// The minimum deposit required to open a stream.
+		#[pallet::constant]
+		type MinStreamDeposit: Get<BalanceOf<Self>>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// All open streams.
+	#[pallet::storage]
+	#[pallet::getter(fn stream)]
+	pub type Streams<T: Config> =
+		StorageMap<_, Twox64Concat, StreamId, Stream<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+
+	/// The identifier of the next stream to be opened.
+	#[pallet::storage]
+	#[pallet::getter(fn next_stream_id)]
+	pub type NextStreamId<T: Config> = StorageValue<_, StreamId, ValueQuery>;
+
+	/// The total amount that an account still owes across all of its open streams.
+	///
+	/// This is the amount reserved on the account by this pallet, unless the reserve was slashed.
+	#[pallet::storage]
+	#[pallet::getter(fn outstanding)]
+	pub type Outstanding<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
+	pub enum Event<T: Config> {
+		/// A stream was opened. \[id, payer, payee, deposit\]
+		StreamOpened(StreamId, T::AccountId, T::AccountId, BalanceOf<T>),
+		/// More funds were added to a stream. \[id, amount\]
+		StreamToppedUp(StreamId, BalanceOf<T>),
+		/// Accrued funds were transferred to the payee of a stream. \[id, payee, amount\]
+		Withdrawn(StreamId, T::AccountId, BalanceOf<T>),
+		/// A stream was closed and the funds still owed were released back to the payer.
+		/// \[id, refunded\]
+		StreamClosed(StreamId, BalanceOf<T>),
+		/// All funds of a stream were paid out and the stream was removed. \[id\]
+		StreamCompleted(StreamId),
+	}
+
+	/// Error for the streaming payments pallet.
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The given stream does not exist.
+		UnknownStream,
+		/// The deposit is too low to open a stream.
+		AmountLow,
+		/// The payer does not have enough free balance to back all of its streams.
+		InsufficientBalance,
+		/// A stream must release a non-zero amount every block.
+		ZeroRate,
+		/// An account cannot stream to itself.
+		SelfStream,
+		/// The origin is not allowed to perform this operation on the stream.
+		NotAllowed,
+		/// No more streams can be opened.
+		NoAvailableStreamId,
+		/// A stream cannot be topped up with zero funds.
+		ZeroAmount,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Open a stream from the sender to `payee`.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have enough free
+		/// balance to back `deposit` in addition to all of its already open streams.
+		///
+		/// - `payee`: The account that receives the stream.
+		/// - `deposit`: The amount that will be streamed. It is reserved on the sender's account.
+		/// - `per_block`: The amount released to the payee every block.
+		/// - `starting_block`: The block of `BlockNumberProvider` from which funds start accruing
+		///   to the payee.
+		///
+		/// Emits `StreamOpened`.
+		#[pallet::weight(T::WeightInfo::open_stream())]
+		pub fn open_stream(
+			origin: OriginFor<T>,
+			payee: <T::Lookup as StaticLookup>::Source,
+			deposit: BalanceOf<T>,
+			per_block: BalanceOf<T>,
+			starting_block: T::BlockNumber,
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+			let payee = T::Lookup::lookup(payee)?;
+			ensure!(payer != payee, Error::<T>::SelfStream);
+			ensure!(deposit >= T::MinStreamDeposit::get(), Error::<T>::AmountLow);
+			ensure!(!per_block.is_zero(), Error::<T>::ZeroRate);
+
+			let id = Self::next_stream_id();
+			let next_id = id.checked_add(1).ok_or(Error::<T>::NoAvailableStreamId)?;
+
+			Self::increase_outstanding(&payer, deposit)?;
+			NextStreamId::<T>::put(next_id);
+			let schedule = VestingInfo {
+				locked: deposit,
+				per_block,
+				starting_block,
+				cliff_block: None,
+				remainder: Zero::zero(),
+			};
+			Streams::<T>::insert(
+				id,
+				Stream {
+					payer: payer.clone(),
+					payee: payee.clone(),
+					schedule,
+					unpaid: Zero::zero(),
+				},
+			);
+
+			Self::deposit_event(Event::<T>::StreamOpened(id, payer, payee, deposit));
+			Ok(())
+		}
+
+		/// Add `amount` to the funds of stream `id`.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the payer of the stream.
+		///
+		/// The stream is settled first, so a stream which ran dry resumes from the current block.
+		///
+		/// Emits `StreamToppedUp`, possibly preceded by `Withdrawn`.
+		#[pallet::weight(T::WeightInfo::top_up())]
+		#[transactional]
+		pub fn top_up(origin: OriginFor<T>, id: StreamId, amount: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut stream = Self::stream(id).ok_or(Error::<T>::UnknownStream)?;
+			ensure!(stream.payer == who, Error::<T>::NotAllowed);
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+
+			Self::settle(id, &mut stream)?;
+			Self::increase_outstanding(&who, amount)?;
+			stream.schedule.locked = stream.schedule.locked.saturating_add(amount);
+			Streams::<T>::insert(id, stream);
+
+			Self::deposit_event(Event::<T>::StreamToppedUp(id, amount));
+			Ok(())
+		}
+
+		/// Transfer everything accrued so far on stream `id` to its payee.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the payee of the stream.
+		///
+		/// Emits `Withdrawn` if anything accrued, and `StreamCompleted` if the stream has no funds
+		/// left afterwards.
+		#[pallet::weight(T::WeightInfo::withdraw())]
+		#[transactional]
+		pub fn withdraw(origin: OriginFor<T>, id: StreamId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut stream = Self::stream(id).ok_or(Error::<T>::UnknownStream)?;
+			ensure!(stream.payee == who, Error::<T>::NotAllowed);
+
+			Self::settle(id, &mut stream)?;
+			if stream.schedule.locked.is_zero() && stream.unpaid.is_zero() {
+				Streams::<T>::remove(id);
+				Self::deposit_event(Event::<T>::StreamCompleted(id));
+			} else {
+				Streams::<T>::insert(id, stream);
+			}
+			Ok(())
+		}
+
+		/// Close stream `id`.
+		///
+		/// The dispatch origin for this call must be _Signed_ by either the payer or the payee of
+		/// the stream.
+		///
+		/// Everything accrued so far is transferred to the payee, the remainder is unreserved on
+		/// the payer's account. Accrued funds which cannot be paid out, because the payee has no
+		/// account and they are below the existential deposit, are unreserved as well.
+		///
+		/// Emits `StreamClosed`, possibly preceded by `Withdrawn`.
+		#[pallet::weight(T::WeightInfo::close_stream())]
+		#[transactional]
+		pub fn close_stream(origin: OriginFor<T>, id: StreamId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut stream = Self::stream(id).ok_or(Error::<T>::UnknownStream)?;
+			ensure!(stream.payer == who || stream.payee == who, Error::<T>::NotAllowed);
+
+			Self::settle(id, &mut stream)?;
+			let refunded = Self::release_outstanding(
+				&stream.payer,
+				stream.schedule.locked.saturating_add(stream.unpaid),
+			);
+			Streams::<T>::remove(id);
+
+			Self::deposit_event(Event::<T>::StreamClosed(id, refunded));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The amount of stream `id` which accrued to the payee but was not withdrawn yet.
+	pub fn accrued(id: StreamId) -> Option<BalanceOf<T>> {
+		Self::stream(id).map(|stream| {
+			let now = T::BlockNumberProvider::current_block_number();
+			let remaining = stream.schedule.locked_at::<T::BlockNumberToBalance>(now);
+			stream.schedule.locked.saturating_sub(remaining).saturating_add(stream.unpaid)
+		})
+	}
+
+	/// Pay everything accrued on `stream` out to its payee and restart the stream's schedule from
+	/// the current block with the remaining amount.
+	///
+	/// If the reserve of the payer was slashed, the payee is only paid what is left of it. If the
+	/// payee has no account and the accrued funds are below the existential deposit, they are kept
+	/// in `unpaid` until enough accrued.
+	///
+	/// The caller is responsible for writing `stream` back to storage.
+	fn settle(
+		id: StreamId,
+		stream: &mut Stream<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	) -> DispatchResult {
+		let now = T::BlockNumberProvider::current_block_number();
+		let remaining = stream.schedule.locked_at::<T::BlockNumberToBalance>(now);
+		let accrued = stream.schedule.locked.saturating_sub(remaining);
+		stream.schedule.locked = remaining;
+		stream.schedule.starting_block = stream.schedule.starting_block.max(now);
+		stream.unpaid = stream.unpaid.saturating_add(accrued);
+
+		if stream.unpaid < T::Currency::minimum_balance() &&
+			T::Currency::total_balance(&stream.payee).is_zero()
+		{
+			return Ok(())
+		}
+
+		// Unreserved and transferred rather than repatriated, so that a new payee account is
+		// created by the transfer.
+		let paid = Self::release_outstanding(&stream.payer, mem::take(&mut stream.unpaid));
+		if !paid.is_zero() {
+			T::Currency::transfer(
+				&stream.payer,
+				&stream.payee,
+				paid,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			Self::deposit_event(Event::<T>::Withdrawn(id, stream.payee.clone(), paid));
+		}
+		Ok(())
+	}
+
+	/// Increase the amount owed by `who` by `amount`, reserving it on their account.
+	fn increase_outstanding(who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+		T::Currency::reserve(who, amount).map_err(|_| Error::<T>::InsufficientBalance)?;
+		Outstanding::<T>::mutate(who, |outstanding| {
+			*outstanding = outstanding.saturating_add(amount)
+		});
+		Ok(())
+	}
+
+	/// Decrease the amount owed by `who` by `amount`, unreserving it on their account.
+	///
+	/// At most the amount `who` owes is unreserved, so that the reserves of other pallets are
+	/// never released. Returns the amount actually unreserved, which is lower if the reserve of
+	/// `who` was slashed.
+	fn release_outstanding(who: &T::AccountId, amount: BalanceOf<T>) -> BalanceOf<T> {
+		Outstanding::<T>::mutate_exists(who, |maybe_outstanding| {
+			let outstanding = maybe_outstanding.unwrap_or_else(Zero::zero);
+			let amount = amount.min(outstanding);
+			*maybe_outstanding = Some(outstanding.saturating_sub(amount)).filter(|o| !o.is_zero());
+			let missing = T::Currency::unreserve(who, amount);
+			amount.saturating_sub(missing)
+		})
+	}
+}
@@ -0,0 +1,315 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2019-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{LockableCurrency, ReservableCurrency, WithdrawReasons},
+};
+
+use super::*;
+use crate::mock::{Balances, Event as TestEvent, ExtBuilder, StreamingPayments, System, Test};
+
+#[test]
+fn open_stream_reserves_deposit() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(StreamingPayments::open_stream(Some(1).into(), 2, 500, 10, 1));
+
+		let stream = StreamingPayments::stream(0).unwrap();
+		assert_eq!(stream.unpaid, 0);
+		assert_eq!(stream.payer, 1);
+		assert_eq!(stream.payee, 2);
+		assert_eq!(
+			stream.schedule,
+			VestingInfo {
+				locked: 500,
+				per_block: 10,
+				starting_block: 1,
+				cliff_block: None,
+				remainder: 0
+			}
+		);
+		assert_eq!(StreamingPayments::outstanding(&1), 500);
+		assert_eq!(StreamingPayments::next_stream_id(), 1);
+		System::assert_last_event(TestEvent::StreamingPayments(Event::StreamOpened(0, 1, 2, 500)));
+
+		// The deposit cannot be spent by the payer.
+		assert_eq!(Balances::reserved_balance(&1), 500);
+		assert_noop!(
+			Balances::transfer(Some(1).into(), 3, 501),
+			pallet_balances::Error::<Test, _>::InsufficientBalance,
+		);
+		assert_ok!(Balances::transfer(Some(1).into(), 3, 500));
+	});
+}
+
+#[test]
+fn open_stream_checks_parameters() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			StreamingPayments::open_stream(Some(1).into(), 1, 500, 10, 1),
+			Error::<Test>::SelfStream,
+		);
+		assert_noop!(
+			StreamingPayments::open_stream(Some(1).into(), 2, 99, 10, 1),
+			Error::<Test>::AmountLow,
+		);
+		assert_noop!(
+			StreamingPayments::open_stream(Some(1).into(), 2, 500, 0, 1),
+			Error::<Test>::ZeroRate,
+		);
+		assert_noop!(
+			StreamingPayments::open_stream(Some(3).into(), 2, 101, 10, 1),
+			Error::<Test>::InsufficientBalance,
+		);
+	});
+}
+
+#[test]
+fn all_streams_of_a_payer_must_be_backed() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(StreamingPayments::open_stream(Some(1).into(), 2, 600, 10, 1));
+		assert_noop!(
+			StreamingPayments::open_stream(Some(1).into(), 3, 401, 10, 1),
+			Error::<Test>::InsufficientBalance,
+		);
+		assert_ok!(StreamingPayments::open_stream(Some(1).into(), 3, 400, 10, 1));
+		assert_eq!(StreamingPayments::outstanding(&1), 1_000);
+	});
+}
+
+#[test]
+fn locked_funds_cannot_back_a_stream() {
+	ExtBuilder::default().build().execute_with(|| {
+		// e.g. the funds of an account which are still vesting.
+		Balances::set_lock(*b"vesting ", &1, 600, WithdrawReasons::all());
+		assert_noop!(
+			StreamingPayments::open_stream(Some(1).into(), 2, 401, 10, 1),
+			Error::<Test>::InsufficientBalance,
+		);
+		assert_ok!(StreamingPayments::open_stream(Some(1).into(), 2, 400, 10, 1));
+
+		// and the stream is paid out from its reserve regardless of the lock.
+		System::set_block_number(11);
+		assert_ok!(StreamingPayments::withdraw(Some(2).into(), 0));
+		assert_eq!(Balances::free_balance(&2), 1_100);
+	});
+}
+
+#[test]
+fn payee_can_withdraw_accrued_funds() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(StreamingPayments::open_stream(Some(1).into(), 2, 500, 10, 1));
+		System::set_block_number(11);
+		assert_eq!(StreamingPayments::accrued(0), Some(100));
+
+		assert_noop!(StreamingPayments::withdraw(Some(1).into(), 0), Error::<Test>::NotAllowed);
+		assert_ok!(StreamingPayments::withdraw(Some(2).into(), 0));
+		System::assert_last_event(TestEvent::StreamingPayments(Event::Withdrawn(0, 2, 100)));
+
+		assert_eq!(Balances::free_balance(&1), 500);
+		assert_eq!(Balances::reserved_balance(&1), 400);
+		assert_eq!(Balances::free_balance(&2), 1_100);
+		assert_eq!(StreamingPayments::outstanding(&1), 400);
+		assert_eq!(
+			StreamingPayments::stream(0).unwrap().schedule,
+			VestingInfo {
+				locked: 400,
+				per_block: 10,
+				starting_block: 11,
+				cliff_block: None,
+				remainder: 0
+			},
+		);
+		assert_eq!(StreamingPayments::accrued(0), Some(0));
+
+		// Withdrawing twice in the same block is a no-op.
+		assert_ok!(StreamingPayments::withdraw(Some(2).into(), 0));
+		assert_eq!(Balances::free_balance(&2), 1_100);
+	});
+}
+
+#[test]
+fn withdrawing_everything_completes_the_stream() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(StreamingPayments::open_stream(Some(1).into(), 2, 500, 10, 1));
+		System::set_block_number(100);
+
+		assert_ok!(StreamingPayments::withdraw(Some(2).into(), 0));
+		System::assert_last_event(TestEvent::StreamingPayments(Event::StreamCompleted(0)));
+		assert_eq!(StreamingPayments::stream(0), None);
+		assert_eq!(StreamingPayments::outstanding(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 500);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&2), 1_500);
+	});
+}
+
+#[test]
+fn top_up_after_stream_ran_dry_does_not_pay_for_empty_blocks() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(StreamingPayments::open_stream(Some(1).into(), 2, 100, 10, 1));
+		System::set_block_number(20);
+
+		assert_noop!(StreamingPayments::top_up(Some(2).into(), 0, 100), Error::<Test>::NotAllowed);
+		assert_noop!(StreamingPayments::top_up(Some(1).into(), 0, 0), Error::<Test>::ZeroAmount);
+		assert_ok!(StreamingPayments::top_up(Some(1).into(), 0, 100));
+		// Everything accrued before the top-up was paid out.
+		assert_eq!(Balances::free_balance(&2), 1_100);
+		assert_eq!(
+			StreamingPayments::stream(0).unwrap().schedule,
+			VestingInfo {
+				locked: 100,
+				per_block: 10,
+				starting_block: 20,
+				cliff_block: None,
+				remainder: 0
+			},
+		);
+		assert_eq!(StreamingPayments::outstanding(&1), 100);
+
+		System::set_block_number(25);
+		assert_eq!(StreamingPayments::accrued(0), Some(50));
+	});
+}
+
+#[test]
+fn close_stream_refunds_the_remainder() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(StreamingPayments::open_stream(Some(1).into(), 2, 500, 10, 1));
+		System::set_block_number(6);
+
+		assert_noop!(StreamingPayments::close_stream(Some(3).into(), 0), Error::<Test>::NotAllowed);
+		assert_ok!(StreamingPayments::close_stream(Some(2).into(), 0));
+		System::assert_last_event(TestEvent::StreamingPayments(Event::StreamClosed(0, 450)));
+
+		assert_eq!(StreamingPayments::stream(0), None);
+		assert_eq!(StreamingPayments::outstanding(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 950);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&2), 1_050);
+		assert_noop!(
+			StreamingPayments::close_stream(Some(1).into(), 0),
+			Error::<Test>::UnknownStream,
+		);
+	});
+}
+
+#[test]
+fn payouts_below_existential_deposit_wait_for_new_payee() {
+	ExtBuilder::default().build().execute_with(|| {
+		// Account 5 does not exist, and the existential deposit is 10.
+		assert_ok!(StreamingPayments::open_stream(Some(1).into(), 5, 500, 3, 1));
+
+		System::set_block_number(3);
+		assert_ok!(StreamingPayments::top_up(Some(1).into(), 0, 100));
+		let stream = StreamingPayments::stream(0).unwrap();
+		assert_eq!(stream.unpaid, 6);
+		assert_eq!(
+			stream.schedule,
+			VestingInfo {
+				locked: 594,
+				per_block: 3,
+				starting_block: 3,
+				cliff_block: None,
+				remainder: 0
+			},
+		);
+		assert_eq!(StreamingPayments::accrued(0), Some(6));
+		assert_eq!(StreamingPayments::outstanding(&1), 600);
+		assert_eq!(Balances::free_balance(&5), 0);
+
+		// Once enough accrued, everything is paid out at once.
+		System::set_block_number(5);
+		assert_ok!(StreamingPayments::top_up(Some(1).into(), 0, 100));
+		System::assert_has_event(TestEvent::StreamingPayments(Event::Withdrawn(0, 5, 12)));
+		assert_eq!(Balances::free_balance(&5), 12);
+		assert_eq!(StreamingPayments::stream(0).unwrap().unpaid, 0);
+		assert_eq!(StreamingPayments::outstanding(&1), 688);
+	});
+}
+
+#[test]
+fn stream_to_new_payee_can_be_closed_early() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(StreamingPayments::open_stream(Some(1).into(), 5, 500, 3, 1));
+
+		// What accrued cannot be paid to account 5, and is refunded with the remainder.
+		System::set_block_number(3);
+		assert_ok!(StreamingPayments::close_stream(Some(1).into(), 0));
+		System::assert_last_event(TestEvent::StreamingPayments(Event::StreamClosed(0, 500)));
+		assert_eq!(StreamingPayments::stream(0), None);
+		assert_eq!(StreamingPayments::outstanding(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 1_000);
+		assert_eq!(Balances::free_balance(&5), 0);
+	});
+}
+
+#[test]
+fn slashed_stream_pays_out_what_is_left() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(StreamingPayments::open_stream(Some(1).into(), 2, 500, 10, 1));
+		// Another pallet reserves funds of the payer, then slashes all but 50 of its reserve.
+		assert_ok!(Balances::reserve(&1, 200));
+		let _ = Balances::slash_reserved(&1, 650);
+		assert_eq!(Balances::reserved_balance(&1), 50);
+
+		System::set_block_number(11);
+		assert_ok!(StreamingPayments::withdraw(Some(2).into(), 0));
+		System::assert_last_event(TestEvent::StreamingPayments(Event::Withdrawn(0, 2, 50)));
+		assert_eq!(Balances::free_balance(&2), 1_050);
+		assert_eq!(StreamingPayments::outstanding(&1), 400);
+
+		assert_ok!(StreamingPayments::close_stream(Some(1).into(), 0));
+		System::assert_last_event(TestEvent::StreamingPayments(Event::StreamClosed(0, 0)));
+		assert_eq!(StreamingPayments::outstanding(&1), 0);
+	});
+}
+
+#[test]
+fn closing_a_stream_leaves_other_reserves_alone() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(StreamingPayments::open_stream(Some(1).into(), 2, 500, 10, 1));
+		assert_ok!(Balances::reserve(&1, 200));
+
+		System::set_block_number(6);
+		assert_ok!(StreamingPayments::close_stream(Some(1).into(), 0));
+		System::assert_last_event(TestEvent::StreamingPayments(Event::StreamClosed(0, 450)));
+		assert_eq!(Balances::reserved_balance(&1), 200);
+		assert_eq!(StreamingPayments::outstanding(&1), 0);
+	});
+}
+
+#[test]
+fn stream_does_not_accrue_before_starting_block() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(StreamingPayments::open_stream(Some(1).into(), 2, 500, 10, 10));
+		System::set_block_number(9);
+		assert_eq!(StreamingPayments::accrued(0), Some(0));
+
+		assert_ok!(StreamingPayments::withdraw(Some(2).into(), 0));
+		assert_eq!(
+			StreamingPayments::stream(0).unwrap().schedule,
+			VestingInfo {
+				locked: 500,
+				per_block: 10,
+				starting_block: 10,
+				cliff_block: None,
+				remainder: 0
+			},
+		);
+	});
+}
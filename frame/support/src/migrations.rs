@@ -16,9 +16,12 @@
 // limitations under the License.
 
 use crate::{
-	traits::{GetStorageVersion, PalletInfoAccess},
+	storage::{bounded_vec::BoundedVec, unhashed, StorageMap, StorageValue},
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, PalletInfoAccess, StorageVersion},
 	weights::{RuntimeDbWeight, Weight},
 };
+use codec::FullCodec;
+use sp_std::{convert::TryFrom, marker::PhantomData, prelude::*};
 
 /// Trait used by [`migrate_from_pallet_version_to_storage_version`] to do the actual migration.
 pub trait PalletVersionToStorageVersionHelper {
@@ -71,3 +74,590 @@ pub fn migrate_from_pallet_version_to_storage_version<
 ) -> Weight {
 	AllPallets::migrate(db_weight)
 }
+
+/// A migration that is only executed when the on-chain storage version of `Pallet` is `FROM`,
+/// after which the storage version is set to `TO`.
+///
+/// `Inner` implements the actual migration and does not need to care about storage versions. This
+/// makes it safe to keep a migration in the runtime's migration list for longer than one upgrade:
+/// it is a no-op for any other on-chain version, so it is executed at most once.
+///
+/// The weight returned includes the storage version read and, if the migration is executed, the
+/// storage version write. The `try-runtime` hooks of `Inner` are only called if the migration is
+/// going to be, respectively was, executed. As all `pre_upgrade` hooks run before the upgrade,
+/// only the first of several chained migrations of a pallet is checked.
+///
+/// # Example
+///
+/// ```ignore
+/// pub type MigrateV0ToV1 = VersionedMigration<
+/// 	0,
+/// 	1,
+/// 	migrations::v1::InnerMigrateV0ToV1<Runtime>,
+/// 	Vesting,
+/// 	<Runtime as frame_system::Config>::DbWeight,
+/// >;
+/// ```
+pub struct VersionedMigration<const FROM: u16, const TO: u16, Inner, Pallet, DbWeight>(
+	PhantomData<(Inner, Pallet, DbWeight)>,
+);
+
+impl<const FROM: u16, const TO: u16, Inner, Pallet, DbWeight>
+	VersionedMigration<FROM, TO, Inner, Pallet, DbWeight>
+where
+	Pallet: GetStorageVersion + PalletInfoAccess,
+{
+	/// Whether the migration applies to the current on-chain storage version.
+	fn should_execute() -> bool {
+		Pallet::on_chain_storage_version() == StorageVersion::new(FROM)
+	}
+
+	/// The key under which `pre_upgrade` records whether the migration is about to be executed.
+	///
+	/// It includes both versions, so that chained migrations of the same pallet keep apart.
+	#[cfg(feature = "try-runtime")]
+	fn executed_key() -> [u8; 32] {
+		let prefix = sp_io::hashing::twox_128(crate::traits::ON_RUNTIME_UPGRADE_PREFIX);
+		let ident = [
+			Pallet::name().as_bytes(),
+			b":VersionedMigration:",
+			&FROM.to_le_bytes()[..],
+			&TO.to_le_bytes()[..],
+		]
+		.concat();
+		let ident = sp_io::hashing::twox_128(&ident);
+
+		let mut final_key = [0u8; 32];
+		final_key[..16].copy_from_slice(&prefix);
+		final_key[16..].copy_from_slice(&ident);
+
+		final_key
+	}
+}
+
+impl<const FROM: u16, const TO: u16, Inner, Pallet, DbWeight> OnRuntimeUpgrade
+	for VersionedMigration<FROM, TO, Inner, Pallet, DbWeight>
+where
+	Inner: OnRuntimeUpgrade,
+	Pallet: GetStorageVersion + PalletInfoAccess,
+	DbWeight: Get<RuntimeDbWeight>,
+{
+	fn on_runtime_upgrade() -> Weight {
+		if !Self::should_execute() {
+			log::info!(
+				"🚚 {} storage version is not {}, skipping migration to {}.",
+				Pallet::name(),
+				FROM,
+				TO,
+			);
+			return DbWeight::get().reads(1)
+		}
+
+		log::info!("🚚 Migrating {} from storage version {} to {}.", Pallet::name(), FROM, TO);
+		let weight = Inner::on_runtime_upgrade();
+		StorageVersion::new(TO).put::<Pallet>();
+
+		weight.saturating_add(DbWeight::get().reads_writes(1, 1))
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		let execute = Self::should_execute();
+		crate::storage::unhashed::put(&Self::executed_key(), &execute);
+		if execute {
+			Inner::pre_upgrade()
+		} else {
+			Ok(())
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		let executed: bool = crate::storage::unhashed::take(&Self::executed_key())
+			.ok_or("pre_upgrade of VersionedMigration was not run")?;
+		if !executed {
+			return Ok(())
+		}
+		// A later migration of the same pallet may have moved the version on already.
+		if Pallet::on_chain_storage_version() < StorageVersion::new(TO) {
+			return Err("Storage version was not updated by VersionedMigration")
+		}
+		Inner::post_upgrade()
+	}
+}
+
+/// A migration which is executed incrementally over multiple blocks.
+///
+/// Instead of migrating all of its storage in a single block, each call to [`Self::step`] only
+/// migrates as much as fits into the given weight limit and returns a cursor to continue from.
+/// This allows migrating storage maps which are too large to be migrated within a single block.
+///
+/// Lazy migrations are started with [`StartLazyMigration`] and driven by
+/// [`LazyMigrationExecutor`]. Until a migration is complete, the pallet owning the migrated storage
+/// must be able to cope with both the old and the new storage format.
+pub trait LazyMigration {
+	/// The unique identifier of the migration, used to derive the storage key of its cursor.
+	const ID: &'static [u8];
+
+	/// The identifier from which the storage key of the cursor is derived, [`Self::ID`] by default.
+	///
+	/// Migrations of instantiable pallets extend it, so that the cursors of the instances are kept
+	/// apart.
+	fn id() -> Vec<u8> {
+		Self::ID.to_vec()
+	}
+
+	/// The position from which on the migration continues in its next step.
+	type Cursor: FullCodec;
+
+	/// Migrate as much storage as possible within `limit`, continuing from `cursor`, which is
+	/// `None` in the first step.
+	///
+	/// Returns the consumed weight and the cursor to continue from in the next step, or `None` if
+	/// the migration is complete.
+	fn step(cursor: Option<Self::Cursor>, limit: Weight) -> (Weight, Option<Self::Cursor>);
+}
+
+/// The storage key under which the cursor of a running lazy migration with the given `id` is
+/// stored.
+fn lazy_migration_key(id: &[u8]) -> [u8; 32] {
+	const LAZY_MIGRATION_PREFIX: &[u8] = b":__LAZY_MIGRATION__:";
+
+	let prefix = sp_io::hashing::twox_128(LAZY_MIGRATION_PREFIX);
+	let ident = sp_io::hashing::twox_128(id);
+
+	let mut final_key = [0u8; 32];
+	final_key[..16].copy_from_slice(&prefix);
+	final_key[16..].copy_from_slice(&ident);
+
+	final_key
+}
+
+/// Trait used by [`LazyMigrationExecutor`] to drive one or a tuple of [`LazyMigration`]s.
+pub trait LazyMigrations {
+	/// Execute the next step of every running migration, in order, as long as `limit` allows.
+	fn execute(limit: Weight, db_weight: &RuntimeDbWeight) -> Weight;
+
+	/// Whether any of the migrations is running.
+	fn is_running() -> bool;
+}
+
+impl<M: LazyMigration> LazyMigrations for M {
+	fn execute(limit: Weight, db_weight: &RuntimeDbWeight) -> Weight {
+		let overhead = db_weight.reads_writes(1, 1);
+		if limit < overhead {
+			return 0
+		}
+
+		let key = lazy_migration_key(&M::id());
+		let cursor = match unhashed::get::<Option<M::Cursor>>(&key) {
+			Some(cursor) => cursor,
+			None => return db_weight.reads(1),
+		};
+
+		let (weight, next) = M::step(cursor, limit - overhead);
+		match next {
+			Some(cursor) => unhashed::put(&key, &Some(cursor)),
+			None => {
+				log::info!(
+					"🚚 Lazy migration {} is complete.",
+					sp_std::str::from_utf8(M::ID).unwrap_or("<invalid utf8>"),
+				);
+				unhashed::kill(&key);
+			},
+		}
+
+		weight.saturating_add(overhead)
+	}
+
+	fn is_running() -> bool {
+		unhashed::exists(&lazy_migration_key(&M::id()))
+	}
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl LazyMigrations for T {
+	fn execute(limit: Weight, db_weight: &RuntimeDbWeight) -> Weight {
+		let mut weight: Weight = 0;
+
+		for_tuples!( #( weight = weight.saturating_add(T::execute(limit.saturating_sub(weight), db_weight)); )* );
+
+		weight
+	}
+
+	fn is_running() -> bool {
+		let mut running = false;
+
+		for_tuples!( #( running = running || T::is_running(); )* );
+
+		running
+	}
+}
+
+/// Start the lazy migration `M`, unless it is running already.
+///
+/// This is meant to be wrapped in a [`VersionedMigration`], so that the migration is only started
+/// once. Note that the storage version is then updated when the migration starts, not when it is
+/// complete.
+pub struct StartLazyMigration<M, DbWeight>(PhantomData<(M, DbWeight)>);
+
+impl<M: LazyMigration, DbWeight: Get<RuntimeDbWeight>> OnRuntimeUpgrade
+	for StartLazyMigration<M, DbWeight>
+{
+	fn on_runtime_upgrade() -> Weight {
+		if M::is_running() {
+			return DbWeight::get().reads(1)
+		}
+
+		log::info!(
+			"🚚 Starting lazy migration {}.",
+			sp_std::str::from_utf8(M::ID).unwrap_or("<invalid utf8>"),
+		);
+		unhashed::put(&lazy_migration_key(&M::id()), &None::<M::Cursor>);
+
+		DbWeight::get().reads_writes(1, 1)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		if !M::is_running() {
+			return Err("Lazy migration was not started")
+		}
+		Ok(())
+	}
+}
+
+/// Executes the running lazy migrations among `Migrations` within a weight budget.
+///
+/// Pallets owning a lazy migration call [`Self::execute`], typically from `on_idle` with the
+/// remaining weight of the block, until the migration is complete.
+///
+/// # Example
+///
+/// ```ignore
+/// fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+/// 	LazyMigrationExecutor::<migrations::v1::MigrateToV1<T>, T::DbWeight>::execute(
+/// 		remaining_weight,
+/// 	)
+/// }
+/// ```
+pub struct LazyMigrationExecutor<Migrations, DbWeight>(PhantomData<(Migrations, DbWeight)>);
+
+impl<Migrations: LazyMigrations, DbWeight: Get<RuntimeDbWeight>>
+	LazyMigrationExecutor<Migrations, DbWeight>
+{
+	/// Execute the next step of each running migration, using at most `limit`.
+	///
+	/// Returns the consumed weight.
+	pub fn execute(limit: Weight) -> Weight {
+		Migrations::execute(limit, &DbWeight::get())
+	}
+
+	/// Whether any of the migrations is still running.
+	pub fn is_running() -> bool {
+		Migrations::is_running()
+	}
+}
+
+/// The policy of [`migrate_to_bounded_vec`] for the items of a vector which do not fit into its
+/// new bound.
+pub trait OnBoundedOverflow<T> {
+	/// Handle `excess`, the trailing items of the old vector which do not fit into the bound.
+	///
+	/// Returns the consumed weight, or an error to abort the migration, in which case the migrated
+	/// storage value is left untouched.
+	fn on_overflow(excess: Vec<T>, db_weight: &RuntimeDbWeight) -> Result<Weight, &'static str>;
+}
+
+/// Drop the items which do not fit into the bound.
+pub struct Truncate;
+
+impl<T> OnBoundedOverflow<T> for Truncate {
+	fn on_overflow(_excess: Vec<T>, _db_weight: &RuntimeDbWeight) -> Result<Weight, &'static str> {
+		Ok(0)
+	}
+}
+
+/// Abort the migration if any item does not fit into the bound.
+pub struct FailOnOverflow;
+
+impl<T> OnBoundedOverflow<T> for FailOnOverflow {
+	fn on_overflow(_excess: Vec<T>, _db_weight: &RuntimeDbWeight) -> Result<Weight, &'static str> {
+		Err("Storage value exceeds its new bound")
+	}
+}
+
+/// Move the items which do not fit into the bound to the storage map `Map`, keyed by their
+/// position among the excess items, starting at `0`.
+pub struct SpillInto<Map>(PhantomData<Map>);
+
+impl<T: FullCodec, Map: StorageMap<u32, T>> OnBoundedOverflow<T> for SpillInto<Map> {
+	fn on_overflow(excess: Vec<T>, db_weight: &RuntimeDbWeight) -> Result<Weight, &'static str> {
+		let count = excess.len() as Weight;
+		for (index, item) in excess.into_iter().enumerate() {
+			Map::insert(index as u32, item);
+		}
+		Ok(db_weight.writes(count))
+	}
+}
+
+/// Migrate the storage value `Item` from an unbounded `Vec<T>` to a `BoundedVec<T, S>`.
+///
+/// The items which do not fit into the bound `S` are handed to the overflow policy `O`, such as
+/// [`Truncate`], [`FailOnOverflow`] or [`SpillInto`]. If `O` fails, `Item` is left untouched and
+/// the error is returned. As a `BoundedVec` encodes like a `Vec`, a value which fits into the bound
+/// is not written at all.
+///
+/// Returns the consumed weight.
+pub fn migrate_to_bounded_vec<Item, T, S, O>(
+	db_weight: &RuntimeDbWeight,
+) -> Result<Weight, &'static str>
+where
+	Item: StorageValue<BoundedVec<T, S>>,
+	T: FullCodec,
+	S: Get<u32>,
+	O: OnBoundedOverflow<T>,
+{
+	let mut items = match unhashed::get::<Vec<T>>(&Item::hashed_key()) {
+		Some(items) => items,
+		None => return Ok(db_weight.reads(1)),
+	};
+
+	let bound = S::get() as usize;
+	if items.len() <= bound {
+		return Ok(db_weight.reads(1))
+	}
+
+	let excess = items.split_off(bound);
+	log::warn!("🚚 {} items of a storage value exceed its bound of {}.", excess.len(), bound);
+	let weight = O::on_overflow(excess, db_weight)?;
+
+	let bounded =
+		BoundedVec::<T, S>::try_from(items).map_err(|_| "Truncated vector is too long")?;
+	Item::put(bounded);
+
+	Ok(weight.saturating_add(db_weight.reads_writes(1, 1)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct MockPallet;
+	impl PalletInfoAccess for MockPallet {
+		fn index() -> usize {
+			0
+		}
+		fn name() -> &'static str {
+			"MockPallet"
+		}
+	}
+	impl GetStorageVersion for MockPallet {
+		fn current_storage_version() -> StorageVersion {
+			StorageVersion::new(2)
+		}
+		fn on_chain_storage_version() -> StorageVersion {
+			StorageVersion::get::<Self>()
+		}
+	}
+
+	const MIGRATION_COUNTER: &[u8] = b":migration_counter:";
+
+	struct CountingMigration;
+	impl OnRuntimeUpgrade for CountingMigration {
+		fn on_runtime_upgrade() -> Weight {
+			let count = unhashed::get_or_default::<u32>(MIGRATION_COUNTER);
+			unhashed::put(MIGRATION_COUNTER, &(count + 1));
+			100
+		}
+	}
+
+	crate::parameter_types! {
+		pub const TestDbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 1, write: 10 };
+	}
+
+	type MigrateV1ToV2 = VersionedMigration<1, 2, CountingMigration, MockPallet, TestDbWeight>;
+
+	#[test]
+	fn versioned_migration_only_runs_from_the_given_version() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			// Nothing happens for an on-chain version other than `FROM`.
+			assert_eq!(MigrateV1ToV2::on_runtime_upgrade(), 1);
+			assert_eq!(unhashed::get::<u32>(MIGRATION_COUNTER), None);
+			assert_eq!(MockPallet::on_chain_storage_version(), StorageVersion::new(0));
+
+			StorageVersion::new(1).put::<MockPallet>();
+			assert_eq!(MigrateV1ToV2::on_runtime_upgrade(), 100 + 1 + 10);
+			assert_eq!(unhashed::get::<u32>(MIGRATION_COUNTER), Some(1));
+			assert_eq!(MockPallet::on_chain_storage_version(), StorageVersion::new(2));
+
+			// Running it again is a no-op.
+			assert_eq!(MigrateV1ToV2::on_runtime_upgrade(), 1);
+			assert_eq!(unhashed::get::<u32>(MIGRATION_COUNTER), Some(1));
+			assert_eq!(MockPallet::on_chain_storage_version(), StorageVersion::new(2));
+		});
+	}
+
+	type MigrateV0ToV1 = VersionedMigration<0, 1, CountingMigration, MockPallet, TestDbWeight>;
+
+	#[test]
+	fn chained_versioned_migrations_run_in_order() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			assert_eq!(<(MigrateV0ToV1, MigrateV1ToV2)>::on_runtime_upgrade(), 2 * (100 + 1 + 10));
+			assert_eq!(unhashed::get::<u32>(MIGRATION_COUNTER), Some(2));
+			assert_eq!(MockPallet::on_chain_storage_version(), StorageVersion::new(2));
+
+			// Only the migrations from the on-chain version on are executed.
+			StorageVersion::new(1).put::<MockPallet>();
+			assert_eq!(<(MigrateV0ToV1, MigrateV1ToV2)>::on_runtime_upgrade(), 1 + 100 + 1 + 10);
+			assert_eq!(unhashed::get::<u32>(MIGRATION_COUNTER), Some(3));
+			assert_eq!(MockPallet::on_chain_storage_version(), StorageVersion::new(2));
+		});
+	}
+
+	#[cfg(feature = "try-runtime")]
+	#[test]
+	fn chained_versioned_migrations_keep_their_upgrade_checks_apart() {
+		type Migrations = (MigrateV0ToV1, MigrateV1ToV2);
+
+		sp_io::TestExternalities::default().execute_with(|| {
+			assert_eq!(Migrations::pre_upgrade(), Ok(()));
+			Migrations::on_runtime_upgrade();
+			assert_eq!(Migrations::post_upgrade(), Ok(()));
+			assert_eq!(MockPallet::on_chain_storage_version(), StorageVersion::new(2));
+
+			// Each `post_upgrade` consumes what its own `pre_upgrade` recorded.
+			assert_eq!(MigrateV0ToV1::pre_upgrade(), Ok(()));
+			assert_eq!(
+				MigrateV1ToV2::post_upgrade(),
+				Err("pre_upgrade of VersionedMigration was not run"),
+			);
+			assert_eq!(MigrateV0ToV1::post_upgrade(), Ok(()));
+		});
+	}
+
+	/// Migrates the `ITEMS` values stored under `[0]..[ITEMS]`, at a weight of 10 per item.
+	struct DoublingMigration;
+	impl DoublingMigration {
+		const ITEMS: u32 = 25;
+	}
+	impl LazyMigration for DoublingMigration {
+		const ID: &'static [u8] = b"DoublingMigration";
+		type Cursor = u32;
+
+		fn step(cursor: Option<u32>, limit: Weight) -> (Weight, Option<u32>) {
+			let start = cursor.unwrap_or(0);
+			let end = Self::ITEMS.min(start + (limit / 10) as u32);
+			for i in start..end {
+				let key = i.to_le_bytes();
+				unhashed::put(&key, &(unhashed::get_or_default::<u32>(&key) * 2));
+			}
+			let next = if end == Self::ITEMS { None } else { Some(end) };
+			((end - start) as Weight * 10, next)
+		}
+	}
+
+	type Executor = LazyMigrationExecutor<(DoublingMigration,), TestDbWeight>;
+
+	#[test]
+	fn lazy_migrations_are_executed_within_the_limit() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			for i in 0..DoublingMigration::ITEMS {
+				unhashed::put(&i.to_le_bytes(), &i);
+			}
+
+			// Nothing happens before the migration was started.
+			assert_eq!(Executor::execute(1_000), 1);
+			assert!(!Executor::is_running());
+			assert_eq!(unhashed::get::<u32>(&1u32.to_le_bytes()), Some(1));
+
+			assert_eq!(
+				StartLazyMigration::<DoublingMigration, TestDbWeight>::on_runtime_upgrade(),
+				11
+			);
+			assert!(Executor::is_running());
+			// Starting again does not reset the cursor.
+			assert_eq!(
+				StartLazyMigration::<DoublingMigration, TestDbWeight>::on_runtime_upgrade(),
+				1
+			);
+
+			// The limit must cover the cursor read and write.
+			assert_eq!(Executor::execute(10), 0);
+
+			// 10 items fit into the first step.
+			assert_eq!(Executor::execute(111), 111);
+			assert_eq!(unhashed::get::<u32>(&9u32.to_le_bytes()), Some(18));
+			assert_eq!(unhashed::get::<u32>(&10u32.to_le_bytes()), Some(10));
+
+			assert_eq!(Executor::execute(111), 111);
+			assert!(Executor::is_running());
+
+			// The last step only migrates the 5 remaining items.
+			assert_eq!(Executor::execute(111), 61);
+			assert!(!Executor::is_running());
+			for i in 0..DoublingMigration::ITEMS {
+				assert_eq!(unhashed::get::<u32>(&i.to_le_bytes()), Some(i * 2));
+			}
+
+			// Nothing happens once the migration is complete.
+			assert_eq!(Executor::execute(1_000), 1);
+			assert_eq!(unhashed::get::<u32>(&1u32.to_le_bytes()), Some(2));
+		});
+	}
+
+	crate::parameter_types! {
+		pub const Three: u32 = 3;
+	}
+
+	crate::generate_storage_alias! { Prefix, Bounded => Value<BoundedVec<u32, Three>> }
+	crate::generate_storage_alias! { Prefix, Spilled => Map<(u32, crate::Twox64Concat), u32> }
+
+	fn put_unbounded(items: Vec<u32>) {
+		unhashed::put(&Bounded::hashed_key(), &items);
+	}
+
+	#[test]
+	fn migrate_to_bounded_vec_keeps_values_within_the_bound() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let db_weight = TestDbWeight::get();
+			assert_eq!(migrate_to_bounded_vec::<Bounded, _, _, FailOnOverflow>(&db_weight), Ok(1));
+			assert_eq!(Bounded::get(), None);
+
+			put_unbounded(vec![1, 2, 3]);
+			assert_eq!(migrate_to_bounded_vec::<Bounded, _, _, FailOnOverflow>(&db_weight), Ok(1));
+			assert_eq!(Bounded::get().unwrap(), vec![1, 2, 3]);
+		});
+	}
+
+	#[test]
+	fn migrate_to_bounded_vec_applies_the_overflow_policy() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let db_weight = TestDbWeight::get();
+
+			put_unbounded(vec![1, 2, 3, 4, 5]);
+			assert_eq!(
+				migrate_to_bounded_vec::<Bounded, _, _, FailOnOverflow>(&db_weight),
+				Err("Storage value exceeds its new bound")
+			);
+			assert_eq!(
+				unhashed::get::<Vec<u32>>(&Bounded::hashed_key()),
+				Some(vec![1, 2, 3, 4, 5])
+			);
+
+			assert_eq!(migrate_to_bounded_vec::<Bounded, _, _, Truncate>(&db_weight), Ok(11));
+			assert_eq!(Bounded::get().unwrap(), vec![1, 2, 3]);
+			assert_eq!(Spilled::iter().count(), 0);
+
+			put_unbounded(vec![1, 2, 3, 4, 5]);
+			assert_eq!(
+				migrate_to_bounded_vec::<Bounded, _, _, SpillInto<Spilled>>(&db_weight),
+				Ok(11 + 20)
+			);
+			assert_eq!(Bounded::get().unwrap(), vec![1, 2, 3]);
+			assert_eq!(Spilled::get(0), Some(4));
+			assert_eq!(Spilled::get(1), Some(5));
+			assert_eq!(Spilled::get(2), None);
+		});
+	}
+}
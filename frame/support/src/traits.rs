@@ -22,8 +22,8 @@
 pub mod tokens;
 pub use tokens::{
 	currency::{
-		Currency, LockIdentifier, LockableCurrency, NamedReservableCurrency, ReservableCurrency,
-		VestingSchedule,
+		BalanceRestriction, Currency, InspectBalanceRestrictions, LockIdentifier, LockableCurrency,
+		NamedReservableCurrency, ReservableCurrency, VestingSchedule,
 	},
 	fungible, fungibles,
 	imbalance::{Imbalance, OnUnbalanced, SignedImbalance},
@@ -55,6 +55,9 @@ pub use misc::{
 	GetBacking, GetDefault, HandleLifetime, IsSubType, IsType, Len, OffchainWorker,
 	OnKilledAccount, OnNewAccount, SameOrOther, Time, TryDrop, UnixTime,
 };
+/// Pallets should use this to read the block number that drives their logic, so that a runtime
+/// can substitute another source than `frame_system`, e.g. the relay chain on a parachain.
+pub use sp_runtime::traits::BlockNumberProvider;
 
 mod stored_map;
 pub use stored_map::{StorageMapShim, StoredMap};
@@ -63,15 +66,16 @@ pub use randomness::Randomness;
 
 mod metadata;
 pub use metadata::{
-	CallMetadata, GetCallMetadata, GetCallName, GetStorageVersion, PalletInfo, PalletInfoAccess,
-	StorageVersion, STORAGE_VERSION_STORAGE_KEY_POSTFIX,
+	CallMetadata, GetCallMetadata, GetCallName, GetStorageVersion, GetStorageVersions, PalletInfo,
+	PalletInfoAccess, PalletStorageVersion, StorageVersion, STORAGE_VERSION_STORAGE_KEY_POSTFIX,
 };
 
 mod hooks;
 #[cfg(feature = "std")]
 pub use hooks::GenesisBuild;
 pub use hooks::{
-	Hooks, OnFinalize, OnGenesis, OnIdle, OnInitialize, OnRuntimeUpgrade, OnTimestampSet,
+	CheckInvariants, Hooks, OnFinalize, OnGenesis, OnIdle, OnInitialize, OnRuntimeUpgrade,
+	OnTimestampSet,
 };
 #[cfg(feature = "try-runtime")]
 pub use hooks::{OnRuntimeUpgradeHelpersExt, ON_RUNTIME_UPGRADE_PREFIX};
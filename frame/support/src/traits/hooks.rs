@@ -20,6 +20,7 @@
 use impl_trait_for_tuples::impl_for_tuples;
 use sp_arithmetic::traits::Saturating;
 use sp_runtime::traits::{AtLeast32BitUnsigned, MaybeSerializeDeserialize};
+use sp_std::prelude::*;
 
 /// The block initialization trait.
 ///
@@ -111,6 +112,24 @@ pub trait OnGenesis {
 	fn on_genesis() {}
 }
 
+/// The invariants of the state of a pallet, which can be checked at any point in time.
+///
+/// Unlike the `try-runtime` state checks, these are available in all builds, so that for instance
+/// development chains can check them periodically.
+pub trait CheckInvariants {
+	/// Check the invariants, returning a description of every one which is violated.
+	fn check_invariants() -> Vec<&'static str>;
+}
+
+#[impl_for_tuples(30)]
+impl CheckInvariants for Tuple {
+	fn check_invariants() -> Vec<&'static str> {
+		let mut violations = Vec::new();
+		for_tuples!( #( violations.extend(Tuple::check_invariants()); )* );
+		violations
+	}
+}
+
 /// Prefix to be used (optionally) for implementing [`OnRuntimeUpgradeHelpersExt::storage_key`].
 #[cfg(feature = "try-runtime")]
 pub const ON_RUNTIME_UPGRADE_PREFIX: &[u8] = b"__ON_RUNTIME_UPGRADE__";
@@ -18,7 +18,11 @@
 //! Traits for managing information attached to pallets and their constituents.
 
 use codec::{Decode, Encode};
+use impl_trait_for_tuples::impl_for_tuples;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
 
 /// Provides information about the pallet setup in the runtime.
 ///
@@ -79,6 +83,7 @@ pub const STORAGE_VERSION_STORAGE_KEY_POSTFIX: &[u8] = b":__STORAGE_VERSION__:";
 /// Each storage version of a pallet is stored in the state under a fixed key. See
 /// [`STORAGE_VERSION_STORAGE_KEY_POSTFIX`] for how this key is built.
 #[derive(RuntimeDebug, Eq, PartialEq, Encode, Decode, Ord, Clone, Copy, PartialOrd, Default)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct StorageVersion(u16);
 
 impl StorageVersion {
@@ -170,6 +175,54 @@ pub trait GetStorageVersion {
 	fn on_chain_storage_version() -> StorageVersion;
 }
 
+/// The storage versions of a pallet, as listed by [`GetStorageVersions`].
+#[derive(RuntimeDebug, Eq, PartialEq, Encode, Decode, Clone)]
+pub struct PalletStorageVersion {
+	/// Name of the pallet as configured in the runtime.
+	pub pallet_name: Vec<u8>,
+	/// The storage version as supported by the pallet.
+	pub current: StorageVersion,
+	/// The storage version of the pallet in the storage.
+	pub on_chain: StorageVersion,
+}
+
+impl PalletStorageVersion {
+	/// The storage versions of the pallet `P`.
+	pub fn of<P: GetStorageVersion + PalletInfoAccess>() -> Self {
+		Self {
+			pallet_name: P::name().as_bytes().to_vec(),
+			current: P::current_storage_version(),
+			on_chain: P::on_chain_storage_version(),
+		}
+	}
+
+	/// Whether the storage of the pallet still has to be migrated to its current version.
+	pub fn is_migration_pending(&self) -> bool {
+		self.on_chain < self.current
+	}
+}
+
+/// Lists the storage versions of a set of pallets, like `AllPalletsWithSystem`.
+pub trait GetStorageVersions {
+	/// Returns the storage versions of all pallets of the set.
+	fn storage_versions() -> Vec<PalletStorageVersion>;
+}
+
+impl<P: GetStorageVersion + PalletInfoAccess> GetStorageVersions for P {
+	fn storage_versions() -> Vec<PalletStorageVersion> {
+		vec![PalletStorageVersion::of::<P>()]
+	}
+}
+
+#[impl_for_tuples(30)]
+impl GetStorageVersions for Tuple {
+	fn storage_versions() -> Vec<PalletStorageVersion> {
+		let mut versions = Vec::new();
+		for_tuples!( #( versions.extend(Tuple::storage_versions()); )* );
+		versions
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -186,4 +239,65 @@ mod tests {
 		assert!(version > StorageVersion::new(1));
 		assert!(version < StorageVersion::new(5));
 	}
+
+	struct Migrated;
+	struct NotMigrated;
+
+	impl GetStorageVersion for Migrated {
+		fn current_storage_version() -> StorageVersion {
+			StorageVersion::new(2)
+		}
+		fn on_chain_storage_version() -> StorageVersion {
+			StorageVersion::new(2)
+		}
+	}
+
+	impl PalletInfoAccess for Migrated {
+		fn index() -> usize {
+			0
+		}
+		fn name() -> &'static str {
+			"Migrated"
+		}
+	}
+
+	impl GetStorageVersion for NotMigrated {
+		fn current_storage_version() -> StorageVersion {
+			StorageVersion::new(5)
+		}
+		fn on_chain_storage_version() -> StorageVersion {
+			StorageVersion::new(4)
+		}
+	}
+
+	impl PalletInfoAccess for NotMigrated {
+		fn index() -> usize {
+			1
+		}
+		fn name() -> &'static str {
+			"NotMigrated"
+		}
+	}
+
+	#[test]
+	fn storage_versions_of_nested_tuples_are_listed() {
+		let versions = <(NotMigrated, (Migrated, ()))>::storage_versions();
+		assert_eq!(
+			versions,
+			vec![
+				PalletStorageVersion {
+					pallet_name: b"NotMigrated".to_vec(),
+					current: StorageVersion::new(5),
+					on_chain: StorageVersion::new(4),
+				},
+				PalletStorageVersion {
+					pallet_name: b"Migrated".to_vec(),
+					current: StorageVersion::new(2),
+					on_chain: StorageVersion::new(2),
+				},
+			],
+		);
+		assert!(versions[0].is_migration_pending());
+		assert!(!versions[1].is_migration_pending());
+	}
 }
@@ -29,7 +29,10 @@ use sp_std::fmt::Debug;
 mod reservable;
 pub use reservable::{NamedReservableCurrency, ReservableCurrency};
 mod lockable;
-pub use lockable::{LockIdentifier, LockableCurrency, VestingSchedule};
+pub use lockable::{
+	BalanceRestriction, InspectBalanceRestrictions, LockIdentifier, LockableCurrency,
+	VestingSchedule,
+};
 
 /// Abstraction over a fungible assets system.
 pub trait Currency<AccountId> {
@@ -19,6 +19,12 @@
 
 use super::{super::misc::WithdrawReasons, Currency};
 use crate::{dispatch::DispatchResult, traits::misc::Get};
+use codec::{Decode, Encode};
+use sp_runtime::{
+	traits::{One, Zero},
+	RuntimeDebug,
+};
+use sp_std::vec::Vec;
 
 /// An identifier for a lock. Used for disambiguating different locks so that
 /// they can be individually replaced or removed.
@@ -64,6 +70,45 @@ pub trait LockableCurrency<AccountId>: Currency<AccountId> {
 	fn remove_lock(id: LockIdentifier, who: &AccountId);
 }
 
+/// Something restricting the use of the balance of an account.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum BalanceRestriction<Balance, ReserveIdentifier> {
+	/// A lock, e.g. by the vesting pallet. Locks overlap rather than add up, so the free balance
+	/// which cannot be withdrawn is the largest amount of all locks.
+	Lock {
+		/// The identifier of the lock.
+		id: LockIdentifier,
+		/// The amount the free balance may not drop below.
+		amount: Balance,
+	},
+	/// A named reserve.
+	NamedReserve {
+		/// The identifier of the reserve.
+		id: ReserveIdentifier,
+		/// The reserved amount.
+		amount: Balance,
+	},
+	/// The reserved balance which is not part of any named reserve, e.g. election bonds.
+	AnonymousReserve {
+		/// The reserved amount.
+		amount: Balance,
+	},
+}
+
+/// A currency whose locks and reserves can be enumerated per account.
+///
+/// This allows to explain why funds of an account cannot be transferred without decoding the
+/// storage of the individual pallets holding them.
+pub trait InspectBalanceRestrictions<AccountId>: Currency<AccountId> {
+	/// The identifier of named reserves.
+	type ReserveIdentifier;
+
+	/// All active locks and reserves on the account of `who`.
+	fn balance_restrictions(
+		who: &AccountId,
+	) -> Vec<BalanceRestriction<Self::Balance, Self::ReserveIdentifier>>;
+}
+
 /// A vesting schedule over a currency. This allows a particular currency to have vesting limits
 /// applied to it.
 pub trait VestingSchedule<AccountId> {
@@ -93,8 +138,48 @@ pub trait VestingSchedule<AccountId> {
 		starting_block: Self::Moment,
 	) -> DispatchResult;
 
+	/// Checks if `add_vesting_schedule` would work against `who`, without adding the schedule.
+	fn can_add_vesting_schedule(
+		who: &AccountId,
+		locked: <Self::Currency as Currency<AccountId>>::Balance,
+		per_block: <Self::Currency as Currency<AccountId>>::Balance,
+		starting_block: Self::Moment,
+	) -> DispatchResult;
+
+	/// Adds a vesting schedule to a given account, merging it into the existing vesting schedule
+	/// of the account, if any.
+	///
+	/// The merged schedule locks what both schedules still lock, and is fully vested when the
+	/// later of them would have been.
+	///
+	/// Is a no-op if the amount to be vested is zero.
+	///
+	/// NOTE: This doesn't alter the free balance of the account.
+	fn merge_vesting_schedule(
+		who: &AccountId,
+		locked: <Self::Currency as Currency<AccountId>>::Balance,
+		per_block: <Self::Currency as Currency<AccountId>>::Balance,
+		starting_block: Self::Moment,
+	) -> DispatchResult;
+
 	/// Remove a vesting schedule for a given account.
 	///
 	/// NOTE: This doesn't alter the free balance of the account.
 	fn remove_vesting_schedule(who: &AccountId);
+
+	/// The amount to unlock per block for `locked` to be fully vested after `length` blocks.
+	///
+	/// Rounded up, so that `locked` is never vested over more than `length` blocks. A `length` of
+	/// zero is treated as a single block.
+	fn per_block_for(
+		locked: <Self::Currency as Currency<AccountId>>::Balance,
+		length: <Self::Currency as Currency<AccountId>>::Balance,
+	) -> <Self::Currency as Currency<AccountId>>::Balance {
+		let length = length.max(One::one());
+		if (locked % length).is_zero() {
+			locked / length
+		} else {
+			locked / length + One::one()
+		}
+	}
 }
@@ -23,6 +23,9 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub use frame_support::traits::{PalletStorageVersion, StorageVersion};
+use sp_std::prelude::*;
+
 sp_api::decl_runtime_apis! {
 	/// The API to query account nonce (aka transaction index).
 	pub trait AccountNonceApi<AccountId, Index> where
@@ -32,4 +35,10 @@ sp_api::decl_runtime_apis! {
 		/// Get current account nonce of given `AccountId`.
 		fn account_nonce(account: AccountId) -> Index;
 	}
+
+	/// The API to query the storage versions of the pallets.
+	pub trait StorageVersionApi {
+		/// Get the current and on-chain storage versions of every pallet of the runtime.
+		fn storage_versions() -> Vec<PalletStorageVersion>;
+	}
 }
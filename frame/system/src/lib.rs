@@ -1635,6 +1635,11 @@ impl<T: Config> BlockNumberProvider for Pallet<T> {
 	fn current_block_number() -> Self::BlockNumber {
 		Pallet::<T>::block_number()
 	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_block_number(block: Self::BlockNumber) {
+		Pallet::<T>::set_block_number(block)
+	}
 }
 
 fn is_providing<T: Default + Eq>(d: &T) -> bool {
@@ -0,0 +1,77 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RPC interface to dry-run extrinsics, to estimate their actual weight and fee.
+
+pub use self::gen_client::Client as TransactionDryRunClient;
+use codec::{Codec, Decode};
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use pallet_transaction_payment_rpc_runtime_api::DryRunInfo;
+pub use pallet_transaction_payment_rpc_runtime_api::TransactionDryRunApi as TransactionDryRunRuntimeApi;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, MaybeDisplay},
+};
+
+use crate::{Error, TransactionPayment};
+
+#[rpc]
+pub trait TransactionDryRunApi<BlockHash, ResponseType> {
+	/// Dry-run the given extrinsic on top of the state of block `at`, or of the best block.
+	///
+	/// Returns the outcome of the extrinsic, its weight and fee once corrected by the dispatch,
+	/// and the events it deposited. Nothing is committed to the state.
+	#[rpc(name = "payment_dryRun")]
+	fn dry_run(&self, encoded_xt: Bytes, at: Option<BlockHash>) -> Result<ResponseType>;
+}
+
+impl<C, Block, Balance> TransactionDryRunApi<<Block as BlockT>::Hash, DryRunInfo<Balance>>
+	for TransactionPayment<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: TransactionDryRunRuntimeApi<Block, Balance>,
+	Balance: Codec + MaybeDisplay,
+{
+	fn dry_run(
+		&self,
+		encoded_xt: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<DryRunInfo<Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let encoded_len = encoded_xt.len() as u32;
+
+		let uxt: Block::Extrinsic = Decode::decode(&mut &*encoded_xt).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::DecodeError.into()),
+			message: "Unable to dry-run the extrinsic.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})?;
+		api.dry_run(&at, uxt, encoded_len).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to dry-run the extrinsic.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
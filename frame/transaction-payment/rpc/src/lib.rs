@@ -33,6 +33,10 @@ use sp_runtime::{
 };
 use std::{convert::TryInto, sync::Arc};
 
+mod dry_run;
+
+pub use dry_run::{TransactionDryRunApi, TransactionDryRunClient, TransactionDryRunRuntimeApi};
+
 #[rpc]
 pub trait TransactionPaymentApi<BlockHash, ResponseType> {
 	#[rpc(name = "payment_queryInfo")]
@@ -49,6 +49,7 @@
 
 use codec::{Decode, Encode};
 
+use sp_core::Bytes;
 use sp_runtime::{
 	traits::{
 		Convert, DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SaturatedConversion, Saturating,
@@ -57,9 +58,10 @@ use sp_runtime::{
 	transaction_validity::{
 		TransactionPriority, TransactionValidity, TransactionValidityError, ValidTransaction,
 	},
-	FixedPointNumber, FixedPointOperand, FixedU128, Perquintill, RuntimeDebug,
+	ApplyExtrinsicResult, FixedPointNumber, FixedPointOperand, FixedU128, Perquintill,
+	RuntimeDebug,
 };
-use sp_std::prelude::*;
+use sp_std::{convert::TryInto, prelude::*};
 
 use frame_support::{
 	dispatch::DispatchResult,
@@ -75,7 +77,7 @@ mod types;
 
 pub use pallet::*;
 pub use payment::*;
-pub use types::{FeeDetails, InclusionFee, RuntimeDispatchInfo};
+pub use types::{DryRunInfo, FeeDetails, InclusionFee, RuntimeDispatchInfo};
 
 /// Fee multiplier.
 pub type Multiplier = FixedU128;
@@ -408,6 +410,51 @@ where
 		Self::compute_fee_details(len, &dispatch_info, 0u32.into())
 	}
 
+	/// Dry-run the given `unchecked_extrinsic` with `apply`, which is usually
+	/// `Executive::apply_extrinsic`.
+	///
+	/// Unlike [`Self::query_info`], the returned weight and fee are the ones after dispatch, and
+	/// the events deposited by the extrinsic are returned as well. The events of the block are
+	/// reset beforehand, hence this must only be called on state which is discarded afterwards,
+	/// like the state of a runtime API call.
+	pub fn query_dry_run<Extrinsic: GetDispatchInfo>(
+		unchecked_extrinsic: Extrinsic,
+		len: u32,
+		apply: impl FnOnce(Extrinsic) -> ApplyExtrinsicResult,
+	) -> DryRunInfo<BalanceOf<T>>
+	where
+		T::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+		<T as frame_system::Config>::Event: TryInto<frame_system::Event<T>>,
+	{
+		let dispatch_info = <Extrinsic as GetDispatchInfo>::get_dispatch_info(&unchecked_extrinsic);
+
+		<frame_system::Pallet<T>>::reset_events();
+		let result = apply(unchecked_extrinsic);
+		let events = <frame_system::Pallet<T>>::events()
+			.into_iter()
+			.map(|record| record.event)
+			.collect::<Vec<_>>();
+
+		// The last event of an applied extrinsic holds its weight after dispatch.
+		let actual_weight = events.last().cloned().and_then(|event| match event.try_into() {
+			Ok(frame_system::Event::ExtrinsicSuccess(info)) |
+			Ok(frame_system::Event::ExtrinsicFailed(_, info)) => Some(info.weight),
+			_ => None,
+		});
+		let actual_fee = actual_weight.map_or_else(Zero::zero, |weight| {
+			let post_info =
+				PostDispatchInfo { actual_weight: Some(weight), pays_fee: dispatch_info.pays_fee };
+			Self::compute_actual_fee(len, &dispatch_info, &post_info, Zero::zero())
+		});
+
+		DryRunInfo {
+			result,
+			actual_weight: actual_weight.unwrap_or_default(),
+			actual_fee,
+			events: events.iter().map(|event| Bytes(event.encode())).collect(),
+		}
+	}
+
 	/// Compute the final fee value for a particular transaction.
 	pub fn compute_fee(len: u32, info: &DispatchInfoOf<T::Call>, tip: BalanceOf<T>) -> BalanceOf<T>
 	where
@@ -1078,6 +1125,55 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn query_dry_run_works() {
+		let call = Call::Balances(BalancesCall::transfer(2, 69));
+		let xt = TestXt::new(call, Some((111111, ())));
+		let info = xt.get_dispatch_info();
+		let len = xt.encode().len() as u32;
+		ExtBuilder::default().base_weight(5).weight_fee(2).build().execute_with(|| {
+			System::set_block_number(1);
+			System::deposit_event(system::Event::NewAccount(1));
+
+			// The dispatch refunds half of its weight.
+			let actual_info = DispatchInfo { weight: info.weight / 2, ..info };
+			let dry_run = TransactionPayment::query_dry_run(xt.clone(), len, |xt| {
+				System::note_applied_extrinsic(
+					&Ok(post_info_from_weight(info.weight / 2)),
+					xt.get_dispatch_info(),
+				);
+				Ok(Ok(()))
+			});
+			assert_eq!(
+				dry_run,
+				DryRunInfo {
+					result: Ok(Ok(())),
+					actual_weight: info.weight / 2,
+					actual_fee: 5 * 2 /* base * weight_fee */
+						+ len as u64 /* len * 1 */
+						+ info.weight / 2 * 2, /* actual weight * weight_fee */
+					events: vec![Bytes(
+						Event::System(system::Event::ExtrinsicSuccess(actual_info)).encode()
+					)],
+				},
+			);
+
+			// Nothing is charged for an extrinsic which cannot be applied.
+			let dry_run = TransactionPayment::query_dry_run(xt, len, |_| {
+				Err(InvalidTransaction::Payment.into())
+			});
+			assert_eq!(
+				dry_run,
+				DryRunInfo {
+					result: Err(InvalidTransaction::Payment.into()),
+					actual_weight: 0,
+					actual_fee: 0,
+					events: vec![],
+				},
+			);
+		});
+	}
+
 	#[test]
 	fn compute_fee_works_without_multiplier() {
 		ExtBuilder::default()
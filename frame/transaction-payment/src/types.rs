@@ -21,7 +21,11 @@ use codec::{Decode, Encode};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
-use sp_runtime::traits::{AtLeast32BitUnsigned, Zero};
+use sp_core::Bytes;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, Zero},
+	ApplyExtrinsicResult,
+};
 use sp_std::prelude::*;
 
 use frame_support::weights::{DispatchClass, Weight};
@@ -107,6 +111,28 @@ pub struct RuntimeDispatchInfo<Balance> {
 	pub partial_fee: Balance,
 }
 
+/// The outcome of dry-running an extrinsic, as queried from the runtime.
+#[derive(Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+pub struct DryRunInfo<Balance> {
+	/// The outcome of applying the extrinsic.
+	pub result: ApplyExtrinsicResult,
+	/// Weight of the dispatch, once corrected by the dispatch itself.
+	///
+	/// Zero if the extrinsic could not be applied.
+	pub actual_weight: Weight,
+	/// The inclusion fee charged for the dispatch, once its weight is corrected.
+	///
+	/// Zero if the extrinsic could not be applied. This does not include a tip.
+	#[cfg_attr(feature = "std", serde(with = "serde_balance"))]
+	pub actual_fee: Balance,
+	/// The events deposited by the extrinsic, each SCALE-encoded.
+	pub events: Vec<Bytes>,
+}
+
 #[cfg(feature = "std")]
 mod serde_balance {
 	use serde::{Deserialize, Deserializer, Serializer};
@@ -163,4 +189,19 @@ mod tests {
 		// should not panic
 		serde_json::to_value(&info).unwrap();
 	}
+
+	#[test]
+	fn should_serialize_and_deserialize_dry_run_info() {
+		let info = DryRunInfo {
+			result: Ok(Ok(())),
+			actual_weight: 5,
+			actual_fee: u128::max_value(),
+			events: vec![Bytes(vec![1, 2]), Bytes(vec![3])],
+		};
+
+		let json_str = r#"{"result":{"Ok":{"Ok":null}},"actualWeight":5,"actualFee":"340282366920938463463374607431768211455","events":["0x0102","0x03"]}"#;
+
+		assert_eq!(serde_json::to_string(&info).unwrap(), json_str);
+		assert_eq!(serde_json::from_str::<DryRunInfo<u128>>(json_str).unwrap(), info);
+	}
 }
@@ -0,0 +1,279 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Vested Rewards Pallet
+//!
+//! - [`Config`]
+//! - [`VestedRewardCurrency`]
+//!
+//! ## Overview
+//!
+//! A pallet which pays out rewards as vesting schedules instead of liquid balance, for chains that
+//! want reward lockups without forking the pallet paying the rewards, e.g. `pallet-staking`.
+//!
+//! Rewards are intercepted by [`VestedRewardCurrency`], a currency adapter wrapping
+//! [`Config::Currency`]. It is used as the `Currency` of the rewarding pallet and forwards every
+//! operation to the wrapped currency, except that funds minted into an account through
+//! `deposit_creating` and `deposit_into_existing` are locked under a vesting schedule created
+//! through the [`VestingSchedule`] trait. `pallet-staking` only mints funds this way when paying
+//! out era rewards and slash reporter rewards.
+//!
+//! Each reward vests linearly over [`Config::VestingPeriod`], starting at the block it was paid.
+//! If the account is still vesting, the amount which is still locked is merged with the new reward
+//! into a single schedule with [`VestingSchedule::merge_vesting_schedule`], which vests until the
+//! later of the end of the existing schedule and the end of the period. If the reward cannot be
+//! vested, it is left liquid and `RewardNotVested` is emitted.
+//!
+//! Note that the vesting operations are not accounted for in the weight of the calls paying out
+//! the rewards.
+//!
+//! ## Usage
+//!
+//! ```ignore
+//! impl pallet_staking::Config for Runtime {
+//! 	type Currency = pallet_vested_rewards::VestedRewardCurrency<Runtime>;
+//! 	// ...
+//! }
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{
+		BlockNumberProvider, Currency, ExistenceRequirement, Imbalance, LockIdentifier,
+		LockableCurrency, SignedImbalance, VestingSchedule, WithdrawReasons,
+	},
+};
+pub use pallet::*;
+use sp_runtime::{
+	traits::{Convert, Zero},
+	DispatchError, DispatchResult,
+};
+use sp_std::marker::PhantomData;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type PositiveImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::PositiveImbalance;
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency rewards are paid in.
+		type Currency: LockableCurrency<Self::AccountId, Moment = Self::BlockNumber>;
+
+		/// The vesting schedule used to lock rewards.
+		type VestingSchedule: VestingSchedule<
+			Self::AccountId,
+			Moment = Self::BlockNumber,
+			Currency = Self::Currency,
+		>;
+
+		/// Convert the block number into a balance.
+		type BlockNumberToBalance: Convert<Self::BlockNumber, BalanceOf<Self>>;
+
+		/// The block number provider, which must match the one used by `VestingSchedule`.
+		type BlockNumberProvider: BlockNumberProvider<BlockNumber = Self::BlockNumber>;
+
+		/// The number of blocks over which a reward vests.
+		#[pallet::constant]
+		type VestingPeriod: Get<Self::BlockNumber>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn integrity_test() {
+			assert!(!T::VestingPeriod::get().is_zero(), "VestingPeriod must not be zero");
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
+	pub enum Event<T: Config> {
+		/// A reward was locked under a vesting schedule. \[who, reward, total_locked\]
+		RewardVested(T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// A reward was left liquid, as it could not be merged into the vesting schedule of the
+		/// account. \[who, reward\]
+		RewardNotVested(T::AccountId, BalanceOf<T>),
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Lock `reward`, which was just deposited to `who`, under a vesting schedule.
+	///
+	/// The reward is merged into the existing vesting schedule of `who`, if any.
+	pub fn vest_reward(who: &T::AccountId, reward: BalanceOf<T>) {
+		if reward.is_zero() {
+			return
+		}
+		let now = T::BlockNumberProvider::current_block_number();
+		let per_block = T::VestingSchedule::per_block_for(
+			reward,
+			T::BlockNumberToBalance::convert(T::VestingPeriod::get()),
+		);
+		if T::VestingSchedule::merge_vesting_schedule(who, reward, per_block, now).is_err() {
+			Self::deposit_event(Event::<T>::RewardNotVested(who.clone(), reward));
+			return
+		}
+
+		let locked = T::VestingSchedule::vesting_balance(who).unwrap_or_else(Zero::zero);
+		Self::deposit_event(Event::<T>::RewardVested(who.clone(), reward, locked));
+	}
+}
+
+/// A currency adapter locking all funds minted into accounts under vesting schedules.
+///
+/// All operations are forwarded to [`Config::Currency`]. Funds deposited through
+/// `deposit_creating` and `deposit_into_existing` are additionally vested through
+/// [`Pallet::vest_reward`].
+pub struct VestedRewardCurrency<T>(PhantomData<T>);
+
+impl<T: Config> Currency<T::AccountId> for VestedRewardCurrency<T> {
+	type Balance = BalanceOf<T>;
+	type PositiveImbalance = PositiveImbalanceOf<T>;
+	type NegativeImbalance = NegativeImbalanceOf<T>;
+
+	fn total_balance(who: &T::AccountId) -> Self::Balance {
+		T::Currency::total_balance(who)
+	}
+
+	fn can_slash(who: &T::AccountId, value: Self::Balance) -> bool {
+		T::Currency::can_slash(who, value)
+	}
+
+	fn total_issuance() -> Self::Balance {
+		T::Currency::total_issuance()
+	}
+
+	fn minimum_balance() -> Self::Balance {
+		T::Currency::minimum_balance()
+	}
+
+	fn burn(amount: Self::Balance) -> Self::PositiveImbalance {
+		T::Currency::burn(amount)
+	}
+
+	fn issue(amount: Self::Balance) -> Self::NegativeImbalance {
+		T::Currency::issue(amount)
+	}
+
+	fn free_balance(who: &T::AccountId) -> Self::Balance {
+		T::Currency::free_balance(who)
+	}
+
+	fn ensure_can_withdraw(
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+		new_balance: Self::Balance,
+	) -> DispatchResult {
+		T::Currency::ensure_can_withdraw(who, amount, reasons, new_balance)
+	}
+
+	fn transfer(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		value: Self::Balance,
+		existence_requirement: ExistenceRequirement,
+	) -> DispatchResult {
+		T::Currency::transfer(source, dest, value, existence_requirement)
+	}
+
+	fn slash(who: &T::AccountId, value: Self::Balance) -> (Self::NegativeImbalance, Self::Balance) {
+		T::Currency::slash(who, value)
+	}
+
+	fn deposit_into_existing(
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> Result<Self::PositiveImbalance, DispatchError> {
+		let imbalance = T::Currency::deposit_into_existing(who, value)?;
+		Pallet::<T>::vest_reward(who, imbalance.peek());
+		Ok(imbalance)
+	}
+
+	fn deposit_creating(who: &T::AccountId, value: Self::Balance) -> Self::PositiveImbalance {
+		let imbalance = T::Currency::deposit_creating(who, value);
+		Pallet::<T>::vest_reward(who, imbalance.peek());
+		imbalance
+	}
+
+	fn withdraw(
+		who: &T::AccountId,
+		value: Self::Balance,
+		reasons: WithdrawReasons,
+		liveness: ExistenceRequirement,
+	) -> Result<Self::NegativeImbalance, DispatchError> {
+		T::Currency::withdraw(who, value, reasons, liveness)
+	}
+
+	fn make_free_balance_be(
+		who: &T::AccountId,
+		balance: Self::Balance,
+	) -> SignedImbalance<Self::Balance, Self::PositiveImbalance> {
+		T::Currency::make_free_balance_be(who, balance)
+	}
+}
+
+impl<T: Config> LockableCurrency<T::AccountId> for VestedRewardCurrency<T> {
+	type Moment = T::BlockNumber;
+	type MaxLocks = <T::Currency as LockableCurrency<T::AccountId>>::MaxLocks;
+
+	fn set_lock(
+		id: LockIdentifier,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) {
+		T::Currency::set_lock(id, who, amount, reasons)
+	}
+
+	fn extend_lock(
+		id: LockIdentifier,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) {
+		T::Currency::extend_lock(id, who, amount, reasons)
+	}
+
+	fn remove_lock(id: LockIdentifier, who: &T::AccountId) {
+		T::Currency::remove_lock(id, who)
+	}
+}
@@ -0,0 +1,167 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::{assert_noop, assert_ok, traits::VestingSchedule as _};
+use pallet_vesting::VestingInfo;
+
+use super::*;
+use crate::mock::{
+	new_test_ext, Balances, Event as TestEvent, RewardCurrency, System, Test, Vesting,
+};
+
+#[test]
+fn deposits_are_vested() {
+	new_test_ext().execute_with(|| {
+		let _ = RewardCurrency::deposit_creating(&5, 300);
+
+		assert_eq!(Balances::free_balance(&5), 300);
+		assert_eq!(
+			Vesting::vesting(&5),
+			Some(VestingInfo {
+				locked: 300,
+				per_block: 10,
+				starting_block: 1,
+				cliff_block: None,
+				remainder: 0,
+			})
+		);
+		System::assert_last_event(TestEvent::VestedRewards(Event::RewardVested(5, 300, 300)));
+
+		assert_ok!(RewardCurrency::deposit_into_existing(&2, 60));
+		assert_eq!(Balances::free_balance(&2), 70);
+		assert_eq!(Vesting::vesting_balance(&2), Some(60));
+		System::assert_last_event(TestEvent::VestedRewards(Event::RewardVested(2, 60, 60)));
+	});
+}
+
+#[test]
+fn rewards_are_vested_within_the_period() {
+	new_test_ext().execute_with(|| {
+		// 299 does not divide into 30 blocks, the amount per block is rounded up.
+		let _ = RewardCurrency::deposit_creating(&5, 299);
+		assert_eq!(
+			Vesting::vesting(&5),
+			Some(VestingInfo {
+				locked: 299,
+				per_block: 10,
+				starting_block: 1,
+				cliff_block: None,
+				remainder: 0,
+			})
+		);
+
+		System::set_block_number(30);
+		assert_eq!(Vesting::vesting_balance(&5), Some(9));
+		System::set_block_number(31);
+		assert_eq!(Vesting::vesting_balance(&5), Some(0));
+	});
+}
+
+#[test]
+fn vested_rewards_cannot_be_transferred() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(RewardCurrency::deposit_into_existing(&2, 300));
+
+		assert_noop!(
+			RewardCurrency::transfer(&2, &3, 20, ExistenceRequirement::AllowDeath),
+			pallet_balances::Error::<Test, _>::LiquidityRestrictions,
+		);
+		assert_ok!(RewardCurrency::transfer(&2, &3, 10, ExistenceRequirement::AllowDeath));
+	});
+}
+
+#[test]
+fn failed_deposits_are_not_vested() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			RewardCurrency::deposit_into_existing(&5, 300),
+			pallet_balances::Error::<Test, _>::DeadAccount,
+		);
+		let _ = RewardCurrency::deposit_creating(&5, 0);
+		assert_eq!(Vesting::vesting(&5), None);
+	});
+}
+
+#[test]
+fn rewards_are_merged() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(RewardCurrency::deposit_into_existing(&2, 300));
+
+		System::set_block_number(16);
+		assert_eq!(Vesting::vesting_balance(&2), Some(150));
+		assert_ok!(RewardCurrency::deposit_into_existing(&2, 150));
+
+		assert_eq!(Balances::free_balance(&2), 460);
+		assert_eq!(
+			Vesting::vesting(&2),
+			Some(VestingInfo {
+				locked: 300,
+				per_block: 10,
+				starting_block: 16,
+				cliff_block: None,
+				remainder: 0,
+			})
+		);
+		System::assert_last_event(TestEvent::VestedRewards(Event::RewardVested(2, 150, 300)));
+	});
+}
+
+#[test]
+fn fully_vested_schedules_are_replaced() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Vesting::add_vesting_schedule(&2, 10, 1, 1));
+		assert_ok!(RewardCurrency::deposit_into_existing(&3, 300));
+
+		System::set_block_number(31);
+		assert_ok!(RewardCurrency::deposit_into_existing(&2, 60));
+		assert_ok!(RewardCurrency::deposit_into_existing(&3, 60));
+
+		for who in &[2, 3] {
+			assert_eq!(
+				Vesting::vesting(who),
+				Some(VestingInfo {
+					locked: 60,
+					per_block: 2,
+					starting_block: 31,
+					cliff_block: None,
+					remainder: 0,
+				})
+			);
+		}
+	});
+}
+
+#[test]
+fn rewards_are_merged_into_other_schedules() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Vesting::add_vesting_schedule(&2, 10, 1, 1));
+
+		assert_ok!(RewardCurrency::deposit_into_existing(&2, 60));
+		assert_eq!(Balances::free_balance(&2), 70);
+		assert_eq!(
+			Vesting::vesting(&2),
+			Some(VestingInfo {
+				locked: 70,
+				per_block: 2,
+				starting_block: 1,
+				cliff_block: None,
+				remainder: 10,
+			})
+		);
+		System::assert_last_event(TestEvent::VestedRewards(Event::RewardVested(2, 60, 70)));
+	});
+}
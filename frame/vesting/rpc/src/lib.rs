@@ -0,0 +1,204 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RPC interface for the vesting pallet.
+
+use std::{convert::TryInto, fmt::Display, sync::Arc};
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Saturating, Zero},
+};
+
+pub use self::gen_client::Client as VestingClient;
+pub use pallet_vesting_runtime_api::VestingApi as VestingRuntimeApi;
+
+/// A vesting schedule, with balances which fit into JSON.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VestingScheduleInfo<BlockNumber> {
+	/// The amount locked by the schedule at its start.
+	pub locked: NumberOrHex,
+	/// The amount unlocked every block after the start.
+	pub per_block: NumberOrHex,
+	/// The block at which the schedule starts unlocking funds.
+	pub starting_block: BlockNumber,
+	/// The block before which the schedule does not unlock any funds, if any.
+	pub cliff_block: Option<BlockNumber>,
+}
+
+/// Vesting RPC methods.
+#[rpc]
+pub trait VestingApi<BlockHash, AccountId, Balance, BlockNumber> {
+	/// The amount of the account of `who` which is still locked by vesting at block
+	/// `block_number`, or `None` if the account is not vesting.
+	///
+	/// `block_number` may be in the future, to compute when the funds unlock. The schedules are
+	/// read from the state of block `at`, the best block by default.
+	#[rpc(name = "vesting_lockedAt")]
+	fn locked_at(
+		&self,
+		who: AccountId,
+		block_number: BlockNumber,
+		at: Option<BlockHash>,
+	) -> Result<Option<NumberOrHex>>;
+
+	/// The vesting schedules of the account of `who`, in the state of block `at`, the best block
+	/// by default.
+	#[rpc(name = "vesting_schedules")]
+	fn schedules(
+		&self,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<VestingScheduleInfo<BlockNumber>>>;
+}
+
+/// A struct that implements the [`VestingApi`].
+pub struct Vesting<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Vesting<C, B> {
+	/// Create new `Vesting` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+fn try_into_rpc_balance<Balance: TryInto<NumberOrHex> + Display + Copy>(
+	value: Balance,
+) -> Result<NumberOrHex> {
+	value.try_into().map_err(|_| RpcError {
+		code: ErrorCode::InvalidParams,
+		message: format!("{} doesn't fit in NumberOrHex representation", value),
+		data: None,
+	})
+}
+
+impl<C, Block, AccountId, Balance, BlockNumber>
+	VestingApi<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber> for Vesting<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: VestingRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+	AccountId: Codec + Clone,
+	Balance: Codec + Copy + Display + Saturating + Zero + TryInto<NumberOrHex>,
+	BlockNumber: Codec,
+{
+	fn locked_at(
+		&self,
+		who: AccountId,
+		block_number: BlockNumber,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let schedules = api
+			.vesting_schedules(&at, who.clone())
+			.map_err(|e| runtime_error("Unable to query vesting schedules.", e))?;
+		let vested = api
+			.vested_at(&at, who, block_number)
+			.map_err(|e| runtime_error("Unable to query vested amount.", e))?;
+
+		match vested {
+			None => Ok(None),
+			Some(vested) => {
+				let locked = schedules
+					.iter()
+					.fold(Balance::zero(), |total, s| total.saturating_add(s.locked));
+				try_into_rpc_balance(locked.saturating_sub(vested)).map(Some)
+			},
+		}
+	}
+
+	fn schedules(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<VestingScheduleInfo<BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		api.vesting_schedules(&at, who)
+			.map_err(|e| runtime_error("Unable to query vesting schedules.", e))?
+			.into_iter()
+			.map(|schedule| {
+				Ok(VestingScheduleInfo {
+					locked: try_into_rpc_balance(schedule.locked)?,
+					per_block: try_into_rpc_balance(schedule.per_block)?,
+					starting_block: schedule.starting_block,
+					cliff_block: schedule.cliff_block,
+				})
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_serialize_vesting_schedule() {
+		let schedule = VestingScheduleInfo {
+			locked: NumberOrHex::Number(1_000),
+			per_block: NumberOrHex::Hex(10.into()),
+			starting_block: 5u32,
+			cliff_block: Some(10),
+		};
+
+		assert_eq!(
+			serde_json::to_string(&schedule).unwrap(),
+			r#"{"locked":1000,"perBlock":"0xa","startingBlock":5,"cliffBlock":10}"#,
+		);
+	}
+}
@@ -0,0 +1,51 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API for querying the vesting state of an account.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_vesting::{VestingInfo, VestingSummary};
+
+sp_api::decl_runtime_apis! {
+	/// API to query the vesting schedules of an account and the amounts they lock.
+	#[api_version(2)]
+	pub trait VestingApi<AccountId, Balance, BlockNumber> where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// The amount of the account of `who` which is still locked by vesting, if it is vesting.
+		fn vesting_balance(who: AccountId) -> Option<Balance>;
+
+		/// The vesting schedules of the account of `who`.
+		fn vesting_schedules(who: AccountId) -> Vec<VestingInfo<Balance, BlockNumber>>;
+
+		/// The amount which the schedules of `who` have vested by block `at`, if it is vesting.
+		///
+		/// This includes what has already been unlocked by `vest`, as long as the schedules were
+		/// not completed.
+		fn vested_at(who: AccountId, at: BlockNumber) -> Option<Balance>;
+
+		/// A summary of the vesting of the account of `who` as of the block queried, if it is
+		/// vesting.
+		fn vesting_summary(who: AccountId) -> Option<VestingSummary<Balance, BlockNumber>>;
+	}
+}
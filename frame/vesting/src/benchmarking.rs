@@ -22,7 +22,8 @@
 use super::*;
 
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
-use frame_system::{Pallet as System, RawOrigin};
+use frame_support::dispatch::UnfilteredDispatchable;
+use frame_system::RawOrigin;
 use sp_runtime::traits::Bounded;
 
 use crate::Pallet as Vesting;
@@ -46,7 +47,7 @@ fn add_vesting_schedule<T: Config>(who: &T::AccountId) -> Result<(), &'static st
 	let per_block = 10u32;
 	let starting_block = 1u32;
 
-	System::<T>::set_block_number(0u32.into());
+	T::BlockNumberProvider::set_block_number(0u32.into());
 
 	// Add schedule to avoid `NotVesting` error.
 	Vesting::<T>::add_vesting_schedule(
@@ -66,19 +67,20 @@ benchmarks! {
 		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
 		add_locks::<T>(&caller, l as u8);
 		add_vesting_schedule::<T>(&caller)?;
-		// At block zero, everything is vested.
-		System::<T>::set_block_number(T::BlockNumber::zero());
+		// At block 5, 40 have vested.
+		T::BlockNumberProvider::set_block_number(5u32.into());
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&caller),
-			Some(100u32.into()),
+			Some(60u32.into()),
 			"Vesting schedule not added",
 		);
 	}: vest(RawOrigin::Signed(caller.clone()))
 	verify {
-		// Nothing happened since everything is still vested.
+		// The vested funds were unlocked, the rest is still locked.
+		assert_eq!(Released::<T>::get(&caller), 40u32.into(), "Funds not released");
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&caller),
-			Some(100u32.into()),
+			Some(60u32.into()),
 			"Vesting schedule was removed",
 		);
 	}
@@ -91,7 +93,7 @@ benchmarks! {
 		add_locks::<T>(&caller, l as u8);
 		add_vesting_schedule::<T>(&caller)?;
 		// At block 20, everything is unvested.
-		System::<T>::set_block_number(20u32.into());
+		T::BlockNumberProvider::set_block_number(20u32.into());
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&caller),
 			Some(BalanceOf::<T>::zero()),
@@ -107,6 +109,43 @@ benchmarks! {
 		);
 	}
 
+	vest_unchanged {
+		let caller = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		add_vesting_schedule::<T>(&caller)?;
+		// At block zero, nothing has vested.
+		T::BlockNumberProvider::set_block_number(T::BlockNumber::zero());
+	}: vest(RawOrigin::Signed(caller.clone()))
+	verify {
+		// Nothing happened since nothing has vested.
+		assert_eq!(Released::<T>::get(&caller), Zero::zero(), "Funds released");
+		assert_eq!(
+			Vesting::<T>::vesting_balance(&caller),
+			Some(100u32.into()),
+			"Vesting schedule was removed",
+		);
+	}
+
+	vest_partial {
+		let l in 0 .. MaxLocksOf::<T>::get();
+
+		let caller = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		add_locks::<T>(&caller, l as u8);
+		add_vesting_schedule::<T>(&caller)?;
+		// At block 5, 40 have vested.
+		T::BlockNumberProvider::set_block_number(5u32.into());
+		assert_eq!(
+			Vesting::<T>::vesting_balance(&caller),
+			Some(60u32.into()),
+			"Vesting schedule not added",
+		);
+	}: _(RawOrigin::Signed(caller.clone()), 10u32.into())
+	verify {
+		// Only 10 of the vested funds were unlocked.
+		assert_eq!(Released::<T>::get(&caller), 10u32.into(), "Funds not released");
+	}
+
 	vest_other_locked {
 		let l in 0 .. MaxLocksOf::<T>::get();
 
@@ -115,21 +154,24 @@ benchmarks! {
 		T::Currency::make_free_balance_be(&other, BalanceOf::<T>::max_value());
 		add_locks::<T>(&other, l as u8);
 		add_vesting_schedule::<T>(&other)?;
-		// At block zero, everything is vested.
-		System::<T>::set_block_number(T::BlockNumber::zero());
+		// At block 5, 40 have vested.
+		T::BlockNumberProvider::set_block_number(5u32.into());
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&other),
-			Some(100u32.into()),
+			Some(60u32.into()),
 			"Vesting schedule not added",
 		);
 
 		let caller: T::AccountId = whitelisted_caller();
+		// So that the caller can be paid the reward, whatever it is.
+		T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance());
 	}: vest_other(RawOrigin::Signed(caller.clone()), other_lookup)
 	verify {
-		// Nothing happened since everything is still vested.
+		// The vested funds were unlocked, the rest is still locked.
+		assert_eq!(Released::<T>::get(&other), 40u32.into(), "Funds not released");
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&other),
-			Some(100u32.into()),
+			Some(60u32.into()),
 			"Vesting schedule was removed",
 		);
 	}
@@ -143,7 +185,7 @@ benchmarks! {
 		add_locks::<T>(&other, l as u8);
 		add_vesting_schedule::<T>(&other)?;
 		// At block 20, everything is unvested.
-		System::<T>::set_block_number(20u32.into());
+		T::BlockNumberProvider::set_block_number(20u32.into());
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&other),
 			Some(BalanceOf::<T>::zero()),
@@ -151,6 +193,8 @@ benchmarks! {
 		);
 
 		let caller: T::AccountId = whitelisted_caller();
+		// So that the caller can be paid the reward, whatever it is.
+		T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance());
 	}: vest_other(RawOrigin::Signed(caller.clone()), other_lookup)
 	verify {
 		// Vesting schedule is removed!
@@ -177,6 +221,41 @@ benchmarks! {
 			locked: transfer_amount,
 			per_block: 10u32.into(),
 			starting_block: 1u32.into(),
+			cliff_block: None,
+			remainder: Zero::zero(),
+		};
+	}: _(RawOrigin::Signed(caller), target_lookup, vesting_schedule)
+	verify {
+		assert_eq!(
+			T::MinVestedTransfer::get(),
+			T::Currency::free_balance(&target),
+			"Transfer didn't happen",
+		);
+		assert_eq!(
+			Vesting::<T>::vesting_balance(&target),
+			Some(T::MinVestedTransfer::get()),
+			"Lock not created",
+		);
+	}
+
+	vested_transfer_keep_alive {
+		let l in 0 .. MaxLocksOf::<T>::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		// Give target existing locks
+		add_locks::<T>(&target, l as u8);
+
+		let transfer_amount = T::MinVestedTransfer::get();
+
+		let vesting_schedule = VestingInfo {
+			locked: transfer_amount,
+			per_block: 10u32.into(),
+			starting_block: 1u32.into(),
+			cliff_block: None,
+			remainder: Zero::zero(),
 		};
 	}: _(RawOrigin::Signed(caller), target_lookup, vesting_schedule)
 	verify {
@@ -209,6 +288,8 @@ benchmarks! {
 			locked: transfer_amount,
 			per_block: 10u32.into(),
 			starting_block: 1u32.into(),
+			cliff_block: None,
+			remainder: Zero::zero(),
 		};
 	}: _(RawOrigin::Root, source_lookup, target_lookup, vesting_schedule)
 	verify {
@@ -223,6 +304,171 @@ benchmarks! {
 			"Lock not created",
 		);
 	}
+
+	force_batch_vested_transfer {
+		let n in 1 .. 100;
+		let l in 0 .. MaxLocksOf::<T>::get();
+
+		let source: T::AccountId = account("source", 0, SEED);
+		let source_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(source.clone());
+		T::Currency::make_free_balance_be(&source, BalanceOf::<T>::max_value());
+
+		let transfer_amount = T::MinVestedTransfer::get();
+		let vesting_schedule = VestingInfo {
+			locked: transfer_amount,
+			per_block: 10u32.into(),
+			starting_block: 1u32.into(),
+			cliff_block: None,
+			remainder: Zero::zero(),
+		};
+		let mut targets = Vec::new();
+		let mut transfers = Vec::new();
+		for i in 0 .. n {
+			let target: T::AccountId = account("target", i, SEED);
+			// Give targets existing locks
+			add_locks::<T>(&target, l as u8);
+			transfers.push((T::Lookup::unlookup(target.clone()), vesting_schedule));
+			targets.push(target);
+		}
+	}: _(RawOrigin::Root, source_lookup, transfers)
+	verify {
+		for target in targets {
+			assert_eq!(
+				Vesting::<T>::vesting_balance(&target),
+				Some(T::MinVestedTransfer::get()),
+				"Lock not created",
+			);
+		}
+	}
+
+	force_remove_vesting_schedule {
+		let l in 0 .. MaxLocksOf::<T>::get();
+
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		T::Currency::make_free_balance_be(&target, BalanceOf::<T>::max_value());
+		add_locks::<T>(&target, l as u8);
+		add_vesting_schedule::<T>(&target)?;
+		// At block zero, everything is still locked and returned.
+		T::BlockNumberProvider::set_block_number(T::BlockNumber::zero());
+		let return_to: T::AccountId = account("return_to", 0, SEED);
+		T::Currency::make_free_balance_be(&return_to, T::Currency::minimum_balance());
+		let return_to_lookup: <T::Lookup as StaticLookup>::Source =
+			T::Lookup::unlookup(return_to.clone());
+
+		let origin = T::ForceOrigin::successful_origin();
+		let call =
+			Call::<T>::force_remove_vesting_schedule(target_lookup, 0, Some(return_to_lookup));
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Vesting::<T>::vesting_balance(&target), None, "Vesting schedule not removed");
+		assert_eq!(
+			T::Currency::free_balance(&return_to),
+			T::Currency::minimum_balance() + 100u32.into(),
+			"Locked funds not returned",
+		);
+	}
+
+	force_set_vesting_schedule {
+		let l in 0 .. MaxLocksOf::<T>::get();
+
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		T::Currency::make_free_balance_be(&target, BalanceOf::<T>::max_value());
+		add_locks::<T>(&target, l as u8);
+		add_vesting_schedule::<T>(&target)?;
+
+		let vesting_schedule = VestingInfo {
+			locked: 200u32.into(),
+			per_block: 10u32.into(),
+			starting_block: 1u32.into(),
+			cliff_block: Some(5u32.into()),
+			remainder: Zero::zero(),
+		};
+	}: _(RawOrigin::Root, target_lookup, vesting_schedule)
+	verify {
+		assert_eq!(
+			Vesting::<T>::vesting_balance(&target),
+			Some(200u32.into()),
+			"Vesting schedule not replaced",
+		);
+	}
+
+	set_vesting_beneficiary {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		add_vesting_schedule::<T>(&caller)?;
+		let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+		let beneficiary_lookup: <T::Lookup as StaticLookup>::Source =
+			T::Lookup::unlookup(beneficiary.clone());
+	}: _(RawOrigin::Signed(caller.clone()), beneficiary_lookup)
+	verify {
+		assert_eq!(
+			VestingBeneficiary::<T>::get(&caller),
+			Some(beneficiary),
+			"Beneficiary not set",
+		);
+	}
+
+	claim_vested_to {
+		let l in 0 .. MaxLocksOf::<T>::get();
+
+		let other: T::AccountId = account("other", 0, SEED);
+		let other_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(other.clone());
+		T::Currency::make_free_balance_be(&other, BalanceOf::<T>::max_value());
+		add_locks::<T>(&other, l as u8);
+		add_vesting_schedule::<T>(&other)?;
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance());
+		VestingBeneficiary::<T>::insert(&other, &caller);
+		// At block 5, 40 have vested.
+		T::BlockNumberProvider::set_block_number(5u32.into());
+	}: _(RawOrigin::Signed(caller.clone()), other_lookup, 40u32.into())
+	verify {
+		assert_eq!(
+			T::Currency::free_balance(&caller),
+			T::Currency::minimum_balance() + 40u32.into(),
+			"Funds not claimed",
+		);
+	}
+
+	pause_schedule {
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		T::Currency::make_free_balance_be(&target, BalanceOf::<T>::max_value());
+		add_vesting_schedule::<T>(&target)?;
+		T::BlockNumberProvider::set_block_number(5u32.into());
+
+		let origin = T::PauseOrigin::successful_origin();
+		let call = Call::<T>::pause_schedule(target_lookup);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(PausedAt::<T>::get(&target), Some(5u32.into()), "Vesting schedule not paused");
+	}
+
+	resume_schedule {
+		let l in 0 .. MaxLocksOf::<T>::get();
+
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
+		T::Currency::make_free_balance_be(&target, BalanceOf::<T>::max_value());
+		add_locks::<T>(&target, l as u8);
+		add_vesting_schedule::<T>(&target)?;
+		PausedAt::<T>::insert(&target, T::BlockNumber::zero());
+		// Paused for 5 blocks, before anything vested.
+		T::BlockNumberProvider::set_block_number(5u32.into());
+
+		let origin = T::PauseOrigin::successful_origin();
+		let call = Call::<T>::resume_schedule(target_lookup);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(PausedAt::<T>::get(&target), None, "Vesting schedule not resumed");
+		assert_eq!(
+			Vesting::<T>::vesting_balance(&target),
+			Some(100u32.into()),
+			"Vesting schedule not delayed",
+		);
+	}
 }
 
 impl_benchmark_test_suite!(
@@ -0,0 +1,213 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Randomized tests of the merging of vesting schedules, run with the `fuzz-tests` feature.
+
+use quickcheck::{Arbitrary, Gen, QuickCheck};
+use sp_runtime::traits::Identity;
+
+use super::*;
+use crate::mock::Test;
+
+type Schedule = VestingInfo<u64, u64>;
+
+/// A value at or next to `0`, `u64::max_value() / 2` or `u64::max_value()`, where the arithmetic
+/// of schedules is the most likely to overflow or to round.
+fn extreme(g: &mut Gen) -> u64 {
+	let offset = u64::arbitrary(g) % 3;
+	let extremes = [offset, u64::max_value() / 2 + offset, u64::max_value() - offset];
+	*g.choose(&extremes).unwrap()
+}
+
+/// An arbitrary value, which is an [`extreme`] one half of the time.
+fn amount(g: &mut Gen) -> u64 {
+	if bool::arbitrary(g) {
+		extreme(g)
+	} else {
+		u64::arbitrary(g)
+	}
+}
+
+/// A block number, which is an [`extreme`] one half of the time.
+#[derive(Clone, Copy, Debug)]
+struct Block(u64);
+
+impl Arbitrary for Block {
+	fn arbitrary(g: &mut Gen) -> Self {
+		Block(amount(g))
+	}
+}
+
+/// A vesting schedule with arbitrary, possibly extreme, amounts and blocks, which may not be
+/// valid.
+#[derive(Clone, Debug)]
+struct ArbitrarySchedule(Schedule);
+
+impl Arbitrary for ArbitrarySchedule {
+	fn arbitrary(g: &mut Gen) -> Self {
+		ArbitrarySchedule(VestingInfo {
+			locked: amount(g),
+			per_block: amount(g),
+			starting_block: amount(g),
+			cliff_block: Option::<()>::arbitrary(g).map(|()| amount(g)),
+			remainder: amount(g),
+		})
+	}
+}
+
+/// A valid vesting schedule with possibly extreme amounts and blocks, such as `per_block` or
+/// `locked` close to the maximum, or a schedule ending at the last block.
+#[derive(Clone, Debug)]
+struct ExtremeSchedule(Schedule);
+
+impl Arbitrary for ExtremeSchedule {
+	fn arbitrary(g: &mut Gen) -> Self {
+		let locked = amount(g).max(1);
+		let mut schedule = VestingInfo {
+			locked,
+			per_block: amount(g).max(1),
+			starting_block: 0,
+			cliff_block: None,
+			remainder: amount(g) % locked,
+		};
+		// The linear curve must end by the last block, and the cliff be after the start.
+		let duration = schedule.ending_block().unwrap();
+		schedule.starting_block = amount(g).min(u64::max_value() - duration);
+		let room = u64::max_value() - schedule.starting_block;
+		schedule.cliff_block =
+			Option::<()>::arbitrary(g).map(|()| schedule.starting_block + 1 + amount(g) % room);
+		ExtremeSchedule(schedule)
+	}
+}
+
+/// A valid vesting schedule with amounts and blocks small enough not to saturate, but large
+/// enough for the merged schedule to be spread over more blocks than it locks.
+#[derive(Clone, Debug)]
+struct SmallSchedule(Schedule);
+
+impl Arbitrary for SmallSchedule {
+	fn arbitrary(g: &mut Gen) -> Self {
+		let starting_block = u64::arbitrary(g) % 1_000;
+		let locked = u64::arbitrary(g) % 10_000 + 1;
+		SmallSchedule(VestingInfo {
+			locked,
+			per_block: u64::arbitrary(g) % locked + 1,
+			starting_block,
+			cliff_block: Option::<u64>::arbitrary(g)
+				.map(|cliff| starting_block + cliff % 1_000 + 1),
+			remainder: u64::arbitrary(g) % locked,
+		})
+	}
+}
+
+fn merge(now: u64, a: Schedule, b: Schedule) -> Option<Schedule> {
+	Pallet::<Test>::merge_vesting_info(now, a, b)
+}
+
+/// Whether merging the valid schedules `a` and `b` at `now` locks what is left of both, until
+/// exactly the later of their ending blocks.
+fn locks_what_is_left_of_both(now: u64, a: Schedule, b: Schedule) -> bool {
+	assert!(a.is_valid() && b.is_valid());
+	let left = a.locked_at::<Identity>(now).saturating_add(b.locked_at::<Identity>(now));
+	let merged = match merge(now, a, b) {
+		Some(merged) => merged,
+		None => return left == 0,
+	};
+
+	let ending_block = a
+		.ending_block_as_balance::<Identity>()
+		.max(b.ending_block_as_balance::<Identity>());
+	merged.is_valid() &&
+		merged.locked == left &&
+		merged.locked_at::<Identity>(now) == left &&
+		merged.ending_block_as_balance::<Identity>() == ending_block &&
+		merged.locked_at::<Identity>(ending_block) == 0
+}
+
+#[test]
+fn merging_never_panics() {
+	fn prop(now: Block, a: ArbitrarySchedule, b: ArbitrarySchedule) {
+		if let Some(merged) = merge(now.0, a.0, b.0) {
+			merged.locked_at::<Identity>(now.0);
+			merged.ending_block_as_balance::<Identity>();
+		}
+	}
+	QuickCheck::new().tests(10_000).quickcheck(prop as fn(_, _, _));
+}
+
+#[test]
+fn merged_schedule_locks_what_is_left_of_both() {
+	fn prop(now: u64, a: SmallSchedule, b: SmallSchedule) -> bool {
+		locks_what_is_left_of_both(now % 3_000, a.0, b.0)
+	}
+	QuickCheck::new().tests(10_000).quickcheck(prop as fn(_, _, _) -> bool);
+}
+
+#[test]
+fn merged_extreme_schedule_locks_what_is_left_of_both() {
+	fn prop(now: Block, a: ExtremeSchedule, b: ExtremeSchedule) -> bool {
+		locks_what_is_left_of_both(now.0, a.0, b.0)
+	}
+	QuickCheck::new().tests(10_000).quickcheck(prop as fn(_, _, _) -> bool);
+}
+
+#[test]
+fn merged_schedule_spread_over_more_blocks_than_it_locks_unlocks_one_per_block_at_the_end() {
+	// 15 are left at block 0, to unlock over the last 15 blocks until block 200, but nothing is
+	// unlocked before the cliff at block 200.
+	let a = VestingInfo {
+		locked: 10,
+		per_block: 10,
+		starting_block: 0,
+		cliff_block: Some(200),
+		remainder: 0,
+	};
+	let b =
+		VestingInfo { locked: 5, per_block: 1, starting_block: 0, cliff_block: None, remainder: 0 };
+	let merged = merge(0, a, b).unwrap();
+	assert_eq!(
+		merged,
+		VestingInfo {
+			locked: 15,
+			per_block: 1,
+			starting_block: 185,
+			cliff_block: Some(200),
+			remainder: 0,
+		}
+	);
+	assert_eq!(merged.ending_block(), Some(200));
+}
+
+#[test]
+fn merged_schedule_starting_at_its_end_unlocks_everything_at_once() {
+	let a = VestingInfo {
+		locked: 10,
+		per_block: 1,
+		starting_block: u64::max_value(),
+		cliff_block: None,
+		remainder: 0,
+	};
+	let b = VestingInfo {
+		locked: 10,
+		per_block: 1,
+		starting_block: 0,
+		cliff_block: None,
+		remainder: 0,
+	};
+	let merged = merge(5, a, b).unwrap();
+	assert_eq!(merged.per_block, merged.locked);
+}
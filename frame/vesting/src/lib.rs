@@ -24,7 +24,16 @@
 //!
 //! A simple pallet providing a means of placing a linear curve on an account's locked balance. This
 //! pallet ensures that there is a lock in place preventing the balance to drop below the *unvested*
-//! amount for any reason other than transaction fee payment.
+//! amount for any reason other than those in [`Config::UnvestedFundsAllowedWithdrawReasons`],
+//! usually transaction fee payment.
+//!
+//! A schedule may have a cliff, before which nothing is vested at all. From the cliff on, the
+//! linear curve applies as if there had been no cliff, so everything which would have vested
+//! until then becomes available at once.
+//!
+//! A schedule may also have a remainder, which is unlocked along with the last per block amount,
+//! so that it is fully vested exactly at a given block. The genesis schedules, given by their
+//! length, and the merged schedules are built this way.
 //!
 //! As the amount vested increases over time, the amount unvested reduces. However, locks remain in
 //! place and explicit action is needed on behalf of the user to ensure that the amount locked is
@@ -32,6 +41,10 @@
 //! either `vest` (in typical case where the sender is calling on their own behalf) or `vest_other`
 //! in case the sender is calling on another account's behalf.
 //!
+//! If [`Config::MaxAutoVestsPerBlock`] is not zero, the schedules which have ended are also
+//! removed, along with their locks, using the weight left over at the end of each block. The
+//! accounts with a vesting beneficiary are left to it.
+//!
 //! ## Interface
 //!
 //! This pallet implements the `VestingSchedule` trait.
@@ -41,10 +54,35 @@
 //! - `vest` - Update the lock, reducing it in line with the amount "vested" so far.
 //! - `vest_other` - Update the lock of another account, reducing it in line with the amount
 //!   "vested" so far.
+//! - `vest_partial` - Reduce the lock by at most a given amount, keeping the rest of the funds
+//!   vested so far locked.
+//! - `vested_transfer_keep_alive` - Transfer funds which vest with a schedule to an account,
+//!   without killing the sender account.
+//! - `force_batch_vested_transfer` - Transfer funds which vest with a schedule from an account to
+//!   many accounts at once.
+//! - `force_remove_vesting_schedule` - Revoke the vesting schedule of an account, optionally
+//!   returning the funds which are still locked.
+//! - `force_set_vesting_schedule` - Replace the vesting schedule of an account.
+//! - `set_vesting_beneficiary` - Let another account claim the funds of the sender account as
+//!   they vest, instead of the sender account.
+//! - `claim_vested_to` - Claim the vested funds of an account which made the sender its
+//!   beneficiary.
+//! - `pause_schedule` - Stop the vesting schedule of an account from unlocking anything.
+//! - `resume_schedule` - Resume a paused vesting schedule, delaying it by the number of blocks it
+//!   was paused for.
+//!
+//! ## Weights
+//!
+//! Only some of the weights in [`weights::SubstrateWeight`] were benchmarked, the others, such as
+//! those of `vest_partial` and `force_remove_vesting_schedule`, are hand-written estimates. Do not
+//! use them in production before benchmarking the pallet against your runtime.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod benchmarking;
+#[cfg(all(test, feature = "fuzz-tests"))]
+mod fuzz_tests;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -52,22 +90,31 @@ mod tests;
 
 pub mod weights;
 
-use codec::{Decode, Encode};
+use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
 	ensure,
 	pallet_prelude::*,
 	traits::{
-		Currency, ExistenceRequirement, Get, LockIdentifier, LockableCurrency, VestingSchedule,
-		WithdrawReasons,
+		fungible, BalanceRestriction, BlockNumberProvider, CheckInvariants, Currency, EnsureOrigin,
+		ExistenceRequirement, Get, InspectBalanceRestrictions, LockIdentifier, LockableCurrency,
+		StorageVersion, VestingSchedule, WithdrawReasons,
 	},
+	transactional,
 };
 use frame_system::{ensure_root, ensure_signed, pallet_prelude::*};
 pub use pallet::*;
 use sp_runtime::{
-	traits::{AtLeast32BitUnsigned, Convert, MaybeSerializeDeserialize, StaticLookup, Zero},
-	RuntimeDebug,
+	traits::{
+		AtLeast32BitUnsigned, Bounded, CheckedAdd, Convert, MaybeSerializeDeserialize, One,
+		Saturating, StaticLookup, Zero,
+	},
+	Perbill, RuntimeDebug,
+};
+use sp_std::{
+	convert::{TryFrom, TryInto},
+	fmt::Debug,
+	prelude::*,
 };
-use sp_std::{fmt::Debug, prelude::*};
 pub use weights::WeightInfo;
 
 type BalanceOf<T> =
@@ -77,8 +124,26 @@ type MaxLocksOf<T> =
 
 const VESTING_ID: LockIdentifier = *b"vesting ";
 
+/// The current storage version.
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+/// Handler for changes to the vesting of an account, so that other pallets can react to them
+/// without scanning events. There is a dummy implementation for `()`, which does nothing.
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+pub trait VestingHooks<AccountId, Balance> {
+	/// The amount of the account of `who` which is still locked by vesting was updated to
+	/// `locked`, which is not zero.
+	fn on_vesting_updated(who: &AccountId, locked: Balance);
+
+	/// The vesting schedule of `who` was removed along with its lock, because the account has
+	/// fully vested or the schedule was revoked.
+	fn on_vesting_completed(who: &AccountId);
+}
+
 /// Struct to encode the vesting schedule of an individual account.
-#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
 pub struct VestingInfo<Balance, BlockNumber> {
 	/// Locked amount at genesis.
 	pub locked: Balance,
@@ -86,30 +151,157 @@ pub struct VestingInfo<Balance, BlockNumber> {
 	pub per_block: Balance,
 	/// Starting block for unlocking(vesting).
 	pub starting_block: BlockNumber,
+	/// Block before which nothing is unlocked, if any. Must be after `starting_block`.
+	pub cliff_block: Option<BlockNumber>,
+	/// Amount unlocked along with the last `per_block`, so that the schedule can end exactly at a
+	/// given block when its length does not divide `locked`. Must be less than `locked`.
+	pub remainder: Balance,
 }
 
 impl<Balance: AtLeast32BitUnsigned + Copy, BlockNumber: AtLeast32BitUnsigned + Copy>
 	VestingInfo<Balance, BlockNumber>
 {
+	/// A schedule unlocking `locked` from `starting_block` until exactly `length` blocks later,
+	/// or until `cliff_block` if it is later. `None` if that block does not fit in a block number.
+	///
+	/// `per_block` is rounded down and the rest is unlocked at the last block as the `remainder`.
+	/// If `locked` is less than `length`, one is unlocked per block over the last `locked` blocks
+	/// instead, and a cliff which is not after this later start is dropped. Everything is
+	/// unlocked at once if `length` is zero.
+	pub fn over_length(
+		locked: Balance,
+		starting_block: BlockNumber,
+		length: Balance,
+		cliff_block: Option<BlockNumber>,
+	) -> Option<Self> {
+		if length.is_zero() {
+			return Some(VestingInfo {
+				locked,
+				per_block: locked,
+				starting_block,
+				cliff_block,
+				remainder: Zero::zero(),
+			})
+		}
+		if locked < length {
+			let delay: u128 = (length - locked).try_into().ok()?;
+			let starting_block = starting_block.checked_add(&BlockNumber::try_from(delay).ok()?)?;
+			return Some(VestingInfo {
+				locked,
+				per_block: One::one(),
+				starting_block,
+				cliff_block: cliff_block.filter(|cliff| *cliff > starting_block),
+				remainder: Zero::zero(),
+			})
+		}
+		Some(VestingInfo {
+			locked,
+			per_block: locked / length,
+			starting_block,
+			cliff_block,
+			remainder: locked % length,
+		})
+	}
+
+	/// Whether the schedule can be stored: it must lock something and unlock something every
+	/// block, its cliff, if any, must be after its starting block, its remainder must be less
+	/// than what it locks, and the block at which it is fully vested must fit in a block number.
+	pub fn is_valid(&self) -> bool {
+		!self.locked.is_zero() &&
+			!self.per_block.is_zero() &&
+			self.has_valid_cliff() &&
+			self.remainder < self.locked &&
+			self.ending_block().is_some()
+	}
+
+	/// Whether the cliff of the schedule, if any, is after its starting block.
+	pub fn has_valid_cliff(&self) -> bool {
+		self.cliff_block.map_or(true, |cliff| cliff > self.starting_block)
+	}
+
+	/// Block at which the schedule is fully vested, or `None` if it never is, because it locks
+	/// something but does not unlock anything every block, or if it does not fit in a block
+	/// number.
+	///
+	/// This is the later of the cliff and the end of the linear curve.
+	pub fn ending_block(&self) -> Option<BlockNumber> {
+		let duration: u128 = self.duration()?.try_into().ok()?;
+		let linear_end = self.starting_block.checked_add(&BlockNumber::try_from(duration).ok()?)?;
+		Some(self.cliff_block.map_or(linear_end, |cliff| cliff.max(linear_end)))
+	}
+
 	/// Amount locked at block `n`.
 	pub fn locked_at<BlockNumberToBalance: Convert<BlockNumber, Balance>>(
 		&self,
 		n: BlockNumber,
 	) -> Balance {
+		if self.cliff_block.map_or(false, |cliff| n < cliff) {
+			return self.locked
+		}
 		// Number of blocks that count toward vesting
 		// Saturating to 0 when n < starting_block
 		let vested_block_count = n.saturating_sub(self.starting_block);
 		let vested_block_count = BlockNumberToBalance::convert(vested_block_count);
-		// Return amount that is still locked in vesting
-		let maybe_balance = vested_block_count.checked_mul(&self.per_block);
-		if let Some(balance) = maybe_balance {
-			self.locked.saturating_sub(balance)
+		// Return amount that is still locked in vesting, the remainder being unlocked along with
+		// the last `per_block`.
+		let linear = self.locked.saturating_sub(self.remainder);
+		match vested_block_count.checked_mul(&self.per_block) {
+			Some(balance) if balance < linear => self.locked.saturating_sub(balance),
+			_ => Zero::zero(),
+		}
+	}
+
+	/// Block number at which the schedule is fully vested, as a balance.
+	///
+	/// This is the later of the cliff and the end of the linear curve, saturating at the maximum
+	/// balance if the schedule is never fully vested.
+	pub fn ending_block_as_balance<BlockNumberToBalance: Convert<BlockNumber, Balance>>(
+		&self,
+	) -> Balance {
+		let linear_end = match self.duration() {
+			Some(duration) =>
+				BlockNumberToBalance::convert(self.starting_block).saturating_add(duration),
+			None => return Bounded::max_value(),
+		};
+		self.cliff_block
+			.map(BlockNumberToBalance::convert)
+			.map_or(linear_end, |cliff| cliff.max(linear_end))
+	}
+
+	/// Number of blocks over which the linear curve unlocks everything, as a balance, or `None`
+	/// if it never does.
+	fn duration(&self) -> Option<Balance> {
+		let linear = self.locked.saturating_sub(self.remainder);
+		if linear.is_zero() {
+			return Some(Zero::zero())
+		}
+		if self.per_block.is_zero() {
+			return None
+		}
+		if (linear % self.per_block).is_zero() {
+			Some(linear / self.per_block)
 		} else {
-			Zero::zero()
+			Some(linear / self.per_block + One::one())
 		}
 	}
 }
 
+/// A summary of the vesting of an account, as of a given block.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct VestingSummary<Balance, BlockNumber> {
+	/// Amount which the vesting schedule still locks.
+	pub locked: Balance,
+	/// Amount which the vesting schedule locked when it was created.
+	pub originally_locked: Balance,
+	/// Next block at which some funds vest, or `None` if everything has vested or the vesting is
+	/// paused.
+	pub next_unlock_block: Option<BlockNumber>,
+	/// Block at which everything has vested.
+	pub ending_block: BlockNumber,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -120,15 +312,57 @@ pub mod pallet {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
 		/// The currency trait.
-		type Currency: LockableCurrency<Self::AccountId>;
+		///
+		/// `fungible::Inspect` tells how much of an account can be transferred once its vesting
+		/// lock is removed, given the locks of other pallets, and `InspectBalanceRestrictions` lets
+		/// the invariant checks read the vesting lock back.
+		type Currency: LockableCurrency<Self::AccountId>
+			+ InspectBalanceRestrictions<Self::AccountId>
+			+ fungible::Inspect<Self::AccountId, Balance = BalanceOf<Self>>;
 
 		/// Convert the block number into a balance.
 		type BlockNumberToBalance: Convert<Self::BlockNumber, BalanceOf<Self>>;
 
-		/// The minimum amount transferred to call `vested_transfer`.
+		/// The minimum amount transferred to call `vested_transfer`. Transfers forced by _Root_ may
+		/// transfer less.
 		#[pallet::constant]
 		type MinVestedTransfer: Get<BalanceOf<Self>>;
 
+		/// Provider for the block number against which vesting schedules are evaluated.
+		///
+		/// The starting and cliff blocks of all schedules are blocks of this provider, so changing
+		/// it on a live chain requires migrating the existing schedules.
+		type BlockNumberProvider: BlockNumberProvider<BlockNumber = Self::BlockNumber>;
+
+		/// The origin which may revoke vesting schedules.
+		type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin which may pause and resume vesting schedules.
+		type PauseOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Handler called whenever the lock of a vesting account is updated, or removed because the
+		/// account has fully vested or its schedule was revoked.
+		type VestingHooks: VestingHooks<Self::AccountId, BalanceOf<Self>>;
+
+		/// The maximum number of vesting accounts checked by `on_idle` in a block, to remove the
+		/// schedules which have ended and their locks. Zero disables automatic vesting.
+		#[pallet::constant]
+		type MaxAutoVestsPerBlock: Get<u32>;
+
+		/// The reasons for which unvested funds may still be withdrawn. The vesting lock applies
+		/// to all other reasons.
+		#[pallet::constant]
+		type UnvestedFundsAllowedWithdrawReasons: Get<WithdrawReasons>;
+
+		/// The share of the funds unlocked by `vest_other` which is paid to its sender, so that
+		/// anyone has an incentive to keep the locks of vesting accounts up to date. Zero disables
+		/// the reward.
+		///
+		/// Being a share, the reward does not grow with how often `vest_other` is called: the
+		/// owner keeps the rest of their vested funds however often the account is poked.
+		#[pallet::constant]
+		type VestOtherReward: Get<Perbill>;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -139,57 +373,181 @@ pub mod pallet {
 	pub type Vesting<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::AccountId, VestingInfo<BalanceOf<T>, T::BlockNumber>>;
 
+	/// The amount of the vesting schedule of an account which is no longer locked.
+	///
+	/// This is less than what has vested if the account unlocked only part of it with
+	/// `vest_partial`.
+	#[pallet::storage]
+	pub type Released<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// The number of vesting accounts, which is the number of entries of [`Vesting`].
+	#[pallet::storage]
+	#[pallet::getter(fn vesting_accounts_count)]
+	pub type VestingAccountsCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The account which may claim the funds of a vesting account as they vest, if any.
+	///
+	/// The vested funds of an account with a beneficiary are only unlocked by `claim_vested_to`,
+	/// which transfers them to the beneficiary.
+	#[pallet::storage]
+	#[pallet::getter(fn vesting_beneficiary)]
+	pub type VestingBeneficiary<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId>;
+
+	/// The block at which the vesting schedule of an account was paused, if it is paused.
+	///
+	/// Nothing vests while a schedule is paused: it is evaluated at this block instead of the
+	/// current one.
+	#[pallet::storage]
+	pub type PausedAt<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber>;
+
+	/// The raw storage key of the last account checked by automatic vesting, after which the next
+	/// check resumes. `None` if it starts over from the first account.
+	#[pallet::storage]
+	pub type AutoVestCursor<T: Config> = StorageValue<_, Vec<u8>>;
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Remove the schedules which have ended, and their locks, within `remaining_weight`.
+		fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::auto_vest(remaining_weight)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
+	}
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
+		/// Vesting schedules as `(who, begin, length, liquid)`: the genesis balance of `who` but
+		/// `liquid` is locked, and unlocks linearly over `length` blocks from block `begin`.
 		pub vesting: Vec<(T::AccountId, T::BlockNumber, T::BlockNumber, BalanceOf<T>)>,
+		/// Vesting schedules given explicitly, each locking `locked` of the genesis balance of
+		/// its account.
+		#[serde(default)]
+		pub schedules: Vec<(T::AccountId, VestingInfo<BalanceOf<T>, T::BlockNumber>)>,
 	}
 
 	#[cfg(feature = "std")]
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
-			GenesisConfig { vesting: Default::default() }
+			GenesisConfig { vesting: Default::default(), schedules: Default::default() }
 		}
 	}
 
-	#[pallet::genesis_build]
-	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
-		fn build(&self) {
-			use sp_runtime::traits::Saturating;
-
-			// Generate initial vesting configuration
-			// * who - Account which we are generating vesting configuration for
-			// * begin - Block when the account will start to vest
-			// * length - Number of blocks from `begin` until fully vested
-			// * liquid - Number of units which can be spent before vesting begins
+	#[cfg(feature = "std")]
+	impl<T: Config> GenesisConfig<T> {
+		/// The vesting schedules of all the accounts of the configuration, computing those given
+		/// as `(who, begin, length, liquid)` from the genesis balances.
+		///
+		/// Fails if an account has more than one schedule, or a schedule is not valid or locks
+		/// more than the genesis balance of its account.
+		pub fn vesting_schedules(
+			&self,
+		) -> Result<Vec<(T::AccountId, VestingInfo<BalanceOf<T>, T::BlockNumber>)>, &'static str>
+		{
+			let mut schedules = Vec::with_capacity(self.vesting.len() + self.schedules.len());
 			for &(ref who, begin, length, liquid) in self.vesting.iter() {
 				let balance = T::Currency::free_balance(who);
-				assert!(!balance.is_zero(), "Currencies must be init'd before vesting");
+				ensure!(!balance.is_zero(), "Currencies must be init'd before vesting");
 				// Total genesis `balance` minus `liquid` equals funds locked for vesting
 				let locked = balance.saturating_sub(liquid);
-				let length_as_balance = T::BlockNumberToBalance::convert(length);
-				let per_block = locked / length_as_balance.max(sp_runtime::traits::One::one());
+				let length = T::BlockNumberToBalance::convert(length);
+				let schedule = VestingInfo::over_length(locked, begin, length, None)
+					.ok_or("Invalid vesting schedule")?;
+				schedules.push((who.clone(), schedule));
+			}
+			schedules.extend(self.schedules.iter().cloned());
 
-				Vesting::<T>::insert(who, VestingInfo { locked, per_block, starting_block: begin });
-				let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
-				T::Currency::set_lock(VESTING_ID, who, locked, reasons);
+			let mut vesting_accounts = sp_std::collections::btree_set::BTreeSet::new();
+			for (who, schedule) in schedules.iter() {
+				ensure!(
+					vesting_accounts.insert(who.clone()),
+					"Account with more than one vesting schedule"
+				);
+				ensure!(schedule.is_valid(), "Invalid vesting schedule");
+				ensure!(
+					schedule.locked <= T::Currency::free_balance(who),
+					"Vesting schedule locking more than the balance of its account"
+				);
+			}
+			Ok(schedules)
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			let schedules = self
+				.vesting_schedules()
+				.unwrap_or_else(|e| panic!("Invalid vesting genesis config: {}", e));
+			for (who, schedule) in schedules {
+				Vesting::<T>::insert(&who, schedule);
+				VestingAccountsCount::<T>::mutate(|count| *count = count.saturating_add(1));
+				let reasons =
+					WithdrawReasons::except(T::UnvestedFundsAllowedWithdrawReasons::get());
+				T::Currency::set_lock(VESTING_ID, &who, schedule.locked, reasons);
 			}
 		}
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
-	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		BalanceOf<T> = "Balance",
+		T::BlockNumber = "BlockNumber"
+	)]
 	pub enum Event<T: Config> {
+		/// A vesting schedule was added to an account, replacing its existing schedule if any.
+		/// \[account, locked, per_block, starting_block, cliff_block, remainder\]
+		VestingScheduleAdded(
+			T::AccountId,
+			BalanceOf<T>,
+			BalanceOf<T>,
+			T::BlockNumber,
+			Option<T::BlockNumber>,
+			BalanceOf<T>,
+		),
 		/// The amount vested has been updated. This could indicate more funds are available. The
 		/// balance given is the amount which is left unvested (and thus locked).
 		/// \[account, unvested\]
 		VestingUpdated(T::AccountId, BalanceOf<T>),
 		/// An \[account\] has become fully vested. No further vesting can happen.
 		VestingCompleted(T::AccountId),
+		/// The vesting schedule of an account was revoked. The balance given is the amount
+		/// transferred to the destination if one was given, otherwise the amount which was still
+		/// locked. \[account, amount\]
+		VestingScheduleRemoved(T::AccountId, BalanceOf<T>),
+		/// The vesting schedule of an \[account\] was paused.
+		VestingPaused(T::AccountId),
+		/// The vesting schedule of an account was resumed, after being paused for the number of
+		/// blocks given. \[account, paused_for\]
+		VestingResumed(T::AccountId, T::BlockNumber),
+		/// A vested transfer of a batch failed, the others were still made.
+		/// \[index, error\]
+		BatchVestedTransferFailed(u32, DispatchError),
+		/// A vesting account made another account its beneficiary. \[account, beneficiary\]
+		VestingBeneficiarySet(T::AccountId, T::AccountId),
+		/// A beneficiary claimed vested funds of a vesting account.
+		/// \[account, beneficiary, amount\]
+		VestedFundsClaimed(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// The sender of `vest_other` was paid out of the funds it unlocked.
+		/// \[account, keeper, reward\]
+		VestOtherRewardPaid(T::AccountId, T::AccountId, BalanceOf<T>),
 	}
 
 	/// Error for the vesting pallet.
@@ -197,10 +555,31 @@ pub mod pallet {
 	pub enum Error<T> {
 		/// The account given is not vesting.
 		NotVesting,
-		/// An existing vesting schedule already exists for this account that cannot be clobbered.
+		/// An existing vesting schedule already exists for this account that cannot be clobbered
+		/// or combined with.
 		ExistingVestingSchedule,
 		/// Amount being transferred is too low to create a vesting schedule.
 		AmountLow,
+		/// The cliff of the vesting schedule is not after its starting block.
+		InvalidScheduleParams,
+		/// The vesting schedule locks nothing.
+		ZeroLocked,
+		/// The vesting schedule unlocks nothing per block.
+		ZeroPerBlock,
+		/// The block at which the vesting schedule is fully vested does not fit in a block number.
+		ScheduleEndsOverflow,
+		/// The vesting schedule is already paused.
+		AlreadyPaused,
+		/// The vesting schedule is not paused.
+		NotPaused,
+		/// The vesting account already has a beneficiary.
+		BeneficiaryAlreadySet,
+		/// Some of the vested funds of the account were already unlocked.
+		FundsAlreadyUnlocked,
+		/// The sender is not the beneficiary of the vesting account.
+		NotBeneficiary,
+		/// The account has no vesting schedule at the given index.
+		ScheduleIndexOutOfBounds,
 	}
 
 	#[pallet::call]
@@ -210,20 +589,51 @@ pub mod pallet {
 		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
 		/// locked under this pallet.
 		///
-		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		/// Emits either `VestingCompleted` or `VestingUpdated`, unless nothing vested since the
+		/// lock was last updated, in which case most of the weight is refunded.
 		///
 		/// # <weight>
 		/// - `O(1)`.
-		/// - DbWeight: 2 Reads, 2 Writes
-		///     - Reads: Vesting Storage, Balances Locks, [Sender Account]
-		///     - Writes: Vesting Storage, Balances Locks, [Sender Account]
+		/// - DbWeight: 6 Reads, 6 Writes
+		///     - Reads: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, VestingAccountsCount, [Sender Account]
+		///     - Writes: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, VestingAccountsCount, [Sender Account]
 		/// # </weight>
 		#[pallet::weight(T::WeightInfo::vest_locked(MaxLocksOf::<T>::get())
 			.max(T::WeightInfo::vest_unlocked(MaxLocksOf::<T>::get()))
 		)]
-		pub fn vest(origin: OriginFor<T>) -> DispatchResult {
+		pub fn vest(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::update_lock_if_vested(who, None)
+		}
+
+		/// Unlock at most `max_unlock` of the vested funds of the sender account, keeping the rest
+		/// locked.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
+		/// locked under this pallet.
+		///
+		/// The funds kept locked are unlocked by any later `vest` or `vest_other` of the account.
+		///
+		/// - `max_unlock`: The maximum amount to unlock.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 5 Reads, 2 Writes
+		///     - Reads: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, [Sender Account]
+		///     - Writes: Released Storage, Balances Locks, [Sender Account]
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::vest_partial(MaxLocksOf::<T>::get()))]
+		pub fn vest_partial(
+			origin: OriginFor<T>,
+			#[pallet::compact] max_unlock: BalanceOf<T>,
+		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
-			Self::update_lock(who)
+			Self::do_update_lock(who, Some(max_unlock)).map(|_| ())
 		}
 
 		/// Unlock any vested funds of a `target` account.
@@ -233,13 +643,20 @@ pub mod pallet {
 		/// - `target`: The account whose vested funds should be unlocked. Must have funds still
 		/// locked under this pallet.
 		///
-		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		/// If funds are unlocked, the sender is paid the [`Config::VestOtherReward`] share of them.
+		/// Nothing is paid if `target` cannot afford it.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`, unless nothing vested since the
+		/// lock was last updated, in which case most of the weight is refunded. Emits
+		/// `VestOtherRewardPaid` if the sender was paid.
 		///
 		/// # <weight>
 		/// - `O(1)`.
-		/// - DbWeight: 3 Reads, 3 Writes
-		///     - Reads: Vesting Storage, Balances Locks, Target Account
-		///     - Writes: Vesting Storage, Balances Locks, Target Account
+		/// - DbWeight: 7 Reads, 7 Writes
+		///     - Reads: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, VestingAccountsCount, Target Account, [Sender Account]
+		///     - Writes: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, VestingAccountsCount, Target Account, [Sender Account]
 		/// # </weight>
 		#[pallet::weight(T::WeightInfo::vest_other_locked(MaxLocksOf::<T>::get())
 			.max(T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get()))
@@ -247,26 +664,32 @@ pub mod pallet {
 		pub fn vest_other(
 			origin: OriginFor<T>,
 			target: <T::Lookup as StaticLookup>::Source,
-		) -> DispatchResult {
-			ensure_signed(origin)?;
-			Self::update_lock(T::Lookup::lookup(target)?)
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::update_lock_if_vested(T::Lookup::lookup(target)?, Some(&who))
 		}
 
 		/// Create a vested transfer.
 		///
 		/// The dispatch origin for this call must be _Signed_.
 		///
+		/// If `target` already has a vesting schedule starting, reaching its cliff and fully vested
+		/// at the same blocks as `schedule`, the two are combined. Otherwise `target` must not be
+		/// vesting.
+		///
 		/// - `target`: The account that should be transferred the vested funds.
 		/// - `amount`: The amount of funds to transfer and will be vested.
 		/// - `schedule`: The vesting schedule attached to the transfer.
 		///
-		/// Emits `VestingCreated`.
+		/// Emits `VestingScheduleAdded`.
 		///
 		/// # <weight>
 		/// - `O(1)`.
-		/// - DbWeight: 3 Reads, 3 Writes
-		///     - Reads: Vesting Storage, Balances Locks, Target Account, [Sender Account]
-		///     - Writes: Vesting Storage, Balances Locks, Target Account, [Sender Account]
+		/// - DbWeight: 7 Reads, 5 Writes
+		///     - Reads: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, VestingAccountsCount, Target Account, [Sender Account]
+		///     - Writes: Vesting Storage, Released Storage, Balances Locks, VestingAccountsCount,
+		///       Target Account, [Sender Account]
 		/// # </weight>
 		#[pallet::weight(T::WeightInfo::vested_transfer(MaxLocksOf::<T>::get()))]
 		pub fn vested_transfer(
@@ -276,44 +699,79 @@ pub mod pallet {
 		) -> DispatchResult {
 			let transactor = ensure_signed(origin)?;
 			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
-
-			let who = T::Lookup::lookup(target)?;
-			ensure!(!Vesting::<T>::contains_key(&who), Error::<T>::ExistingVestingSchedule);
-
-			T::Currency::transfer(
+			let target = T::Lookup::lookup(target)?;
+			Self::do_vested_transfer(
 				&transactor,
-				&who,
-				schedule.locked,
+				&target,
+				schedule,
 				ExistenceRequirement::AllowDeath,
-			)?;
-
-			Self::add_vesting_schedule(
-				&who,
-				schedule.locked,
-				schedule.per_block,
-				schedule.starting_block,
 			)
-			.expect("user does not have an existing vesting schedule; q.e.d.");
+		}
 
-			Ok(())
+		/// Same as the [`vested_transfer`][`Call::vested_transfer`] call, but with a check that the
+		/// transfer will not kill the origin account.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// If `target` already has a vesting schedule starting, reaching its cliff and fully vested
+		/// at the same blocks as `schedule`, the two are combined. Otherwise `target` must not be
+		/// vesting.
+		///
+		/// - `target`: The account that should be transferred the vested funds.
+		/// - `schedule`: The vesting schedule attached to the transfer.
+		///
+		/// Emits `VestingScheduleAdded`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 7 Reads, 5 Writes
+		///     - Reads: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, VestingAccountsCount, Target Account, [Sender Account]
+		///     - Writes: Vesting Storage, Released Storage, Balances Locks, VestingAccountsCount,
+		///       Target Account, [Sender Account]
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::vested_transfer_keep_alive(MaxLocksOf::<T>::get()))]
+		pub fn vested_transfer_keep_alive(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		) -> DispatchResult {
+			let transactor = ensure_signed(origin)?;
+			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
+			let target = T::Lookup::lookup(target)?;
+			Self::do_vested_transfer(
+				&transactor,
+				&target,
+				schedule,
+				ExistenceRequirement::KeepAlive,
+			)
 		}
 
 		/// Force a vested transfer.
 		///
 		/// The dispatch origin for this call must be _Root_.
 		///
+		/// Unlike with `vested_transfer`, the amount transferred may be below
+		/// [`Config::MinVestedTransfer`].
+		///
+		/// If `target` already has a vesting schedule starting, reaching its cliff and fully vested
+		/// at the same blocks as `schedule`, the two are combined. Otherwise `target` must not be
+		/// vesting.
+		///
 		/// - `source`: The account whose funds should be transferred.
 		/// - `target`: The account that should be transferred the vested funds.
 		/// - `amount`: The amount of funds to transfer and will be vested.
 		/// - `schedule`: The vesting schedule attached to the transfer.
 		///
-		/// Emits `VestingCreated`.
+		/// Emits `VestingScheduleAdded`.
 		///
 		/// # <weight>
 		/// - `O(1)`.
-		/// - DbWeight: 4 Reads, 4 Writes
-		///     - Reads: Vesting Storage, Balances Locks, Target Account, Source Account
-		///     - Writes: Vesting Storage, Balances Locks, Target Account, Source Account
+		/// - DbWeight: 8 Reads, 6 Writes
+		///     - Reads: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, VestingAccountsCount, Target Account, Source Account
+		///     - Writes: Vesting Storage, Released Storage, Balances Locks, VestingAccountsCount,
+		///       Target Account, Source Account
 		/// # </weight>
 		#[pallet::weight(T::WeightInfo::force_vested_transfer(MaxLocksOf::<T>::get()))]
 		pub fn force_vested_transfer(
@@ -323,53 +781,768 @@ pub mod pallet {
 			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
 		) -> DispatchResult {
 			ensure_root(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
-
 			let target = T::Lookup::lookup(target)?;
 			let source = T::Lookup::lookup(source)?;
-			ensure!(!Vesting::<T>::contains_key(&target), Error::<T>::ExistingVestingSchedule);
+			Self::do_vested_transfer(&source, &target, schedule, ExistenceRequirement::AllowDeath)
+		}
 
-			T::Currency::transfer(
-				&source,
-				&target,
-				schedule.locked,
-				ExistenceRequirement::AllowDeath,
-			)?;
+		/// Force many vested transfers from the same account.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		///
+		/// Each transfer is made as with [`force_vested_transfer`][`Call::force_vested_transfer`].
+		/// A transfer which fails does not prevent the others from being made, its index in
+		/// `transfers` and its error are reported with a `BatchVestedTransferFailed` event.
+		///
+		/// - `source`: The account whose funds should be transferred.
+		/// - `transfers`: The accounts that should be transferred vested funds, each with the
+		///   vesting schedule attached to its transfer.
+		///
+		/// Emits `VestingScheduleAdded` for each transfer made, and `BatchVestedTransferFailed`
+		/// for each transfer which failed.
+		///
+		/// # <weight>
+		/// - `O(N)` where `N` is the number of transfers.
+		/// - DbWeight: 2 + 6 * N Reads, 2 + 4 * N Writes
+		///     - Reads: Source Account, VestingAccountsCount, Vesting Storage, Released Storage,
+		///       PausedAt Storage, VestingBeneficiary Storage, Balances Locks, Target Accounts
+		///     - Writes: Source Account, VestingAccountsCount, Vesting Storage, Released Storage,
+		///       Balances Locks, Target Accounts
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::force_batch_vested_transfer(
+			transfers.len() as u32,
+			MaxLocksOf::<T>::get(),
+		))]
+		pub fn force_batch_vested_transfer(
+			origin: OriginFor<T>,
+			source: <T::Lookup as StaticLookup>::Source,
+			transfers: Vec<(
+				<T::Lookup as StaticLookup>::Source,
+				VestingInfo<BalanceOf<T>, T::BlockNumber>,
+			)>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let source = T::Lookup::lookup(source)?;
+			for (index, (target, schedule)) in transfers.into_iter().enumerate() {
+				let res =
+					T::Lookup::lookup(target).map_err(DispatchError::from).and_then(|target| {
+						Self::do_vested_transfer(
+							&source,
+							&target,
+							schedule,
+							ExistenceRequirement::AllowDeath,
+						)
+					});
+				if let Err(e) = res {
+					Self::deposit_event(Event::<T>::BatchVestedTransferFailed(index as u32, e));
+				}
+			}
+			Ok(())
+		}
 
-			Self::add_vesting_schedule(
-				&target,
-				schedule.locked,
-				schedule.per_block,
-				schedule.starting_block,
-			)
-			.expect("user does not have an existing vesting schedule; q.e.d.");
+		/// Revoke the vesting schedule of an account.
+		///
+		/// The dispatch origin for this call must be `ForceOrigin`.
+		///
+		/// - `target`: The account whose vesting schedule should be removed.
+		/// - `schedule_index`: The index of the schedule to remove. An account has a single
+		///   schedule, so this must be 0.
+		/// - `return_to`: The account the amount which is still locked should be transferred to,
+		///   if any. Otherwise the account keeps it, fully unlocked. Only what the other locks of
+		///   `target` leave transferable is transferred.
+		///
+		/// Emits `VestingScheduleRemoved`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 6 Reads, 8 Writes
+		///     - Reads: Vesting Storage, PausedAt Storage, Balances Locks, VestingAccountsCount,
+		///       Target Account, [Return Account]
+		///     - Writes: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, VestingAccountsCount, Target Account, [Return Account]
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::force_remove_vesting_schedule(MaxLocksOf::<T>::get()))]
+		#[transactional]
+		pub fn force_remove_vesting_schedule(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule_index: u32,
+			return_to: Option<<T::Lookup as StaticLookup>::Source>,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			let who = T::Lookup::lookup(target)?;
+			ensure!(Vesting::<T>::contains_key(&who), Error::<T>::NotVesting);
+			ensure!(schedule_index == 0, Error::<T>::ScheduleIndexOutOfBounds);
+			let now = Self::vesting_block(&who, T::BlockNumberProvider::current_block_number());
+			let schedule = Self::take_schedule(&who).ok_or(Error::<T>::NotVesting)?;
+			let mut amount = schedule
+				.locked_at::<T::BlockNumberToBalance>(now)
+				.min(T::Currency::free_balance(&who));
+			T::Currency::remove_lock(VESTING_ID, &who);
+
+			if let Some(return_to) = return_to {
+				let return_to = T::Lookup::lookup(return_to)?;
+				amount = amount
+					.min(<T::Currency as fungible::Inspect<_>>::reducible_balance(&who, false));
+				T::Currency::transfer(&who, &return_to, amount, ExistenceRequirement::AllowDeath)?;
+			}
+			T::VestingHooks::on_vesting_completed(&who);
+
+			Self::deposit_event(Event::<T>::VestingScheduleRemoved(who, amount));
+			Ok(())
+		}
+
+		/// Replace the vesting schedule of an account, for instance to correct a schedule created
+		/// at genesis.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		///
+		/// - `target`: The account whose vesting schedule should be replaced. Must be vesting.
+		/// - `schedule`: The new vesting schedule. No funds are transferred, the lock is updated
+		///   to what `schedule` still locks.
+		///
+		/// The new schedule starts over: nothing of it counts as unlocked already, and the pause
+		/// and the beneficiary of the account, if any, are removed.
+		///
+		/// Emits `VestingScheduleAdded`, then either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 6 Reads, 6 Writes
+		///     - Reads: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, Target Account
+		///     - Writes: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, Target Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::force_set_vesting_schedule(MaxLocksOf::<T>::get()))]
+		pub fn force_set_vesting_schedule(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::ensure_valid_schedule(&schedule)?;
+
+			let who = T::Lookup::lookup(target)?;
+			ensure!(Vesting::<T>::contains_key(&who), Error::<T>::NotVesting);
+
+			// The new schedule starts over: it is not paused and has no beneficiary.
+			PausedAt::<T>::remove(&who);
+			VestingBeneficiary::<T>::remove(&who);
+			Self::insert_schedule(&who, schedule, Zero::zero());
+			Ok(())
+		}
+
+		/// Let `beneficiary` claim the funds of the sender account as they vest.
+		///
+		/// From then on, the vested funds of the sender account are no longer unlocked by `vest`,
+		/// `vest_other` or `vest_partial`, but only when `beneficiary` claims them with
+		/// `claim_vested_to`. The beneficiary cannot be changed until the account has fully
+		/// vested.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must be vesting, with
+		/// none of its vested funds unlocked yet.
+		///
+		/// - `beneficiary`: The account which may claim the vested funds.
+		///
+		/// Emits `VestingBeneficiarySet`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 3 Reads, 1 Write
+		///     - Reads: Vesting Storage, Released Storage, VestingBeneficiary Storage
+		///     - Writes: VestingBeneficiary Storage
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::set_vesting_beneficiary())]
+		pub fn set_vesting_beneficiary(
+			origin: OriginFor<T>,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+			ensure!(Vesting::<T>::contains_key(&who), Error::<T>::NotVesting);
+			ensure!(Released::<T>::get(&who).is_zero(), Error::<T>::FundsAlreadyUnlocked);
+			ensure!(
+				!VestingBeneficiary::<T>::contains_key(&who),
+				Error::<T>::BeneficiaryAlreadySet
+			);
+
+			VestingBeneficiary::<T>::insert(&who, &beneficiary);
+			Self::deposit_event(Event::<T>::VestingBeneficiarySet(who, beneficiary));
+			Ok(())
+		}
+
+		/// Unlock at most `max_amount` of the vested funds of `from`, and transfer them to the
+		/// sender.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must be the
+		/// beneficiary of `from`.
+		///
+		/// - `from`: The vesting account whose vested funds should be claimed.
+		/// - `max_amount`: The maximum amount to claim.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`, then `VestedFundsClaimed`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 7 Reads, 7 Writes
+		///     - Reads: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, VestingAccountsCount, Vesting Account, [Sender Account]
+		///     - Writes: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, VestingAccountsCount, Vesting Account, [Sender Account]
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::claim_vested_to(MaxLocksOf::<T>::get()))]
+		#[transactional]
+		pub fn claim_vested_to(
+			origin: OriginFor<T>,
+			from: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] max_amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let beneficiary = ensure_signed(origin)?;
+			let from = T::Lookup::lookup(from)?;
+			ensure!(
+				Self::vesting_beneficiary(&from).as_ref() == Some(&beneficiary),
+				Error::<T>::NotBeneficiary
+			);
+
+			Self::do_claim_vested(&from, &beneficiary, Some(max_amount)).map(|_| ())
+		}
+
+		/// Pause the vesting schedule of an account, so that nothing vests until it is resumed.
+		///
+		/// The dispatch origin for this call must be `PauseOrigin`.
+		///
+		/// - `target`: The account whose vesting schedule should be paused. Must be vesting.
+		///
+		/// Emits `VestingPaused`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 2 Reads, 1 Write
+		///     - Reads: Vesting Storage, PausedAt Storage
+		///     - Writes: PausedAt Storage
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::pause_schedule())]
+		pub fn pause_schedule(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			let who = T::Lookup::lookup(target)?;
+			ensure!(Vesting::<T>::contains_key(&who), Error::<T>::NotVesting);
+			ensure!(!PausedAt::<T>::contains_key(&who), Error::<T>::AlreadyPaused);
+
+			PausedAt::<T>::insert(&who, T::BlockNumberProvider::current_block_number());
+			Self::deposit_event(Event::<T>::VestingPaused(who));
+			Ok(())
+		}
 
+		/// Resume the paused vesting schedule of an account.
+		///
+		/// The schedule is delayed by the number of blocks it was paused for: its starting block
+		/// and its cliff, if any, are moved forward, so that it continues from where it was
+		/// paused.
+		///
+		/// The dispatch origin for this call must be `PauseOrigin`.
+		///
+		/// - `target`: The account whose vesting schedule should be resumed. Must be paused.
+		///
+		/// Emits `VestingScheduleAdded`, then either `VestingCompleted` or `VestingUpdated`, then
+		/// `VestingResumed`.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - DbWeight: 6 Reads, 5 Writes
+		///     - Reads: Vesting Storage, Released Storage, PausedAt Storage, VestingBeneficiary
+		///       Storage, Balances Locks, Target Account
+		///     - Writes: Vesting Storage, Released Storage, PausedAt Storage, Balances Locks, Target
+		///       Account
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::resume_schedule(MaxLocksOf::<T>::get()))]
+		pub fn resume_schedule(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			let who = T::Lookup::lookup(target)?;
+			let schedule = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+			let paused_at = PausedAt::<T>::get(&who).ok_or(Error::<T>::NotPaused)?;
+			let paused_for =
+				T::BlockNumberProvider::current_block_number().saturating_sub(paused_at);
+			let delay = |block: T::BlockNumber| {
+				block.checked_add(&paused_for).ok_or(Error::<T>::ScheduleEndsOverflow)
+			};
+			let schedule = VestingInfo {
+				starting_block: delay(schedule.starting_block)?,
+				cliff_block: schedule.cliff_block.map(delay).transpose()?,
+				..schedule
+			};
+			Self::ensure_valid_schedule(&schedule)?;
+
+			PausedAt::<T>::remove(&who);
+			// The delayed schedule locks as much as the paused one did, what was unlocked of it
+			// stays unlocked.
+			Self::insert_schedule(&who, schedule, Released::<T>::get(&who));
+			Self::deposit_event(Event::<T>::VestingResumed(who, paused_for));
 			Ok(())
 		}
 	}
 }
 
 impl<T: Config> Pallet<T> {
+	/// All the vesting accounts, along with their vesting schedules.
+	///
+	/// The accounts are iterated in the order of their storage keys, which does not change from
+	/// block to block. The iteration can be resumed in a later block by passing the
+	/// [`last_raw_key`][frame_support::storage::PrefixIterator::last_raw_key] of the iterator to
+	/// `Vesting::<T>::iter_from`.
+	pub fn vesting_accounts_iter() -> frame_support::storage::PrefixIterator<(
+		T::AccountId,
+		VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	)> {
+		Vesting::<T>::iter()
+	}
+
+	/// A summary of the vesting of `who` as of the current block, if `who` is vesting.
+	///
+	/// This follows the schedule, regardless of whether the lock has been updated since.
+	pub fn account_vesting_summary(
+		who: &T::AccountId,
+	) -> Option<VestingSummary<BalanceOf<T>, T::BlockNumber>> {
+		let paused = PausedAt::<T>::contains_key(who);
+		let now = Self::vesting_block(who, T::BlockNumberProvider::current_block_number());
+
+		if let Some(schedule) = Self::vesting(who) {
+			let locked = schedule.locked_at::<T::BlockNumberToBalance>(now);
+			let next_unlock_block = match schedule.cliff_block {
+				_ if paused || locked.is_zero() => None,
+				Some(cliff) if now < cliff => Some(cliff),
+				// Nothing is unlocked at the starting block itself.
+				_ => Some(now.max(schedule.starting_block).saturating_add(One::one())),
+			};
+			Some(VestingSummary {
+				locked,
+				originally_locked: schedule.locked,
+				next_unlock_block,
+				ending_block: schedule.ending_block().unwrap_or_else(Bounded::max_value),
+			})
+		} else {
+			None
+		}
+	}
+
+	/// The amount which the vesting schedule of `who` has vested by block `n`, if `who` is vesting.
+	pub fn vested_at(who: &T::AccountId, n: T::BlockNumber) -> Option<BalanceOf<T>> {
+		let n = Self::vesting_block(who, n);
+		Self::vesting(who).map(|schedule| {
+			schedule.locked.saturating_sub(schedule.locked_at::<T::BlockNumberToBalance>(n))
+		})
+	}
+
+	/// The amount which the vesting schedules of `who` still lock at block `n`, or zero if `who`
+	/// is not vesting.
+	///
+	/// This follows the schedules, regardless of whether the lock has been updated since.
+	pub fn total_locked_at(who: &T::AccountId, n: T::BlockNumber) -> BalanceOf<T> {
+		Self::schedules_locked_at(who, n)
+			.into_iter()
+			.fold(Zero::zero(), |total, (locked, _)| total.saturating_add(locked))
+	}
+
+	/// For each vesting schedule of `who`, the amount it still locks at block `n` and the block at
+	/// which it is fully vested, as a balance.
+	pub fn schedules_locked_at(
+		who: &T::AccountId,
+		n: T::BlockNumber,
+	) -> Vec<(BalanceOf<T>, BalanceOf<T>)> {
+		let n = Self::vesting_block(who, n);
+		Self::vesting(who)
+			.into_iter()
+			.map(|schedule| {
+				(
+					schedule.locked_at::<T::BlockNumberToBalance>(n),
+					schedule.ending_block_as_balance::<T::BlockNumberToBalance>(),
+				)
+			})
+			.collect()
+	}
+
+	/// The amount of the vesting lock on the account of `who`, zero if there is none.
+	fn vesting_lock(who: &T::AccountId) -> BalanceOf<T> {
+		T::Currency::balance_restrictions(who)
+			.into_iter()
+			.find_map(|restriction| match restriction {
+				BalanceRestriction::Lock { id, amount } if id == VESTING_ID => Some(amount),
+				_ => None,
+			})
+			.unwrap_or_else(Zero::zero)
+	}
+
+	/// The block against which the vesting schedule of `who` is evaluated at block `n`: `n`, or
+	/// the block at which the schedule was paused if it is paused.
+	fn vesting_block(who: &T::AccountId, n: T::BlockNumber) -> T::BlockNumber {
+		PausedAt::<T>::get(who).map_or(n, |paused_at| paused_at.min(n))
+	}
+
+	/// Update the lock of `who` like [`Self::update_lock`], unless nothing vested since it was
+	/// last updated.
+	///
+	/// The lock is then left as is, and only the weight of checking it is charged. Otherwise
+	/// `keeper`, if given, is paid the [`Config::VestOtherReward`] share of the funds unlocked.
+	fn update_lock_if_vested(
+		who: T::AccountId,
+		keeper: Option<&T::AccountId>,
+	) -> DispatchResultWithPostInfo {
+		let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+		let now = Self::vesting_block(&who, T::BlockNumberProvider::current_block_number());
+		let locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
+		let locked_before = vesting.locked.saturating_sub(Released::<T>::get(&who));
+		if !locked_now.is_zero() && locked_now == locked_before {
+			return Ok(Some(T::WeightInfo::vest_unchanged()).into())
+		}
+
+		let unlocked = Self::do_update_lock(who.clone(), None)?;
+		let reward = T::VestOtherReward::get() * unlocked;
+		if let Some(keeper) = keeper.filter(|_| !reward.is_zero()) {
+			// The funds unlocked may already be spent, in which case there is no reward.
+			if T::Currency::transfer(&who, keeper, reward, ExistenceRequirement::KeepAlive).is_ok()
+			{
+				Self::deposit_event(Event::<T>::VestOtherRewardPaid(who, keeper.clone(), reward));
+			}
+		}
+		Ok(().into())
+	}
+
 	/// (Re)set or remove the pallet's currency lock on `who`'s account in accordance with their
 	/// current unvested amount.
 	fn update_lock(who: T::AccountId) -> DispatchResult {
+		Self::do_update_lock(who, None).map(|_| ())
+	}
+
+	/// (Re)set or remove the pallet's currency lock on `who`'s account in accordance with their
+	/// current unvested amount, unlocking at most `max_unlock` if given, and return the amount
+	/// newly unlocked.
+	///
+	/// Nothing is unlocked if `who` has a beneficiary, who claims the vested funds instead.
+	fn do_update_lock(
+		who: T::AccountId,
+		max_unlock: Option<BalanceOf<T>>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let max_unlock = if VestingBeneficiary::<T>::contains_key(&who) {
+			Some(Zero::zero())
+		} else {
+			max_unlock
+		};
+		Self::unlock_vested(who, max_unlock)
+	}
+
+	/// (Re)set or remove the pallet's currency lock on `who`'s account in accordance with their
+	/// current unvested amount, unlocking at most `max_unlock` if given, and return the amount
+	/// newly unlocked.
+	fn unlock_vested(
+		who: T::AccountId,
+		max_unlock: Option<BalanceOf<T>>,
+	) -> Result<BalanceOf<T>, DispatchError> {
 		let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
-		let now = <frame_system::Pallet<T>>::block_number();
-		let locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
+		let now = Self::vesting_block(&who, T::BlockNumberProvider::current_block_number());
+		let mut locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
+		let locked_before = vesting.locked.saturating_sub(Released::<T>::get(&who));
+		if let Some(max_unlock) = max_unlock {
+			locked_now = locked_now.max(locked_before.saturating_sub(max_unlock));
+		}
+		let unlocked = locked_before.saturating_sub(locked_now);
 
 		if locked_now.is_zero() {
 			T::Currency::remove_lock(VESTING_ID, &who);
-			Vesting::<T>::remove(&who);
+			Self::take_schedule(&who);
+			T::VestingHooks::on_vesting_completed(&who);
 			Self::deposit_event(Event::<T>::VestingCompleted(who));
 		} else {
-			let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
+			let reasons = WithdrawReasons::except(T::UnvestedFundsAllowedWithdrawReasons::get());
 			T::Currency::set_lock(VESTING_ID, &who, locked_now, reasons);
+			Released::<T>::insert(&who, vesting.locked.saturating_sub(locked_now));
+			T::VestingHooks::on_vesting_updated(&who, locked_now);
 			Self::deposit_event(Event::<T>::VestingUpdated(who, locked_now));
 		}
+		Ok(unlocked)
+	}
+
+	/// Unlock at most `max_amount`, if given, of the vested funds of `from`, and transfer them to
+	/// its `beneficiary`. Returns the amount transferred.
+	#[transactional]
+	fn do_claim_vested(
+		from: &T::AccountId,
+		beneficiary: &T::AccountId,
+		max_amount: Option<BalanceOf<T>>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let unlocked = Self::unlock_vested(from.clone(), max_amount)?;
+		T::Currency::transfer(from, beneficiary, unlocked, ExistenceRequirement::AllowDeath)?;
+		Self::deposit_event(Event::<T>::VestedFundsClaimed(
+			from.clone(),
+			beneficiary.clone(),
+			unlocked,
+		));
+		Ok(unlocked)
+	}
+
+	/// Transfer the funds locked by `schedule` from `source` to `target`, and vest them with
+	/// `schedule`.
+	fn do_vested_transfer(
+		source: &T::AccountId,
+		target: &T::AccountId,
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		existence_requirement: ExistenceRequirement,
+	) -> DispatchResult {
+		Self::ensure_valid_schedule(&schedule)?;
+		let amount = schedule.locked;
+		let schedule = match Self::vesting(target) {
+			None => schedule,
+			Some(existing) => Self::combine_aligned_schedules(existing, schedule)
+				.ok_or(Error::<T>::ExistingVestingSchedule)?,
+		};
+
+		T::Currency::transfer(source, target, amount, existence_requirement)?;
+
+		// What was unlocked of the existing schedule is still unlocked from the combined one.
+		Self::insert_schedule(target, schedule, Released::<T>::get(target));
+
+		Ok(())
+	}
+
+	/// Ensure that `schedule` can be stored, failing with the reason it can't otherwise.
+	fn ensure_valid_schedule(
+		schedule: &VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> DispatchResult {
+		ensure!(!schedule.locked.is_zero(), Error::<T>::ZeroLocked);
+		ensure!(!schedule.per_block.is_zero(), Error::<T>::ZeroPerBlock);
+		ensure!(schedule.has_valid_cliff(), Error::<T>::InvalidScheduleParams);
+		ensure!(schedule.remainder < schedule.locked, Error::<T>::InvalidScheduleParams);
+		ensure!(schedule.ending_block().is_some(), Error::<T>::ScheduleEndsOverflow);
+		Ok(())
+	}
+
+	/// Combine the schedules `a` and `b` into a single schedule, if they start, reach their cliff
+	/// and are fully vested at the same blocks.
+	///
+	/// At every block, the combined schedule locks what `a` and `b` lock together.
+	fn combine_aligned_schedules(
+		a: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		b: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>> {
+		let aligned = a.starting_block == b.starting_block &&
+			a.cliff_block == b.cliff_block &&
+			a.ending_block_as_balance::<T::BlockNumberToBalance>() ==
+				b.ending_block_as_balance::<T::BlockNumberToBalance>();
+		if !aligned {
+			return None
+		}
+		Some(VestingInfo {
+			locked: a.locked.checked_add(&b.locked)?,
+			per_block: a.per_block.checked_add(&b.per_block)?,
+			starting_block: a.starting_block,
+			cliff_block: a.cliff_block,
+			remainder: a.remainder.checked_add(&b.remainder)?,
+		})
+	}
+
+	/// Check up to [`Config::MaxAutoVestsPerBlock`] vesting accounts, resuming after the account
+	/// checked last, and remove the schedules which have ended along with their locks.
+	///
+	/// The accounts with a vesting beneficiary are skipped: their vested funds stay locked until
+	/// the beneficiary claims them, so their schedules can't be removed here.
+	///
+	/// Stops before the weight of the checks exceeds `remaining_weight`, and returns the weight
+	/// consumed.
+	fn auto_vest(remaining_weight: Weight) -> Weight {
+		let max_accounts = T::MaxAutoVestsPerBlock::get();
+		let db_weight = T::DbWeight::get();
+		let per_account = T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get());
+		// Reading and writing the cursor.
+		let mut consumed = db_weight.reads_writes(1, 1);
+		if max_accounts.is_zero() || consumed.saturating_add(per_account) > remaining_weight {
+			return 0
+		}
+
+		let mut accounts = match AutoVestCursor::<T>::get() {
+			Some(cursor) => Vesting::<T>::iter_from(cursor),
+			None => Vesting::<T>::iter(),
+		};
+		let now = T::BlockNumberProvider::current_block_number();
+		let mut checked = 0;
+		while checked < max_accounts && consumed.saturating_add(per_account) <= remaining_weight {
+			let (who, schedule) = match accounts.next() {
+				Some(account) => account,
+				None => {
+					AutoVestCursor::<T>::kill();
+					return consumed
+				},
+			};
+			if VestingBeneficiary::<T>::contains_key(&who) {
+				consumed = consumed.saturating_add(db_weight.reads(1));
+				checked += 1;
+				continue
+			}
+			let vesting_block = Self::vesting_block(&who, now);
+			if schedule.locked_at::<T::BlockNumberToBalance>(vesting_block).is_zero() {
+				// it can't fail, the account is vesting.
+				let res = Self::update_lock(who);
+				debug_assert!(res.is_ok());
+				consumed = consumed.saturating_add(per_account);
+			} else {
+				consumed = consumed.saturating_add(db_weight.reads(3));
+			}
+			checked += 1;
+		}
+
+		AutoVestCursor::<T>::put(accounts.last_raw_key().to_vec());
+		consumed
+	}
+
+	/// Store `schedule` as the vesting schedule of `who`, replacing the existing one if any, of
+	/// which `released` is already unlocked, and lock the funds which are still vesting.
+	///
+	/// The pause and the beneficiary of `who`, if any, are kept.
+	fn insert_schedule(
+		who: &T::AccountId,
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		released: BalanceOf<T>,
+	) {
+		if Vesting::<T>::mutate(who, |existing| existing.replace(schedule)).is_none() {
+			VestingAccountsCount::<T>::mutate(|count| *count = count.saturating_add(1));
+		}
+		if released.is_zero() {
+			Released::<T>::remove(who);
+		} else {
+			Released::<T>::insert(who, released);
+		}
+		Self::deposit_event(Event::<T>::VestingScheduleAdded(
+			who.clone(),
+			schedule.locked,
+			schedule.per_block,
+			schedule.starting_block,
+			schedule.cliff_block,
+			schedule.remainder,
+		));
+		// it can't fail, but even if somehow it did, we don't really care.
+		let res = Self::update_lock(who.clone());
+		debug_assert!(res.is_ok());
+	}
+
+	/// Remove the vesting schedule of `who`, if any, along with everything stored about it but
+	/// its lock.
+	fn take_schedule(who: &T::AccountId) -> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>> {
+		let schedule = Vesting::<T>::take(who)?;
+		Released::<T>::remove(who);
+		PausedAt::<T>::remove(who);
+		VestingBeneficiary::<T>::remove(who);
+		VestingAccountsCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+		Some(schedule)
+	}
+
+	/// Merge the vesting schedules `a` and `b` into a single schedule, as of block `now`.
+	///
+	/// The merged schedule locks what both schedules still lock at `now`. It starts at `now`, or
+	/// at the later starting block if it is ahead, and unlocks linearly until exactly the later of
+	/// the blocks at which `a` and `b` are fully vested, see [`VestingInfo::over_length`]. It
+	/// keeps the later cliff which is still ahead, if any, so that merging never unlocks funds
+	/// before the cliff they were subject to.
+	///
+	/// Returns `None` if both schedules are fully vested.
+	fn merge_vesting_info(
+		now: T::BlockNumber,
+		a: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		b: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>> {
+		let locked = a
+			.locked_at::<T::BlockNumberToBalance>(now)
+			.saturating_add(b.locked_at::<T::BlockNumberToBalance>(now));
+		if locked.is_zero() {
+			return None
+		}
+
+		let ending_block = a
+			.ending_block_as_balance::<T::BlockNumberToBalance>()
+			.max(b.ending_block_as_balance::<T::BlockNumberToBalance>());
+		let starting_block = now.max(a.starting_block).max(b.starting_block);
+		let duration =
+			ending_block.saturating_sub(T::BlockNumberToBalance::convert(starting_block));
+		let cliff_block = a
+			.cliff_block
+			.into_iter()
+			.chain(b.cliff_block)
+			.filter(|cliff| *cliff > starting_block)
+			.max();
+
+		VestingInfo::over_length(locked, starting_block, duration, cliff_block)
+	}
+
+	/// Ensure the correctness of the state of this pallet.
+	///
+	/// This is run by the `try-runtime` hooks both before and after a runtime upgrade. It checks
+	/// that no stored vesting schedule has a zero `locked` or `per_block` amount, or a cliff which
+	/// is not after its starting block, since such schedules are never created, and that the
+	/// vesting accounts are counted correctly.
+	///
+	/// It also checks that the vesting lock of each account is the amount of its schedule which
+	/// was not released yet, and that this covers what is still vesting, paused time excluded.
+	#[cfg(feature = "try-runtime")]
+	pub fn do_try_state() -> Result<(), &'static str> {
+		let now = T::BlockNumberProvider::current_block_number();
+		let mut count = 0u32;
+		for (who, schedule) in Vesting::<T>::iter() {
+			ensure!(!schedule.locked.is_zero(), "Vesting schedule with zero locked amount.");
+			ensure!(!schedule.per_block.is_zero(), "Vesting schedule with zero per block amount.");
+			ensure!(schedule.is_valid(), "Invalid vesting schedule.");
+			let locked = schedule.locked.saturating_sub(Released::<T>::get(&who));
+			ensure!(
+				Self::vesting_lock(&who) == locked,
+				"Vesting lock does not match the schedule."
+			);
+			let vesting_block = Self::vesting_block(&who, now);
+			ensure!(
+				schedule.locked_at::<T::BlockNumberToBalance>(vesting_block) <= locked,
+				"Vesting lock is below the amount still vesting."
+			);
+			count += 1;
+		}
+		ensure!(count == VestingAccountsCount::<T>::get(), "Wrong number of vesting accounts.");
 		Ok(())
 	}
 }
 
+impl<T: Config> CheckInvariants for Pallet<T> {
+	/// Check that no vesting schedule has a zero `locked` amount, that the vesting lock of each
+	/// account is the amount of its schedule not released yet and covers what is still vesting,
+	/// and that the sum of the amounts which are still vesting does not exceed the total issuance.
+	fn check_invariants() -> Vec<&'static str> {
+		let now = T::BlockNumberProvider::current_block_number();
+		let mut violations = Vec::new();
+		let mut still_vesting = BalanceOf::<T>::zero();
+		for (who, schedule) in Vesting::<T>::iter() {
+			if schedule.locked.is_zero() {
+				violations.push("Vesting: schedule with zero locked amount.");
+			}
+			let locked = schedule.locked.saturating_sub(Released::<T>::get(&who));
+			if Self::vesting_lock(&who) != locked {
+				violations.push("Vesting: lock does not match the schedule.");
+			}
+			let vesting_block = Self::vesting_block(&who, now);
+			let locked_now = schedule.locked_at::<T::BlockNumberToBalance>(vesting_block);
+			if locked_now > locked {
+				violations.push("Vesting: lock is below the amount still vesting.");
+			}
+			still_vesting = still_vesting.saturating_add(locked_now);
+		}
+		if still_vesting > T::Currency::total_issuance() {
+			violations.push("Vesting: more funds are vesting than issued.");
+		}
+		violations
+	}
+}
+
 impl<T: Config> VestingSchedule<T::AccountId> for Pallet<T>
 where
 	BalanceOf<T>: MaybeSerializeDeserialize + Debug,
@@ -380,7 +1553,7 @@ where
 	/// Get the amount that is currently being vested and cannot be transferred out of this account.
 	fn vesting_balance(who: &T::AccountId) -> Option<BalanceOf<T>> {
 		if let Some(v) = Self::vesting(who) {
-			let now = <frame_system::Pallet<T>>::block_number();
+			let now = Self::vesting_block(who, T::BlockNumberProvider::current_block_number());
 			let locked_now = v.locked_at::<T::BlockNumberToBalance>(now);
 			Some(T::Currency::free_balance(who).min(locked_now))
 		} else {
@@ -407,22 +1580,93 @@ where
 		if locked.is_zero() {
 			return Ok(())
 		}
-		if Vesting::<T>::contains_key(who) {
-			Err(Error::<T>::ExistingVestingSchedule)?
+		Self::can_add_vesting_schedule(who, locked, per_block, starting_block)?;
+		Self::insert_schedule(
+			who,
+			VestingInfo {
+				locked,
+				per_block,
+				starting_block,
+				cliff_block: None,
+				remainder: Zero::zero(),
+			},
+			Zero::zero(),
+		);
+		Ok(())
+	}
+
+	/// Checks if `add_vesting_schedule` would work against `who`: it fails if `who` already has a
+	/// vesting schedule or `per_block` is zero, unless the amount to be vested is zero.
+	fn can_add_vesting_schedule(
+		who: &T::AccountId,
+		locked: BalanceOf<T>,
+		per_block: BalanceOf<T>,
+		starting_block: T::BlockNumber,
+	) -> DispatchResult {
+		if !locked.is_zero() {
+			let schedule = VestingInfo {
+				locked,
+				per_block,
+				starting_block,
+				cliff_block: None,
+				remainder: Zero::zero(),
+			};
+			Self::ensure_valid_schedule(&schedule)?;
+			ensure!(!Vesting::<T>::contains_key(who), Error::<T>::ExistingVestingSchedule);
+		}
+		Ok(())
+	}
+
+	/// Adds a vesting schedule to a given account, merging it into the existing vesting schedule
+	/// of the account, if any.
+	///
+	/// The merged schedule starts at the current block at the earliest, and keeps the later cliff
+	/// which is still ahead, if any. If the account has a beneficiary, the funds which have vested
+	/// already are transferred to it first.
+	///
+	/// Is a no-op if the amount to be vested is zero.
+	fn merge_vesting_schedule(
+		who: &T::AccountId,
+		locked: BalanceOf<T>,
+		per_block: BalanceOf<T>,
+		starting_block: T::BlockNumber,
+	) -> DispatchResult {
+		if locked.is_zero() {
+			return Ok(())
+		}
+		let schedule = VestingInfo {
+			locked,
+			per_block,
+			starting_block,
+			cliff_block: None,
+			remainder: Zero::zero(),
+		};
+		Self::ensure_valid_schedule(&schedule)?;
+		// The merged schedule only locks what is still vesting, so the beneficiary is first paid
+		// what has vested already.
+		if let Some(beneficiary) = Self::vesting_beneficiary(who) {
+			Self::do_claim_vested(who, &beneficiary, None)?;
+		}
+		let merged = match Self::vesting(who) {
+			None => Some(schedule),
+			Some(existing) => {
+				let now = Self::vesting_block(who, T::BlockNumberProvider::current_block_number());
+				Self::merge_vesting_info(now, existing, schedule)
+			},
+		};
+		match merged {
+			Some(merged) => Self::insert_schedule(who, merged, Zero::zero()),
+			// Both schedules are fully vested, this removes the existing one.
+			None => Self::update_lock(who.clone())?,
 		}
-		let vesting_schedule = VestingInfo { locked, per_block, starting_block };
-		Vesting::<T>::insert(who, vesting_schedule);
-		// it can't fail, but even if somehow it did, we don't really care.
-		let res = Self::update_lock(who.clone());
-		debug_assert!(res.is_ok());
 		Ok(())
 	}
 
-	/// Remove a vesting schedule for a given account.
+	/// Remove a vesting schedule for a given account, unlocking all of its funds.
 	fn remove_vesting_schedule(who: &T::AccountId) {
-		Vesting::<T>::remove(who);
-		// it can't fail, but even if somehow it did, we don't really care.
-		let res = Self::update_lock(who.clone());
-		debug_assert!(res.is_ok());
+		if Self::take_schedule(who).is_some() {
+			T::Currency::remove_lock(VESTING_ID, who);
+			T::VestingHooks::on_vesting_completed(who);
+		}
 	}
 }
@@ -0,0 +1,300 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the vesting pallet.
+//!
+//! Each migration is a [`VersionedMigration`], which only runs from the storage version it
+//! migrates, so it is safe to keep in the runtime's migrations for longer than one upgrade.
+
+use super::*;
+use frame_support::{migrations::VersionedMigration, traits::OnRuntimeUpgrade, weights::Weight};
+
+/// Migration to version 1, adding a cliff to the vesting schedules.
+pub mod v1 {
+	use super::*;
+	use frame_support::storage::unhashed;
+
+	/// A vesting schedule as stored up to version 0.
+	#[derive(Decode)]
+	struct OldVestingInfo<Balance, BlockNumber> {
+		locked: Balance,
+		per_block: Balance,
+		starting_block: BlockNumber,
+	}
+
+	/// A vesting schedule as stored in versions 1 and 2.
+	#[derive(Encode, Decode)]
+	pub(super) struct VestingInfoV1<Balance, BlockNumber> {
+		pub(super) locked: Balance,
+		pub(super) per_block: Balance,
+		pub(super) starting_block: BlockNumber,
+		pub(super) cliff_block: Option<BlockNumber>,
+	}
+
+	/// Translate all vesting schedules to schedules without a cliff, regardless of the storage
+	/// version. Use [`MigrateV0ToV1`] instead.
+	pub struct InnerMigrateV0ToV1<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for InnerMigrateV0ToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			// Written in the format of version 1 rather than the current one, which the later
+			// migrations translate from.
+			let mut translated: Weight = 0;
+			for who in Vesting::<T>::iter_keys().collect::<Vec<_>>() {
+				let key = Vesting::<T>::hashed_key_for(&who);
+				let old = unhashed::get::<OldVestingInfo<BalanceOf<T>, T::BlockNumber>>(&key);
+				if let Some(old) = old {
+					translated += 1;
+					unhashed::put(
+						&key,
+						&VestingInfoV1 {
+							locked: old.locked,
+							per_block: old.per_block,
+							starting_block: old.starting_block,
+							cliff_block: None,
+						},
+					);
+				}
+			}
+
+			log::info!(target: "runtime::vesting", "Migrated {} vesting schedules to v1", translated);
+			T::DbWeight::get().reads_writes(translated, translated)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			frame_support::ensure!(
+				Vesting::<T>::iter_keys().all(|who| {
+					unhashed::get::<VestingInfoV1<BalanceOf<T>, T::BlockNumber>>(
+						&Vesting::<T>::hashed_key_for(&who),
+					)
+					.map_or(false, |schedule| schedule.cliff_block.is_none())
+				}),
+				"A migrated vesting schedule has a cliff.",
+			);
+			Ok(())
+		}
+	}
+
+	/// Migrate the storage version 0 to 1, see [`InnerMigrateV0ToV1`].
+	pub type MigrateV0ToV1<T> = VersionedMigration<
+		0,
+		1,
+		InnerMigrateV0ToV1<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::mock::{ExtBuilder, Test};
+		use frame_support::storage::unhashed;
+
+		#[test]
+		fn migration_adds_no_cliff() {
+			ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+				StorageVersion::new(0).put::<Pallet<Test>>();
+				unhashed::put_raw(
+					&Vesting::<Test>::hashed_key_for(4u64),
+					&(1_000u64, 10u64, 5u64).encode(),
+				);
+
+				MigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+				assert_eq!(
+					unhashed::get_raw(&Vesting::<Test>::hashed_key_for(4u64)),
+					Some((1_000u64, 10u64, 5u64, None::<u64>).encode()),
+				);
+				assert_eq!(StorageVersion::get::<Pallet<Test>>(), 1);
+
+				// Only executed from version 0.
+				unhashed::put_raw(
+					&Vesting::<Test>::hashed_key_for(3u64),
+					&(1_000u64, 10u64, 5u64).encode(),
+				);
+				MigrateV0ToV1::<Test>::on_runtime_upgrade();
+				assert_eq!(
+					unhashed::get_raw(&Vesting::<Test>::hashed_key_for(3u64)),
+					Some((1_000u64, 10u64, 5u64).encode()),
+				);
+			});
+		}
+	}
+}
+
+/// Migration to version 2, counting the vesting accounts.
+pub mod v2 {
+	use super::*;
+
+	/// Initialize [`VestingAccountsCount`] with the number of vesting accounts, regardless of the
+	/// storage version. Use [`MigrateV1ToV2`] instead.
+	pub struct InnerMigrateV1ToV2<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for InnerMigrateV1ToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let count = Vesting::<T>::iter_keys().count() as u32;
+			VestingAccountsCount::<T>::put(count);
+
+			log::info!(target: "runtime::vesting", "Counted {} vesting accounts for v2", count);
+			T::DbWeight::get().reads_writes(count as Weight, 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			frame_support::ensure!(
+				VestingAccountsCount::<T>::get() as usize == Vesting::<T>::iter_keys().count(),
+				"Wrong number of vesting accounts.",
+			);
+			Ok(())
+		}
+	}
+
+	/// Migrate the storage version 1 to 2, see [`InnerMigrateV1ToV2`].
+	pub type MigrateV1ToV2<T> = VersionedMigration<
+		1,
+		2,
+		InnerMigrateV1ToV2<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::mock::{ExtBuilder, Test};
+
+		#[test]
+		fn migration_counts_vesting_accounts() {
+			ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+				StorageVersion::new(1).put::<Pallet<Test>>();
+				VestingAccountsCount::<Test>::kill();
+
+				MigrateV1ToV2::<Test>::on_runtime_upgrade();
+
+				// Accounts 1, 2 and 12 vest from genesis.
+				assert_eq!(VestingAccountsCount::<Test>::get(), 3);
+				assert_eq!(StorageVersion::get::<Pallet<Test>>(), 2);
+			});
+		}
+	}
+}
+
+/// Migration to version 3, adding a remainder to the vesting schedules.
+pub mod v3 {
+	use super::{v1::VestingInfoV1, *};
+
+	/// Translate all vesting schedules to schedules without a remainder, regardless of the
+	/// storage version. Use [`MigrateV2ToV3`] instead.
+	pub struct InnerMigrateV2ToV3<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for InnerMigrateV2ToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut translated: Weight = 0;
+			Vesting::<T>::translate::<VestingInfoV1<BalanceOf<T>, T::BlockNumber>, _>(|_, old| {
+				translated += 1;
+				Some(VestingInfo {
+					locked: old.locked,
+					per_block: old.per_block,
+					starting_block: old.starting_block,
+					cliff_block: old.cliff_block,
+					remainder: Zero::zero(),
+				})
+			});
+
+			log::info!(target: "runtime::vesting", "Migrated {} vesting schedules to v3", translated);
+			T::DbWeight::get().reads_writes(translated, translated)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			frame_support::ensure!(
+				Vesting::<T>::iter().all(|(_, schedule)| schedule.remainder.is_zero()),
+				"A migrated vesting schedule has a remainder.",
+			);
+			Ok(())
+		}
+	}
+
+	/// Migrate the storage version 2 to 3, see [`InnerMigrateV2ToV3`].
+	pub type MigrateV2ToV3<T> = VersionedMigration<
+		2,
+		3,
+		InnerMigrateV2ToV3<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::mock::{ExtBuilder, Test};
+		use frame_support::storage::unhashed;
+
+		#[test]
+		fn migration_adds_no_remainder() {
+			ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+				StorageVersion::new(2).put::<Pallet<Test>>();
+				unhashed::put_raw(
+					&Vesting::<Test>::hashed_key_for(4u64),
+					&(1_000u64, 10u64, 5u64, Some(20u64)).encode(),
+				);
+
+				MigrateV2ToV3::<Test>::on_runtime_upgrade();
+
+				assert_eq!(
+					Vesting::<Test>::get(4),
+					Some(VestingInfo {
+						locked: 1_000,
+						per_block: 10,
+						starting_block: 5,
+						cliff_block: Some(20),
+						remainder: 0,
+					}),
+				);
+				assert_eq!(StorageVersion::get::<Pallet<Test>>(), 3);
+			});
+		}
+
+		#[test]
+		fn migrations_chain_from_version_0() {
+			ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+				StorageVersion::new(0).put::<Pallet<Test>>();
+				unhashed::put_raw(
+					&Vesting::<Test>::hashed_key_for(4u64),
+					&(1_000u64, 10u64, 5u64).encode(),
+				);
+
+				type Migrations =
+					(v1::MigrateV0ToV1<Test>, v2::MigrateV1ToV2<Test>, MigrateV2ToV3<Test>);
+				Migrations::on_runtime_upgrade();
+
+				assert_eq!(
+					Vesting::<Test>::get(4),
+					Some(VestingInfo {
+						locked: 1_000,
+						per_block: 10,
+						starting_block: 5,
+						cliff_block: None,
+						remainder: 0,
+					}),
+				);
+				assert_eq!(StorageVersion::get::<Pallet<Test>>(), 3);
+			});
+		}
+	}
+}
@@ -87,12 +87,61 @@ impl pallet_balances::Config for Test {
 parameter_types! {
 	pub const MinVestedTransfer: u64 = 256 * 2;
 	pub static ExistentialDeposit: u64 = 0;
+	pub static ExternalBlockNumber: Option<u64> = None;
+	pub static MaxAutoVestsPerBlock: u32 = 0;
+	pub static UnvestedFundsAllowedWithdrawReasons: WithdrawReasons =
+		WithdrawReasons::except(WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE);
+	pub static VestOtherReward: Perbill = Perbill::zero();
+	/// The calls to `MockVestingHooks`, with the locked amount if the vesting was updated.
+	pub static VestingHookCalls: Vec<(u64, Option<u64>)> = vec![];
 }
+
+/// The local block number, unless an external clock is set with `ExternalBlockNumber`.
+pub struct MockBlockNumberProvider;
+impl BlockNumberProvider for MockBlockNumberProvider {
+	type BlockNumber = u64;
+
+	fn current_block_number() -> u64 {
+		ExternalBlockNumber::get().unwrap_or_else(System::block_number)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_block_number(block: u64) {
+		match ExternalBlockNumber::get() {
+			Some(_) => ExternalBlockNumber::set(Some(block)),
+			None => System::set_block_number(block),
+		}
+	}
+}
+
+/// Records its calls into `VestingHookCalls`.
+pub struct MockVestingHooks;
+impl VestingHooks<u64, u64> for MockVestingHooks {
+	fn on_vesting_updated(who: &u64, locked: u64) {
+		let mut calls = VestingHookCalls::get();
+		calls.push((*who, Some(locked)));
+		VestingHookCalls::set(calls);
+	}
+
+	fn on_vesting_completed(who: &u64) {
+		let mut calls = VestingHookCalls::get();
+		calls.push((*who, None));
+		VestingHookCalls::set(calls);
+	}
+}
+
 impl Config for Test {
+	type BlockNumberProvider = MockBlockNumberProvider;
 	type BlockNumberToBalance = Identity;
 	type Currency = Balances;
 	type Event = Event;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type MaxAutoVestsPerBlock = MaxAutoVestsPerBlock;
 	type MinVestedTransfer = MinVestedTransfer;
+	type PauseOrigin = frame_system::EnsureRoot<u64>;
+	type UnvestedFundsAllowedWithdrawReasons = UnvestedFundsAllowedWithdrawReasons;
+	type VestOtherReward = VestOtherReward;
+	type VestingHooks = MockVestingHooks;
 	type WeightInfo = ();
 }
 
@@ -130,6 +179,7 @@ impl ExtBuilder {
 				(2, 10, 20, 0),
 				(12, 10, 20, 5 * self.existential_deposit),
 			],
+			schedules: vec![],
 		}
 		.assimilate_storage(&mut t)
 		.unwrap();
@@ -17,10 +17,13 @@
 
 use frame_support::{assert_noop, assert_ok};
 use frame_system::RawOrigin;
-use sp_runtime::traits::BadOrigin;
+use sp_runtime::traits::{BadOrigin, Identity};
 
 use super::*;
-use crate::mock::{Balances, ExtBuilder, System, Test, Vesting};
+use crate::mock::{
+	Balances, ExtBuilder, ExternalBlockNumber, MaxAutoVestsPerBlock, MaxLocks, System, Test,
+	UnvestedFundsAllowedWithdrawReasons, VestOtherReward, Vesting, VestingHookCalls,
+};
 
 #[test]
 fn check_vesting_status() {
@@ -35,16 +38,22 @@ fn check_vesting_status() {
 			locked: 256 * 5,
 			per_block: 128, // Vesting over 10 blocks
 			starting_block: 0,
+			cliff_block: None,
+			remainder: 0,
 		};
 		let user2_vesting_schedule = VestingInfo {
 			locked: 256 * 20,
 			per_block: 256, // Vesting over 20 blocks
 			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
 		};
 		let user12_vesting_schedule = VestingInfo {
 			locked: 256 * 5,
 			per_block: 64, // Vesting over 20 blocks
 			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
 		};
 		assert_eq!(Vesting::vesting(&1), Some(user1_vesting_schedule)); // Account 1 has a vesting schedule
 		assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule)); // Account 2 has a vesting schedule
@@ -76,12 +85,90 @@ fn check_vesting_status() {
 	});
 }
 
+#[test]
+fn genesis_accepts_explicit_schedules() {
+	let schedule = VestingInfo {
+		locked: 256 * 5,
+		per_block: 64,
+		starting_block: 10,
+		cliff_block: Some(20),
+		remainder: 0,
+	};
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(3, 256 * 30)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	GenesisConfig::<Test> { vesting: vec![], schedules: vec![(3, schedule)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	sp_io::TestExternalities::new(t).execute_with(|| {
+		assert_eq!(Vesting::vesting(&3), Some(schedule));
+		assert_eq!(Vesting::vesting_balance(&3), Some(256 * 5));
+		System::set_block_number(20);
+		assert_eq!(Vesting::vesting_balance(&3), Some(256 * 5 - 10 * 64));
+	});
+}
+
+#[test]
+fn genesis_schedules_are_validated() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		let schedule = VestingInfo {
+			locked: 256 * 5,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
+		};
+		let config = |vesting, schedules| GenesisConfig::<Test> { vesting, schedules };
+
+		// Account 3 has 256 * 30 at genesis, all of which vests over 20 blocks.
+		assert_eq!(
+			config(vec![(3, 10, 20, 0)], vec![(4, schedule)]).vesting_schedules(),
+			Ok(vec![
+				(
+					3,
+					VestingInfo {
+						locked: 256 * 30,
+						per_block: 256 * 30 / 20,
+						starting_block: 10,
+						cliff_block: None,
+						remainder: 0,
+					}
+				),
+				(4, schedule),
+			]),
+		);
+		assert_eq!(
+			config(vec![(4, 10, 20, 0)], vec![(4, schedule)]).vesting_schedules(),
+			Err("Account with more than one vesting schedule"),
+		);
+		// Nothing is locked if account 3 keeps all of its balance liquid.
+		assert_eq!(
+			config(vec![(3, 10, 20, 256 * 30)], vec![]).vesting_schedules(),
+			Err("Invalid vesting schedule"),
+		);
+		assert_eq!(
+			config(vec![], vec![(4, VestingInfo { per_block: 0, ..schedule })]).vesting_schedules(),
+			Err("Invalid vesting schedule"),
+		);
+		assert_eq!(
+			config(vec![], vec![(4, VestingInfo { locked: 256 * 41, ..schedule })])
+				.vesting_schedules(),
+			Err("Vesting schedule locking more than the balance of its account"),
+		);
+		assert_eq!(
+			config(vec![(5, 10, 20, 0)], vec![]).vesting_schedules(),
+			Err("Currencies must be init'd before vesting"),
+		);
+	});
+}
+
 #[test]
 fn unvested_balance_should_not_transfer() {
 	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
 		let user1_free_balance = Balances::free_balance(&1);
 		assert_eq!(user1_free_balance, 100); // Account 1 has free balance
-									 // Account 1 has only 5 units vested at block 1 (plus 50 unvested)
+									   // Account 1 has only 5 units vested at block 1 (plus 50 unvested)
 		assert_eq!(Vesting::vesting_balance(&1), Some(45));
 		assert_noop!(
 			Balances::transfer(Some(1).into(), 2, 56),
@@ -95,7 +182,7 @@ fn vested_balance_should_transfer() {
 	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
 		let user1_free_balance = Balances::free_balance(&1);
 		assert_eq!(user1_free_balance, 100); // Account 1 has free balance
-									 // Account 1 has only 5 units vested at block 1 (plus 50 unvested)
+									   // Account 1 has only 5 units vested at block 1 (plus 50 unvested)
 		assert_eq!(Vesting::vesting_balance(&1), Some(45));
 		assert_ok!(Vesting::vest(Some(1).into()));
 		assert_ok!(Balances::transfer(Some(1).into(), 2, 55));
@@ -107,7 +194,7 @@ fn vested_balance_should_transfer_using_vest_other() {
 	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
 		let user1_free_balance = Balances::free_balance(&1);
 		assert_eq!(user1_free_balance, 100); // Account 1 has free balance
-									 // Account 1 has only 5 units vested at block 1 (plus 50 unvested)
+									   // Account 1 has only 5 units vested at block 1 (plus 50 unvested)
 		assert_eq!(Vesting::vesting_balance(&1), Some(45));
 		assert_ok!(Vesting::vest_other(Some(2).into(), 1));
 		assert_ok!(Balances::transfer(Some(1).into(), 2, 55));
@@ -144,7 +231,7 @@ fn liquid_funds_should_transfer_with_delayed_vesting() {
 		let user12_free_balance = Balances::free_balance(&12);
 
 		assert_eq!(user12_free_balance, 2560); // Account 12 has free balance
-									   // Account 12 has liquid funds
+										 // Account 12 has liquid funds
 		assert_eq!(Vesting::vesting_balance(&12), Some(user12_free_balance - 256 * 5));
 
 		// Account 12 has delayed vesting
@@ -152,6 +239,8 @@ fn liquid_funds_should_transfer_with_delayed_vesting() {
 			locked: 256 * 5,
 			per_block: 64, // Vesting over 20 blocks
 			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
 		};
 		assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule));
 
@@ -160,6 +249,79 @@ fn liquid_funds_should_transfer_with_delayed_vesting() {
 	});
 }
 
+#[test]
+fn vesting_lock_overlaps_other_locks() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		// Account 1 has 45 units still vesting at block 1, and 60 units locked by another pallet.
+		assert_ok!(Vesting::vest(Some(1).into()));
+		Balances::set_lock(*b"staking ", &1, 60, WithdrawReasons::all());
+		assert_eq!(Balances::locks(&1).len(), 2);
+
+		// The two locks overlap, so only the largest of them is frozen.
+		assert_noop!(
+			Balances::transfer(Some(1).into(), 2, 41),
+			pallet_balances::Error::<Test, _>::LiquidityRestrictions,
+		);
+		assert_ok!(Balances::transfer(Some(1).into(), 2, 40));
+		assert_eq!(Vesting::vesting_balance(&1), Some(45));
+	});
+}
+
+#[test]
+fn vesting_does_not_release_other_locks() {
+	ExtBuilder::default().existential_deposit(10).build().execute_with(|| {
+		Balances::set_lock(*b"staking ", &1, 60, WithdrawReasons::all());
+
+		// Once account 1 is fully vested, only the vesting lock is removed.
+		System::set_block_number(10);
+		assert_ok!(Vesting::vest(Some(1).into()));
+		assert_eq!(Vesting::vesting(&1), None);
+		let locks = Balances::locks(&1);
+		assert_eq!(locks.len(), 1);
+		assert_eq!(locks[0].id, *b"staking ");
+		assert_eq!(locks[0].amount, 60);
+		assert_noop!(
+			Balances::transfer(Some(1).into(), 2, 41),
+			pallet_balances::Error::<Test, _>::LiquidityRestrictions,
+		);
+
+		// Removing the other lock leaves account 1 with all of its funds transferable.
+		Balances::remove_lock(*b"staking ", &1);
+		assert_ok!(Balances::transfer(Some(1).into(), 2, 100));
+	});
+}
+
+#[test]
+fn vest_partial_unlocks_at_most_the_given_amount() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		let vesting_lock = || Balances::locks(&2)[0].amount;
+		// Account 2 vests 256 per block from block 10, 256 * 5 have vested by block 15.
+		System::set_block_number(15);
+		assert_ok!(Vesting::vest_partial(Some(2).into(), 256 * 2));
+		assert_eq!(vesting_lock(), 256 * 18);
+		assert_eq!(Released::<Test>::get(&2), 256 * 2);
+
+		// No more than what has vested is unlocked.
+		assert_ok!(Vesting::vest_partial(Some(2).into(), 256 * 10));
+		assert_eq!(vesting_lock(), 256 * 15);
+
+		System::set_block_number(20);
+		assert_ok!(Vesting::vest_partial(Some(2).into(), 256));
+		assert_eq!(vesting_lock(), 256 * 14);
+		// `vest` unlocks everything which has vested.
+		assert_ok!(Vesting::vest(Some(2).into()));
+		assert_eq!(vesting_lock(), 256 * 10);
+		assert_eq!(Released::<Test>::get(&2), 256 * 10);
+
+		System::set_block_number(30);
+		assert_ok!(Vesting::vest_partial(Some(2).into(), 256 * 20));
+		assert_eq!(Vesting::vesting(&2), None);
+		assert!(Balances::locks(&2).is_empty());
+		assert_eq!(Released::<Test>::get(&2), 0);
+		assert_noop!(Vesting::vest_partial(Some(2).into(), 256), Error::<Test>::NotVesting);
+	});
+}
+
 #[test]
 fn vested_transfer_works() {
 	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
@@ -174,6 +336,8 @@ fn vested_transfer_works() {
 			locked: 256 * 5,
 			per_block: 64, // Vesting over 20 blocks
 			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
 		};
 		assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule));
 		// Now account 4 should have vesting.
@@ -212,14 +376,19 @@ fn vested_transfer_correctly_fails() {
 			locked: 256 * 20,
 			per_block: 256, // Vesting over 20 blocks
 			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
 		};
 		assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
 
-		// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+		// The vesting schedule we will try to create, fails due to pre-existence of a schedule
+		// which does not end at the same block.
 		let new_vesting_schedule = VestingInfo {
 			locked: 256 * 5,
 			per_block: 64, // Vesting over 20 blocks
-			starting_block: 10,
+			starting_block: 20,
+			cliff_block: None,
+			remainder: 0,
 		};
 		assert_noop!(
 			Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule),
@@ -227,8 +396,13 @@ fn vested_transfer_correctly_fails() {
 		);
 
 		// Fails due to too low transfer amount.
-		let new_vesting_schedule_too_low =
-			VestingInfo { locked: 256 * 1, per_block: 64, starting_block: 10 };
+		let new_vesting_schedule_too_low = VestingInfo {
+			locked: 256 * 1,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
+		};
 		assert_noop!(
 			Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule_too_low),
 			Error::<Test>::AmountLow,
@@ -240,6 +414,88 @@ fn vested_transfer_correctly_fails() {
 	});
 }
 
+#[test]
+fn vested_transfer_keep_alive_works() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		assert_eq!(Balances::free_balance(&3), 256 * 30);
+		let schedule = |locked| VestingInfo {
+			locked,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
+		};
+
+		// Account 3 can't transfer its whole balance, which would kill it.
+		assert_noop!(
+			Vesting::vested_transfer_keep_alive(Some(3).into(), 4, schedule(256 * 30)),
+			pallet_balances::Error::<Test, _>::KeepAlive,
+		);
+		// It may keep just the existential deposit.
+		assert_noop!(
+			Vesting::vested_transfer_keep_alive(Some(3).into(), 4, schedule(256 * 29 + 1)),
+			pallet_balances::Error::<Test, _>::KeepAlive,
+		);
+		assert_ok!(Vesting::vested_transfer_keep_alive(Some(3).into(), 4, schedule(256 * 29)));
+		assert_eq!(Balances::free_balance(&3), 256);
+		assert_eq!(Vesting::vesting(&4), Some(schedule(256 * 29)));
+		assert_eq!(Vesting::vesting_balance(&4), Some(256 * 29));
+	});
+}
+
+#[test]
+fn vested_transfer_may_kill_the_sender() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		let schedule = VestingInfo {
+			locked: 256 * 30,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
+		};
+		assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+		assert_eq!(Balances::free_balance(&3), 0);
+		assert!(!System::account_exists(&3));
+		assert_eq!(Vesting::vesting_balance(&4), Some(256 * 30));
+	});
+}
+
+#[test]
+fn vested_transfer_combines_aligned_schedules() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 vests 256 per block from block 10 until block 30.
+		let schedule = VestingInfo {
+			locked: 256 * 5,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
+		};
+		assert_ok!(Vesting::vested_transfer(Some(3).into(), 2, schedule));
+		let combined = VestingInfo {
+			locked: 256 * 25,
+			per_block: 320,
+			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
+		};
+		assert_eq!(Vesting::vesting(&2), Some(combined));
+		assert_eq!(Balances::free_balance(&2), 256 * 25);
+
+		System::set_block_number(20);
+		assert_eq!(Vesting::vesting_balance(&2), Some(256 * 10 + 64 * 10));
+		System::set_block_number(30);
+		assert_eq!(Vesting::vesting_balance(&2), Some(0));
+
+		// Schedules with different cliffs are not combined.
+		let with_cliff = VestingInfo { cliff_block: Some(20), ..schedule };
+		assert_noop!(
+			Vesting::vested_transfer(Some(3).into(), 2, with_cliff),
+			Error::<Test>::ExistingVestingSchedule,
+		);
+	});
+}
+
 #[test]
 fn force_vested_transfer_works() {
 	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
@@ -254,6 +510,8 @@ fn force_vested_transfer_works() {
 			locked: 256 * 5,
 			per_block: 64, // Vesting over 20 blocks
 			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
 		};
 		assert_noop!(
 			Vesting::force_vested_transfer(Some(4).into(), 3, 4, new_vesting_schedule),
@@ -301,35 +559,1129 @@ fn force_vested_transfer_correctly_fails() {
 			locked: 256 * 20,
 			per_block: 256, // Vesting over 20 blocks
 			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
 		};
 		assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
 
-		// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+		// The vesting schedule we will try to create, fails due to pre-existence of a schedule
+		// which does not end at the same block.
 		let new_vesting_schedule = VestingInfo {
 			locked: 256 * 5,
 			per_block: 64, // Vesting over 20 blocks
-			starting_block: 10,
+			starting_block: 20,
+			cliff_block: None,
+			remainder: 0,
 		};
 		assert_noop!(
 			Vesting::force_vested_transfer(RawOrigin::Root.into(), 4, 2, new_vesting_schedule),
 			Error::<Test>::ExistingVestingSchedule,
 		);
 
-		// Fails due to too low transfer amount.
-		let new_vesting_schedule_too_low =
-			VestingInfo { locked: 256 * 1, per_block: 64, starting_block: 10 };
+		// Verify no currency transfer happened.
+		assert_eq!(user2_free_balance, 256 * 20);
+		assert_eq!(user4_free_balance, 256 * 40);
+	});
+}
+
+#[test]
+fn force_vested_transfer_allows_low_amounts() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Below `MinVestedTransfer`, which only applies to signed vested transfers.
+		let schedule = VestingInfo {
+			locked: 256 * 1,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
+		};
+		assert_noop!(
+			Vesting::vested_transfer(Some(3).into(), 4, schedule),
+			Error::<Test>::AmountLow,
+		);
+
+		assert_ok!(Vesting::force_vested_transfer(RawOrigin::Root.into(), 3, 4, schedule));
+		assert_eq!(Vesting::vesting(&4), Some(schedule));
+		assert_eq!(Balances::free_balance(&4), 256 * 41);
+		assert_eq!(Vesting::vesting_balance(&4), Some(256));
+	});
+}
+
+#[test]
+fn check_invariants_reports_zero_locked_schedules() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		assert!(Vesting::check_invariants().is_empty());
+
+		let schedule = VestingInfo {
+			locked: 0,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
+		};
+		crate::Vesting::<Test>::insert(&4, schedule);
+		assert_eq!(Vesting::check_invariants(), vec!["Vesting: schedule with zero locked amount."]);
+	});
+}
+
+#[test]
+fn check_invariants_reports_wrong_vesting_locks() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 vests 256 per block from block 10 until block 30.
+		System::set_block_number(15);
+		assert_ok!(Vesting::vest(Some(2).into()));
+		// The lock is not updated until the account is poked again.
+		System::set_block_number(20);
+		assert!(Vesting::check_invariants().is_empty());
+
+		Balances::set_lock(VESTING_ID, &2, 256 * 10, WithdrawReasons::all());
+		assert_eq!(Vesting::check_invariants(), vec!["Vesting: lock does not match the schedule."]);
+
+		// Only 256 * 10 has vested by block 20.
+		Balances::set_lock(VESTING_ID, &2, 256 * 8, WithdrawReasons::all());
+		Released::<Test>::insert(&2, 256 * 12);
+		assert_eq!(
+			Vesting::check_invariants(),
+			vec!["Vesting: lock is below the amount still vesting."]
+		);
+	});
+}
+
+#[test]
+fn vested_transfer_with_cliff_works() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Vesting over 20 blocks from block 10, nothing unlocked before block 15.
+		let schedule = VestingInfo {
+			locked: 256 * 5,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: Some(15),
+			remainder: 0,
+		};
+		assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+		assert_eq!(Vesting::vesting(&4), Some(schedule));
+
+		System::set_block_number(14);
+		assert_eq!(Vesting::vesting_balance(&4), Some(256 * 5));
+		assert_ok!(Vesting::vest(Some(4).into()));
+		assert_eq!(Vesting::vesting(&4), Some(schedule));
+
+		// Everything vested since the starting block is unlocked at the cliff.
+		System::set_block_number(15);
+		assert_eq!(Vesting::vesting_balance(&4), Some(256 * 5 - 5 * 64));
+
+		System::set_block_number(30);
+		assert_eq!(Vesting::vesting_balance(&4), Some(0));
+		assert_ok!(Vesting::vest(Some(4).into()));
+		assert_eq!(Vesting::vesting(&4), None);
+	});
+}
+
+#[test]
+fn cliff_after_linear_end_locks_everything_until_cliff() {
+	let schedule = VestingInfo {
+		locked: 100u64,
+		per_block: 10,
+		starting_block: 10,
+		cliff_block: Some(30),
+		remainder: 0,
+	};
+	assert_eq!(schedule.locked_at::<Identity>(29), 100);
+	assert_eq!(schedule.locked_at::<Identity>(30), 0);
+	assert_eq!(schedule.ending_block_as_balance::<Identity>(), 30);
+
+	let schedule = VestingInfo { cliff_block: Some(15), ..schedule };
+	assert_eq!(schedule.locked_at::<Identity>(14), 100);
+	assert_eq!(schedule.locked_at::<Identity>(15), 50);
+	assert_eq!(schedule.ending_block_as_balance::<Identity>(), 20);
+
+	let schedule = VestingInfo { locked: 101, cliff_block: None, ..schedule };
+	assert_eq!(schedule.ending_block_as_balance::<Identity>(), 21);
+}
+
+#[test]
+fn zero_per_block_schedules_never_fully_vest() {
+	let schedule = VestingInfo {
+		locked: 100u64,
+		per_block: 0,
+		starting_block: 10,
+		cliff_block: None,
+		remainder: 0,
+	};
+	assert_eq!(schedule.locked_at::<Identity>(u64::MAX), 100);
+	assert_eq!(schedule.ending_block(), None);
+	assert_eq!(schedule.ending_block_as_balance::<Identity>(), u64::MAX);
+
+	let schedule = VestingInfo { locked: 0, ..schedule };
+	assert_eq!(schedule.ending_block(), Some(10));
+	assert_eq!(schedule.ending_block_as_balance::<Identity>(), 10);
+}
+
+#[test]
+fn vested_transfer_with_invalid_cliff_fails() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		let schedule = VestingInfo {
+			locked: 256 * 5,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: Some(10),
+			remainder: 0,
+		};
+		assert_noop!(
+			Vesting::vested_transfer(Some(3).into(), 4, schedule),
+			Error::<Test>::InvalidScheduleParams,
+		);
+		assert_noop!(
+			Vesting::force_vested_transfer(RawOrigin::Root.into(), 3, 4, schedule),
+			Error::<Test>::InvalidScheduleParams,
+		);
+	});
+}
+
+#[test]
+fn vested_transfer_with_zero_per_block_fails() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		let schedule = VestingInfo {
+			locked: 256 * 5,
+			per_block: 0,
+			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
+		};
+		assert_noop!(
+			Vesting::vested_transfer(Some(3).into(), 4, schedule),
+			Error::<Test>::ZeroPerBlock,
+		);
+		assert_noop!(
+			Vesting::merge_vesting_schedule(&4, 256 * 5, 0, 10),
+			Error::<Test>::ZeroPerBlock,
+		);
+	});
+}
+
+#[test]
+fn vested_transfer_ending_after_the_last_block_fails() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		let schedule = VestingInfo {
+			locked: 256 * 5,
+			per_block: 64,
+			starting_block: u64::MAX - 10,
+			cliff_block: None,
+			remainder: 0,
+		};
+		assert_eq!(schedule.ending_block(), None);
+		assert_noop!(
+			Vesting::vested_transfer(Some(3).into(), 4, schedule),
+			Error::<Test>::ScheduleEndsOverflow,
+		);
+
+		let schedule = VestingInfo { starting_block: u64::MAX - 20, ..schedule };
+		assert_eq!(schedule.ending_block(), Some(u64::MAX));
+		assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn try_state_detects_zero_per_block() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		assert_ok!(Vesting::do_try_state());
+
+		let mut schedule = Vesting::vesting(&2).unwrap();
+		schedule.per_block = 0;
+		crate::Vesting::<Test>::insert(2, schedule);
+		assert_eq!(Vesting::do_try_state(), Err("Vesting schedule with zero per block amount."));
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn try_state_detects_wrong_vesting_locks() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 vests 256 per block from block 10 until block 30.
+		System::set_block_number(15);
+		assert_ok!(Vesting::vest(Some(2).into()));
+		assert_ok!(Vesting::do_try_state());
+		// The lock is not updated until the account is poked again.
+		System::set_block_number(20);
+		assert_ok!(Vesting::do_try_state());
+
+		Balances::set_lock(VESTING_ID, &2, 256 * 10, WithdrawReasons::all());
+		assert_eq!(Vesting::do_try_state(), Err("Vesting lock does not match the schedule."));
+
+		// What was released must have vested, only 256 * 10 has by block 20.
+		Balances::set_lock(VESTING_ID, &2, 256 * 8, WithdrawReasons::all());
+		Released::<Test>::insert(&2, 256 * 12);
+		assert_eq!(Vesting::do_try_state(), Err("Vesting lock is below the amount still vesting."));
+
+		Balances::remove_lock(VESTING_ID, &2);
+		assert_eq!(Vesting::do_try_state(), Err("Vesting lock does not match the schedule."));
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn upgrade_hooks_check_the_vesting_accounts_count() {
+	use frame_support::traits::OnRuntimeUpgrade;
+
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		assert_ok!(<Vesting as OnRuntimeUpgrade>::pre_upgrade());
+		assert_ok!(<Vesting as OnRuntimeUpgrade>::post_upgrade());
+
+		crate::VestingAccountsCount::<Test>::mutate(|count| *count += 1);
+		assert_eq!(
+			<Vesting as OnRuntimeUpgrade>::pre_upgrade(),
+			Err("Wrong number of vesting accounts.")
+		);
+		assert_eq!(
+			<Vesting as OnRuntimeUpgrade>::post_upgrade(),
+			Err("Wrong number of vesting accounts.")
+		);
+	});
+}
+
+#[test]
+fn vesting_follows_the_block_number_provider() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 vests 256 per block from block 10, against a clock still at block 5.
+		System::set_block_number(20);
+		ExternalBlockNumber::set(Some(5));
+		assert_eq!(Vesting::vesting_balance(&2), Some(256 * 20));
+		assert_ok!(Vesting::vest(Some(2).into()));
+		assert_eq!(Vesting::vesting_balance(&2), Some(256 * 20));
+
+		ExternalBlockNumber::set(Some(15));
+		assert_ok!(Vesting::vest(Some(2).into()));
+		assert_eq!(Vesting::vesting_balance(&2), Some(256 * 15));
+		ExternalBlockNumber::set(None);
+	});
+}
+
+#[test]
+fn force_remove_vesting_schedule_works() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 vests 256 per block from block 10, 256 * 5 have vested by block 15.
+		System::set_block_number(15);
+		assert_noop!(Vesting::force_remove_vesting_schedule(Some(2).into(), 2, 0, None), BadOrigin);
+		assert_noop!(
+			Vesting::force_remove_vesting_schedule(RawOrigin::Root.into(), 4, 0, None),
+			Error::<Test>::NotVesting,
+		);
+		assert_noop!(
+			Vesting::force_remove_vesting_schedule(RawOrigin::Root.into(), 2, 1, None),
+			Error::<Test>::ScheduleIndexOutOfBounds,
+		);
+
+		VestingHookCalls::set(vec![]);
+		assert_ok!(Vesting::force_remove_vesting_schedule(RawOrigin::Root.into(), 2, 0, Some(3)));
+		assert_eq!(VestingHookCalls::get(), vec![(2, None)]);
+		assert_eq!(Vesting::vesting(&2), None);
+		assert!(Balances::locks(&2).is_empty());
+		assert_eq!(Balances::free_balance(&2), 256 * 5);
+		assert_eq!(Balances::free_balance(&3), 256 * 45);
+		System::assert_last_event(crate::Event::<Test>::VestingScheduleRemoved(2, 256 * 15).into());
+
+		// Without a destination, the funds stay on the account, unlocked.
+		assert_ok!(Vesting::force_remove_vesting_schedule(RawOrigin::Root.into(), 12, 0, None));
+		assert_eq!(Vesting::vesting(&12), None);
+		assert!(Balances::locks(&12).is_empty());
+		assert_eq!(Balances::free_balance(&12), 256 * 10);
+	});
+}
+
+#[test]
+fn force_remove_vesting_schedule_keeps_other_locks() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 still has 256 * 15 locked by vesting, and 256 * 10 locked by another pallet.
+		System::set_block_number(15);
+		Balances::set_lock(*b"staking ", &2, 256 * 10, WithdrawReasons::all());
+
+		// Only what the other lock leaves transferable is returned.
+		VestingHookCalls::set(vec![]);
+		assert_ok!(Vesting::force_remove_vesting_schedule(RawOrigin::Root.into(), 2, 0, Some(3)));
+		assert_eq!(VestingHookCalls::get(), vec![(2, None)]);
+		assert_eq!(Vesting::vesting(&2), None);
+		assert_eq!(Balances::free_balance(&2), 256 * 10);
+		assert_eq!(Balances::free_balance(&3), 256 * 40);
+		assert_eq!(Balances::locks(&2).len(), 1);
+		System::assert_last_event(crate::Event::<Test>::VestingScheduleRemoved(2, 256 * 10).into());
+	});
+}
+
+#[test]
+fn vested_at_works() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 vests 256 per block from block 10.
+		assert_eq!(Vesting::vested_at(&2, 5), Some(0));
+		assert_eq!(Vesting::vested_at(&2, 15), Some(256 * 5));
+		assert_eq!(Vesting::vested_at(&2, 100), Some(256 * 20));
+		assert_eq!(Vesting::vested_at(&4, 15), None);
+	});
+}
+
+#[test]
+fn locked_at_future_blocks_works() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 vests 256 per block from block 10 until block 30.
+		assert_eq!(Vesting::total_locked_at(&2, 5), 256 * 20);
+		assert_eq!(Vesting::total_locked_at(&2, 15), 256 * 15);
+		assert_eq!(Vesting::total_locked_at(&2, 100), 0);
+		assert_eq!(Vesting::schedules_locked_at(&2, 15), vec![(256 * 15, 30)]);
+
+		assert_eq!(Vesting::total_locked_at(&4, 15), 0);
+		assert_eq!(Vesting::schedules_locked_at(&4, 15), vec![]);
+	});
+}
+
+#[test]
+fn vesting_hooks_are_called() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		VestingHookCalls::set(vec![]);
+
+		// Account 2 vests 256 per block from block 10 until block 30.
+		System::set_block_number(15);
+		assert_ok!(Vesting::vest(Some(2).into()));
+		assert_eq!(VestingHookCalls::get(), vec![(2, Some(256 * 15))]);
+
+		System::set_block_number(30);
+		assert_ok!(Vesting::vest_other(Some(3).into(), 2));
+		assert_eq!(VestingHookCalls::get(), vec![(2, Some(256 * 15)), (2, None)]);
+
+		// Revoking a schedule removes its lock as well.
+		assert_ok!(Vesting::force_remove_vesting_schedule(RawOrigin::Root.into(), 12, 0, None));
+		assert_eq!(VestingHookCalls::get(), vec![(2, Some(256 * 15)), (2, None), (12, None)]);
+	});
+}
+
+#[test]
+fn force_set_vesting_schedule_works() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 12 vests 256 * 5 from block 10, replace it by a schedule with a cliff.
+		let schedule = VestingInfo {
+			locked: 256 * 8,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: Some(20),
+			remainder: 0,
+		};
+		assert_noop!(Vesting::force_set_vesting_schedule(Some(12).into(), 12, schedule), BadOrigin);
 		assert_noop!(
-			Vesting::force_vested_transfer(
+			Vesting::force_set_vesting_schedule(RawOrigin::Root.into(), 4, schedule),
+			Error::<Test>::NotVesting,
+		);
+		assert_noop!(
+			Vesting::force_set_vesting_schedule(
 				RawOrigin::Root.into(),
-				3,
-				4,
-				new_vesting_schedule_too_low
+				12,
+				VestingInfo { locked: 0, ..schedule },
 			),
-			Error::<Test>::AmountLow,
+			Error::<Test>::ZeroLocked,
 		);
 
-		// Verify no currency transfer happened.
-		assert_eq!(user2_free_balance, 256 * 20);
-		assert_eq!(user4_free_balance, 256 * 40);
+		System::set_block_number(15);
+		assert_ok!(Vesting::force_set_vesting_schedule(RawOrigin::Root.into(), 12, schedule));
+		assert_eq!(Vesting::vesting(&12), Some(schedule));
+		assert_eq!(Vesting::vesting_balance(&12), Some(256 * 8));
+		System::assert_last_event(crate::Event::<Test>::VestingUpdated(12, 256 * 8).into());
+	});
+}
+
+#[test]
+fn force_set_vesting_schedule_starts_over() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 vests 256 per block from block 10 until block 30.
+		assert_ok!(Vesting::set_vesting_beneficiary(Some(2).into(), 3));
+		System::set_block_number(15);
+		assert_ok!(Vesting::claim_vested_to(Some(3).into(), 2, 256 * 2));
+		assert_ok!(Vesting::pause_schedule(RawOrigin::Root.into(), 2));
+		assert_eq!(Released::<Test>::get(&2), 256 * 2);
+
+		let schedule = VestingInfo {
+			locked: 256 * 10,
+			per_block: 256,
+			starting_block: 20,
+			cliff_block: None,
+			remainder: 0,
+		};
+		assert_ok!(Vesting::force_set_vesting_schedule(RawOrigin::Root.into(), 2, schedule));
+		assert_eq!(Released::<Test>::get(&2), 0);
+		assert_eq!(PausedAt::<Test>::get(&2), None);
+		assert_eq!(Vesting::vesting_beneficiary(&2), None);
+		assert_eq!(Balances::locks(&2)[0].amount, 256 * 10);
+
+		// The account unlocks the new schedule itself.
+		System::set_block_number(22);
+		assert_ok!(Vesting::vest(Some(2).into()));
+		assert_eq!(Balances::locks(&2)[0].amount, 256 * 8);
+	});
+}
+
+#[test]
+fn combined_schedules_keep_what_was_unlocked() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 vests 256 per block from block 10 until block 30.
+		assert_ok!(Vesting::set_vesting_beneficiary(Some(2).into(), 3));
+		System::set_block_number(15);
+		assert_ok!(Vesting::claim_vested_to(Some(3).into(), 2, 256 * 2));
+
+		let schedule = VestingInfo {
+			locked: 256 * 20,
+			per_block: 256,
+			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
+		};
+		assert_ok!(Vesting::vested_transfer(Some(4).into(), 2, schedule));
+		assert_eq!(Vesting::vesting(&2).unwrap().locked, 256 * 40);
+		assert_eq!(Released::<Test>::get(&2), 256 * 2);
+		// Everything the account holds is locked for the beneficiary, but what it claimed.
+		assert_eq!(Balances::free_balance(&2), 256 * 38);
+		assert_eq!(Balances::locks(&2)[0].amount, 256 * 38);
+
+		assert_ok!(Vesting::claim_vested_to(Some(3).into(), 2, 256 * 40));
+		assert_eq!(Balances::free_balance(&3), 256 * 40);
+		assert_eq!(Balances::locks(&2)[0].amount, 256 * 30);
+	});
+}
+
+#[test]
+fn merged_schedules_pay_the_beneficiary_first() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 vests 256 per block from block 10 until block 30.
+		assert_ok!(Vesting::set_vesting_beneficiary(Some(2).into(), 3));
+		System::set_block_number(15);
+		assert_ok!(Vesting::claim_vested_to(Some(3).into(), 2, 256 * 2));
+
+		let _ = Balances::deposit_creating(&2, 256 * 2);
+		assert_ok!(Vesting::merge_vesting_schedule(&2, 256 * 2, 64, 15));
+		assert_eq!(Balances::free_balance(&3), 256 * 35);
+		assert_eq!(Released::<Test>::get(&2), 0);
+		assert_eq!(Vesting::vesting(&2).unwrap().locked, 256 * 17);
+		assert_eq!(Balances::free_balance(&2), 256 * 17);
+		assert_eq!(Balances::locks(&2)[0].amount, 256 * 17);
+		assert_eq!(Vesting::vesting_beneficiary(&2), Some(3));
+	});
+}
+
+#[test]
+fn can_add_vesting_schedule_works() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		assert_ok!(Vesting::can_add_vesting_schedule(&4, 256 * 5, 64, 10));
+		assert_noop!(
+			Vesting::can_add_vesting_schedule(&2, 256 * 5, 64, 10),
+			Error::<Test>::ExistingVestingSchedule,
+		);
+		assert_noop!(
+			Vesting::can_add_vesting_schedule(&4, 256 * 5, 0, 10),
+			Error::<Test>::ZeroPerBlock,
+		);
+		assert_ok!(Vesting::can_add_vesting_schedule(&2, 0, 64, 10));
+	});
+}
+
+#[test]
+fn merge_vesting_schedule_works() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Without an existing schedule, the schedule is added as is.
+		assert_ok!(Vesting::merge_vesting_schedule(&4, 256 * 5, 64, 10));
+		assert_eq!(
+			Vesting::vesting(&4),
+			Some(VestingInfo {
+				locked: 256 * 5,
+				per_block: 64,
+				starting_block: 10,
+				cliff_block: None,
+				remainder: 0,
+			})
+		);
+
+		// Account 2 vests 256 per block from block 10 until block 30, 256 * 15 are still locked at
+		// block 15. The merged schedule ends at block 40.
+		System::set_block_number(15);
+		assert_ok!(Vesting::merge_vesting_schedule(&2, 256 * 5, 64, 20));
+		let merged = VestingInfo {
+			locked: 256 * 20,
+			per_block: 256,
+			starting_block: 20,
+			cliff_block: None,
+			remainder: 0,
+		};
+		assert_eq!(Vesting::vesting(&2), Some(merged));
+		assert_eq!(Vesting::vesting_balance(&2), Some(256 * 20));
+	});
+}
+
+#[test]
+fn per_block_for_rounds_up() {
+	assert_eq!(Vesting::per_block_for(300, 100), 3);
+	assert_eq!(Vesting::per_block_for(299, 100), 3);
+	assert_eq!(Vesting::per_block_for(301, 100), 4);
+	assert_eq!(Vesting::per_block_for(50, 100), 1);
+	assert_eq!(Vesting::per_block_for(50, 0), 50);
+	assert_eq!(Vesting::per_block_for(0, 100), 0);
+}
+
+#[test]
+fn merge_vesting_schedule_keeps_cliff_ahead() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Vesting over 20 blocks from block 10, nothing unlocked before block 25.
+		let schedule = VestingInfo {
+			locked: 256 * 5,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: Some(25),
+			remainder: 0,
+		};
+		assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+
+		System::set_block_number(15);
+		assert_ok!(Vesting::merge_vesting_schedule(&4, 256 * 2, 64, 15));
+		let merged = Vesting::vesting(&4).unwrap();
+		assert_eq!(merged.locked, 256 * 7);
+		assert_eq!(merged.starting_block, 15);
+		assert_eq!(merged.cliff_block, Some(25));
+		assert_eq!(merged.locked_at::<Identity>(24), 256 * 7);
+		assert_eq!(merged.ending_block_as_balance::<Identity>(), 30);
+
+		// A cliff which has passed is dropped.
+		System::set_block_number(26);
+		assert_ok!(Vesting::merge_vesting_schedule(&4, 256 * 2, 64, 26));
+		assert_eq!(Vesting::vesting(&4).unwrap().cliff_block, None);
+	});
+}
+
+#[test]
+fn merge_vesting_schedule_removes_fully_vested_schedules() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 has fully vested by block 30, and so has the merged schedule.
+		System::set_block_number(30);
+		assert_ok!(Vesting::merge_vesting_schedule(&2, 256, 256, 1));
+		assert_eq!(Vesting::vesting(&2), None);
+		assert!(Balances::locks(&2).is_empty());
+	});
+}
+
+#[test]
+fn remove_vesting_schedule_unlocks_all_funds() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		assert!(!Balances::locks(&1).is_empty());
+		VestingHookCalls::set(vec![]);
+		Vesting::remove_vesting_schedule(&1);
+		assert_eq!(Vesting::vesting(&1), None);
+		assert!(Balances::locks(&1).is_empty());
+		assert_eq!(VestingAccountsCount::<Test>::get(), 2);
+		assert_eq!(VestingHookCalls::get(), vec![(1, None)]);
+
+		// Removing a schedule which does not exist is a no-op.
+		Vesting::remove_vesting_schedule(&1);
+		assert_eq!(VestingAccountsCount::<Test>::get(), 2);
+		assert_eq!(VestingHookCalls::get(), vec![(1, None)]);
+	});
+}
+
+#[test]
+fn on_idle_removes_ended_schedules() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Automatic vesting is disabled by default.
+		System::set_block_number(20);
+		assert_eq!(Vesting::on_idle(20, Weight::max_value()), 0);
+		assert!(Vesting::vesting(&1).is_some());
+
+		MaxAutoVestsPerBlock::set(10);
+		// Nothing is checked without enough weight left for a single account.
+		let per_account = <Test as Config>::WeightInfo::vest_other_unlocked(MaxLocks::get());
+		assert_eq!(Vesting::on_idle(20, per_account - 1), 0);
+		assert!(Vesting::vesting(&1).is_some());
+
+		// Only the schedule of account 1 has ended by block 20.
+		assert!(Vesting::on_idle(20, Weight::max_value()) > 0);
+		assert_eq!(Vesting::vesting(&1), None);
+		assert!(Balances::locks(&1).is_empty());
+		assert!(Vesting::vesting(&2).is_some());
+		assert!(Vesting::vesting(&12).is_some());
+		// All accounts were checked, so the next check starts over.
+		assert_eq!(AutoVestCursor::<Test>::get(), None);
+
+		// The schedules of accounts 2 and 12 have ended by block 30, one is removed per block.
+		MaxAutoVestsPerBlock::set(1);
+		System::set_block_number(30);
+		Vesting::on_idle(30, Weight::max_value());
+		assert!(Vesting::vesting(&2).is_none() != Vesting::vesting(&12).is_none());
+		assert!(AutoVestCursor::<Test>::get().is_some());
+
+		Vesting::on_idle(31, Weight::max_value());
+		assert_eq!(Vesting::vesting(&2), None);
+		assert_eq!(Vesting::vesting(&12), None);
+		assert!(Balances::locks(&2).is_empty());
+		assert!(Balances::locks(&12).is_empty());
+
+		Vesting::on_idle(32, Weight::max_value());
+		assert_eq!(AutoVestCursor::<Test>::get(), None);
+		MaxAutoVestsPerBlock::set(0);
+	});
+}
+
+#[test]
+fn on_idle_skips_accounts_with_a_beneficiary() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		MaxAutoVestsPerBlock::set(10);
+		assert_ok!(Vesting::set_vesting_beneficiary(Some(2).into(), 3));
+		let schedule = Vesting::vesting(&2);
+		let lock = Balances::locks(&2);
+
+		// All schedules have ended by block 30, but the funds of account 2 are left for the
+		// beneficiary, pass after pass.
+		System::set_block_number(30);
+		Vesting::on_idle(30, Weight::max_value());
+		assert_eq!(Vesting::vesting(&1), None);
+		assert_eq!(Vesting::vesting(&12), None);
+		for n in 31..35 {
+			System::set_block_number(n);
+			System::reset_events();
+			Vesting::on_idle(n, Weight::max_value());
+			assert_eq!(Vesting::vesting(&2), schedule);
+			assert_eq!(Balances::locks(&2), lock);
+			assert!(System::events().is_empty());
+		}
+
+		assert_ok!(Vesting::claim_vested_to(Some(3).into(), 2, 256 * 20));
+		assert_eq!(Vesting::vesting(&2), None);
+		MaxAutoVestsPerBlock::set(0);
+	});
+}
+
+#[test]
+fn vesting_schedule_events_are_deposited() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		let vesting_events = || {
+			System::events()
+				.into_iter()
+				.filter_map(|record| match record.event {
+					crate::mock::Event::Vesting(event) => Some(event),
+					_ => None,
+				})
+				.collect::<Vec<_>>()
+		};
+
+		let schedule = VestingInfo {
+			locked: 256 * 5,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: Some(15),
+			remainder: 0,
+		};
+		assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+		assert_eq!(
+			vesting_events(),
+			vec![
+				Event::VestingScheduleAdded(4, 256 * 5, 64, 10, Some(15), 0),
+				Event::VestingUpdated(4, 256 * 5),
+			],
+		);
+
+		System::reset_events();
+		System::set_block_number(30);
+		assert_ok!(Vesting::vest(Some(4).into()));
+		assert_eq!(vesting_events(), vec![Event::VestingCompleted(4)]);
+	});
+}
+
+#[test]
+fn vesting_lock_reasons_are_configurable() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// By default, unvested funds can still pay for fees.
+		assert_eq!(Balances::locks(&2)[0].reasons, pallet_balances::Reasons::Misc);
+
+		UnvestedFundsAllowedWithdrawReasons::set(WithdrawReasons::empty());
+		System::set_block_number(15);
+		assert_ok!(Vesting::vest(Some(2).into()));
+		assert_eq!(Balances::locks(&2)[0].reasons, pallet_balances::Reasons::All);
+		UnvestedFundsAllowedWithdrawReasons::set(WithdrawReasons::except(
+			WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE,
+		));
+	});
+}
+
+#[test]
+fn vesting_info_max_encoded_len_is_tight() {
+	let schedule = VestingInfo {
+		locked: u64::MAX,
+		per_block: u64::MAX,
+		starting_block: u64::MAX,
+		cliff_block: Some(u64::MAX),
+		remainder: 0,
+	};
+	assert_eq!(schedule.encode().len(), VestingInfo::<u64, u64>::max_encoded_len());
+}
+
+#[test]
+fn paused_schedules_do_not_vest() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 vests 256 per block from block 10 until block 30.
+		assert_noop!(Vesting::pause_schedule(Some(2).into(), 2), BadOrigin);
+		assert_noop!(Vesting::pause_schedule(RawOrigin::Root.into(), 4), Error::<Test>::NotVesting,);
+		assert_noop!(Vesting::resume_schedule(RawOrigin::Root.into(), 2), Error::<Test>::NotPaused);
+
+		System::set_block_number(15);
+		assert_ok!(Vesting::pause_schedule(RawOrigin::Root.into(), 2));
+		System::assert_last_event(crate::Event::<Test>::VestingPaused(2).into());
+		assert_noop!(
+			Vesting::pause_schedule(RawOrigin::Root.into(), 2),
+			Error::<Test>::AlreadyPaused,
+		);
+
+		// Nothing vests while the schedule is paused.
+		System::set_block_number(20);
+		assert_eq!(Vesting::vesting_balance(&2), Some(256 * 15));
+		assert_eq!(Vesting::total_locked_at(&2, 25), 256 * 15);
+		assert_ok!(Vesting::vest(Some(2).into()));
+		System::assert_last_event(crate::Event::<Test>::VestingUpdated(2, 256 * 15).into());
+
+		// Resuming delays the schedule by the 5 blocks it was paused for.
+		assert_ok!(Vesting::resume_schedule(RawOrigin::Root.into(), 2));
+		System::assert_last_event(crate::Event::<Test>::VestingResumed(2, 5).into());
+		assert_eq!(
+			Vesting::vesting(&2),
+			Some(VestingInfo {
+				locked: 256 * 20,
+				per_block: 256,
+				starting_block: 15,
+				cliff_block: None,
+				remainder: 0,
+			}),
+		);
+		assert_eq!(PausedAt::<Test>::get(&2), None);
+		assert_eq!(Vesting::vesting_balance(&2), Some(256 * 15));
+
+		System::set_block_number(25);
+		assert_eq!(Vesting::vesting_balance(&2), Some(256 * 10));
+	});
+}
+
+#[test]
+fn vest_refunds_weight_if_nothing_vested() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 vests 256 per block from block 10, nothing vested at block 1.
+		System::reset_events();
+		let post_info = Vesting::vest(Some(2).into()).unwrap();
+		assert_eq!(post_info.actual_weight, Some(<() as WeightInfo>::vest_unchanged()));
+		assert!(System::events().is_empty());
+
+		System::set_block_number(15);
+		let post_info = Vesting::vest_other(Some(3).into(), 2).unwrap();
+		assert_eq!(post_info.actual_weight, None);
+		System::assert_last_event(crate::Event::<Test>::VestingUpdated(2, 256 * 15).into());
+
+		// Calling again in the same block does not change anything.
+		System::reset_events();
+		let post_info = Vesting::vest(Some(2).into()).unwrap();
+		assert_eq!(post_info.actual_weight, Some(<() as WeightInfo>::vest_unchanged()));
+		assert_eq!(Balances::locks(&2)[0].amount, 256 * 15);
+		assert!(System::events().is_empty());
+	});
+}
+
+#[test]
+fn force_batch_vested_transfer_reports_failed_transfers() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		let schedule = VestingInfo {
+			locked: 256 * 5,
+			per_block: 64,
+			starting_block: 20,
+			cliff_block: None,
+			remainder: 0,
+		};
+		assert_noop!(
+			Vesting::force_batch_vested_transfer(Some(3).into(), 3, vec![(4, schedule)]),
+			BadOrigin,
+		);
+
+		// Account 2 is already vesting with a schedule which is not aligned.
+		assert_ok!(Vesting::force_batch_vested_transfer(
+			RawOrigin::Root.into(),
+			3,
+			vec![(4, schedule), (2, schedule), (5, schedule)],
+		));
+		assert_eq!(Vesting::vesting(&4), Some(schedule));
+		assert_eq!(Vesting::vesting(&5), Some(schedule));
+		assert_eq!(Balances::free_balance(&3), 256 * 30 - 256 * 10);
+		assert!(System::events().iter().any(|record| record.event ==
+			crate::Event::<Test>::BatchVestedTransferFailed(
+				1,
+				Error::<Test>::ExistingVestingSchedule.into()
+			)
+			.into()));
+	});
+}
+
+#[test]
+fn vesting_accounts_are_counted() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Accounts 1, 2 and 12 vest from genesis.
+		assert_eq!(Vesting::vesting_accounts_count(), 3);
+		let accounts = || {
+			let mut accounts =
+				Vesting::vesting_accounts_iter().map(|(who, _)| who).collect::<Vec<_>>();
+			accounts.sort();
+			accounts
+		};
+		assert_eq!(accounts(), vec![1, 2, 12]);
+
+		let schedule = VestingInfo {
+			locked: 256 * 5,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: None,
+			remainder: 0,
+		};
+		assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+		assert_eq!(Vesting::vesting_accounts_count(), 4);
+		// Replacing a schedule does not add an account.
+		assert_ok!(Vesting::force_set_vesting_schedule(RawOrigin::Root.into(), 4, schedule));
+		assert_eq!(Vesting::vesting_accounts_count(), 4);
+
+		assert_ok!(Vesting::force_remove_vesting_schedule(RawOrigin::Root.into(), 12, 0, None));
+		System::set_block_number(30);
+		assert_ok!(Vesting::vest(Some(2).into()));
+		assert_eq!(Vesting::vesting_accounts_count(), 2);
+		assert_eq!(accounts(), vec![1, 4]);
+	});
+}
+
+#[test]
+fn beneficiary_claims_vested_funds() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 2 vests 256 per block from block 10 until block 30.
+		assert_noop!(
+			Vesting::set_vesting_beneficiary(Some(4).into(), 3),
+			Error::<Test>::NotVesting,
+		);
+		assert_ok!(Vesting::set_vesting_beneficiary(Some(2).into(), 3));
+		System::assert_last_event(crate::Event::<Test>::VestingBeneficiarySet(2, 3).into());
+		assert_noop!(
+			Vesting::set_vesting_beneficiary(Some(2).into(), 4),
+			Error::<Test>::BeneficiaryAlreadySet,
+		);
+
+		// The vesting account can no longer unlock its vested funds.
+		System::set_block_number(15);
+		assert_ok!(Vesting::vest(Some(2).into()));
+		assert_eq!(Balances::locks(&2)[0].amount, 256 * 20);
+
+		assert_noop!(
+			Vesting::claim_vested_to(Some(4).into(), 2, 256),
+			Error::<Test>::NotBeneficiary,
+		);
+		assert_ok!(Vesting::claim_vested_to(Some(3).into(), 2, 256 * 2));
+		System::assert_last_event(crate::Event::<Test>::VestedFundsClaimed(2, 3, 256 * 2).into());
+		assert_eq!(Balances::locks(&2)[0].amount, 256 * 18);
+		assert_eq!(Balances::free_balance(&2), 256 * 18);
+		assert_eq!(Balances::free_balance(&3), 256 * 32);
+
+		// At most what has vested can be claimed, and the schedule completes once all is claimed.
+		assert_ok!(Vesting::claim_vested_to(Some(3).into(), 2, 256 * 20));
+		assert_eq!(Balances::free_balance(&3), 256 * 35);
+		System::set_block_number(30);
+		assert_ok!(Vesting::claim_vested_to(Some(3).into(), 2, 256 * 20));
+		assert_eq!(Balances::free_balance(&3), 256 * 50);
+		assert_eq!(Vesting::vesting(&2), None);
+		assert_eq!(Vesting::vesting_beneficiary(&2), None);
+	});
+}
+
+#[test]
+fn beneficiary_must_be_set_before_funds_are_unlocked() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		System::set_block_number(15);
+		assert_ok!(Vesting::vest(Some(2).into()));
+		assert_noop!(
+			Vesting::set_vesting_beneficiary(Some(2).into(), 3),
+			Error::<Test>::FundsAlreadyUnlocked,
+		);
+	});
+}
+
+#[test]
+fn genesis_schedules_unlock_everything_exactly_after_their_length() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		// Account 3 has 256 * 30 at genesis, which does not divide evenly over 7 blocks.
+		let config = GenesisConfig::<Test> { vesting: vec![(3, 10, 7, 0)], schedules: vec![] };
+		let (_, schedule) = config.vesting_schedules().unwrap()[0];
+		assert_eq!((schedule.per_block, schedule.remainder), (1097, 1));
+		assert_eq!(schedule.ending_block(), Some(17));
+		assert_eq!(schedule.locked_at::<Identity>(16), 1097 + 1);
+		assert_eq!(schedule.locked_at::<Identity>(17), 0);
+
+		// Rounding 10 over 9 blocks up would unlock everything after 5 blocks.
+		let config =
+			GenesisConfig::<Test> { vesting: vec![(3, 10, 9, 256 * 30 - 10)], schedules: vec![] };
+		let (_, schedule) = config.vesting_schedules().unwrap()[0];
+		assert_eq!((schedule.per_block, schedule.remainder), (1, 1));
+		assert_eq!(schedule.ending_block(), Some(19));
+		assert_eq!(schedule.locked_at::<Identity>(15), 5);
+		assert_eq!(schedule.locked_at::<Identity>(18), 2);
+		assert_eq!(schedule.locked_at::<Identity>(19), 0);
+
+		// Less than one per block, one is unlocked per block over the last 5 blocks.
+		let config =
+			GenesisConfig::<Test> { vesting: vec![(3, 10, 9, 256 * 30 - 5)], schedules: vec![] };
+		let (_, schedule) = config.vesting_schedules().unwrap()[0];
+		assert_eq!(
+			schedule,
+			VestingInfo {
+				locked: 5,
+				per_block: 1,
+				starting_block: 14,
+				cliff_block: None,
+				remainder: 0,
+			}
+		);
+		assert_eq!(schedule.ending_block(), Some(19));
+	});
+}
+
+#[test]
+fn schedules_over_a_length_end_exactly_after_it() {
+	// A linear congruential generator, so that the schedules are the same on every run.
+	let mut seed = 42u64;
+	let mut next = |max: u64| {
+		seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+		(seed >> 33) % max
+	};
+	for _ in 0..10_000 {
+		let locked = next(1_000_000) + 1;
+		// Lengths up to twice what is locked, so that some of them lock less than one per block.
+		let length = next(2 * locked) + 1;
+		let begin = next(1_000);
+		let schedule = VestingInfo::<u64, u64>::over_length(locked, begin, length, None).unwrap();
+
+		assert!(schedule.is_valid(), "{:?}", schedule);
+		assert_eq!(schedule.ending_block(), Some(begin + length), "{:?}", schedule);
+		assert_eq!(schedule.locked_at::<Identity>(begin), locked, "{:?}", schedule);
+		assert!(schedule.locked_at::<Identity>(begin + length - 1) > 0, "{:?}", schedule);
+		assert_eq!(schedule.locked_at::<Identity>(begin + length), 0, "{:?}", schedule);
+		// Never ahead of the exact linear curve.
+		let n = next(length);
+		let exact = (locked as u128 * (length - n) as u128 + length as u128 - 1) / length as u128;
+		assert!(schedule.locked_at::<Identity>(begin + n) as u128 >= exact, "{:?}", schedule);
+	}
+}
+
+#[test]
+fn schedules_are_fully_vested_exactly_at_their_ending_block() {
+	// A linear congruential generator, so that the schedules are the same on every run.
+	let mut seed = 42u64;
+	let mut next = |max: u64| {
+		seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+		(seed >> 33) % max
+	};
+	for _ in 0..10_000 {
+		let locked = next(1_000_000) + 1;
+		let per_block = next(locked) + 1;
+		let starting_block = next(1_000);
+		let cliff_block = if next(2) == 0 { None } else { Some(starting_block + next(1_000) + 1) };
+		let remainder = if next(2) == 0 { 0 } else { next(locked) };
+		let schedule = VestingInfo { locked, per_block, starting_block, cliff_block, remainder };
+
+		let ending_block = schedule.ending_block().unwrap();
+		assert_eq!(schedule.ending_block_as_balance::<Identity>(), ending_block);
+		assert_eq!(schedule.locked_at::<Identity>(ending_block), 0, "{:?}", schedule);
+		assert!(schedule.locked_at::<Identity>(ending_block - 1) > 0, "{:?}", schedule);
+	}
+}
+
+#[test]
+fn vest_other_rewards_the_sender() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		VestOtherReward::set(Perbill::from_percent(5));
+
+		// Account 2 vests 256 per block from block 10 until block 30, 5% of 256 * 5 is 64.
+		System::set_block_number(15);
+		assert_ok!(Vesting::vest_other(Some(3).into(), 2));
+		System::assert_last_event(crate::Event::<Test>::VestOtherRewardPaid(2, 3, 64).into());
+		assert_eq!(Balances::free_balance(&2), 256 * 20 - 64);
+		assert_eq!(Balances::free_balance(&3), 256 * 30 + 64);
+
+		// Nothing is paid if nothing is unlocked.
+		assert_ok!(Vesting::vest_other(Some(3).into(), 2));
+		assert_eq!(Balances::free_balance(&3), 256 * 30 + 64);
+
+		// Nor if the account cannot afford it: account 12 vests 64 per block from block 10, but
+		// has less than what is still locked.
+		assert_ok!(Balances::set_balance(RawOrigin::Root.into(), 12, 300, 0));
+		System::set_block_number(20);
+		assert_ok!(Vesting::vest_other(Some(3).into(), 12));
+		assert_eq!(Balances::locks(&12)[0].amount, 256 * 5 - 10 * 64);
+		assert_eq!(Balances::free_balance(&12), 300);
+		assert_eq!(Balances::free_balance(&3), 256 * 30 + 64);
+
+		VestOtherReward::set(Perbill::zero());
+	});
+}
+
+#[test]
+fn vest_other_reward_does_not_grow_with_pokes() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		VestOtherReward::set(Perbill::from_rational(1u32, 256u32));
+
+		// Account 2 vests 256 per block from block 10 until block 30, and is poked every block.
+		for n in 11..=30 {
+			System::set_block_number(n);
+			assert_ok!(Vesting::vest_other(Some(3).into(), 2));
+		}
+		assert_eq!(Vesting::vesting(&2), None);
+		// The keeper got 1 out of every 256 unlocked, the owner kept the rest.
+		assert_eq!(Balances::free_balance(&3), 256 * 30 + 20);
+		assert_eq!(Balances::free_balance(&2), 256 * 20 - 20);
+
+		VestOtherReward::set(Perbill::zero());
+	});
+}
+
+#[test]
+fn account_vesting_summary_works() {
+	ExtBuilder::default().existential_deposit(256).build().execute_with(|| {
+		assert_eq!(Vesting::account_vesting_summary(&4), None);
+
+		// Account 2 vests 256 per block from block 10 until block 30.
+		let summary = VestingSummary {
+			locked: 256 * 20,
+			originally_locked: 256 * 20,
+			next_unlock_block: Some(11),
+			ending_block: 30,
+		};
+		assert_eq!(Vesting::account_vesting_summary(&2), Some(summary));
+		System::set_block_number(15);
+		assert_eq!(
+			Vesting::account_vesting_summary(&2),
+			Some(VestingSummary { locked: 256 * 15, next_unlock_block: Some(16), ..summary }),
+		);
+		assert_ok!(Vesting::pause_schedule(RawOrigin::Root.into(), 2));
+		assert_eq!(
+			Vesting::account_vesting_summary(&2),
+			Some(VestingSummary { locked: 256 * 15, next_unlock_block: None, ..summary }),
+		);
+
+		// Nothing unlocks before the cliff.
+		let schedule = VestingInfo {
+			locked: 256 * 5,
+			per_block: 64,
+			starting_block: 10,
+			cliff_block: Some(20),
+			remainder: 0,
+		};
+		assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, schedule));
+		assert_eq!(Vesting::account_vesting_summary(&4).unwrap().next_unlock_block, Some(20));
+
+		System::set_block_number(30);
+		assert_eq!(
+			Vesting::account_vesting_summary(&4),
+			Some(VestingSummary {
+				locked: 0,
+				originally_locked: 256 * 5,
+				next_unlock_block: None,
+				ending_block: 30,
+			}),
+		);
 	});
 }
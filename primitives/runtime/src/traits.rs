@@ -1585,6 +1585,14 @@ pub trait BlockNumberProvider {
 	/// ```
 	/// .
 	fn current_block_number() -> Self::BlockNumber;
+
+	/// Set the current block number.
+	///
+	/// Only needed by benchmarks, which have to move the clock forward. Providers reading the
+	/// block number from somewhere which cannot be written, such as the relay chain, may keep the
+	/// default implementation, which does nothing.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_block_number(_block: Self::BlockNumber) {}
 }
 
 #[cfg(test)]
@@ -98,6 +98,7 @@ pub fn default_config(
 		rpc_http_threads: None,
 		rpc_cors: None,
 		rpc_methods: Default::default(),
+		rpc_method_filter: Default::default(),
 		rpc_max_payload: None,
 		prometheus_config: None,
 		telemetry_endpoints: None,
@@ -106,6 +106,7 @@ where
 		rpc_ws_max_connections: Default::default(),
 		rpc_http_threads: Default::default(),
 		rpc_methods: Default::default(),
+		rpc_method_filter: Default::default(),
 		rpc_max_payload: Default::default(),
 		state_cache_child_ratio: Default::default(),
 		state_cache_size: Default::default(),
@@ -87,6 +87,12 @@ impl BenchmarkCmd {
 			}
 		}
 
+		if let Some(json_file) = &self.json_file {
+			if json_file.is_dir() || json_file.file_name().is_none() {
+				return Err("JSON output file is invalid!".into())
+			}
+		}
+
 		if let Some(header_file) = &self.header {
 			if !header_file.is_file() {
 				return Err("Header file is invalid!".into())
@@ -236,6 +242,10 @@ impl BenchmarkCmd {
 			crate::writer::write_results(&batches, &storage_info, output_path, self)?;
 		}
 
+		if let Some(json_file) = &self.json_file {
+			crate::writer::write_json(&batches, &storage_info, json_file, self)?;
+		}
+
 		for batch in batches.into_iter() {
 			// Print benchmark metadata
 			println!(
@@ -74,6 +74,13 @@ pub struct BenchmarkCmd {
 	#[structopt(long)]
 	pub header: Option<std::path::PathBuf>,
 
+	/// Also output the analysed benchmarks as JSON to a file at the given path.
+	///
+	/// The JSON contains the components, the regression fit and its standard errors of every
+	/// benchmark, so that weight changes can be compared by other tools.
+	#[structopt(long)]
+	pub json_file: Option<std::path::PathBuf>,
+
 	/// Path to Handlebars template file used for outputting benchmark results. (Optional)
 	#[structopt(long)]
 	pub template: Option<std::path::PathBuf>,
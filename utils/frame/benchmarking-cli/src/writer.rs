@@ -15,7 +15,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-// Outputs benchmark results to Rust files that can be ingested by the runtime.
+// Outputs benchmark results to Rust files that can be ingested by the runtime, and optionally to
+// a JSON file that can be processed by other tools.
 
 use core::convert::TryInto;
 use std::{
@@ -82,6 +83,15 @@ struct CmdData {
 	analysis_choice: String,
 }
 
+// This is the structure we write to the JSON output, one entry for each pallet instance.
+#[derive(Serialize, Debug, Clone)]
+struct JsonPalletData {
+	pallet: String,
+	instance: String,
+	cmd: CmdData,
+	benchmarks: Vec<BenchmarkData>,
+}
+
 // This encodes the component name and whether that component is used.
 #[derive(Serialize, Debug, Clone, Eq, PartialEq)]
 struct Component {
@@ -297,17 +307,7 @@ pub fn write_results(
 		cmd.output_analysis.clone().try_into().map_err(|e| io_error(e))?;
 
 	// Capture individual args
-	let cmd_data = CmdData {
-		steps: cmd.steps.clone(),
-		repeat: cmd.repeat.clone(),
-		lowest_range_values: cmd.lowest_range_values.clone(),
-		highest_range_values: cmd.highest_range_values.clone(),
-		execution: format!("{:?}", cmd.execution),
-		wasm_execution: cmd.wasm_method.to_string(),
-		chain: format!("{:?}", cmd.shared_params.chain),
-		db_cache: cmd.database_cache_size,
-		analysis_choice: format!("{:?}", analysis_choice),
-	};
+	let cmd_data = cmd_data(cmd, &analysis_choice);
 
 	// New Handlebars instance with helpers.
 	let mut handlebars = handlebars::Handlebars::new();
@@ -352,6 +352,59 @@ pub fn write_results(
 	Ok(())
 }
 
+// Write the analysed benchmark data of all pallets to a single JSON file, so that weight changes
+// can be diffed and checked by other tools.
+pub fn write_json(
+	batches: &[BenchmarkBatch],
+	storage_info: &[StorageInfo],
+	path: &PathBuf,
+	cmd: &BenchmarkCmd,
+) -> Result<(), std::io::Error> {
+	let analysis_choice: AnalysisChoice =
+		cmd.output_analysis.clone().try_into().map_err(|e| io_error(e))?;
+	let json_data =
+		map_json_results(batches, storage_info, &analysis_choice, cmd_data(cmd, &analysis_choice))?;
+
+	let output_file = fs::File::create(path)?;
+	serde_json::to_writer_pretty(output_file, &json_data).map_err(|e| io_error(&e.to_string()))
+}
+
+// Organize the results like `map_results`, but as a list sorted by pallet and instance, so that
+// the output is stable between runs.
+fn map_json_results(
+	batches: &[BenchmarkBatch],
+	storage_info: &[StorageInfo],
+	analysis_choice: &AnalysisChoice,
+	cmd_data: CmdData,
+) -> Result<Vec<JsonPalletData>, std::io::Error> {
+	let mut json_data = map_results(batches, storage_info, analysis_choice)?
+		.into_iter()
+		.map(|((pallet, instance), benchmarks)| JsonPalletData {
+			pallet,
+			instance,
+			cmd: cmd_data.clone(),
+			benchmarks,
+		})
+		.collect::<Vec<_>>();
+	json_data.sort_by(|a, b| (&a.pallet, &a.instance).cmp(&(&b.pallet, &b.instance)));
+	Ok(json_data)
+}
+
+// Capture the metadata of the `BenchmarkCmd` which is forwarded to the outputs.
+fn cmd_data(cmd: &BenchmarkCmd, analysis_choice: &AnalysisChoice) -> CmdData {
+	CmdData {
+		steps: cmd.steps.clone(),
+		repeat: cmd.repeat.clone(),
+		lowest_range_values: cmd.lowest_range_values.clone(),
+		highest_range_values: cmd.highest_range_values.clone(),
+		execution: format!("{:?}", cmd.execution),
+		wasm_execution: cmd.wasm_method.to_string(),
+		chain: format!("{:?}", cmd.shared_params.chain),
+		db_cache: cmd.database_cache_size,
+		analysis_choice: format!("{:?}", analysis_choice),
+	}
+}
+
 // This function looks at the keys touched during the benchmark, and the storage info we collected
 // from the pallets, and creates comments with information about the storage keys touched during
 // each benchmark.
@@ -579,4 +632,34 @@ mod test {
 		assert_eq!(second_pallet_benchmark.name, "first_benchmark");
 		check_data(second_pallet_benchmark, "c", 3, 4);
 	}
+
+	#[test]
+	fn map_json_results_works() {
+		let json_data = map_json_results(
+			&[
+				test_data(b"second", b"first", BenchmarkParameter::c, 3, 4),
+				test_data(b"first", b"first", BenchmarkParameter::a, 10, 3),
+			],
+			&[],
+			&AnalysisChoice::default(),
+			CmdData { steps: 5, repeat: 1, ..Default::default() },
+		)
+		.unwrap();
+
+		// Sorted by pallet.
+		assert_eq!(json_data.len(), 2);
+		assert_eq!(json_data[0].pallet, "first_pallet");
+		assert_eq!(json_data[1].pallet, "second_pallet");
+		check_data(&json_data[0].benchmarks[0], "a", 10, 3);
+
+		let value = serde_json::to_value(&json_data[0]).unwrap();
+		assert_eq!(value["instance"], "instance");
+		assert_eq!(value["cmd"]["steps"], 5);
+		assert_eq!(value["benchmarks"][0]["name"], "first_benchmark");
+		assert_eq!(value["benchmarks"][0]["base_weight"], "10000");
+		assert_eq!(value["benchmarks"][0]["component_weight"][0]["name"], "a");
+		assert_eq!(value["benchmarks"][0]["component_weight"][0]["slope"], "3000");
+		assert_eq!(value["benchmarks"][0]["component_weight"][0]["error"], "0");
+		assert_eq!(value["benchmarks"][0]["components"][1]["is_used"], false);
+	}
 }
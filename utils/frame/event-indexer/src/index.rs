@@ -0,0 +1,214 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The layout of the index in the offchain database.
+
+use std::collections::BTreeMap;
+
+use codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use sp_core::{offchain::OffchainStorage, Bytes};
+
+/// The prefix of the offchain database under which the index is kept, apart from the storage of
+/// the offchain workers of the runtime under [`sp_offchain::STORAGE_PREFIX`].
+const INDEX_STORAGE_PREFIX: &[u8] = b"event-indexer";
+
+/// The key of the state of the index: the number of entries of each topic, followed by the number
+/// of the last indexed block.
+const STATE_KEY: &[u8] = b"state";
+
+/// An entry of the index.
+#[derive(Encode, Decode, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedEvent<BlockHash, BlockNumber> {
+	/// Hash of the block the entry was read from.
+	pub block_hash: BlockHash,
+	/// Number of the block the entry was read from.
+	pub block_number: BlockNumber,
+	/// Index of the event within the events of the block, or `None` if the entry holds the value
+	/// of a storage item.
+	pub event_index: Option<u32>,
+	/// SCALE-encoded event, or raw storage value.
+	pub data: Bytes,
+}
+
+/// The index of events, on top of an offchain storage.
+///
+/// The entries of each topic are numbered from `0` in the order in which they were pushed.
+///
+/// The offchain storage cannot write several keys at once. Hence the entries of a block are
+/// written first, after the entries of their topic, and only become part of the index when the
+/// state of the index is updated, in a single write. If indexing a block is interrupted, the
+/// block is indexed again from scratch, overwriting the entries written so far.
+#[derive(Clone)]
+pub struct EventIndex<S> {
+	storage: S,
+}
+
+impl<S: OffchainStorage> EventIndex<S> {
+	/// Create a new index on top of `storage`.
+	pub fn new(storage: S) -> Self {
+		Self { storage }
+	}
+
+	/// The number of entries indexed under `topic`.
+	pub fn count(&self, topic: &str) -> u64 {
+		self.counts().get(topic).copied().unwrap_or_default()
+	}
+
+	/// The entry of `topic` at `position`, if any.
+	pub fn get<BlockHash: Decode, BlockNumber: Decode>(
+		&self,
+		topic: &str,
+		position: u64,
+	) -> Option<IndexedEvent<BlockHash, BlockNumber>> {
+		// entries beyond the count are left over by an interrupted block.
+		if position >= self.count(topic) {
+			return None
+		}
+		self.read(&entry_key(topic, position))
+	}
+
+	/// The number of the last block whose entries were pushed, if any.
+	pub fn last_indexed<BlockNumber: Decode>(&self) -> Option<BlockNumber> {
+		self.read::<(BTreeMap<String, u64>, Option<BlockNumber>)>(STATE_KEY)
+			.and_then(|(_, last_indexed)| last_indexed)
+	}
+
+	/// Append the `entries` of block `number` to the entries of their topic, and record that the
+	/// block was indexed.
+	pub fn push_block<BlockHash: Encode, BlockNumber: Encode>(
+		&mut self,
+		number: BlockNumber,
+		entries: &[(&str, IndexedEvent<BlockHash, BlockNumber>)],
+	) {
+		let mut counts = self.counts();
+		for (topic, entry) in entries {
+			let count = counts.entry(topic.to_string()).or_default();
+			self.write(&entry_key(topic, *count), entry);
+			*count += 1;
+		}
+		self.write(STATE_KEY, &(counts, Some(number)));
+	}
+
+	/// The number of entries of each topic.
+	fn counts(&self) -> BTreeMap<String, u64> {
+		// the number of the last indexed block follows, but its type is not known here.
+		self.read(STATE_KEY).unwrap_or_default()
+	}
+
+	fn read<T: Decode>(&self, key: &[u8]) -> Option<T> {
+		let value = self.storage.get(INDEX_STORAGE_PREFIX, key)?;
+		match T::decode(&mut &value[..]) {
+			Ok(value) => Some(value),
+			Err(e) => {
+				log::error!(
+					target: "event-indexer",
+					"Corrupted index entry {}: {:?}",
+					sp_core::hexdisplay::HexDisplay::from(&key),
+					e,
+				);
+				None
+			},
+		}
+	}
+
+	fn write<T: Encode>(&mut self, key: &[u8], value: &T) {
+		self.storage.set(INDEX_STORAGE_PREFIX, key, &value.encode())
+	}
+}
+
+fn entry_key(topic: &str, position: u64) -> Vec<u8> {
+	// Big-endian, so that the entries of a topic are sorted by position in the database.
+	[&b"entry:"[..], topic.as_bytes(), b":", &position.to_be_bytes()].concat()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{offchain::storage::InMemOffchainStorage, H256};
+
+	fn entry(block_number: u32, event_index: Option<u32>) -> IndexedEvent<H256, u32> {
+		IndexedEvent {
+			block_hash: H256::repeat_byte(block_number as u8),
+			block_number,
+			event_index,
+			data: Bytes(vec![1, 2, 3]),
+		}
+	}
+
+	#[test]
+	fn entries_are_numbered_per_topic() {
+		let mut index = EventIndex::new(InMemOffchainStorage::default());
+		assert_eq!(index.count("vesting"), 0);
+		assert_eq!(index.get::<H256, u32>("vesting", 0), None);
+
+		index.push_block(1, &[("vesting", entry(1, Some(0))), ("mmr", entry(1, None))]);
+		index.push_block(2, &[("vesting", entry(2, Some(3)))]);
+
+		assert_eq!(index.count("vesting"), 2);
+		assert_eq!(index.count("mmr"), 1);
+		assert_eq!(index.get("vesting", 0), Some(entry(1, Some(0))));
+		assert_eq!(index.get("vesting", 1), Some(entry(2, Some(3))));
+		assert_eq!(index.get("mmr", 0), Some(entry(1, None)));
+		assert_eq!(index.get::<H256, u32>("mmr", 1), None);
+	}
+
+	#[test]
+	fn last_indexed_block_is_tracked() {
+		let mut index = EventIndex::new(InMemOffchainStorage::default());
+		assert_eq!(index.last_indexed::<u32>(), None);
+
+		index.push_block::<H256, u32>(5, &[]);
+		assert_eq!(index.last_indexed(), Some(5u32));
+	}
+
+	#[test]
+	fn entries_of_an_interrupted_block_are_ignored_and_overwritten() {
+		let mut index = EventIndex::new(InMemOffchainStorage::default());
+		index.push_block(1, &[("vesting", entry(1, Some(0)))]);
+
+		// the entry of block 2 was written, but not the state of the index.
+		index.write(&entry_key("vesting", 1), &entry(2, Some(0)));
+		assert_eq!(index.count("vesting"), 1);
+		assert_eq!(index.get::<H256, u32>("vesting", 1), None);
+		assert_eq!(index.last_indexed(), Some(1u32));
+
+		index.push_block(2, &[("vesting", entry(2, Some(1)))]);
+		assert_eq!(index.count("vesting"), 2);
+		assert_eq!(index.get("vesting", 1), Some(entry(2, Some(1))));
+	}
+
+	#[test]
+	fn index_is_kept_apart_from_the_offchain_worker_storage() {
+		let mut index = EventIndex::new(InMemOffchainStorage::default());
+		index.push_block(1, &[("vesting", entry(1, Some(0)))]);
+
+		assert!(index.storage.get(INDEX_STORAGE_PREFIX, STATE_KEY).is_some());
+		assert_eq!(index.storage.get(sp_offchain::STORAGE_PREFIX, STATE_KEY), None);
+	}
+
+	#[test]
+	fn should_serialize_indexed_event() {
+		let actual = serde_json::to_string(&entry(1, Some(2))).unwrap();
+
+		assert_eq!(
+			actual,
+			r#"{"blockHash":"0x0101010101010101010101010101010101010101010101010101010101010101","blockNumber":1,"eventIndex":2,"data":"0x010203"}"#
+		);
+	}
+}
@@ -0,0 +1,152 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The task writing the events of finalized blocks into the index.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Decode;
+use frame_support::Parameter;
+use frame_system::EventRecord;
+use futures::StreamExt;
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::{offchain::OffchainStorage, storage::StorageKey, twox_128, Bytes};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, Member, NumberFor, One},
+};
+
+use crate::{EventIndex, IndexedEvent};
+
+/// The key of the storage value `item` of the pallet named `pallet` in the runtime.
+pub fn storage_value_key(pallet: &str, item: &str) -> StorageKey {
+	let mut key = twox_128(pallet.as_bytes()).to_vec();
+	key.extend_from_slice(&twox_128(item.as_bytes()));
+	StorageKey(key)
+}
+
+/// Writes the selected events of each finalized block into an [`EventIndex`].
+///
+/// Events are selected by a function returning the topic to index them under, if any. Pallets
+/// which do not deposit an event for what should be indexed, such as a new MMR root, can have a
+/// storage value indexed for every block instead.
+pub struct EventIndexer<Block, Client, B, S, Event> {
+	client: Arc<Client>,
+	index: EventIndex<S>,
+	topic_of: Box<dyn Fn(&Event) -> Option<&'static str> + Send>,
+	storage_values: Vec<(&'static str, StorageKey)>,
+	_phantom: PhantomData<(Block, B)>,
+}
+
+impl<Block, Client, B, S, Event> EventIndexer<Block, Client, B, S, Event>
+where
+	Block: BlockT,
+	Client: BlockchainEvents<Block> + HeaderBackend<Block> + StorageProvider<Block, B>,
+	B: Backend<Block>,
+	S: OffchainStorage,
+	Event: Parameter + Member,
+{
+	/// Create an indexer writing into `storage` the events for which `topic_of` returns a topic.
+	pub fn new(
+		client: Arc<Client>,
+		storage: S,
+		topic_of: impl Fn(&Event) -> Option<&'static str> + Send + 'static,
+	) -> Self {
+		Self {
+			client,
+			index: EventIndex::new(storage),
+			topic_of: Box::new(topic_of),
+			storage_values: Vec::new(),
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Also index the value at `key` under `topic`, for every block in which it is set.
+	pub fn with_storage_value(mut self, topic: &'static str, key: StorageKey) -> Self {
+		self.storage_values.push((topic, key));
+		self
+	}
+
+	/// Index the finalized blocks, until the client stops.
+	///
+	/// Indexing resumes after the last indexed block, if any, or otherwise starts at the first
+	/// block finalized after the indexer is started.
+	pub async fn run(mut self) {
+		let mut finality_notifications = self.client.finality_notification_stream();
+		while let Some(notification) = finality_notifications.next().await {
+			if let Err(e) = self.index_up_to(*notification.header.number()) {
+				log::error!(target: "event-indexer", "Failed to index finalized blocks: {}", e);
+			}
+		}
+	}
+
+	fn index_up_to(&mut self, finalized: NumberFor<Block>) -> Result<(), String> {
+		let mut number = self
+			.index
+			.last_indexed::<NumberFor<Block>>()
+			.map_or(finalized, |last| last + One::one());
+		while number <= finalized {
+			self.index_block(number)?;
+			number += One::one();
+		}
+		Ok(())
+	}
+
+	fn index_block(&mut self, number: NumberFor<Block>) -> Result<(), String> {
+		let hash = self
+			.client
+			.hash(number)
+			.map_err(|e| e.to_string())?
+			.ok_or_else(|| format!("Finalized block #{} not found", number))?;
+		let id = BlockId::Hash(hash);
+		let entry = |event_index, data| IndexedEvent {
+			block_hash: hash,
+			block_number: number,
+			event_index,
+			data: Bytes(data),
+		};
+
+		// Everything is read before anything is written, and the block is then added to the index
+		// at once, see `EventIndex`.
+		let mut entries = Vec::new();
+		let events = self
+			.client
+			.storage(&id, &storage_value_key("System", "Events"))
+			.map_err(|e| e.to_string())?;
+		if let Some(events) = events {
+			let records = Vec::<EventRecord<Event, Block::Hash>>::decode(&mut &events.0[..])
+				.map_err(|e| {
+					format!("Failed to decode the events of block #{}: {:?}", number, e)
+				})?;
+			for (i, record) in records.into_iter().enumerate() {
+				if let Some(topic) = (self.topic_of)(&record.event) {
+					entries.push((topic, entry(Some(i as u32), record.event.encode())));
+				}
+			}
+		}
+
+		for (topic, key) in self.storage_values.iter() {
+			if let Some(value) = self.client.storage(&id, key).map_err(|e| e.to_string())? {
+				entries.push((*topic, entry(None, value.0)));
+			}
+		}
+
+		self.index.push_block(number, &entries);
+		Ok(())
+	}
+}
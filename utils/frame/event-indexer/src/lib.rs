@@ -0,0 +1,36 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![warn(missing_docs)]
+
+//! Index the events of selected FRAME pallets into the offchain database.
+//!
+//! The [`EventIndexer`] follows the finalized blocks of a full node and writes the events of the
+//! runtime it is interested in, as well as the values of selected storage items, into the
+//! offchain database. Entries are grouped by topic and numbered in the order in which they were
+//! indexed, so that the [`EventIndexerApi`] RPC methods can page through them without an external
+//! indexer.
+//!
+//! Only finalized blocks are indexed, hence the index never has to be reverted.
+
+mod index;
+mod indexer;
+mod rpc;
+
+pub use index::{EventIndex, IndexedEvent};
+pub use indexer::{storage_value_key, EventIndexer};
+pub use rpc::{EventIndexerApi, EventIndexerRpc, MAX_ENTRIES};
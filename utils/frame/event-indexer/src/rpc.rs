@@ -0,0 +1,145 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RPC methods to query the index.
+
+use std::marker::PhantomData;
+
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_core::offchain::OffchainStorage;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+
+use crate::{EventIndex, IndexedEvent};
+
+/// The maximum number of entries returned by a single `eventIndexer_entries` call.
+pub const MAX_ENTRIES: u32 = 1_000;
+
+const TOO_MANY_ENTRIES_ERROR: i64 = 8100;
+
+/// Event indexer RPC methods.
+#[rpc]
+pub trait EventIndexerApi<BlockHash, BlockNumber> {
+	/// The number of entries indexed under `topic`.
+	#[rpc(name = "eventIndexer_count")]
+	fn count(&self, topic: String) -> Result<u64>;
+
+	/// Up to `limit` entries indexed under `topic`, starting at position `from`.
+	///
+	/// Entries are numbered from `0` in the order in which they were indexed, which is the order
+	/// of the blocks and of the events within each block. `limit` may not exceed [`MAX_ENTRIES`].
+	#[rpc(name = "eventIndexer_entries")]
+	fn entries(
+		&self,
+		topic: String,
+		from: u64,
+		limit: u32,
+	) -> Result<Vec<IndexedEvent<BlockHash, BlockNumber>>>;
+}
+
+/// An implementation of the event indexer RPC methods.
+pub struct EventIndexerRpc<Block, S> {
+	index: EventIndex<S>,
+	_marker: PhantomData<Block>,
+}
+
+impl<Block, S: OffchainStorage> EventIndexerRpc<Block, S> {
+	/// Create new `EventIndexerRpc`, reading the index from the given offchain storage.
+	pub fn new(storage: S) -> Self {
+		Self { index: EventIndex::new(storage), _marker: Default::default() }
+	}
+}
+
+impl<Block, S> EventIndexerApi<Block::Hash, NumberFor<Block>> for EventIndexerRpc<Block, S>
+where
+	Block: BlockT,
+	S: OffchainStorage + 'static,
+{
+	fn count(&self, topic: String) -> Result<u64> {
+		Ok(self.index.count(&topic))
+	}
+
+	fn entries(
+		&self,
+		topic: String,
+		from: u64,
+		limit: u32,
+	) -> Result<Vec<IndexedEvent<Block::Hash, NumberFor<Block>>>> {
+		if limit > MAX_ENTRIES {
+			return Err(Error {
+				code: ErrorCode::ServerError(TOO_MANY_ENTRIES_ERROR),
+				message: format!("At most {} entries can be requested at once", MAX_ENTRIES),
+				data: Some(limit.into()),
+			})
+		}
+
+		let to = from.saturating_add(limit.into()).min(self.index.count(&topic));
+		Ok((from..to).filter_map(|position| self.index.get(&topic, position)).collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{offchain::storage::InMemOffchainStorage, Bytes, H256};
+	use sp_runtime::{generic, traits::BlakeTwo256, OpaqueExtrinsic};
+
+	type Block = generic::Block<generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+	fn rpc_with_entries(topic: &str, count: u32) -> EventIndexerRpc<Block, InMemOffchainStorage> {
+		let mut index = EventIndex::new(InMemOffchainStorage::default());
+		for block_number in 0..count {
+			let entry = IndexedEvent {
+				block_hash: H256::repeat_byte(block_number as u8),
+				block_number,
+				event_index: Some(0),
+				data: Bytes(vec![]),
+			};
+			index.push_block(block_number, &[(topic, entry)]);
+		}
+		EventIndexerRpc { index, _marker: Default::default() }
+	}
+
+	#[test]
+	fn entries_are_paged() {
+		let rpc = rpc_with_entries("elections", 5);
+		assert_eq!(rpc.count("elections".into()).unwrap(), 5);
+		assert_eq!(rpc.count("vesting".into()).unwrap(), 0);
+
+		let numbers = |from, limit| {
+			rpc.entries("elections".into(), from, limit)
+				.unwrap()
+				.into_iter()
+				.map(|entry| entry.block_number)
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(numbers(0, 2), vec![0, 1]);
+		assert_eq!(numbers(3, 10), vec![3, 4]);
+		assert_eq!(numbers(5, 1), Vec::<u32>::new());
+		assert_eq!(numbers(u64::MAX, 1), Vec::<u32>::new());
+	}
+
+	#[test]
+	fn too_many_entries_cannot_be_requested() {
+		let rpc = rpc_with_entries("elections", 1);
+		assert!(rpc.entries("elections".into(), 0, MAX_ENTRIES).is_ok());
+		assert_eq!(
+			rpc.entries("elections".into(), 0, MAX_ENTRIES + 1).unwrap_err().code,
+			ErrorCode::ServerError(TOO_MANY_ENTRIES_ERROR),
+		);
+	}
+}
@@ -111,8 +111,13 @@ pub struct OnlineConfig<B: BlockT> {
 	pub at: Option<B::Hash>,
 	/// An optional state snapshot file to WRITE to, not for reading. Not written if set to `None`.
 	pub state_snapshot: Option<SnapshotConfig>,
-	/// The modules to scrape. If empty, entire chain state will be scraped.
+	/// The modules to scrape. If empty, and no storage item is given, entire chain state will be
+	/// scraped.
 	pub modules: Vec<String>,
+	/// The storage items to scrape, as `(module, item)` names, in addition to `modules`.
+	///
+	/// This should be used to scrape only some storage items of a module, like a storage map.
+	pub storage_items: Vec<(String, String)>,
 	/// Transport config.
 	pub transport: Transport,
 }
@@ -134,6 +139,7 @@ impl<B: BlockT> Default for OnlineConfig<B> {
 			at: None,
 			state_snapshot: None,
 			modules: vec![],
+			storage_items: vec![],
 		}
 	}
 }
@@ -359,7 +365,7 @@ impl<B: BlockT> Builder<B> {
 			.clone();
 		info!(target: LOG_TARGET, "scraping key-pairs from remote @ {:?}", at);
 
-		let mut keys_and_values = if config.modules.len() > 0 {
+		let mut keys_and_values = if config.modules.len() > 0 || config.storage_items.len() > 0 {
 			let mut filtered_kv = vec![];
 			for f in config.modules.iter() {
 				let hashed_prefix = StorageKey(twox_128(f.as_bytes()).to_vec());
@@ -373,6 +379,19 @@ impl<B: BlockT> Builder<B> {
 				);
 				filtered_kv.extend(module_kv);
 			}
+			for (module, item) in config.storage_items.iter() {
+				let hashed_prefix = storage_item_prefix(module, item);
+				let item_kv = self.rpc_get_pairs_paged(hashed_prefix.clone(), at).await?;
+				info!(
+					target: LOG_TARGET,
+					"downloaded data for storage item {}::{} (count: {} / prefix: {:?}).",
+					module,
+					item,
+					item_kv.len(),
+					HexDisplay::from(&hashed_prefix),
+				);
+				filtered_kv.extend(item_kv);
+			}
 			filtered_kv
 		} else {
 			info!(target: LOG_TARGET, "downloading data for all modules.");
@@ -484,17 +503,38 @@ impl<B: BlockT> Builder<B> {
 	/// Build the test externalities.
 	pub async fn build(self) -> Result<TestExternalities, &'static str> {
 		let kv = self.pre_build().await?;
-		let mut ext = TestExternalities::new_empty();
+		Ok(externalities_from(kv))
+	}
+}
 
-		info!(target: LOG_TARGET, "injecting a total of {} keys", kv.len());
-		for (k, v) in kv {
-			let (k, v) = (k.0, v.0);
-			// Insert the key,value pair into the test trie backend
-			ext.insert(k, v);
-		}
+/// The hashed prefix of all keys of the storage item `item` of the module `module`.
+pub fn storage_item_prefix(module: &str, item: &str) -> StorageKey {
+	StorageKey([twox_128(module.as_bytes()), twox_128(item.as_bytes())].concat())
+}
 
-		Ok(ext)
+/// Load the state snapshot at `path` into new test externalities.
+///
+/// This is the synchronous equivalent of building with [`Mode::Offline`], meant to be used by
+/// regression tests running against a snapshot of a live chain.
+pub fn load_state_snapshot<P: AsRef<Path>>(path: P) -> Result<TestExternalities, &'static str> {
+	let path = path.as_ref();
+	info!(target: LOG_TARGET, "scraping key-pairs from state snapshot {:?}", path);
+	let bytes = fs::read(path).map_err(|_| "fs::read failed.")?;
+	let kv = Decode::decode(&mut &*bytes).map_err(|_| "decode failed")?;
+	Ok(externalities_from(kv))
+}
+
+fn externalities_from(kv: Vec<KeyPair>) -> TestExternalities {
+	let mut ext = TestExternalities::new_empty();
+
+	info!(target: LOG_TARGET, "injecting a total of {} keys", kv.len());
+	for (k, v) in kv {
+		let (k, v) = (k.0, v.0);
+		// Insert the key,value pair into the test trie backend
+		ext.insert(k, v);
 	}
+
+	ext
 }
 
 #[cfg(test)]
@@ -529,6 +569,30 @@ mod tests {
 			.expect("Can't read state snapshot file")
 			.execute_with(|| {});
 	}
+
+	#[test]
+	fn can_load_state_snapshot_synchronously() {
+		init_logger();
+		load_state_snapshot("test_data/proxy_test")
+			.expect("Can't read state snapshot file")
+			.execute_with(|| {});
+	}
+
+	#[test]
+	fn loaded_state_snapshot_contains_the_storage_items() {
+		init_logger();
+		let prefix = storage_item_prefix("Vesting", "Vesting");
+		let key = [prefix.0.clone(), vec![1, 2, 3]].concat();
+		let pairs: Vec<KeyPair> = vec![(StorageKey(key.clone()), StorageData(vec![4, 5, 6]))];
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("vesting_snapshot");
+		fs::write(&path, pairs.encode()).unwrap();
+
+		load_state_snapshot(&path).unwrap().execute_with(|| {
+			assert_eq!(sp_io::storage::get(&key), Some(vec![4, 5, 6]));
+			assert_eq!(sp_io::storage::next_key(&prefix.0), Some(key));
+		});
+	}
 }
 
 #[cfg(all(test, feature = "remote-test"))]
@@ -602,6 +666,34 @@ mod remote_tests {
 			});
 	}
 
+	#[tokio::test]
+	async fn can_create_state_snapshot_of_storage_items() {
+		init_logger();
+		let path = "test_storage_items_snapshot_to_remove.bin";
+		Builder::<Block>::new()
+			.mode(Mode::Online(OnlineConfig {
+				state_snapshot: Some(SnapshotConfig::new(path)),
+				storage_items: vec![
+					("Vesting".to_owned(), "Vesting".to_owned()),
+					("PhragmenElection".to_owned(), "Voting".to_owned()),
+				],
+				..Default::default()
+			}))
+			.build()
+			.await
+			.expect("Can't reach the remote node. Is it running?")
+			.execute_with(|| {});
+
+		load_state_snapshot(path)
+			.expect("Can't read state snapshot file")
+			.execute_with(|| {
+				let prefix = storage_item_prefix("PhragmenElection", "Voting");
+				assert!(sp_io::storage::next_key(&prefix.0)
+					.map_or(false, |key| key.starts_with(&prefix.0)));
+			});
+		std::fs::remove_file(path).unwrap();
+	}
+
 	#[tokio::test]
 	async fn can_create_state_snapshot() {
 		init_logger();
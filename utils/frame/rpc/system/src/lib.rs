@@ -37,6 +37,13 @@ use sp_runtime::{generic::BlockId, traits};
 pub use self::gen_client::Client as SystemClient;
 pub use frame_system_rpc_runtime_api::AccountNonceApi;
 
+mod storage_version;
+
+pub use storage_version::{
+	PalletStorageVersionInfo, StorageVersionApi, StorageVersionClient, StorageVersionRuntimeApi,
+	StorageVersions,
+};
+
 /// Future that resolves to account nonce.
 pub type FutureResult<T> = Box<dyn Future<Item = T, Error = RpcError> + Send>;
 
@@ -0,0 +1,127 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RPC method to inspect the storage versions of the pallets.
+
+use std::sync::Arc;
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits};
+
+pub use self::gen_client::Client as StorageVersionClient;
+pub use frame_system_rpc_runtime_api::StorageVersionApi as StorageVersionRuntimeApi;
+use frame_system_rpc_runtime_api::{PalletStorageVersion, StorageVersion};
+
+use crate::Error;
+
+/// The storage versions of a pallet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PalletStorageVersionInfo {
+	/// Name of the pallet as configured in the runtime.
+	pub pallet: String,
+	/// The storage version as supported by the pallet.
+	pub current: StorageVersion,
+	/// The storage version of the pallet in the storage.
+	pub on_chain: StorageVersion,
+	/// Whether the storage of the pallet still has to be migrated to its current version.
+	pub migration_pending: bool,
+}
+
+impl From<PalletStorageVersion> for PalletStorageVersionInfo {
+	fn from(version: PalletStorageVersion) -> Self {
+		Self {
+			pallet: String::from_utf8_lossy(&version.pallet_name).into_owned(),
+			current: version.current,
+			on_chain: version.on_chain,
+			migration_pending: version.is_migration_pending(),
+		}
+	}
+}
+
+/// Storage version RPC methods.
+#[rpc]
+pub trait StorageVersionApi<BlockHash> {
+	/// Returns the current and on-chain storage versions of every pallet of the runtime at the
+	/// given block, or at the best block.
+	///
+	/// A pallet whose on-chain storage version is behind its current storage version still has
+	/// to migrate its storage.
+	#[rpc(name = "system_storageVersions")]
+	fn storage_versions(&self, at: Option<BlockHash>) -> Result<Vec<PalletStorageVersionInfo>>;
+}
+
+/// An implementation of the storage version RPC methods on full client.
+pub struct StorageVersions<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> StorageVersions<C, B> {
+	/// Create new `StorageVersions` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block> StorageVersionApi<<Block as traits::Block>::Hash> for StorageVersions<C, Block>
+where
+	Block: traits::Block,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: StorageVersionRuntimeApi<Block>,
+{
+	fn storage_versions(
+		&self,
+		at: Option<<Block as traits::Block>::Hash>,
+	) -> Result<Vec<PalletStorageVersionInfo>> {
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let versions = self.client.runtime_api().storage_versions(&at).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to query storage versions.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})?;
+
+		Ok(versions.into_iter().map(Into::into).collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_serialize_pallet_storage_version() {
+		let info: PalletStorageVersionInfo = PalletStorageVersion {
+			pallet_name: b"Elections".to_vec(),
+			current: StorageVersion::new(5),
+			on_chain: StorageVersion::new(4),
+		}
+		.into();
+
+		assert_eq!(
+			serde_json::to_string(&info).unwrap(),
+			r#"{"pallet":"Elections","current":5,"onChain":4,"migrationPending":true}"#,
+		);
+	}
+}
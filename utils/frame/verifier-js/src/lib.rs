@@ -0,0 +1,143 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! MMR proof verification and vesting schedule math of FRAME, exposed to JavaScript.
+//!
+//! The functions of this crate run exactly the on-chain logic of `pallet-mmr` and
+//! `pallet-vesting`, so that front-ends can verify proofs and render unlock curves client-side.
+//! All inputs are SCALE-encoded, as returned by the RPCs and storage queries of a node.
+//!
+//! The types match the ones of the Substrate node runtime: MMR nodes are hashed with
+//! [`BlakeTwo256`], balances are `u128` and block numbers are `u32`.
+
+use codec::Decode;
+use pallet_mmr::primitives::{DataOrHash, EncodableOpaqueLeaf, Proof};
+use pallet_vesting::VestingInfo;
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, ConvertInto};
+use wasm_bindgen::prelude::*;
+
+/// The balance type of the vesting schedules.
+pub type Balance = u128;
+/// The block number type of the vesting schedules.
+pub type BlockNumber = u32;
+
+/// Verify that `leaf` is part of the MMR with root `root`.
+///
+/// - `root`: The 32 bytes of the MMR root hash.
+/// - `leaf`: The SCALE-encoded leaf, as returned by the `mmr_generateProof` RPC.
+/// - `proof`: The SCALE-encoded proof, as returned by the `mmr_generateProof` RPC.
+///
+/// Returns whether the proof is valid, or an error if the inputs are malformed.
+#[wasm_bindgen(js_name = verifyMmrProof)]
+pub fn verify_mmr_proof(root: &[u8], leaf: &[u8], proof: &[u8]) -> Result<bool, JsValue> {
+	do_verify_mmr_proof(root, leaf, proof).map_err(JsValue::from_str)
+}
+
+/// The amount locked by the vesting schedule `schedule` at block `block_number`.
+///
+/// - `schedule`: The SCALE-encoded `VestingInfo`, as stored by `pallet-vesting`.
+///
+/// The amount is returned as a decimal string, as it may not fit into a JavaScript number.
+#[wasm_bindgen(js_name = vestingLockedAt)]
+pub fn vesting_locked_at(schedule: &[u8], block_number: BlockNumber) -> Result<String, JsValue> {
+	do_vesting_locked_at(schedule, block_number)
+		.map(|locked| locked.to_string())
+		.map_err(JsValue::from_str)
+}
+
+fn do_verify_mmr_proof(root: &[u8], leaf: &[u8], proof: &[u8]) -> Result<bool, &'static str> {
+	if root.len() != H256::len_bytes() {
+		return Err("Invalid MMR root length")
+	}
+	let root = H256::from_slice(root);
+	let leaf = EncodableOpaqueLeaf::decode(&mut &*leaf).map_err(|_| "Invalid MMR leaf")?;
+	let proof = Proof::<H256>::decode(&mut &*proof).map_err(|_| "Invalid MMR proof")?;
+
+	let node = DataOrHash::Data(leaf.into_opaque_leaf());
+	Ok(pallet_mmr::verify_leaf_proof::<BlakeTwo256, _>(root, node, proof).is_ok())
+}
+
+fn do_vesting_locked_at(
+	schedule: &[u8],
+	block_number: BlockNumber,
+) -> Result<Balance, &'static str> {
+	let schedule = VestingInfo::<Balance, BlockNumber>::decode(&mut &*schedule)
+		.map_err(|_| "Invalid vesting schedule")?;
+	Ok(schedule.locked_at::<ConvertInto>(block_number))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use sp_runtime::traits::Hash;
+
+	#[test]
+	fn should_verify_mmr_proof() {
+		// A MMR with a single leaf, whose root is the hash of the leaf.
+		let leaf = vec![1_u8, 2, 3, 4];
+		let root = BlakeTwo256::hash(&leaf);
+		let encoded_leaf = EncodableOpaqueLeaf(leaf).encode();
+		let proof = Proof::<H256> { leaf_index: 0, leaf_count: 1, items: vec![] }.encode();
+
+		assert_eq!(do_verify_mmr_proof(root.as_bytes(), &encoded_leaf, &proof), Ok(true));
+		assert_eq!(
+			do_verify_mmr_proof(H256::repeat_byte(1).as_bytes(), &encoded_leaf, &proof),
+			Ok(false)
+		);
+		assert_eq!(
+			do_verify_mmr_proof(&root.as_bytes()[1..], &encoded_leaf, &proof),
+			Err("Invalid MMR root length")
+		);
+		assert_eq!(
+			do_verify_mmr_proof(root.as_bytes(), &encoded_leaf, &proof[1..]),
+			Err("Invalid MMR proof")
+		);
+	}
+
+	#[test]
+	fn should_compute_vesting_locked_at() {
+		let schedule = VestingInfo::<Balance, BlockNumber> {
+			locked: 100,
+			per_block: 10,
+			starting_block: 5,
+			cliff_block: None,
+			remainder: 0,
+		}
+		.encode();
+
+		assert_eq!(do_vesting_locked_at(&schedule, 0), Ok(100));
+		assert_eq!(do_vesting_locked_at(&schedule, 5), Ok(100));
+		assert_eq!(do_vesting_locked_at(&schedule, 8), Ok(70));
+		assert_eq!(do_vesting_locked_at(&schedule, 15), Ok(0));
+		assert_eq!(do_vesting_locked_at(&schedule, 100), Ok(0));
+		assert_eq!(do_vesting_locked_at(&schedule[1..], 5), Err("Invalid vesting schedule"));
+
+		let with_cliff = VestingInfo::<Balance, BlockNumber> {
+			locked: 100,
+			per_block: 10,
+			starting_block: 5,
+			cliff_block: Some(10),
+			remainder: 0,
+		}
+		.encode();
+
+		assert_eq!(do_vesting_locked_at(&with_cliff, 8), Ok(100));
+		assert_eq!(do_vesting_locked_at(&with_cliff, 10), Ok(50));
+	}
+}